@@ -0,0 +1,115 @@
+//! Account Exporter Example
+//!
+//! Poll the clearinghouse state, open orders, vault equities and staking
+//! delegations of one or more addresses and serve them as Prometheus gauges
+//! at `/metrics`: account value, margin, leverage, and per coin the position
+//! size, unrealized PnL and distance to liquidation (format:
+//! `hyperliquid_examples::account_metrics`). Without addresses it exports the
+//! accounts `--account` selects from the profile file (`all` for every one),
+//! else the account of `PRIVATE_KEY`.
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint/TOKEN"
+//! cargo run --bin account_exporter -- 0xabc...,0xdef... --interval 30
+//! cargo run --bin account_exporter -- --listen 0.0.0.0:9185
+//! cargo run --bin account_exporter -- --account all
+//! curl localhost:9185/metrics
+//! ```
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use clap::Parser;
+use hyperliquid_examples::account_metrics::AccountMetrics;
+use hyperliquid_examples::accounts::Accounts;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{stream_duration, Config, Requires};
+
+#[derive(Debug, Parser)]
+#[command(name = "account_exporter", about = "Serve account state of addresses as Prometheus gauges")]
+struct Args {
+    /// Addresses, comma-separated (default: the --account selection, else the PRIVATE_KEY account)
+    addresses: Option<String>,
+    /// Address of the metrics endpoint
+    #[arg(long, default_value = "127.0.0.1:9185")]
+    listen: SocketAddr,
+    /// Seconds between polls
+    #[arg(long, default_value_t = 15)]
+    interval: u64,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require_accounts("account_exporter", Requires::Endpoint);
+    let args = Args::parse_from(std::iter::once("account_exporter".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+    let info = TypedInfo::from_sdk(&sdk);
+
+    println!("Account Exporter Example");
+    println!("{}", "=".repeat(50));
+
+    let selected = Accounts::selected(&config)?;
+    let addresses: Vec<String> = match &args.addresses {
+        Some(list) => list.split(',').map(|a| a.trim().to_string()).collect(),
+        None if !selected.is_empty() => selected.iter().map(|a| a.address.clone()).collect(),
+        None => sdk.address().map(|a| format!("{:?}", a)).into_iter().collect(),
+    };
+    if addresses.is_empty() {
+        return Err("no addresses given and no PRIVATE_KEY set".into());
+    }
+
+    let metrics = AccountMetrics::new(&addresses);
+    println!("\n1. Accounts:");
+    for address in metrics.addresses() {
+        println!("   {}", address);
+    }
+
+    let server = metrics.serve(args.listen).await?;
+    println!("\n2. Metrics at http://{}/metrics", server.addr());
+
+    println!("\n3. Polling every {}s:", args.interval);
+    let deadline = async {
+        // Until Ctrl-C, or for HL_STREAM_SECONDS
+        match std::env::var_os("HL_STREAM_SECONDS") {
+            Some(_) => tokio::time::sleep(stream_duration(0)).await,
+            None => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    };
+    tokio::pin!(deadline);
+    let mut poll = tokio::time::interval(Duration::from_secs(args.interval.max(1)));
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            _ = poll.tick() => {
+                for address in metrics.addresses() {
+                    if let Err(e) = metrics.poll(&info, &address).await {
+                        println!("   {}: Error: {}", address, e);
+                        continue;
+                    }
+                    let Some(snapshot) = metrics.snapshot(&address) else { continue };
+                    println!(
+                        "   {}: value ${}, leverage {}x, {} positions, {} open orders",
+                        address,
+                        snapshot.state.margin_summary.account_value,
+                        snapshot.leverage().round_dp(2),
+                        snapshot.state.asset_positions.len(),
+                        snapshot.open_orders.len()
+                    );
+                }
+            }
+        }
+    }
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
@@ -0,0 +1,331 @@
+//! Execution Algo Example
+//!
+//! Work a large order into the market over time
+//! (`hyperliquid_examples::execution`): TWAP slices, VWAP slices weighted
+//! by the coin's usual volume at that time of day, or a share of the volume
+//! traded (POV), as IOC children or GTC children at the touch, never beyond
+//! `--limit-px`. Type `p`, `r` or `c` and Enter to pause, resume or cancel.
+//! `--native` runs the exchange's own TWAP instead and follows its progress
+//! the same way.
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
+//! export PRIVATE_KEY="0x..."
+//! cargo run --bin algo -- buy BTC 0.1 --duration 30m --slices 30
+//! cargo run --bin algo -- sell ETH 5 --schedule vwap --duration 2h --slices 24 --lookback 14
+//! cargo run --bin algo -- buy BTC 0.1 --schedule pov --rate 5 --duration 1h --limit-px 97000
+//! cargo run --bin algo -- buy BTC 0.1 --duration 30m --child limit --interval 10
+//! cargo run --bin algo -- buy BTC 0.1 --duration 30m --native           # exchange TWAP
+//! cargo run --bin algo -- buy BTC 0.1 --duration 30m --dry-run          # sign and print, nothing sent
+//! ```
+
+use std::io::BufRead;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use clap::Parser;
+use hyperliquid_examples::cli::{OrderSide, Since};
+use hyperliquid_examples::dry_run;
+use hyperliquid_examples::execution::{
+    Activity, Algo, ChildStyle, Control, ExecStatus, NativeTwap, Schedule, VolumeProfile,
+};
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{paper, stream_duration, AssetRegistry, Config, Market, Requires};
+use hyperliquid_sdk::HyperliquidSDK;
+use rust_decimal::Decimal;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Parser)]
+#[command(name = "algo", about = "Work an order into the market over time")]
+struct Args {
+    side: OrderSide,
+    /// Symbol, e.g. BTC, xyz:XYZ100, PURR/USDC
+    coin: String,
+    /// Size in the base asset (rounded down to the lot)
+    size: Decimal,
+    /// How the size is spread over time
+    #[arg(long, value_parser = ["twap", "vwap", "pov"], default_value = "twap")]
+    schedule: String,
+    /// Time to work the order over (POV: the longest it runs), e.g. 90s, 30m, 2h
+    #[arg(long, default_value = "10m")]
+    duration: Since,
+    /// Number of slices (TWAP, VWAP)
+    #[arg(long, default_value_t = 10)]
+    slices: usize,
+    /// Percentage of the market's volume to trade (POV)
+    #[arg(long, default_value = "10")]
+    rate: Decimal,
+    /// Days of candles the VWAP profile is built from
+    #[arg(long, default_value_t = 7)]
+    lookback: u64,
+    /// Never buy above (sell below) this price
+    #[arg(long)]
+    limit_px: Option<Decimal>,
+    /// Child orders: `ioc` takes liquidity, `limit` rests at the touch until the next check
+    #[arg(long, default_value = "ioc")]
+    child: ChildStyle,
+    /// Seconds between two checks of the schedule
+    #[arg(long, default_value_t = 1.0)]
+    interval: f64,
+    /// Run the exchange's TWAP instead (5 to 1440 minutes)
+    #[arg(long)]
+    native: bool,
+    /// Randomize the native TWAP's slices
+    #[arg(long)]
+    randomize: bool,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("algo", Requires::Signer);
+    let args = Args::parse_from(std::iter::once("algo".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+
+    println!("Execution Algo Example");
+    println!("{}", "=".repeat(50));
+
+    let user = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
+    println!("Address: {}", user);
+
+    let Since::Ago(duration) = args.duration else {
+        return Err(format!("--duration takes a length like 30m, not a time: {:?}", args.duration).into());
+    };
+    let registry = AssetRegistry::load(&sdk).await?;
+    let market = registry.resolve(&args.coin)?;
+    let info = TypedInfo::from_sdk(&sdk);
+    let interval = Duration::from_secs_f64(args.interval.max(0.05));
+
+    let job = Job { market, args: &args, duration, interval };
+    if args.native {
+        native(&config, &info, &user, job).await?;
+    } else {
+        client(&config, &sdk, &info, &user, job).await?;
+    }
+
+    paper::report();
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
+
+/// The order to work and how often to check on it
+#[derive(Clone, Copy)]
+struct Job<'a> {
+    market: &'a Market,
+    args: &'a Args,
+    duration: Duration,
+    interval: Duration,
+}
+
+/// Work the order from here, one child at a time
+async fn client(
+    config: &Config,
+    sdk: &HyperliquidSDK,
+    info: &TypedInfo,
+    user: &str,
+    job: Job<'_>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Job { market, args, duration, interval } = job;
+    let algo = match args.side {
+        OrderSide::Buy => Algo::buy(&market.symbol, args.size),
+        OrderSide::Sell => Algo::sell(&market.symbol, args.size),
+    };
+    let algo = match args.schedule.as_str() {
+        "vwap" => {
+            let slice_ms = duration.as_millis() as u64 / args.slices.max(1) as u64;
+            let profile = VolumeProfile::load(info, &market.symbol, now_ms(), slice_ms, args.slices, args.lookback).await?;
+            algo.vwap(duration, profile)
+        }
+        "pov" => algo.pov(args.rate / Decimal::ONE_HUNDRED, duration),
+        _ => algo.twap(duration, args.slices),
+    }
+    .child(args.child);
+    let algo = match args.limit_px {
+        Some(px) => algo.limit_px(px),
+        None => algo,
+    };
+    let mut execution = algo.start(market, user, Instant::now())?;
+
+    println!("\n1. Schedule:");
+    println!(
+        "   {} {} {} over {:?}, {} slices, {} children{}",
+        execution.algo.schedule,
+        if execution.algo.is_buy { "buy" } else { "sell" },
+        execution.algo.size,
+        execution.algo.duration,
+        execution.algo.slices,
+        execution.algo.child,
+        execution.algo.limit_px.map(|px| format!(", limit {}", px)).unwrap_or_default()
+    );
+
+    if config.dry_run {
+        println!("\n2. First child:");
+        match execution.next_child(Instant::now(), &info.l2_book(&market.symbol).await?) {
+            Some(child) => {
+                println!("   {}", child);
+                dry_run::show(config, &execution.child_action(&child)).await;
+            }
+            None => println!("   Nothing due yet"),
+        }
+        return Ok(());
+    }
+
+    println!("\n2. Executing (p + Enter pauses, r resumes, c cancels):");
+    let control = Control::default();
+    keyboard(control.clone());
+
+    // POV follows the coin's trades; the other schedules only need the clock
+    let mut stream = sdk.stream().on_error(|e| eprintln!("   [Error] {}", e));
+    let pov = matches!(execution.algo.schedule, Schedule::Pov(_));
+    let mut trades = if pov { execution.watch_trades(&mut stream) } else { mpsc::unbounded_channel().1 };
+    if pov {
+        stream.start()?;
+    }
+
+    let mut ticker = tokio::time::interval(interval);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut interrupted = false;
+    while matches!(execution.status, ExecStatus::Running | ExecStatus::Paused) {
+        tokio::select! {
+            _ = &mut ctrl_c, if !interrupted => {
+                interrupted = true;
+                control.cancel();
+            }
+            Some(trade) = trades.recv() => match trade {
+                Ok(trade) => execution.on_trade(&trade),
+                Err(e) => println!("   Error: {}", e),
+            },
+            _ = ticker.tick() => {
+                match execution.step(config, info, &control, Instant::now()).await {
+                    Ok(activities) => {
+                        for activity in &activities {
+                            println!("   {}", activity);
+                        }
+                        if activities.iter().any(|a| matches!(a, Activity::Sent(_) | Activity::Expired(_))) {
+                            println!("   {}", execution.progress());
+                        }
+                    }
+                    Err(e) => println!("   Error: {}", e),
+                }
+            }
+        }
+    }
+    if pov {
+        stream.stop();
+    }
+
+    println!("\n3. Result:");
+    println!("   {}", execution.progress());
+    for child in &execution.children {
+        println!("   {}", child);
+    }
+    Ok(())
+}
+
+/// Run the exchange's TWAP and follow it until it ends, or until Ctrl-C
+async fn native(
+    config: &Config,
+    info: &TypedInfo,
+    user: &str,
+    job: Job<'_>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Job { market, args, duration, interval } = job;
+    let minutes = duration.as_secs().div_ceil(60);
+    let mut twap = NativeTwap::new(market, args.side == OrderSide::Buy, args.size, minutes, args.randomize)?;
+
+    println!("\n1. Native TWAP:");
+    println!(
+        "   {} {} {} over {} minutes{}",
+        if twap.is_buy { "Buy" } else { "Sell" },
+        twap.size,
+        twap.coin,
+        twap.minutes,
+        if twap.randomize { ", randomized" } else { "" }
+    );
+    if config.dry_run {
+        dry_run::show(config, &twap.action()).await;
+        return Ok(());
+    }
+    match twap.place(config).await {
+        Ok(twap_id) => println!("   TWAP ID: {}", twap_id),
+        Err(e) => {
+            println!("   Error: {}", e);
+            return Ok(());
+        }
+    }
+
+    println!("\n2. Progress:");
+    let deadline = async {
+        // Until Ctrl-C, or for HL_STREAM_SECONDS
+        match std::env::var_os("HL_STREAM_SECONDS") {
+            Some(_) => tokio::time::sleep(stream_duration(0)).await,
+            None => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    };
+    tokio::pin!(deadline);
+    let mut ticker = tokio::time::interval(interval);
+    let mut last = None;
+    let running = loop {
+        tokio::select! {
+            _ = &mut deadline => break true,
+            _ = ticker.tick() => match twap.progress(info, user).await {
+                Ok(progress) => {
+                    if last.as_ref() != Some(&progress) {
+                        println!("   {}", progress);
+                    }
+                    let running = progress.status == "activated";
+                    last = Some(progress);
+                    if !running {
+                        break false;
+                    }
+                }
+                Err(e) => println!("   Error: {}", e),
+            }
+        }
+    };
+
+    if running {
+        println!("\n3. Cancelling:");
+        match twap.cancel(config).await {
+            Ok(()) => println!("   Cancelled"),
+            Err(e) => println!("   Error: {}", e),
+        }
+        match twap.progress(info, user).await {
+            Ok(progress) => println!("   {}", progress),
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Pause, resume or cancel from the keyboard
+fn keyboard(control: Control) {
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            match line.trim() {
+                "p" => control.pause(),
+                "r" => control.resume(),
+                "c" => control.cancel(),
+                _ => {}
+            }
+        }
+    });
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
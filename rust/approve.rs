@@ -6,36 +6,26 @@
 //! ```bash
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
+//! cargo run --example approve -- --dry-run   # sign and print, nothing sent
 //! cargo run --example approve
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::dry_run::{self, actions};
+use hyperliquid_examples::{Config, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example approve");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("approve", Requires::Signer);
+    let sdk = config.connect().await?;
 
     println!("Builder Fee Approval Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
@@ -53,12 +43,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Approve builder fee (1% max)
     println!("\n2. Approving builder fee (1% max)...");
-    match sdk.approve_builder_fee(Some("1%")).await {
-        Ok(result) => {
-            println!("   Result: {:?}", result);
-        }
-        Err(e) => {
-            println!("   Error: {}", e);
+    if config.dry_run {
+        dry_run::show(&config, &actions::approve_builder_fee("1%")).await;
+    } else {
+        match sdk.approve_builder_fee(Some("1%")).await {
+            Ok(result) => {
+                println!("   Result: {:?}", result);
+            }
+            Err(e) => {
+                println!("   Error: {}", e);
+            }
         }
     }
 
@@ -0,0 +1,184 @@
+//! Bars Example
+//!
+//! Resample trades into time, tick, volume, dollar or imbalance bars with
+//! VWAP and the taker buy/sell volume split, live from the trades stream or in
+//! batch from a `recorder` directory (format: `hyperliquid_examples::bars`).
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint/TOKEN"
+//! cargo run --bin bars -- BTC --bar 1m --bar volume:10
+//! cargo run --bin bars -- BTC,ETH --bar dollar:1000000 --bar imbalance:5 --grpc
+//! cargo run --bin bars -- --from recordings/ --bar tick:100    # no endpoint needed
+//! ```
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use clap::Parser;
+use hyperliquid_examples::bars::{build, read_trades, Bar, BarBuilder, BarKind};
+use hyperliquid_examples::recorder::now_ms;
+use hyperliquid_examples::{stream_duration, AssetRegistry, Config, Requires};
+
+/// Bars printed per kind in batch mode
+const PRINT_BARS: usize = 10;
+
+/// How often time bars are closed while streaming
+const POLL_EVERY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Parser)]
+#[command(name = "bars", about = "Build time, tick, volume, dollar and imbalance bars from trades")]
+struct Args {
+    /// Coins, comma-separated (default: BTC live, every recorded coin with --from)
+    coins: Option<String>,
+    /// Bar to build, repeatable: 1m, tick:500, volume:10, dollar:1000000, imbalance:5
+    #[arg(long = "bar", default_value = "1m")]
+    bars: Vec<BarKind>,
+    /// Build from the trades of a recording instead of the live stream
+    #[arg(long)]
+    from: Option<PathBuf>,
+    /// Stream trades over gRPC instead of WebSocket
+    #[arg(long)]
+    grpc: bool,
+}
+
+fn print_bar(bar: &Bar) {
+    println!(
+        "   [{}] {} t={} O={} H={} L={} C={} V={} VWAP={} buy={} sell={} n={}",
+        bar.kind,
+        bar.coin,
+        bar.open_time,
+        bar.open,
+        bar.high,
+        bar.low,
+        bar.close,
+        bar.volume,
+        bar.vwap.round_dp(6),
+        bar.buy_volume,
+        bar.sell_volume,
+        bar.trades
+    );
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?;
+    let args = Args::parse_from(std::iter::once("bars".to_string()).chain(config.args.clone()));
+    let coins: Vec<String> = args.coins.iter().flat_map(|c| c.split(',')).map(|c| c.trim().to_string()).collect();
+
+    println!("Bars Example");
+    println!("{}", "=".repeat(50));
+
+    // Batch: every trade of the recording at once
+    if let Some(dir) = &args.from {
+        println!("\n1. Trades of {}:", dir.display());
+        let mut trades = read_trades(dir)?;
+        if !coins.is_empty() {
+            trades.retain(|t| coins.contains(&t.coin));
+        }
+        let mut recorded: Vec<&str> = trades.iter().map(|t| t.coin.as_str()).collect();
+        recorded.sort();
+        recorded.dedup();
+        println!("   {} trades of {}", trades.len(), recorded.join(", "));
+
+        println!("\n2. Bars:");
+        for &kind in &args.bars {
+            let bars = build(kind, &trades);
+            println!("   {}: {} closed bars", kind, bars.len());
+            for bar in bars.iter().take(PRINT_BARS) {
+                print_bar(bar);
+            }
+        }
+
+        println!("\n{}", "=".repeat(50));
+        println!("Done!");
+        return Ok(());
+    }
+
+    let sdk = config.require("bars", Requires::Endpoint).connect().await?;
+    let registry = AssetRegistry::load(&sdk).await?;
+    let coins: Vec<String> = if coins.is_empty() {
+        vec!["BTC".to_string()]
+    } else {
+        coins.iter().map(|c| registry.resolve(c).map(|m| m.symbol.clone())).collect::<Result<_, _>>()?
+    };
+    let names: Vec<&str> = coins.iter().map(String::as_str).collect();
+
+    let builders = Arc::new(Mutex::new(args.bars.iter().map(|&kind| BarBuilder::new(kind)).collect::<Vec<_>>()));
+    let feed = builders.clone();
+    let on_trades = move |data: serde_json::Value| {
+        for builder in feed.lock().unwrap().iter_mut() {
+            match builder.ingest(&data) {
+                Ok(bars) => bars.iter().for_each(print_bar),
+                Err(e) => eprintln!("   [Error] bad trade payload: {}", e),
+            }
+        }
+    };
+
+    let mut stream = sdk.stream()
+        .on_open(|| {
+            println!("   [Connected]");
+        })
+        .on_error(|e| {
+            eprintln!("   [Error] {}", e);
+        });
+    let mut grpc = sdk.grpc()
+        .on_connect(|| {
+            println!("   [Connected]");
+        })
+        .on_error(|e| {
+            eprintln!("   [Error] {}", e);
+        });
+
+    let kinds: Vec<String> = args.bars.iter().map(|k| k.to_string()).collect();
+    let source = if args.grpc { "gRPC" } else { "WebSocket" };
+    println!("\n1. Building {} bars of {} ({}):", kinds.join(", "), coins.join(", "), source);
+    if args.grpc {
+        grpc.trades(&names, on_trades);
+        grpc.start()?;
+    } else {
+        stream.trades(&names, on_trades);
+        stream.start()?;
+    }
+
+    // Close time bars on the clock, not only when the next trade arrives
+    let duration = stream_duration(60);
+    let deadline = tokio::time::sleep(duration);
+    tokio::pin!(deadline);
+    let mut poll = tokio::time::interval(POLL_EVERY);
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            _ = tokio::signal::ctrl_c() => break,
+            _ = poll.tick() => {
+                let now = now_ms();
+                for builder in builders.lock().unwrap().iter_mut() {
+                    builder.poll(now).iter().for_each(print_bar);
+                }
+            }
+        }
+    }
+
+    stream.stop();
+    grpc.stop();
+
+    println!("\n2. Summary:");
+    for builder in builders.lock().unwrap().iter_mut() {
+        let open = builder.finish();
+        println!("   {}: {} closed, {} still open", builder.kind(), builder.closed(), open.len());
+        for bar in &open {
+            print_bar(bar);
+        }
+    }
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
@@ -0,0 +1,163 @@
+//! Bracket Order Example
+//!
+//! Enter a position with its take-profit and stop-loss attached, all in one
+//! action (`hyperliquid_examples::bracket`), then keep the two in step with
+//! the entry: resized to what it filled, and one cancelled once the other
+//! fires. With `--position-tpsl` they close the whole position instead.
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
+//! export PRIVATE_KEY="0x..."
+//! cargo run --bin bracket -- buy BTC 0.001 --tp 101000 --sl 91000
+//! cargo run --bin bracket -- sell ETH 0.1 --px 3600 --tp 3300 --sl 3800 --position-tpsl
+//! cargo run --bin bracket -- buy BTC 0.001 --sl 91000 --dry-run   # sign and print, nothing sent
+//! ```
+
+use std::time::Duration;
+
+use clap::Parser;
+use hyperliquid_examples::bracket::{Bracket, BracketOrder, BracketStatus, Leg};
+use hyperliquid_examples::cli::OrderSide;
+use hyperliquid_examples::dry_run;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{paper, stream_duration, AssetRegistry, Config, Requires};
+use rust_decimal::Decimal;
+
+#[derive(Debug, Parser)]
+#[command(name = "bracket", about = "Enter with an attached take-profit and stop-loss")]
+struct Args {
+    side: OrderSide,
+    /// Perp symbol, e.g. BTC, xyz:XYZ100
+    coin: String,
+    /// Size in the base asset (rounded down to the lot)
+    size: Decimal,
+    /// Limit price of the entry; market order without it
+    #[arg(long)]
+    px: Option<Decimal>,
+    /// Take-profit trigger price
+    #[arg(long)]
+    tp: Option<Decimal>,
+    /// Stop-loss trigger price
+    #[arg(long)]
+    sl: Option<Decimal>,
+    /// Attach the TP/SL to the position rather than to the entry
+    #[arg(long)]
+    position_tpsl: bool,
+    /// Seconds between syncs
+    #[arg(long, default_value_t = 2)]
+    interval: u64,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("bracket", Requires::Signer);
+    let args = Args::parse_from(std::iter::once("bracket".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+    let info = TypedInfo::from_sdk(&sdk);
+
+    println!("Bracket Order Example");
+    println!("{}", "=".repeat(50));
+
+    let user = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
+    println!("Address: {}", user);
+
+    let mut bracket = match args.side {
+        OrderSide::Buy => Bracket::buy(&args.coin, args.size),
+        OrderSide::Sell => Bracket::sell(&args.coin, args.size),
+    };
+    if let Some(px) = args.px {
+        bracket = bracket.limit(px);
+    }
+    if let Some(px) = args.tp {
+        bracket = bracket.take_profit(px);
+    }
+    if let Some(px) = args.sl {
+        bracket = bracket.stop_loss(px);
+    }
+    if args.position_tpsl {
+        bracket = bracket.position_tpsl();
+    }
+
+    println!("\n1. Placing:");
+    if config.dry_run {
+        let registry = AssetRegistry::load(&sdk).await?;
+        let market = registry.resolve(&args.coin)?;
+        let order = bracket.build(market, info.mid(&market.symbol).await?)?;
+        dry_run::show(&config, &order.action()).await;
+    } else {
+        match bracket.place(&config, &sdk).await {
+            Ok(order) => watch(&config, &info, &user, args.interval, order).await,
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
+
+    paper::report();
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
+
+/// Sync `order` every `interval` seconds until it closes, or until Ctrl-C
+async fn watch(config: &Config, info: &TypedInfo, user: &str, interval: u64, mut order: BracketOrder) {
+    print_legs(&order);
+
+    println!("\n2. Keeping TP/SL in step every {}s:", interval.max(1));
+    let deadline = async {
+        // Until Ctrl-C, or for HL_STREAM_SECONDS
+        match std::env::var_os("HL_STREAM_SECONDS") {
+            Some(_) => tokio::time::sleep(stream_duration(0)).await,
+            None => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    };
+    tokio::pin!(deadline);
+    let mut poll = tokio::time::interval(Duration::from_secs(interval.max(1)));
+    while matches!(order.status, BracketStatus::Pending | BracketStatus::Open) {
+        tokio::select! {
+            _ = &mut deadline => break,
+            _ = poll.tick() => match order.sync(config, info, user).await {
+                Ok(adjustments) => {
+                    for adjustment in adjustments {
+                        println!("   {}", adjustment);
+                    }
+                }
+                Err(e) => println!("   Error: {}", e),
+            },
+        }
+    }
+
+    println!("\n3. Final state:");
+    print_legs(&order);
+}
+
+fn print_legs(order: &BracketOrder) {
+    println!("   {} {} ({}), status {}", if order.is_buy { "Buy" } else { "Sell" }, order.coin, order.grouping, order.status);
+    for leg in order.legs() {
+        println!("   {}", leg_line(leg));
+    }
+}
+
+fn leg_line(leg: &Leg) -> String {
+    let mut line = match leg.trigger_px {
+        Some(trigger) => format!("{}: {} at trigger {}", leg.kind, leg.size, trigger),
+        None => format!("{}: {} @ {}", leg.kind, leg.size, leg.limit_px),
+    };
+    match (leg.oid, leg.open) {
+        (Some(oid), true) => line.push_str(&format!(", open (oid {})", oid)),
+        (None, true) => line.push_str(", waiting for the entry"),
+        _ => line.push_str(", closed"),
+    }
+    if let Some(avg) = leg.avg_px {
+        line.push_str(&format!(", filled {} @ {}", leg.filled, avg));
+    }
+    line
+}
@@ -9,33 +9,20 @@
 //! cargo run --example builder_fee
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::{connect, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example builder_fee");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("builder_fee", Requires::Signer).await?;
 
     println!("Builder Fee Management Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
@@ -7,52 +7,36 @@
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
 //! cargo run --example cancel_all
+//! cargo run --example cancel_all -- --paper   # simulated fills, nothing sent
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, paper, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example cancel_all");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("cancel_all", Requires::Signer).await?;
 
     println!("Cancel All Orders Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
     // Check open orders first
     println!("\n1. Current Open Orders:");
-    match sdk.open_orders().await {
+    let address = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
+    match TypedInfo::from_sdk(&sdk).open_orders(&address, None).await {
         Ok(orders) => {
-            if let Some(arr) = orders.as_array() {
-                println!("   {} open orders", arr.len());
-                for (i, order) in arr.iter().take(5).enumerate() {
-                    let coin = order.get("coin").and_then(|v| v.as_str()).unwrap_or("?");
-                    let side = order.get("side").and_then(|v| v.as_str()).unwrap_or("?");
-                    let sz = order.get("sz").and_then(|v| v.as_str()).unwrap_or("?");
-                    let px = order.get("limitPx").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] {} {} {} @ {}", i + 1, coin, side, sz, px);
-                }
+            println!("   {} open orders", orders.len());
+            for (i, order) in orders.iter().take(5).enumerate() {
+                println!("   [{}] {} {} {} @ {}", i + 1, order.coin, order.side, order.sz, order.limit_px);
             }
         }
         Err(e) => println!("   Error: {}", e),
@@ -72,6 +56,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     //     Err(e) => println!("   Error: {}", e),
     // }
 
+    paper::report();
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
@@ -9,49 +9,37 @@
 //! cargo run --example cancel_by_cloid
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, AssetRegistry, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example cancel_by_cloid");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("cancel_by_cloid", Requires::Signer).await?;
 
     println!("Cancel by Client Order ID Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
+    // Check the symbol exists before sending anything
+    AssetRegistry::load(&sdk).await?.resolve("BTC")?;
+
     // Check open orders with cloid
     println!("\n1. Open Orders (checking for cloid):");
-    match sdk.open_orders().await {
+    let address = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
+    match TypedInfo::from_sdk(&sdk).open_orders(&address, None).await {
         Ok(orders) => {
-            if let Some(arr) = orders.as_array() {
-                println!("   {} open orders", arr.len());
-                for order in arr.iter().take(5) {
-                    let coin = order.get("coin").and_then(|v| v.as_str()).unwrap_or("?");
-                    let oid = order.get("oid").and_then(|v| v.as_u64()).unwrap_or(0);
-                    let cloid = order.get("cloid").and_then(|v| v.as_str()).unwrap_or("none");
-                    println!("   {} OID={} CLOID={}", coin, oid, cloid);
-                }
+            println!("   {} open orders", orders.len());
+            for order in orders.iter().take(5) {
+                let cloid = order.cloid.as_deref().unwrap_or("none");
+                println!("   {} OID={} CLOID={}", order.coin, order.oid, cloid);
             }
         }
         Err(e) => println!("   Error: {}", e),
@@ -0,0 +1,180 @@
+//! Candle Backfill Example
+//!
+//! Keep a local SQLite history of candles: walk back in time for each coin
+//! and interval, refill missing bars, and with `--follow` keep the open
+//! candle current from the `candle` WebSocket channel (format:
+//! `hyperliquid_examples::candles`). Run it again later to top the database
+//! up; it resumes from what is already stored.
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint/TOKEN"
+//! cargo run --bin candle_backfill -- BTC,ETH 1h,1d --since 365d
+//! cargo run --bin candle_backfill -- BTC 1m --since 3d --db btc.db --follow
+//! ```
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use clap::Parser;
+use hyperliquid_examples::candles::{backfill, refill_gaps, CandleStore, INTERVALS};
+use hyperliquid_examples::cli::Since;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::recorder::now_ms;
+use hyperliquid_examples::{stream_duration, AssetRegistry, Config, Requires};
+
+#[derive(Debug, Parser)]
+#[command(name = "candle_backfill", about = "Backfill candles into SQLite and keep them current")]
+struct Args {
+    /// Coins, comma-separated
+    coins: String,
+    /// Intervals, comma-separated (1m, 5m, 15m, 30m, 1h, 4h, 1d)
+    #[arg(default_value = "1h")]
+    intervals: String,
+    /// How far back to fill: a duration ago (90d) or a time in ms
+    #[arg(long, default_value = "30d")]
+    since: Since,
+    /// Walk back from here instead of now (a duration ago or a time in ms)
+    #[arg(long)]
+    until: Option<Since>,
+    /// SQLite database
+    #[arg(long, default_value = "candles.db")]
+    db: PathBuf,
+    /// Then stream the open candles until Ctrl-C (or for HL_STREAM_SECONDS)
+    #[arg(long)]
+    follow: bool,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("candle_backfill", Requires::Endpoint);
+    let args = Args::parse_from(std::iter::once("candle_backfill".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+    let info = TypedInfo::from_sdk(&sdk);
+
+    println!("Candle Backfill Example");
+    println!("{}", "=".repeat(50));
+
+    let registry = AssetRegistry::load(&sdk).await?;
+    let coins: Vec<String> = args
+        .coins
+        .split(',')
+        .map(|coin| registry.resolve(coin.trim()).map(|m| m.symbol.clone()))
+        .collect::<Result<_, _>>()?;
+    let intervals: Vec<&str> = args.intervals.split(',').map(str::trim).collect();
+    for interval in &intervals {
+        if !INTERVALS.contains(interval) {
+            return Err(format!("unsupported interval '{}' (use {})", interval, INTERVALS.join(", ")).into());
+        }
+    }
+
+    let mut store = CandleStore::open(&args.db)?;
+    println!("\n1. Database {}:", args.db.display());
+    let series = store.series()?;
+    if series.is_empty() {
+        println!("   (empty)");
+    }
+    for s in &series {
+        println!("   {} {}: {} candles ({} - {})", s.coin, s.interval, s.candles, s.first, s.last);
+    }
+
+    // Newest first, then back to --since
+    let now = now_ms();
+    let since = args.since.start_ms(now);
+    let until = args.until.map_or(now, |u| u.start_ms(now));
+    println!("\n2. Backfilling {} - {}:", since, until);
+    for coin in &coins {
+        for interval in &intervals {
+            match backfill(&mut store, &info, coin, interval, since, until, now).await {
+                Ok(report) => {
+                    let first = store.first(coin, interval)?.map(|c| c.open_time).unwrap_or_default();
+                    let end = if report.exhausted { " (no older history)" } else { "" };
+                    println!(
+                        "   {} {}: {} candles in {} requests, back to {}{}",
+                        coin, interval, report.stored, report.requests, first, end
+                    );
+                }
+                Err(e) => println!("   {} {}: Error: {}", coin, interval, e),
+            }
+        }
+    }
+
+    println!("\n3. Gaps:");
+    for coin in &coins {
+        for interval in &intervals {
+            let before = store.gaps(coin, interval)?;
+            match refill_gaps(&mut store, &info, coin, interval, now_ms()).await {
+                Ok(remaining) => {
+                    let missing: u64 = remaining.iter().map(|g| g.bars).sum();
+                    println!(
+                        "   {} {}: {} found, {} refilled, {} bars still missing",
+                        coin,
+                        interval,
+                        before.len(),
+                        before.len().saturating_sub(remaining.len()),
+                        missing
+                    );
+                    for gap in remaining.iter().take(5) {
+                        println!("      {} - {} ({} bars)", gap.from, gap.to, gap.bars);
+                    }
+                }
+                Err(e) => println!("   {} {}: Error: {}", coin, interval, e),
+            }
+        }
+    }
+
+    if args.follow {
+        let store = Arc::new(Mutex::new(store));
+        let mut stream = sdk.stream()
+            .on_open(|| {
+                println!("   [Connected]");
+            })
+            .on_error(|e| {
+                eprintln!("   [Error] {}", e);
+            });
+
+        println!("\n4. Following open candles:");
+        for coin in &coins {
+            for interval in &intervals {
+                let store = store.clone();
+                stream.candle(coin, interval, move |data| {
+                    match store.lock().unwrap().apply_stream(&data, now_ms()) {
+                        Ok(candles) => {
+                            for c in candles {
+                                println!("   {} {} t={} O={} H={} L={} C={} V={}", c.coin, c.interval, c.open_time, c.open, c.high, c.low, c.close, c.volume);
+                            }
+                        }
+                        Err(e) => eprintln!("   [Error] {}", e),
+                    }
+                });
+            }
+        }
+        stream.start()?;
+
+        // Until Ctrl-C, or for HL_STREAM_SECONDS
+        match std::env::var_os("HL_STREAM_SECONDS") {
+            Some(_) => tokio::time::sleep(stream_duration(0)).await,
+            None => tokio::signal::ctrl_c().await?,
+        }
+        stream.stop();
+
+        let store = store.lock().unwrap();
+        for coin in &coins {
+            for interval in &intervals {
+                if let Some(open) = store.open_candle(coin, interval)? {
+                    println!("   {} {} open candle t={} C={}", coin, interval, open.open_time, open.close);
+                }
+            }
+        }
+    }
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
@@ -8,23 +8,24 @@
 //! cargo run --example evm_example
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::{Config, Requires};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").unwrap_or_else(|_| {
-        eprintln!("Error: Set ENDPOINT environment variable");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        std::process::exit(1);
-    });
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("evm_example", Requires::Endpoint);
 
     println!("Hyperliquid EVM API Example");
     println!("{}", "=".repeat(50));
-    println!("Endpoint: {}...", &endpoint[..endpoint.len().min(50)]);
+    println!("Endpoint: {}", config.redacted_endpoint());
     println!();
 
     // Create SDK and get EVM client
-    let sdk = HyperliquidSDK::new().endpoint(&endpoint).build().await?;
+    let sdk = config.connect().await?;
     let evm = sdk.evm();
 
     // ══════════════════════════════════════════════════════════════════════════
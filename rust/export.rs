@@ -0,0 +1,144 @@
+//! Parquet Export Example
+//!
+//! Export candles over any date range, your fills, or recent HyperCore
+//! trades to a Parquet file with decimal, timestamp and dictionary columns,
+//! ready for pandas or DuckDB (format: `hyperliquid_examples::export`).
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint/TOKEN"
+//! cargo run --bin export -- candles BTC 1h --since 90d
+//! cargo run --bin export -- candles ETH 1m --since 1737000000000 --until 1737400000000 --out eth.parquet
+//! cargo run --bin export -- fills --since 30d            # PRIVATE_KEY's account, or --user 0x...
+//! cargo run --bin export -- trades --coin BTC --blocks 100
+//! ```
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::{Parser, Subcommand};
+use hyperliquid_examples::cli::Since;
+use hyperliquid_examples::export::{self, candle_schema, fill_schema};
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{AssetRegistry, Config, Error, Requires};
+
+#[derive(Debug, Parser)]
+#[command(name = "export", about = "Export Hyperliquid market and account data to Parquet")]
+struct Args {
+    #[command(subcommand)]
+    dataset: Dataset,
+    /// Output file (default: named after the dataset)
+    #[arg(long, global = true)]
+    out: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Dataset {
+    /// OHLCV candles of one coin
+    Candles {
+        coin: String,
+        /// 1m, 3m, 5m, 15m, 30m, 1h, 2h, 4h, 8h, 12h, 1d, 3d, 1w or 1M
+        interval: String,
+        /// Start: a duration ago (30d) or a time in ms
+        #[arg(long, default_value = "30d")]
+        since: Since,
+        /// End: a duration ago or a time in ms (default: now)
+        #[arg(long)]
+        until: Option<Since>,
+    },
+    /// Fills of an account
+    Fills {
+        /// Account address (default: PRIVATE_KEY's)
+        #[arg(long)]
+        user: Option<String>,
+        #[arg(long, default_value = "30d")]
+        since: Since,
+        #[arg(long)]
+        until: Option<Since>,
+    },
+    /// Trades of the latest HyperCore blocks
+    Trades {
+        #[arg(long)]
+        coin: Option<String>,
+        /// Number of recent blocks
+        #[arg(long, default_value_t = 100)]
+        blocks: u32,
+    },
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("export", Requires::Endpoint);
+    let args = Args::parse_from(std::iter::once("export".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+    let info = TypedInfo::from_sdk(&sdk);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+
+    println!("Parquet Export Example");
+    println!("{}", "=".repeat(50));
+
+    let (path, schema, rows, bytes) = match &args.dataset {
+        Dataset::Candles { coin, interval, since, until } => {
+            let coin = AssetRegistry::load(&sdk).await?.resolve(coin)?.symbol.clone();
+            let (start, end) = (since.start_ms(now), until.map_or(now, |u| u.start_ms(now)));
+            println!("\n1. Fetching {} {} candles ({} - {} ms)...", coin, interval, start, end);
+            let candles = export::candles(&info, &coin, interval, start, end).await?;
+            println!("   {} candles", candles.len());
+            if let (Some(first), Some(last)) = (candles.first(), candles.last()) {
+                println!("   First open {} close {}, last open {} close {}", first.open_time, first.close, last.open_time, last.close);
+            }
+            let path = args.out.clone().unwrap_or_else(|| format!("{}_{}.parquet", coin, interval).into());
+            let bytes = export::write_candles(&path, &candles)?;
+            (path, candle_schema(), candles.len(), bytes)
+        }
+        Dataset::Fills { user, since, until } => {
+            let user = match user {
+                Some(user) => user.clone(),
+                None => sdk
+                    .address()
+                    .map(|a| format!("{:?}", a))
+                    .ok_or_else(|| Error::ConfigError("fills needs --user or PRIVATE_KEY".to_string()))?,
+            };
+            let (start, end) = (since.start_ms(now), until.map_or(now, |u| u.start_ms(now)));
+            println!("\n1. Fetching fills of {} ({} - {} ms)...", user, start, end);
+            let fills = export::user_fills(&info, &user, start, end).await?;
+            let fees: rust_decimal::Decimal = fills.iter().map(|f| f.fee).sum();
+            println!("   {} fills, {} in fees", fills.len(), fees);
+            let path = args.out.clone().unwrap_or_else(|| "fills.parquet".into());
+            let bytes = export::write_fills(&path, &fills)?;
+            (path, fill_schema(), fills.len(), bytes)
+        }
+        Dataset::Trades { coin, blocks } => {
+            let coin = match coin {
+                Some(coin) => Some(AssetRegistry::load(&sdk).await?.resolve(coin)?.symbol.clone()),
+                None => None,
+            };
+            println!("\n1. Fetching trades of the last {} blocks ({})...", blocks, coin.as_deref().unwrap_or("all coins"));
+            let trades = export::latest_trades(&sdk.core(), *blocks, coin.as_deref()).await?;
+            println!("   {} trade sides", trades.len());
+            let path = args.out.clone().unwrap_or_else(|| "trades.parquet".into());
+            let bytes = export::write_fills(&path, &trades)?;
+            (path, fill_schema(), trades.len(), bytes)
+        }
+    };
+
+    println!("\n2. Wrote {}:", path.display());
+    println!("   {} rows, {} bytes", rows, bytes);
+
+    println!("\n3. Schema:");
+    for field in schema.fields() {
+        let null = if field.is_nullable() { " (nullable)" } else { "" };
+        println!("   {:<16} {}{}", field.name(), field.data_type(), null);
+    }
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
+
@@ -7,43 +7,39 @@
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
 //! cargo run --example fluent_builder
+//! cargo run --example fluent_builder -- --paper   # simulated fills, nothing sent
 //! ```
 
-use hyperliquid_sdk::{HyperliquidSDK, Order, TriggerOrder};
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, paper, AssetRegistry, Requires, Rounding};
+use hyperliquid_sdk::{Order, TriggerOrder};
+use rust_decimal::Decimal;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
-
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example fluent_builder");
-        std::process::exit(1);
-    }
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("fluent_builder", Requires::Signer).await?;
 
     println!("Fluent Order Builder Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
-    // Get current price
-    let mid = sdk.get_mid("BTC").await?;
+    // Validate BTC and get its tick/lot limits, then the current price
+    let btc = AssetRegistry::load(&sdk).await?.precision("BTC")?;
+    let mid = TypedInfo::from_sdk(&sdk).mid("BTC").await?;
+    let size = btc.sz(Decimal::new(1, 3), Rounding::Down)?;
     println!("\nBTC mid price: ${:.2}", mid);
 
+    // Percentage offsets from the mid; resting prices round away from it
+    let pct = |p: i64| btc.px_away_from(mid * Decimal::new(p, 2), mid);
+
     // ══════════════════════════════════════════════════════════════════════════
     // Order Builder
     // ══════════════════════════════════════════════════════════════════════════
@@ -55,24 +51,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // GTC Limit Buy
     println!("\n1. GTC Limit Buy:");
     let order = Order::buy("BTC")
-        .size(0.001)
-        .price(mid * 0.97)
+        .size_decimal(size.value())
+        .price(pct(97)?.to_f64())
         .gtc();
     println!("   {:?}", order);
 
     // IOC Limit Sell
     println!("\n2. IOC Limit Sell:");
     let order = Order::sell("BTC")
-        .size(0.001)
-        .price(mid * 1.03)
+        .size_decimal(size.value())
+        .price(pct(103)?.to_f64())
         .ioc();
     println!("   {:?}", order);
 
     // ALO (Post-Only) Buy
     println!("\n3. ALO (Post-Only) Buy:");
     let order = Order::buy("BTC")
-        .size(0.001)
-        .price(mid * 0.95)
+        .size_decimal(size.value())
+        .price(pct(95)?.to_f64())
         .alo();
     println!("   {:?}", order);
 
@@ -86,8 +82,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Reduce-Only Order
     println!("\n5. Reduce-Only Sell:");
     let order = Order::sell("BTC")
-        .size(0.001)
-        .price(mid * 1.05)
+        .size_decimal(size.value())
+        .price(pct(105)?.to_f64())
         .gtc()
         .reduce_only();
     println!("   {:?}", order);
@@ -103,23 +99,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Stop Loss
     println!("\n6. Stop Loss:");
     let trigger = TriggerOrder::stop_loss("BTC")
-        .size(0.001)
-        .trigger_price(mid * 0.95);
+        .size(size.to_f64())
+        .trigger_price(pct(95)?.to_f64());
     println!("   {:?}", trigger);
 
     // Take Profit
     println!("\n7. Take Profit:");
     let trigger = TriggerOrder::take_profit("BTC")
-        .size(0.001)
-        .trigger_price(mid * 1.10);
+        .size(size.to_f64())
+        .trigger_price(pct(110)?.to_f64());
     println!("   {:?}", trigger);
 
     // Stop Loss with Limit
     println!("\n8. Stop Loss with Limit:");
     let trigger = TriggerOrder::stop_loss("BTC")
-        .size(0.001)
-        .trigger_price(mid * 0.95)
-        .limit(mid * 0.94);
+        .size(size.to_f64())
+        .trigger_price(pct(95)?.to_f64())
+        .limit(pct(94)?.to_f64());
     println!("   {:?}", trigger);
 
     // ══════════════════════════════════════════════════════════════════════════
@@ -131,8 +127,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "─".repeat(50));
 
     let order = Order::buy("BTC")
-        .size(0.001)
-        .price(mid * 0.97)
+        .size_decimal(size.value())
+        .price(pct(97)?.to_f64())
         .gtc();
 
     println!("\n9. Placing order...");
@@ -152,6 +148,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => println!("   Error: {}", e),
     }
 
+    paper::report();
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
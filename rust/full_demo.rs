@@ -15,7 +15,10 @@
 //! cargo run --example full_demo
 //! ```
 
+use hyperliquid_examples::models::{decode, stream_items, Fill, OrderUpdate, Trade, TypedInfo};
+use hyperliquid_examples::{Config, Requires};
 use hyperliquid_sdk::{HyperliquidSDK, Order};
+use rust_decimal::Decimal;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -35,95 +38,48 @@ fn subsection(title: &str) {
 async fn demo_info_api(sdk: &HyperliquidSDK) -> Result<(), Box<dyn std::error::Error>> {
     separator("INFO API");
 
-    let info = sdk.info();
+    let info = TypedInfo::from_sdk(sdk);
 
     subsection("Market Prices");
     let mids = info.all_mids(None).await?;
-    if let Some(obj) = mids.as_object() {
-        println!("Total markets: {}", obj.len());
-        for coin in &["BTC", "ETH", "SOL", "DOGE"] {
-            if let Some(price) = mids.get(*coin).and_then(|v| v.as_str()) {
-                let p: f64 = price.parse().unwrap_or(0.0);
-                println!("  {}: ${:.2}", coin, p);
-            }
+    println!("Total markets: {}", mids.len());
+    for coin in &["BTC", "ETH", "SOL", "DOGE"] {
+        if let Some(price) = mids.get(*coin) {
+            println!("  {}: ${:.2}", coin, price);
         }
     }
 
     subsection("Order Book");
-    let book = info.l2_book("BTC", None, None).await?;
-    if let Some(levels) = book.get("levels").and_then(|l| l.as_array()) {
-        let bids = levels.get(0).and_then(|b| b.as_array());
-        let asks = levels.get(1).and_then(|a| a.as_array());
-
-        if let (Some(bids), Some(asks)) = (bids, asks) {
-            if let (Some(best_bid), Some(best_ask)) = (bids.first(), asks.first()) {
-                let bid_px: f64 = best_bid
-                    .get("px")
-                    .and_then(|p| p.as_str())
-                    .unwrap_or("0")
-                    .parse()
-                    .unwrap_or(0.0);
-                let bid_sz = best_bid.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-                let ask_px: f64 = best_ask
-                    .get("px")
-                    .and_then(|p| p.as_str())
-                    .unwrap_or("0")
-                    .parse()
-                    .unwrap_or(0.0);
-                let ask_sz = best_ask.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-                let spread = ask_px - bid_px;
-
-                println!("  Best Bid: {} @ ${:.2}", bid_sz, bid_px);
-                println!("  Best Ask: {} @ ${:.2}", ask_sz, ask_px);
-                println!("  Spread: ${:.2}", spread);
-            }
-        }
+    let book = info.l2_book("BTC").await?;
+    if let (Some(best_bid), Some(best_ask)) = (book.best_bid(), book.best_ask()) {
+        println!("  Best Bid: {} @ ${:.2}", best_bid.sz, best_bid.px);
+        println!("  Best Ask: {} @ ${:.2}", best_ask.sz, best_ask.px);
+        println!("  Spread: ${:.2}", best_ask.px - best_bid.px);
     }
 
     subsection("Recent Trades");
     let trades = info.recent_trades("ETH").await?;
-    if let Some(trades_arr) = trades.as_array() {
-        println!("Last 3 ETH trades:");
-        for t in trades_arr.iter().take(3) {
-            let sz = t.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-            let px: f64 = t
-                .get("px")
-                .and_then(|p| p.as_str())
-                .unwrap_or("0")
-                .parse()
-                .unwrap_or(0.0);
-            let side = t.get("side").and_then(|s| s.as_str()).unwrap_or("?");
-            println!("  {} @ ${:.2} ({})", sz, px, side);
-        }
+    println!("Last 3 ETH trades:");
+    for t in trades.iter().take(3) {
+        println!("  {} @ ${:.2} ({})", t.sz, t.px, t.side);
     }
 
     subsection("Exchange Metadata");
     let meta = info.meta().await?;
-    if let Some(universe) = meta.get("universe").and_then(|u| u.as_array()) {
-        println!("Total perp markets: {}", universe.len());
-    }
+    println!("Total perp markets: {}", meta.universe.len());
 
     subsection("Predicted Funding");
     let fundings = info.predicted_fundings().await?;
-    if let Some(fundings_arr) = fundings.as_array() {
-        // Sort by absolute funding rate
-        let mut sorted: Vec<_> = fundings_arr
-            .iter()
-            .filter_map(|f| {
-                let coin = f.get("coin").and_then(|c| c.as_str())?;
-                let rate: f64 = f
-                    .get("fundingRate")
-                    .and_then(|r| r.as_str())
-                    .and_then(|s| s.parse().ok())?;
-                Some((coin, rate))
-            })
-            .collect();
-        sorted.sort_by(|a, b| b.1.abs().partial_cmp(&a.1.abs()).unwrap());
-
-        println!("Top 3 funding rates:");
-        for (coin, rate) in sorted.iter().take(3) {
-            println!("  {}: {:+.4}% (8h)", coin, rate * 100.0);
-        }
+    // Sort by absolute funding rate
+    let mut sorted: Vec<_> = fundings
+        .iter()
+        .filter_map(|f| Some((f.coin(), f.hyperliquid()?.funding_rate)))
+        .collect();
+    sorted.sort_by_key(|(_, rate)| std::cmp::Reverse(rate.abs()));
+
+    println!("Top 3 funding rates:");
+    for (coin, rate) in sorted.iter().take(3) {
+        println!("  {}: {:+.4}% (1h)", coin, rate * Decimal::ONE_HUNDRED);
     }
 
     Ok(())
@@ -144,39 +100,22 @@ async fn demo_hypercore_api(sdk: &HyperliquidSDK) -> Result<(), Box<dyn std::err
     }
 
     subsection("Recent Trades");
-    let trades = hc.latest_trades(Some(5), None).await?;
-    if let Some(trades_arr) = trades.as_array() {
-        println!("Last 5 trades across all markets:");
-        for t in trades_arr {
-            let coin = t.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-            let sz = t.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-            let px: f64 = t
-                .get("px")
-                .and_then(|p| p.as_str())
-                .unwrap_or("0")
-                .parse()
-                .unwrap_or(0.0);
-            println!("  {}: {} @ ${:.2}", coin, sz, px);
-        }
+    let trades: Vec<Fill> = decode(hc.latest_trades(Some(5), None).await?)?;
+    println!("Last 5 trades across all markets:");
+    for t in &trades {
+        println!("  {}: {} @ ${:.2}", t.coin, t.sz, t.px);
     }
 
     subsection("Recent Orders");
-    match hc.latest_orders(Some(5)).await {
+    let orders = match hc.latest_orders(Some(5)).await {
+        Ok(value) => decode::<Vec<OrderUpdate>>(value),
+        Err(e) => Err(e.into()),
+    };
+    match orders {
         Ok(orders) => {
-            if let Some(orders_arr) = orders.as_array() {
-                println!("Last 5 orders:");
-                for o in orders_arr {
-                    let coin = o.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-                    let side = o.get("side").and_then(|s| s.as_str()).unwrap_or("?");
-                    let status = o.get("status").and_then(|s| s.as_str()).unwrap_or("?");
-                    let px: f64 = o
-                        .get("limitPx")
-                        .and_then(|p| p.as_str())
-                        .unwrap_or("0")
-                        .parse()
-                        .unwrap_or(0.0);
-                    println!("  {}: {} @ ${:.2} - {}", coin, side, px, status);
-                }
+            println!("Last 5 orders:");
+            for o in &orders {
+                println!("  {}: {} @ ${:.2} - {}", o.order.coin, o.order.side, o.order.limit_px, o.status);
             }
         }
         Err(e) => {
@@ -243,13 +182,14 @@ async fn demo_websocket(sdk: &HyperliquidSDK, duration: u64) {
     stream.trades(&["BTC", "ETH"], move |data| {
         let count = trade_count_cb.fetch_add(1, Ordering::SeqCst);
         if count < 3 {
-            if let Some(trades) = data.get("data").and_then(|d| d.as_array()) {
-                for trade in trades.iter().take(1) {
-                    let coin = trade.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-                    let sz = trade.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-                    let px = trade.get("px").and_then(|p| p.as_str()).unwrap_or("?");
-                    println!("  [TRADE] {}: {} @ {}", coin, sz, px);
+            match stream_items::<Trade>(&data, "trades") {
+                Some(Ok(trades)) => {
+                    for trade in trades.iter().take(1) {
+                        println!("  [TRADE] {}: {} @ {}", trade.coin, trade.sz, trade.px);
+                    }
                 }
+                Some(Err(e)) => println!("  [ERROR] bad trade payload: {}", e),
+                None => {}
             }
         }
     });
@@ -329,8 +269,13 @@ async fn demo_trading(sdk: &HyperliquidSDK) {
     println!("  Close pos:  sdk.close_position(\"BTC\").await");
 }
 
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt::init();
 
     println!();
@@ -338,35 +283,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  HYPERLIQUID SDK - FULL DEMO");
     println!("{}", "*".repeat(60));
 
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
-
-    if endpoint.is_none() {
-        println!();
-        println!("Error: ENDPOINT not set");
-        println!();
-        println!("Usage:");
-        println!("  export ENDPOINT='https://your-endpoint/TOKEN'");
-        println!("  cargo run --example full_demo");
-        std::process::exit(1);
-    }
+    let config = Config::load()?.require("full_demo", Requires::Endpoint);
 
     println!();
-    if let Some(ref ep) = endpoint {
-        let display_len = ep.len().min(50);
-        println!("Endpoint: {}...", &ep[..display_len]);
-    }
+    println!("Endpoint: {}", config.redacted_endpoint());
+    println!("Network: {}", config.network);
 
     // Build SDK
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ref ep) = endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(ref pk) = private_key {
-        builder = builder.private_key(pk);
-    }
-
-    let sdk = builder.build().await?;
+    let sdk = config.connect().await?;
 
     // Run all demos using the same SDK instance
     demo_info_api(&sdk).await?;
@@ -375,7 +299,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     demo_websocket(&sdk, 5).await;
     demo_grpc(&sdk, 5).await;
 
-    if private_key.is_some() {
+    if config.private_key.is_some() {
         demo_trading(&sdk).await;
     } else {
         println!();
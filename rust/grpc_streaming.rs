@@ -14,40 +14,58 @@
 //! cargo run --example grpc_streaming
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::MarketEvent;
+use hyperliquid_examples::{stream_duration, Config, Requires};
+use serde_json::Value;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+
+/// Print the trades and book changes of a message. Trades and book updates
+/// arrive in different shapes over WebSocket and gRPC; [`MarketEvent`] gives
+/// both the same fields, so this is the same function as in `websocket_streaming`.
+fn print_events(data: &Value) {
+    let events = match MarketEvent::parse(data) {
+        Ok(events) => events,
+        Err(e) => return println!("[ERROR] bad payload: {}", e),
+    };
+    for event in events {
+        match event {
+            MarketEvent::Trade(trade) => {
+                let side_name = if trade.side.is_buy() { "BUY" } else { "SELL" };
+                println!("[TRADE] {}: {} {} @ ${:.2}", trade.coin, side_name, trade.sz, trade.px);
+            }
+            MarketEvent::BookDelta(update) => {
+                if let (Some(bid), Some(ask)) = (update.bids.first(), update.asks.first()) {
+                    println!("[BOOK] {}: Bid ${:.2} | Ask ${:.2} | Spread ${:.2}", update.coin, bid.px, ask.px, ask.px - bid.px);
+                }
+            }
+            MarketEvent::BookSnapshot(book) => {
+                println!("[L2] {}: {} bid levels, {} ask levels", book.coin, book.bids().len(), book.asks().len());
+            }
+            MarketEvent::Block(block) => println!("[BLOCK] #{}", block.number),
+            _ => {}
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt::init();
 
-    let endpoint = std::env::var("ENDPOINT").ok();
-
-    if endpoint.is_none() {
-        println!("Hyperliquid gRPC Streaming Example");
-        println!("{}", "=".repeat(50));
-        println!();
-        println!("Usage:");
-        println!("  export ENDPOINT='https://YOUR-ENDPOINT/TOKEN'");
-        println!("  cargo run --example grpc_streaming");
-        std::process::exit(1);
-    }
+    let config = Config::load()?.require("grpc_streaming", Requires::Endpoint);
 
     println!("Hyperliquid gRPC Streaming Example");
     println!("{}", "=".repeat(50));
-    if let Some(ref ep) = endpoint {
-        let display_len = ep.len().min(60);
-        println!("Endpoint: {}...", &ep[..display_len]);
-    }
+    println!("Endpoint: {}", config.redacted_endpoint());
     println!();
 
     // Create SDK
-    let sdk = HyperliquidSDK::new()
-        .endpoint(endpoint.as_ref().unwrap())
-        .build()
-        .await?;
+    let sdk = config.connect().await?;
 
     // Create counters
     let trade_count = Arc::new(AtomicUsize::new(0));
@@ -82,18 +100,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Subscribing to BTC and ETH trades...");
     let _trade_sub = stream.trades(&["BTC", "ETH"], move |data| {
         trade_count_cb.fetch_add(1, Ordering::SeqCst);
-
-        let coin = data.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-        let px = data
-            .get("px")
-            .and_then(|p| p.as_str())
-            .unwrap_or("0")
-            .parse::<f64>()
-            .unwrap_or(0.0);
-        let sz = data.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-        let side = data.get("side").and_then(|s| s.as_str()).unwrap_or("?");
-        let side_name = if side == "B" { "BUY" } else { "SELL" };
-        println!("[TRADE] {}: {} {} @ ${:.2}", coin, side_name, sz, px);
+        print_events(&data);
     });
 
     // ─────────────────────────────────────────────────────────────────────────
@@ -103,30 +110,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Subscribing to BTC book updates...");
     let _book_sub = stream.book_updates(&["BTC"], move |data| {
         book_count_cb.fetch_add(1, Ordering::SeqCst);
-
-        let coin = data.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-        let bids = data.get("bids").and_then(|b| b.as_array());
-        let asks = data.get("asks").and_then(|a| a.as_array());
-
-        if let (Some(bids), Some(asks)) = (bids, asks) {
-            if let (Some(best_bid), Some(best_ask)) = (bids.first(), asks.first()) {
-                let bid_px = best_bid
-                    .get("price")
-                    .and_then(|p| p.as_str())
-                    .or_else(|| best_bid.get("price").and_then(|p| p.as_f64()).map(|_| "0"))
-                    .unwrap_or("0")
-                    .parse::<f64>()
-                    .unwrap_or(0.0);
-                let ask_px = best_ask
-                    .get("price")
-                    .and_then(|p| p.as_str())
-                    .or_else(|| best_ask.get("price").and_then(|p| p.as_f64()).map(|_| "0"))
-                    .unwrap_or("0")
-                    .parse::<f64>()
-                    .unwrap_or(0.0);
-                println!("[BOOK] {}: Bid ${:.2} | Ask ${:.2}", coin, bid_px, ask_px);
-            }
-        }
+        print_events(&data);
     });
 
     // ─────────────────────────────────────────────────────────────────────────
@@ -134,14 +118,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // ─────────────────────────────────────────────────────────────────────────
 
     println!("Subscribing to ETH L2 order book...");
-    let _l2_sub = stream.l2_book("ETH", move |data| {
-        let coin = data.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-        let bids = data.get("bids").and_then(|b| b.as_array());
-        let asks = data.get("asks").and_then(|a| a.as_array());
-        let bid_levels = bids.map(|b| b.len()).unwrap_or(0);
-        let ask_levels = asks.map(|a| a.len()).unwrap_or(0);
-        println!("[L2] {}: {} bid levels, {} ask levels", coin, bid_levels, ask_levels);
-    });
+    let _l2_sub = stream.l2_book("ETH", |data| print_events(&data));
 
     // ─────────────────────────────────────────────────────────────────────────
     // Subscribe to Blocks
@@ -150,12 +127,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Subscribing to blocks...");
     let _block_sub = stream.blocks(move |data| {
         block_count_cb.fetch_add(1, Ordering::SeqCst);
-
-        let block_num = data
-            .get("block_number")
-            .and_then(|b| b.as_u64())
-            .unwrap_or(0);
-        println!("[BLOCK] #{}", block_num);
+        print_events(&data);
     });
 
     // ─────────────────────────────────────────────────────────────────────────
@@ -163,13 +135,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // ─────────────────────────────────────────────────────────────────────────
 
     println!();
-    println!("Streaming via gRPC... (will run for 30 seconds)");
+    let duration = stream_duration(30);
+    println!("Streaming via gRPC... (will run for {} seconds)", duration.as_secs());
     println!("{}", "-".repeat(50));
 
     stream.start()?;
 
-    // Run for 30 seconds
-    tokio::time::sleep(Duration::from_secs(30)).await;
+    // Run for 30 seconds (HL_STREAM_SECONDS overrides)
+    tokio::time::sleep(duration).await;
 
     // Stop the stream
     stream.stop();
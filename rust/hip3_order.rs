@@ -8,54 +8,37 @@
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
 //! cargo run --example hip3_order
+//! cargo run --example hip3_order -- --paper   # simulated fills, nothing sent
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::{connect, paper, AssetRegistry, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example hip3_order");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("hip3_order", Requires::Signer).await?;
 
     println!("HIP-3 Market Order Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
-    // List HIP-3 DEXes
+    // List HIP-3 DEXes and their markets
     println!("\n1. Available HIP-3 DEXes:");
-    match sdk.dexes().await {
-        Ok(dexes) => {
-            if let Some(arr) = dexes.get("perpDexs").and_then(|v| v.as_array()) {
-                for (i, dex) in arr.iter().take(5).enumerate() {
-                    let name = dex.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] {}", i + 1, name);
-                }
-                if arr.len() > 5 {
-                    println!("   ... and {} more", arr.len() - 5);
-                }
-            }
-        }
-        Err(e) => println!("   Error: {}", e),
+    let registry = AssetRegistry::load(&sdk).await?;
+    let dexes = registry.dexes();
+    for (i, dex) in dexes.iter().take(5).enumerate() {
+        let markets: Vec<&str> = registry.hip3(Some(dex)).map(|m| m.symbol.as_str()).take(3).collect();
+        println!("   [{}] {} ({})", i + 1, dex, markets.join(", "));
+    }
+    if dexes.len() > 5 {
+        println!("   ... and {} more", dexes.len() - 5);
     }
 
     // HIP-3 market format
@@ -65,6 +48,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Trade on HIP-3 market
     println!("\n3. Trade on HIP-3 Market:");
+    match registry.resolve("xyz:SILVER") {
+        Ok(market) => println!("   xyz:SILVER is asset {} ({} size decimals)", market.asset_id, market.sz_decimals),
+        Err(e) => println!("   Error: {}", e),
+    }
     println!("   sdk.market_buy(\"xyz:SILVER\").notional(11.0)");
 
     // Uncomment to execute
@@ -83,6 +70,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("  - Use 'dex:SYMBOL' format");
     println!("  - Check liquidity before trading");
 
+    paper::report();
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
@@ -0,0 +1,36 @@
+//! hl — Hyperliquid from the command line
+//!
+//! One binary for the operations the other examples show one file at a time,
+//! with the coin, size and price on the command line. See `src/cli/mod.rs`
+//! for the subcommands, `--json` output and exit codes.
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
+//! export PRIVATE_KEY="0x..."
+//! cargo run --bin hl -- order buy BTC 0.01 --px 60000 --tif alo
+//! cargo run --bin hl -- cancel --cloid 0x0123456789abcdef0123456789abcdef
+//! cargo run --bin hl -- book ETH --depth 20
+//! cargo run --bin hl -- candles BTC 1h --since 24h --json
+//! cargo run --bin hl -- withdraw 100 --to 0x... --dry-run
+//! ```
+
+use std::process::ExitCode;
+
+use clap::Parser;
+use hyperliquid_examples::cli::{self, Cli};
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    // Before the runtime starts (see hyperliquid_examples::prepare); the
+    // command reports a config that fails to load
+    if let Ok(config) = cli.global.config() {
+        config.prepare();
+    }
+    run(cli)
+}
+
+#[tokio::main]
+async fn run(cli: Cli) -> ExitCode {
+    ExitCode::from(cli::run(cli).await.code())
+}
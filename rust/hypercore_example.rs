@@ -8,23 +8,25 @@
 //! cargo run --example hypercore_example
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::{decode, Fill, OrderUpdate};
+use hyperliquid_examples::{Config, Requires};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").unwrap_or_else(|_| {
-        eprintln!("Error: Set ENDPOINT environment variable");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        std::process::exit(1);
-    });
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("hypercore_example", Requires::Endpoint);
 
     println!("Hyperliquid HyperCore API Example");
     println!("{}", "=".repeat(50));
-    println!("Endpoint: {}...", &endpoint[..endpoint.len().min(50)]);
+    println!("Endpoint: {}", config.redacted_endpoint());
     println!();
 
     // Create SDK and get HyperCore client
-    let sdk = HyperliquidSDK::new().endpoint(&endpoint).build().await?;
+    let sdk = config.connect().await?;
     let hc = sdk.core();
 
     // ══════════════════════════════════════════════════════════════════════════
@@ -55,31 +57,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "-".repeat(30));
 
     // Get latest trades (all coins)
-    let trades = hc.latest_trades(Some(5), None).await?;
-    if let Some(trades_arr) = trades.as_array() {
-        println!("Last {} trades:", trades_arr.len());
-        for trade in trades_arr.iter().take(5) {
-            let coin = trade.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-            let px = trade.get("px").and_then(|p| p.as_str()).unwrap_or("0");
-            let sz = trade.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-            let side = trade.get("side").and_then(|s| s.as_str()).unwrap_or("?");
-            let price: f64 = px.parse().unwrap_or(0.0);
-            println!("  {}: {} @ ${:.2} ({})", coin, sz, price, side);
-        }
+    let trades: Vec<Fill> = decode(hc.latest_trades(Some(5), None).await?)?;
+    println!("Last {} trades:", trades.len());
+    for trade in trades.iter().take(5) {
+        println!("  {}: {} @ ${:.2} ({})", trade.coin, trade.sz, trade.px, trade.side);
     }
     println!();
 
     // Get trades for specific coin
-    let btc_trades = hc.latest_trades(Some(3), Some("BTC")).await?;
-    if let Some(trades_arr) = btc_trades.as_array() {
-        println!("Last {} BTC trades:", trades_arr.len());
-        for trade in trades_arr {
-            let px = trade.get("px").and_then(|p| p.as_str()).unwrap_or("0");
-            let sz = trade.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-            let side = trade.get("side").and_then(|s| s.as_str()).unwrap_or("?");
-            let price: f64 = px.parse().unwrap_or(0.0);
-            println!("  {} @ ${:.2} ({})", sz, price, side);
-        }
+    let btc_trades: Vec<Fill> = decode(hc.latest_trades(Some(3), Some("BTC")).await?)?;
+    println!("Last {} BTC trades:", btc_trades.len());
+    for trade in &btc_trades {
+        println!("  {} @ ${:.2} ({})", trade.sz, trade.px, trade.side);
     }
     println!();
 
@@ -89,19 +78,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Recent Orders");
     println!("{}", "-".repeat(30));
 
-    match hc.latest_orders(Some(5)).await {
+    let orders = match hc.latest_orders(Some(5)).await {
+        Ok(value) => decode::<Vec<OrderUpdate>>(value),
+        Err(e) => Err(e.into()),
+    };
+    match orders {
         Ok(orders) => {
-            if let Some(orders_arr) = orders.as_array() {
-                println!("Last {} orders:", orders_arr.len());
-                for order in orders_arr.iter().take(5) {
-                    let coin = order.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-                    let side = order.get("side").and_then(|s| s.as_str()).unwrap_or("?");
-                    let px = order.get("limitPx").and_then(|p| p.as_str()).unwrap_or("0");
-                    let sz = order.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-                    let status = order.get("status").and_then(|s| s.as_str()).unwrap_or("?");
-                    let price: f64 = px.parse().unwrap_or(0.0);
-                    println!("  {}: {} {} @ ${:.2} - {}", coin, side, sz, price, status);
-                }
+            println!("Last {} orders:", orders.len());
+            for update in orders.iter().take(5) {
+                let o = &update.order;
+                println!("  {}: {} {} @ ${:.2} - {}", o.coin, o.side, o.sz, o.limit_px, update.status);
             }
         }
         Err(e) => {
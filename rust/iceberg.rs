@@ -0,0 +1,218 @@
+//! Iceberg Order Example
+//!
+//! Work a large resting order without showing its size
+//! (`hyperliquid_examples::iceberg`): only a clip of it rests on the book,
+//! and the next clip is placed when one fills. Clip sizes and prices can be
+//! randomized. Fills come from the `userFills` stream, with
+//! `sdk.order_status` polled in case the stream misses one. Ctrl-C cancels
+//! the resting clip.
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
+//! export PRIVATE_KEY="0x..."
+//! cargo run --bin iceberg -- buy BTC 1 --px 95000 --clip 0.05
+//! cargo run --bin iceberg -- sell ETH 20 --px 3700 --clip 1 --clip-variance 25 --price-offset 3
+//! cargo run --bin iceberg -- buy BTC 1 --px 95000 --clip 0.05 --tif gtc --poll 2
+//! cargo run --bin iceberg -- buy BTC 1 --px 95000 --clip 0.05 --dry-run   # sign and print, nothing sent
+//! ```
+
+use std::time::Duration;
+
+use clap::Parser;
+use hyperliquid_examples::cli::OrderSide;
+use hyperliquid_examples::dry_run;
+use hyperliquid_examples::iceberg::{Iceberg, IcebergOrder};
+use hyperliquid_examples::{paper, stream_duration, AssetRegistry, Config, Requires};
+use hyperliquid_sdk::{HyperliquidSDK, Order};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Parser)]
+#[command(name = "iceberg", about = "Rest a large order a clip at a time")]
+struct Args {
+    side: OrderSide,
+    /// Symbol, e.g. BTC, xyz:XYZ100, PURR/USDC
+    coin: String,
+    /// Total size in the base asset (rounded down to the lot)
+    size: Decimal,
+    /// Limit price
+    #[arg(long)]
+    px: Decimal,
+    /// Size shown at a time
+    #[arg(long)]
+    clip: Decimal,
+    /// Vary each clip's size randomly by up to this percentage
+    #[arg(long, default_value = "0")]
+    clip_variance: Decimal,
+    /// Rest each clip up to this many ticks behind the limit, at random
+    #[arg(long, default_value_t = 0)]
+    price_offset: u32,
+    /// `alo` (post-only) or `gtc`
+    #[arg(long, value_parser = ["alo", "gtc"], default_value = "alo")]
+    tif: String,
+    /// Seconds between two `order_status` checks of the resting clip
+    #[arg(long, default_value_t = 5.0)]
+    poll: f64,
+    /// Seed of the randomization
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("iceberg", Requires::Signer);
+    let args = Args::parse_from(std::iter::once("iceberg".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+
+    println!("Iceberg Order Example");
+    println!("{}", "=".repeat(50));
+
+    let user = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
+    println!("Address: {}", user);
+
+    let order = match args.side {
+        OrderSide::Buy => Order::buy(&args.coin),
+        OrderSide::Sell => Order::sell(&args.coin),
+    }
+    .size_decimal(args.size);
+    let order = match args.tif.as_str() {
+        "gtc" => order.gtc(),
+        _ => order.alo(),
+    };
+    // The price is rounded onto the tick when the iceberg is built
+    let mut iceberg = Iceberg::new(order.price(args.px.to_f64().unwrap_or_default()), args.clip)
+        .clip_variance(args.clip_variance / Decimal::ONE_HUNDRED)
+        .price_offset(args.price_offset);
+    if let Some(seed) = args.seed {
+        iceberg = iceberg.seed(seed);
+    }
+
+    println!("\n1. Placing:");
+    if config.dry_run {
+        let registry = AssetRegistry::load(&sdk).await?;
+        let mut order = iceberg.build(registry.resolve(&args.coin)?)?;
+        print_iceberg(&order);
+        if let Some(clip) = order.next_clip() {
+            println!("   {}", clip);
+            dry_run::show(&config, &order.clip_action(&clip)).await;
+        }
+    } else {
+        match iceberg.place(&config, &sdk).await {
+            Ok(order) => work(&config, &sdk, &user, Duration::from_secs_f64(args.poll.max(0.05)), order).await?,
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
+
+    paper::report();
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
+
+/// Replenish `order` as its clips fill, until it is done or Ctrl-C
+async fn work(
+    config: &Config,
+    sdk: &HyperliquidSDK,
+    user: &str,
+    poll: Duration,
+    mut order: IcebergOrder,
+) -> Result<(), Box<dyn std::error::Error>> {
+    print_iceberg(&order);
+    for clip in &order.clips {
+        println!("   {}", clip);
+    }
+
+    println!("\n2. Working (order status every {:?}):", poll);
+    let mut stream = sdk.stream().on_error(|e| eprintln!("   [Error] {}", e));
+    let mut events = order.watch(&mut stream, user);
+    stream.start()?;
+
+    let deadline = async {
+        // Until Ctrl-C, or for HL_STREAM_SECONDS
+        match std::env::var_os("HL_STREAM_SECONDS") {
+            Some(_) => tokio::time::sleep(stream_duration(0)).await,
+            None => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    };
+    tokio::pin!(deadline);
+    let mut ticker = tokio::time::interval(poll);
+    while order.is_working() {
+        let changed = tokio::select! {
+            _ = &mut deadline => break,
+            event = events.recv() => {
+                match event {
+                    Some(Ok(event)) => order.on_event(&event),
+                    Some(Err(e)) => {
+                        println!("   Error: {}", e);
+                        false
+                    }
+                    None => break,
+                }
+            }
+            _ = ticker.tick() => match order.sync(sdk).await {
+                Ok(changed) => changed,
+                Err(e) => {
+                    println!("   Error: {}", e);
+                    false
+                }
+            },
+        };
+        if changed {
+            println!("   {}", order.progress());
+        }
+        match order.replenish(config).await {
+            Ok(Some(clip)) => println!("   {}", clip),
+            Ok(None) => {}
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
+    stream.stop();
+
+    let mut section = 3;
+    if order.is_working() {
+        println!("\n3. Cancelling the resting clip:");
+        match order.cancel(config).await {
+            Ok(()) => println!("   Cancelled"),
+            Err(e) => println!("   Error: {}", e),
+        }
+        section = 4;
+    }
+
+    println!("\n{}. Final state:", section);
+    println!("   {}", order.progress());
+    for clip in &order.clips {
+        println!("   {}", clip);
+    }
+    Ok(())
+}
+
+fn print_iceberg(order: &IcebergOrder) {
+    println!(
+        "   {} {} {} at {}, clips of {}{}{}",
+        if order.is_buy { "Buy" } else { "Sell" },
+        order.size,
+        order.coin,
+        order.px,
+        order.clip,
+        if order.clip_variance.is_zero() {
+            String::new()
+        } else {
+            format!(" ±{}%", (order.clip_variance * Decimal::ONE_HUNDRED).normalize())
+        },
+        if order.price_offset == 0 {
+            String::new()
+        } else {
+            format!(", up to {} ticks behind", order.price_offset)
+        }
+    );
+}
@@ -8,28 +8,22 @@
 //! cargo run --example info_batch_queries
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  cargo run --example info_batch_queries");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("info_batch_queries", Requires::Endpoint).await?;
 
     println!("Info API Batch Queries Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    let sdk = builder.build().await?;
-    let info = sdk.info();
+    let info = TypedInfo::from_sdk(&sdk);
 
     // Get all mid prices
     println!("\n1. All Mid Prices:");
@@ -37,7 +31,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Ok(mids) => {
             let assets = ["BTC", "ETH", "SOL", "DOGE", "ARB"];
             for asset in &assets {
-                if let Some(mid) = mids.get(*asset).and_then(|v| v.as_str()) {
+                if let Some(mid) = mids.get(*asset) {
                     println!("   {}: ${}", asset, mid);
                 }
             }
@@ -48,40 +42,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Get exchange metadata
     println!("\n2. Exchange Metadata:");
     match info.meta().await {
-        Ok(meta) => {
-            if let Some(universe) = meta.get("universe").and_then(|v| v.as_array()) {
-                println!("   Perp markets: {}", universe.len());
-            }
-        }
+        Ok(meta) => println!("   Perp markets: {}", meta.universe.len()),
         Err(e) => println!("   Error: {}", e),
     }
 
     match info.spot_meta().await {
-        Ok(spot) => {
-            if let Some(tokens) = spot.get("tokens").and_then(|v| v.as_array()) {
-                println!("   Spot tokens: {}", tokens.len());
-            }
-        }
+        Ok(spot) => println!("   Spot tokens: {}", spot.tokens.len()),
         Err(e) => println!("   Error: {}", e),
     }
 
     // Get L2 orderbook for multiple assets
     println!("\n3. Orderbook Spreads:");
     for asset in &["BTC", "ETH", "SOL"] {
-        match info.l2_book(asset, None, None).await {
+        match info.l2_book(asset).await {
             Ok(book) => {
-                if let Some(levels) = book.get("levels").and_then(|v| v.as_array()) {
-                    if levels.len() >= 2 {
-                        let bids = levels[0].as_array();
-                        let asks = levels[1].as_array();
-                        if let (Some(bids), Some(asks)) = (bids, asks) {
-                            if let (Some(bid), Some(ask)) = (bids.first(), asks.first()) {
-                                let bid_px = bid.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                                let ask_px = ask.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                                println!("   {}: bid={} ask={}", asset, bid_px, ask_px);
-                            }
-                        }
-                    }
+                if let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) {
+                    println!("   {}: bid={} ask={}", asset, bid.px, ask.px);
                 }
             }
             Err(e) => println!("   {}: Error - {}", asset, e),
@@ -92,12 +68,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n4. Predicted Funding Rates:");
     match info.predicted_fundings().await {
         Ok(fundings) => {
-            if let Some(arr) = fundings.as_array() {
-                for (i, funding) in arr.iter().take(5).enumerate() {
-                    let asset = funding.get("asset").and_then(|v| v.as_str()).unwrap_or("?");
-                    let rate = funding.get("predictedFunding").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] {}: {}", i + 1, asset, rate);
-                }
+            for (i, funding) in fundings.iter().take(5).enumerate() {
+                let rate = funding
+                    .hyperliquid()
+                    .map(|r| r.funding_rate.to_string())
+                    .unwrap_or_else(|| "?".to_string());
+                println!("   [{}] {}: {}", i + 1, funding.coin(), rate);
             }
         }
         Err(e) => println!("   Error: {}", e),
@@ -8,29 +8,23 @@
 //! cargo run --example info_candles
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, Requires};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  cargo run --example info_candles");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("info_candles", Requires::Endpoint).await?;
 
     println!("Info API Candles Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    let sdk = builder.build().await?;
-    let info = sdk.info();
+    let info = TypedInfo::from_sdk(&sdk);
 
     // Time range: last 24 hours
     let now = SystemTime::now()
@@ -43,20 +37,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n1. BTC 1-Hour Candles (last 24h):");
     match info.candles("BTC", "1h", start_time, Some(now)).await {
         Ok(candles) => {
-            if let Some(arr) = candles.as_array() {
-                println!("   Received {} candles", arr.len());
-                for (i, candle) in arr.iter().take(5).enumerate() {
-                    let t = candle.get("t").and_then(|v| v.as_i64()).unwrap_or(0);
-                    let o = candle.get("o").and_then(|v| v.as_str()).unwrap_or("?");
-                    let h = candle.get("h").and_then(|v| v.as_str()).unwrap_or("?");
-                    let l = candle.get("l").and_then(|v| v.as_str()).unwrap_or("?");
-                    let c = candle.get("c").and_then(|v| v.as_str()).unwrap_or("?");
-                    let v = candle.get("v").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] t={} O={} H={} L={} C={} V={}", i + 1, t, o, h, l, c, v);
-                }
-                if arr.len() > 5 {
-                    println!("   ... and {} more", arr.len() - 5);
-                }
+            println!("   Received {} candles", candles.len());
+            for (i, c) in candles.iter().take(5).enumerate() {
+                println!(
+                    "   [{}] t={} O={} H={} L={} C={} V={}",
+                    i + 1,
+                    c.open_time,
+                    c.open,
+                    c.high,
+                    c.low,
+                    c.close,
+                    c.volume
+                );
+            }
+            if candles.len() > 5 {
+                println!("   ... and {} more", candles.len() - 5);
             }
         }
         Err(e) => println!("   Error: {}", e),
@@ -67,12 +62,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let start_4h = now - (4 * 60 * 60 * 1000);
     match info.candles("ETH", "15m", start_4h, Some(now)).await {
         Ok(candles) => {
-            if let Some(arr) = candles.as_array() {
-                println!("   Received {} candles", arr.len());
-                for (i, candle) in arr.iter().take(5).enumerate() {
-                    let c = candle.get("c").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] close={}", i + 1, c);
-                }
+            println!("   Received {} candles", candles.len());
+            for (i, c) in candles.iter().take(5).enumerate() {
+                println!("   [{}] close={}", i + 1, c.close);
             }
         }
         Err(e) => println!("   Error: {}", e),
@@ -8,24 +8,27 @@
 //! cargo run --example info_example
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{Config, Requires};
+use rust_decimal::Decimal;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").unwrap_or_else(|_| {
-        eprintln!("Error: Set ENDPOINT environment variable");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        std::process::exit(1);
-    });
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("info_example", Requires::Endpoint);
 
     println!("Hyperliquid Info API Example");
     println!("{}", "=".repeat(50));
-    println!("Endpoint: {}...", &endpoint[..endpoint.len().min(50)]);
+    println!("Endpoint: {}", config.redacted_endpoint());
     println!();
 
     // Create SDK and get Info client
-    let sdk = HyperliquidSDK::new().endpoint(&endpoint).build().await?;
-    let info = sdk.info();
+    let sdk = config.connect().await?;
+    let info = TypedInfo::from_sdk(&sdk);
 
     // ══════════════════════════════════════════════════════════════════════════
     // Market Data
@@ -35,53 +38,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Get all mid prices
     let mids = info.all_mids(None).await?;
-    if let Some(btc) = mids.get("BTC").and_then(|v| v.as_str()) {
+    if let Some(btc) = mids.get("BTC") {
         println!("BTC mid: ${}", btc);
     }
-    if let Some(eth) = mids.get("ETH").and_then(|v| v.as_str()) {
+    if let Some(eth) = mids.get("ETH") {
         println!("ETH mid: ${}", eth);
     }
-    if let Some(obj) = mids.as_object() {
-        println!("Total assets: {}", obj.len());
-    }
+    println!("Total assets: {}", mids.len());
     println!();
 
     // Get L2 order book
-    let book = info.l2_book("BTC", None, None).await?;
-    if let Some(levels) = book.get("levels").and_then(|l| l.as_array()) {
-        let bids = levels.get(0).and_then(|b| b.as_array());
-        let asks = levels.get(1).and_then(|a| a.as_array());
-
-        if let (Some(bids), Some(asks)) = (bids, asks) {
-            if let (Some(best_bid), Some(best_ask)) = (bids.first(), asks.first()) {
-                let bid_px = best_bid.get("px").and_then(|p| p.as_str()).unwrap_or("?");
-                let bid_sz = best_bid.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-                let ask_px = best_ask.get("px").and_then(|p| p.as_str()).unwrap_or("?");
-                let ask_sz = best_ask.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-
-                let bid_price: f64 = bid_px.parse().unwrap_or(0.0);
-                let ask_price: f64 = ask_px.parse().unwrap_or(0.0);
-                let spread = ask_price - bid_price;
-
-                println!("BTC Book:");
-                println!("  Best Bid: {} @ ${:.2}", bid_sz, bid_price);
-                println!("  Best Ask: {} @ ${:.2}", ask_sz, ask_price);
-                println!("  Spread: ${:.2}", spread);
-            }
-        }
+    let book = info.l2_book("BTC").await?;
+    if let (Some(best_bid), Some(best_ask)) = (book.best_bid(), book.best_ask()) {
+        println!("BTC Book:");
+        println!("  Best Bid: {} @ ${:.2}", best_bid.sz, best_bid.px);
+        println!("  Best Ask: {} @ ${:.2}", best_ask.sz, best_ask.px);
+        println!("  Spread: ${:.2}", best_ask.px - best_bid.px);
     }
     println!();
 
     // Get recent trades
     let trades = info.recent_trades("ETH").await?;
-    if let Some(trades_arr) = trades.as_array() {
-        println!("Recent ETH trades: {}", trades_arr.len());
-        if let Some(last_trade) = trades_arr.first() {
-            let sz = last_trade.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-            let px = last_trade.get("px").and_then(|p| p.as_str()).unwrap_or("0");
-            let price: f64 = px.parse().unwrap_or(0.0);
-            println!("  Last: {} @ ${:.2}", sz, price);
-        }
+    println!("Recent ETH trades: {}", trades.len());
+    if let Some(last_trade) = trades.first() {
+        println!("  Last: {} @ ${:.2}", last_trade.sz, last_trade.px);
     }
     println!();
 
@@ -92,15 +72,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "-".repeat(30));
 
     let meta = info.meta().await?;
-    if let Some(universe) = meta.get("universe").and_then(|u| u.as_array()) {
-        println!("Total perp markets: {}", universe.len());
-
-        // Show a few markets
-        for asset in universe.iter().take(5) {
-            let name = asset.get("name").and_then(|n| n.as_str()).unwrap_or("?");
-            let sz_decimals = asset.get("szDecimals").and_then(|s| s.as_u64()).unwrap_or(0);
-            println!("  {}: {} size decimals", name, sz_decimals);
-        }
+    println!("Total perp markets: {}", meta.universe.len());
+
+    // Show a few markets
+    for asset in meta.universe.iter().take(5) {
+        println!("  {}: {} size decimals", asset.name, asset.sz_decimals);
     }
     println!();
 
@@ -115,35 +91,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     match info.clearinghouse_state(user_address, None).await {
         Ok(state) => {
-            let equity = state
-                .get("marginSummary")
-                .and_then(|m| m.get("accountValue"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("0")
-                .parse::<f64>()
-                .unwrap_or(0.0);
-            println!("Account equity: ${:.2}", equity);
-
-            if let Some(positions) = state.get("assetPositions").and_then(|p| p.as_array()) {
-                if !positions.is_empty() {
-                    println!("Open positions: {}", positions.len());
-                    for pos in positions.iter().take(3) {
-                        if let Some(position) = pos.get("position") {
-                            let coin = position.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-                            let size = position.get("szi").and_then(|s| s.as_str()).unwrap_or("0");
-                            let entry = position.get("entryPx").and_then(|e| e.as_str()).unwrap_or("?");
-                            let pnl = position
-                                .get("unrealizedPnl")
-                                .and_then(|p| p.as_str())
-                                .unwrap_or("0")
-                                .parse::<f64>()
-                                .unwrap_or(0.0);
-                            println!("  {}: {} @ {} (PnL: ${:.2})", coin, size, entry, pnl);
-                        }
-                    }
-                } else {
-                    println!("  No open positions");
+            println!("Account equity: ${:.2}", state.margin_summary.account_value);
+
+            if !state.asset_positions.is_empty() {
+                println!("Open positions: {}", state.asset_positions.len());
+                for pos in state.asset_positions.iter().take(3) {
+                    let p = &pos.position;
+                    let entry = p.entry_px.map(|px| px.to_string()).unwrap_or_else(|| "?".to_string());
+                    println!("  {}: {} @ {} (PnL: ${:.2})", p.coin, p.szi, entry, p.unrealized_pnl);
                 }
+            } else {
+                println!("  No open positions");
             }
         }
         Err(e) => {
@@ -160,18 +118,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     match info.predicted_fundings().await {
         Ok(fundings) => {
-            if let Some(fundings_arr) = fundings.as_array() {
-                println!("Predicted funding rates for {} assets:", fundings_arr.len());
-                for f in fundings_arr.iter().take(5) {
-                    let coin = f.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-                    let rate = f
-                        .get("fundingRate")
-                        .and_then(|r| r.as_str())
-                        .unwrap_or("0")
-                        .parse::<f64>()
-                        .unwrap_or(0.0)
-                        * 100.0;
-                    println!("  {}: {:.4}%", coin, rate);
+            println!("Predicted funding rates for {} assets:", fundings.len());
+            for f in fundings.iter().take(5) {
+                if let Some(rate) = f.hyperliquid() {
+                    println!("  {}: {:.4}%", f.coin(), rate.funding_rate * Decimal::ONE_HUNDRED);
                 }
             }
         }
@@ -8,44 +8,33 @@
 //! cargo run --example info_vaults
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, Requires};
+use rust_decimal::Decimal;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  cargo run --example info_vaults");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("info_vaults", Requires::Endpoint).await?;
 
     println!("Info API Vaults Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    let sdk = builder.build().await?;
-    let info = sdk.info();
+    let info = TypedInfo::from_sdk(&sdk);
 
     // List all vaults
     println!("\n1. Vault Summaries:");
-    match info.vault_summaries().await {
+    let summaries = info.vault_summaries().await;
+    match &summaries {
         Ok(vaults) => {
-            if let Some(arr) = vaults.as_array() {
-                println!("   Total vaults: {}", arr.len());
-                for (i, vault) in arr.iter().take(5).enumerate() {
-                    let name = vault.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                    let tvl = vault.get("tvl").and_then(|v| v.as_str()).unwrap_or("?");
-                    let addr = vault.get("vaultAddress").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] {} (TVL: ${})", i + 1, name, tvl);
-                    if addr.len() > 20 {
-                        println!("       {}...", &addr[..20]);
-                    }
-                }
+            println!("   Total vaults: {}", vaults.len());
+            for (i, vault) in vaults.iter().take(5).enumerate() {
+                println!("   [{}] {} (TVL: ${:.2})", i + 1, vault.name, vault.tvl);
+                println!("       {}", vault.vault_address);
             }
         }
         Err(e) => println!("   Error: {}", e),
@@ -54,13 +43,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Leading vaults (requires a user address)
     println!("\n2. Leading Vaults:");
     let address_str = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_else(|| "0x0000000000000000000000000000000000000000".to_string());
-    match info.leading_vaults(&address_str).await {
+    match info.raw().leading_vaults(&address_str).await {
         Ok(vaults) => {
             if let Some(arr) = vaults.as_array() {
                 for (i, vault) in arr.iter().take(3).enumerate() {
                     let name = vault.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                    let pnl = vault.get("pnl").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] {}: PnL ${}", i + 1, name, pnl);
+                    println!("   [{}] {}", i + 1, name);
                 }
             }
         }
@@ -69,26 +57,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Vault details (if we have a vault address)
     println!("\n3. Vault Details:");
-    match info.vault_summaries().await {
-        Ok(vaults) => {
-            if let Some(arr) = vaults.as_array() {
-                if let Some(first) = arr.first() {
-                    let addr = first.get("vaultAddress").and_then(|v| v.as_str()).unwrap_or("");
-                    if !addr.is_empty() {
-                        match info.vault_details(addr, None).await {
-                            Ok(details) => {
-                                let name = details.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                                let tvl = details.get("tvl").and_then(|v| v.as_str()).unwrap_or("?");
-                                println!("   Name: {}", name);
-                                println!("   TVL: ${}", tvl);
-                            }
-                            Err(e) => println!("   Error: {}", e),
-                        }
-                    }
-                }
+    if let Some(first) = summaries.ok().as_ref().and_then(|v| v.first()) {
+        match info.vault_details(&first.vault_address, None).await {
+            Ok(details) => {
+                println!("   Name: {}", details.name);
+                println!("   Leader: {}", details.leader);
+                println!("   APR: {:.2}%", details.apr * Decimal::ONE_HUNDRED);
+                println!("   Accepting deposits: {}", details.allow_deposits);
             }
+            Err(e) => println!("   Error: {}", e),
         }
-        Err(_) => {}
     }
 
     // User vault positions (if private key provided)
@@ -96,13 +74,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("\n4. Your Vault Positions:");
         match info.user_vault_equities(&address_str).await {
             Ok(positions) => {
-                if let Some(arr) = positions.as_array() {
-                    if arr.is_empty() {
-                        println!("   No vault positions");
-                    } else {
-                        for pos in arr {
-                            println!("   {:?}", pos);
-                        }
+                if positions.is_empty() {
+                    println!("   No vault positions");
+                } else {
+                    for pos in &positions {
+                        println!("   {}: ${:.2}", pos.vault_address, pos.equity);
                     }
                 }
             }
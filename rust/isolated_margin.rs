@@ -9,56 +9,41 @@
 //! cargo run --example isolated_margin
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, AssetRegistry, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example isolated_margin");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("isolated_margin", Requires::Signer).await?;
 
     println!("Isolated Margin Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
+    // Check the symbol exists before sending anything
+    AssetRegistry::load(&sdk).await?.resolve("BTC")?;
+
     // Check current positions
     println!("\n1. Current Positions:");
-    let info = sdk.info();
+    let info = TypedInfo::from_sdk(&sdk);
     let address_str = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
 
     match info.clearinghouse_state(&address_str, None).await {
         Ok(state) => {
-            if let Some(positions) = state.get("assetPositions").and_then(|p| p.as_array()) {
-                if positions.is_empty() {
-                    println!("   No open positions");
-                } else {
-                    for pos in positions {
-                        if let Some(position) = pos.get("position") {
-                            let coin = position.get("coin").and_then(|v| v.as_str()).unwrap_or("?");
-                            let szi = position.get("szi").and_then(|v| v.as_str()).unwrap_or("0");
-                            let margin = position.get("marginUsed").and_then(|v| v.as_str()).unwrap_or("?");
-                            println!("   {} size={} margin={}", coin, szi, margin);
-                        }
-                    }
+            if state.asset_positions.is_empty() {
+                println!("   No open positions");
+            } else {
+                for pos in &state.asset_positions {
+                    let p = &pos.position;
+                    println!("   {} size={} margin={} ({})", p.coin, p.szi, p.margin_used, p.leverage.kind);
                 }
             }
         }
@@ -6,70 +6,66 @@
 //! ```bash
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
+//! cargo run --example leverage -- --dry-run   # sign and print, nothing sent
 //! cargo run --example leverage
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::dry_run::{self, actions};
+use hyperliquid_examples::{AssetRegistry, Config, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example leverage");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("leverage", Requires::Signer);
+    let sdk = config.connect().await?;
 
     println!("Leverage Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
-    // Get max leverage for BTC
+    // Get max leverage for BTC (and check the symbol before sending anything)
     println!("\n1. BTC Market Info:");
-    let info = sdk.info();
-    match info.meta().await {
-        Ok(meta) => {
-            if let Some(universe) = meta.get("universe").and_then(|v| v.as_array()) {
-                for asset in universe {
-                    if asset.get("name").and_then(|v| v.as_str()) == Some("BTC") {
-                        let max_leverage = asset.get("maxLeverage").and_then(|v| v.as_u64()).unwrap_or(0);
-                        println!("   Max Leverage: {}x", max_leverage);
-                        break;
-                    }
-                }
-            }
+    let registry = AssetRegistry::load(&sdk).await?;
+    let btc = registry.resolve("BTC")?;
+    if let Some(max_leverage) = btc.max_leverage {
+        println!("   Max Leverage: {}x", max_leverage);
+    }
+    if let Some(table) = &btc.margin_table {
+        for tier in &table.margin_tiers {
+            println!("   From ${}: {}x", tier.lower_bound, tier.max_leverage);
         }
-        Err(e) => println!("   Error: {}", e),
+    }
+    if btc.only_isolated {
+        println!("   Isolated margin only");
     }
 
     // Set leverage (cross margin)
     println!("\n2. Set BTC Leverage to 10x (Cross):");
-    match sdk.update_leverage("BTC", 10, true).await {
-        Ok(result) => println!("   Result: {:?}", result),
-        Err(e) => println!("   Error: {}", e),
+    if config.dry_run {
+        dry_run::show(&config, &actions::update_leverage(btc.asset_id, 10, true)).await;
+    } else {
+        match sdk.update_leverage("BTC", 10, true).await {
+            Ok(result) => println!("   Result: {:?}", result),
+            Err(e) => println!("   Error: {}", e),
+        }
     }
 
     // Set leverage (isolated margin)
     println!("\n3. Set BTC Leverage to 5x (Isolated):");
-    match sdk.update_leverage("BTC", 5, false).await {
-        Ok(result) => println!("   Result: {:?}", result),
-        Err(e) => println!("   Error: {}", e),
+    if config.dry_run {
+        dry_run::show(&config, &actions::update_leverage(btc.asset_id, 5, false)).await;
+    } else {
+        match sdk.update_leverage("BTC", 5, false).await {
+            Ok(result) => println!("   Result: {:?}", result),
+            Err(e) => println!("   Error: {}", e),
+        }
     }
 
     println!("\n{}", "-".repeat(50));
@@ -8,78 +8,63 @@
 //! cargo run --example markets
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::{connect, AssetRegistry, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  cargo run --example markets");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("markets", Requires::Endpoint).await?;
 
     println!("Markets Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    let sdk = builder.build().await?;
+    // Perps, spot and HIP-3 merged into one registry (cached on disk)
+    let registry = AssetRegistry::load(&sdk).await?;
 
     // Get all markets
     println!("\n1. Perpetual Markets:");
-    let info = sdk.info();
-    match info.meta().await {
-        Ok(meta) => {
-            if let Some(universe) = meta.get("universe").and_then(|v| v.as_array()) {
-                println!("   Total: {} markets", universe.len());
-                for (i, asset) in universe.iter().take(10).enumerate() {
-                    let name = asset.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                    let sz_decimals = asset.get("szDecimals").and_then(|v| v.as_u64()).unwrap_or(0);
-                    let max_leverage = asset.get("maxLeverage").and_then(|v| v.as_u64()).unwrap_or(0);
-                    println!("   [{}] {}: sz_decimals={}, max_leverage={}x",
-                        i + 1, name, sz_decimals, max_leverage);
-                }
-                if universe.len() > 10 {
-                    println!("   ... and {} more", universe.len() - 10);
-                }
-            }
-        }
-        Err(e) => println!("   Error: {}", e),
+    let perps: Vec<_> = registry.perps().collect();
+    println!("   Total: {} markets", perps.len());
+    for (i, market) in perps.iter().take(10).enumerate() {
+        println!("   [{}] {} (asset {}): sz_decimals={}, max_leverage={}x",
+            i + 1, market.symbol, market.asset_id, market.sz_decimals, market.max_leverage.unwrap_or_default());
+    }
+    if perps.len() > 10 {
+        println!("   ... and {} more", perps.len() - 10);
     }
 
     // Get spot markets
     println!("\n2. Spot Markets:");
-    match info.spot_meta().await {
-        Ok(spot) => {
-            if let Some(universe) = spot.get("universe").and_then(|v| v.as_array()) {
-                println!("   Total: {} markets", universe.len());
-                for (i, market) in universe.iter().take(5).enumerate() {
-                    let name = market.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] {}", i + 1, name);
-                }
-            }
-        }
-        Err(e) => println!("   Error: {}", e),
+    let spot: Vec<_> = registry.spot().collect();
+    println!("   Total: {} markets", spot.len());
+    for (i, market) in spot.iter().take(5).enumerate() {
+        let pair = market.pair.as_deref().unwrap_or_default();
+        println!("   [{}] {} ({}) asset {}", i + 1, market.symbol, pair, market.asset_id);
     }
 
     // Get DEXes (HIP-3)
     println!("\n3. HIP-3 DEXes:");
-    match sdk.dexes().await {
-        Ok(dexes) => {
-            if let Some(arr) = dexes.get("perpDexs").and_then(|v| v.as_array()) {
-                println!("   Total: {} DEXes", arr.len());
-                for (i, dex) in arr.iter().take(5).enumerate() {
-                    let name = dex.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] {}", i + 1, name);
-                }
-            }
+    let dexes = registry.dexes();
+    println!("   Total: {} DEXes", dexes.len());
+    for (i, dex) in dexes.iter().take(5).enumerate() {
+        let count = registry.hip3(Some(dex)).count();
+        println!("   [{}] {} ({} markets)", i + 1, dex, count);
+    }
+
+    // Look up by symbol or asset id
+    println!("\n4. Lookups:");
+    for symbol in ["BTC", "HYPE/USDC", "btc"] {
+        match registry.resolve(symbol) {
+            Ok(market) => println!("   {} -> asset {}", symbol, market.asset_id),
+            Err(e) => println!("   {} -> {}", symbol, e),
         }
-        Err(e) => println!("   Error: {}", e),
+    }
+    if let Some(market) = registry.by_id(10_000) {
+        println!("   asset 10000 -> {}", market.symbol);
     }
 
     println!("\n{}", "=".repeat(50));
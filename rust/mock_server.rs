@@ -0,0 +1,90 @@
+//! Mock Server Example
+//!
+//! Run a local mock of the Hyperliquid APIs and point the other examples at
+//! it. Orders match against a scripted book; no funds or keys are at risk.
+//!
+//! # Usage
+//! ```bash
+//! cargo run --bin mock_server -- --fixtures my-market.json
+//! # in another shell, paste the printed exports, then for example:
+//! cargo run --bin place_order
+//! ```
+//!
+//! Options:
+//! - `--port N` (default 0 = any free port)
+//! - `--host IP` (default 127.0.0.1; bind port 80 of a loopback address to
+//!   also serve WebSocket streams)
+//! - `--fixtures FILE` (JSON laid over the built-in market, see
+//!   `src/mock/script.rs`)
+
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+
+use hyperliquid_examples::mock::{MockServer, Script};
+use hyperliquid_examples::Error;
+
+/// Anvil's first dev key; the mock does not check signatures
+const DEV_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut host: IpAddr = [127, 0, 0, 1].into();
+    let mut port = 0u16;
+    let mut fixtures: Option<PathBuf> = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| Error::ConfigError(format!("{} needs a value", arg)))
+        };
+        match arg.as_str() {
+            "--host" => host = value()?.parse()?,
+            "--port" => port = value()?.parse()?,
+            "--fixtures" => fixtures = Some(value()?.into()),
+            other => return Err(Error::ConfigError(format!("unknown option {}", other)).into()),
+        }
+    }
+
+    println!("Mock Server Example");
+    println!("{}", "=".repeat(50));
+
+    // Load fixtures
+    println!("\n1. Loading fixtures:");
+    let script = match &fixtures {
+        Some(path) => {
+            println!("   {} (over the built-in market)", path.display());
+            Script::load(path)?
+        }
+        None => {
+            println!("   Built-in market");
+            Script::default_market()
+        }
+    };
+    println!("   Books: {}", script.books.keys().cloned().collect::<Vec<_>>().join(", "));
+
+    // Start the server
+    println!("\n2. Starting server:");
+    let server = MockServer::bind(SocketAddr::new(host, port), script).await?;
+    println!("   Listening on {}", server.addr());
+    println!("   Account: {}", server.engine().address());
+    if server.addr().port() != 80 {
+        println!("   WebSocket streams need port 80 (--host 127.0.0.2 --port 80)");
+    }
+
+    println!("\n3. Point the examples at it:");
+    for (key, value) in server.env() {
+        println!("   export {}='{}'", key, value);
+    }
+    println!("   export PRIVATE_KEY='{}'", DEV_KEY);
+
+    println!("\n4. Serving (Ctrl-C to stop)...");
+    tokio::signal::ctrl_c().await?;
+
+    println!("\n   Actions received: {}", server.actions().len());
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
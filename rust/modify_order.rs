@@ -7,48 +7,43 @@
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
 //! cargo run --example modify_order
+//! cargo run --example modify_order -- --paper   # simulated fills, nothing sent
 //! ```
 
-use hyperliquid_sdk::{HyperliquidSDK, Order, TIF};
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, paper, AssetRegistry, Requires, Rounding};
+use hyperliquid_sdk::{Order, TIF};
+use rust_decimal::Decimal;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example modify_order");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("modify_order", Requires::Signer).await?;
 
     println!("Modify Order Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
-    // Get current price
-    let mid = sdk.get_mid("BTC").await?;
+    // Validate BTC and get its tick/lot limits, then the current price
+    let btc = AssetRegistry::load(&sdk).await?.precision("BTC")?;
+    let mid = TypedInfo::from_sdk(&sdk).mid("BTC").await?;
+    let size = btc.sz(Decimal::new(1, 3), Rounding::Down)?;
     println!("\nBTC mid price: ${:.2}", mid);
 
     // Place an order first
     println!("\n1. Placing initial order...");
+    let price = btc.px_away_from(mid * Decimal::new(95, 2), mid)?;  // 5% below mid
+    println!("   Price: ${} Size: {}", price, size);
     let order = Order::buy("BTC")
-        .size(0.001)
-        .price(mid * 0.95)  // 5% below mid
+        .size_decimal(size.value())
+        .price(price.to_f64())
         .gtc();
 
     let placed = sdk.order(order).await?;
@@ -58,15 +53,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     if let Some(oid) = placed.oid {
         // Modify the order - improve price
         println!("\n2. Modifying order (better price)...");
+        let new_price = btc.px_away_from(mid * Decimal::new(96, 2), mid)?;
+        println!("   New price: ${}", new_price);
         match sdk.modify(
             oid,
             "BTC",
-            true,           // is_buy
-            0.001,          // size
-            mid * 0.96,     // new price (closer to mid)
+            true,               // is_buy
+            size.to_f64(),      // size
+            new_price.to_f64(), // new price (closer to mid)
             TIF::Gtc,
-            false,          // reduce_only
-            None,           // cloid
+            false,              // reduce_only
+            None,               // cloid
         ).await {
             Ok(modified) => {
                 println!("   Status: {}", modified.status);
@@ -83,6 +80,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    paper::report();
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
@@ -0,0 +1,161 @@
+//! OCO Manager Example
+//!
+//! Keep one-cancels-other groups of resting orders (`hyperliquid_examples::oco`):
+//! once an order of a group fills, the others are cancelled. Groups are saved
+//! to disk and picked up again by the next run, which first catches up with
+//! fills and cancels that happened in between.
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
+//! export PRIVATE_KEY="0x..."
+//! cargo run --bin oco -- --group 1234,1235            # group two resting BTC orders
+//! cargo run --bin oco -- --coin ETH --group 0x...,5678  # by cloid or oid
+//! cargo run --bin oco -- --demo                       # a buy below and a sell above the mid
+//! cargo run --bin oco                                 # watch the saved groups
+//! ```
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use hyperliquid_examples::models::{decode, OpenOrder, TypedInfo};
+use hyperliquid_examples::oco::{OcoManager, OrderRef};
+use hyperliquid_examples::{stream_duration, AssetRegistry, Config, Requires, Rounding};
+use hyperliquid_sdk::TIF;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Parser)]
+#[command(name = "oco", about = "Cancel the other orders of a group once one fills")]
+struct Args {
+    /// Coin of a new group
+    #[arg(long, default_value = "BTC")]
+    coin: String,
+    /// Resting orders of a new group, comma-separated oids or cloids
+    #[arg(long)]
+    group: Option<String>,
+    /// Place a limit buy 3% below and a limit sell 3% above the mid as a new group
+    #[arg(long, conflicts_with = "group")]
+    demo: bool,
+    /// State file (default: oco-<network>.json in the cache directory)
+    #[arg(long)]
+    state: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("oco", Requires::Signer);
+    let args = Args::parse_from(std::iter::once("oco".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+
+    println!("OCO Manager Example");
+    println!("{}", "=".repeat(50));
+
+    let user = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
+    println!("Address: {}", user);
+
+    let oco = match &args.state {
+        Some(path) => OcoManager::open(path)?,
+        None => OcoManager::for_network(config.network)?,
+    };
+
+    println!("\n1. Saved groups ({}):", oco.path().display());
+    let groups = oco.groups();
+    if groups.is_empty() {
+        println!("   None");
+    }
+    for group in &groups {
+        println!("   {}", group);
+    }
+
+    println!("\n2. Catching up with open orders and fills:");
+    match oco.resume(&sdk).await {
+        Ok(cancels) if cancels.is_empty() => println!("   Nothing to cancel"),
+        Ok(cancels) => {
+            for cancel in cancels {
+                match oco.cancel(&sdk, &cancel).await {
+                    Ok(()) => println!("   {}: done", cancel),
+                    Err(e) => println!("   Error: {}", e),
+                }
+            }
+        }
+        Err(e) => println!("   Error: {}", e),
+    }
+
+    if args.group.is_some() || args.demo {
+        println!("\n3. New group:");
+        // Orders given, or placed here
+        let orders: Vec<OrderRef> = match &args.group {
+            Some(list) => list.split(',').map(str::parse).collect::<Result<_, _>>()?,
+            None => place_demo(&sdk, &args.coin).await?,
+        };
+        let open: Vec<OpenOrder> = decode(sdk.open_orders().await?)?;
+        match oco.add(&args.coin, &orders, &open) {
+            Ok(group) => println!("   {}", group),
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
+
+    println!("\n4. Watching orderUpdates and userFills:");
+    let mut stream = sdk.stream().on_error(|e| eprintln!("   [Error] {}", e));
+    let mut cancels = oco.watch(&mut stream, &user);
+    stream.start()?;
+
+    let deadline = async {
+        // Until Ctrl-C, or for HL_STREAM_SECONDS
+        match std::env::var_os("HL_STREAM_SECONDS") {
+            Some(_) => tokio::time::sleep(stream_duration(0)).await,
+            None => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    };
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            cancel = cancels.recv() => match cancel {
+                Some(Ok(cancel)) => match oco.cancel(&sdk, &cancel).await {
+                    Ok(()) => println!("   {}: done", cancel),
+                    Err(e) => println!("   Error: {}", e),
+                },
+                Some(Err(e)) => println!("   Error: {}", e),
+                None => break,
+            },
+        }
+    }
+    stream.stop();
+
+    println!("\n5. Groups:");
+    for group in oco.groups() {
+        println!("   {}{}", group, if group.is_done() { " - done" } else { "" });
+    }
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
+
+/// Rest a buy 3% below and a sell 3% above the mid
+async fn place_demo(sdk: &hyperliquid_sdk::HyperliquidSDK, coin: &str) -> Result<Vec<OrderRef>, Box<dyn std::error::Error>> {
+    let precision = AssetRegistry::load(sdk).await?.precision(coin)?;
+    let mid = TypedInfo::from_sdk(sdk).mid(coin).await?;
+    let size = precision.sz(Decimal::new(11, 0) / mid, Rounding::Up)?;
+
+    let mut orders = Vec::new();
+    for (is_buy, px) in [(true, mid * Decimal::new(97, 2)), (false, mid * Decimal::new(103, 2))] {
+        let px = precision.px_away_from(px, mid)?;
+        let placed = match is_buy {
+            true => sdk.buy(coin, size.to_f64(), px.to_f64(), TIF::Gtc).await?,
+            false => sdk.sell(coin, size.to_f64(), px.to_f64(), TIF::Gtc).await?,
+        };
+        println!("   {} {} {} @ {}: {} (oid {:?})", if is_buy { "Buy" } else { "Sell" }, size, coin, px, placed.status, placed.oid);
+        orders.extend(placed.oid.map(OrderRef::Oid));
+    }
+    Ok(orders)
+}
@@ -9,33 +9,21 @@
 //! cargo run --example open_orders
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example open_orders");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("open_orders", Requires::Signer).await?;
 
     println!("Open Orders Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
@@ -44,29 +32,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\nOpen Orders:");
     println!("{}", "-".repeat(50));
 
-    match sdk.open_orders().await {
-        Ok(orders) => {
-            if let Some(arr) = orders.as_array() {
-                if arr.is_empty() {
-                    println!("No open orders");
-                } else {
-                    println!("Total: {} orders\n", arr.len());
+    let address = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
+    let info = TypedInfo::from_sdk(&sdk);
 
-                    for order in arr {
-                        let oid = order.get("oid").and_then(|v| v.as_u64()).unwrap_or(0);
-                        let coin = order.get("coin").and_then(|v| v.as_str()).unwrap_or("?");
-                        let side = order.get("side").and_then(|v| v.as_str()).unwrap_or("?");
-                        let side_str = if side == "B" { "BUY" } else { "SELL" };
-                        let sz = order.get("sz").and_then(|v| v.as_str()).unwrap_or("?");
-                        let px = order.get("limitPx").and_then(|v| v.as_str()).unwrap_or("?");
-                        let order_type = order.get("orderType").and_then(|v| v.as_str()).unwrap_or("?");
-                        let cloid = order.get("cloid").and_then(|v| v.as_str()).unwrap_or("");
+    match info.frontend_open_orders(&address, None).await {
+        Ok(orders) => {
+            if orders.is_empty() {
+                println!("No open orders");
+            } else {
+                println!("Total: {} orders\n", orders.len());
 
-                        println!("[OID {}] {} {} {} @ {} ({})",
-                            oid, coin, side_str, sz, px, order_type);
-                        if !cloid.is_empty() {
-                            println!("         cloid: {}", cloid);
-                        }
+                for order in &orders {
+                    let order_type = order.order_type.as_deref().unwrap_or("?");
+                    println!("[OID {}] {} {} {} @ {} ({})",
+                        order.oid, order.coin, order.side, order.sz, order.limit_px, order_type);
+                    if let Some(cloid) = &order.cloid {
+                        println!("         cloid: {}", cloid);
                     }
                 }
             }
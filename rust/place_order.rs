@@ -7,47 +7,41 @@
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
 //! cargo run --example place_order
+//! cargo run --example place_order -- --paper   # simulated fills, nothing sent
 //! ```
 
-use hyperliquid_sdk::{HyperliquidSDK, Order, TIF};
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, paper, AssetRegistry, Requires, Rounding};
+use hyperliquid_sdk::{Order, TIF};
+use rust_decimal::Decimal;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
-
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example place_order");
-        std::process::exit(1);
-    }
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("place_order", Requires::Signer).await?;
 
     println!("Place Order Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
-    // Get current price
-    let mid = sdk.get_mid("BTC").await?;
+    // Validate BTC and get its tick/lot limits, then the current price
+    let btc = AssetRegistry::load(&sdk).await?.precision("BTC")?;
+    let mid = TypedInfo::from_sdk(&sdk).mid("BTC").await?;
+    let size = btc.sz(Decimal::new(1, 3), Rounding::Down)?;
     println!("\nBTC mid price: ${:.2}", mid);
 
     // Method 1: Simple buy/sell
     println!("\n1. Simple Limit Buy (3% below mid):");
-    let buy_price = mid * 0.97;
-    match sdk.buy("BTC", 0.001, buy_price, TIF::Gtc).await {
+    let buy_price = btc.px_away_from(mid * Decimal::new(97, 2), mid)?;
+    println!("   Price: ${} Size: {}", buy_price, size);
+    match sdk.buy("BTC", size.to_f64(), buy_price.to_f64(), TIF::Gtc).await {
         Ok(order) => {
             println!("   Status: {}", order.status);
             println!("   OID: {:?}", order.oid);
@@ -63,8 +57,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Method 2: Fluent builder
     println!("\n2. Fluent Builder Order:");
     let order = Order::buy("BTC")
-        .size(0.001)
-        .price(buy_price)
+        .size_decimal(size.value())
+        .price(buy_price.to_f64())
         .gtc();
 
     match sdk.order(order).await {
@@ -83,8 +77,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Method 3: IOC order
     println!("\n3. IOC Order (Immediate or Cancel):");
     let order = Order::buy("BTC")
-        .size(0.001)
-        .price(buy_price)
+        .size_decimal(size.value())
+        .price(buy_price.to_f64())
         .ioc();
 
     match sdk.order(order).await {
@@ -98,8 +92,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Method 4: Post-only (ALO)
     println!("\n4. Post-Only Order (Add Liquidity Only):");
     let order = Order::buy("BTC")
-        .size(0.001)
-        .price(buy_price)
+        .size_decimal(size.value())
+        .price(buy_price.to_f64())
         .alo();
 
     match sdk.order(order).await {
@@ -115,6 +109,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => println!("   Error: {}", e),
     }
 
+    paper::report();
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
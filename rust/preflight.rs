@@ -9,37 +9,28 @@
 //! cargo run --example preflight
 //! ```
 
-use hyperliquid_sdk::{HyperliquidSDK, Side};
+use hyperliquid_examples::{connect, AssetRegistry, Requires};
+use hyperliquid_sdk::Side;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example preflight");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("preflight", Requires::Signer).await?;
 
     println!("Preflight Validation Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
+    // Check the symbol exists before sending anything
+    AssetRegistry::load(&sdk).await?.resolve("BTC")?;
+
     // Get current price
     let mid = sdk.get_mid("BTC").await?;
     println!("\nBTC mid price: ${:.2}", mid);
@@ -0,0 +1,194 @@
+//! Market Data Recorder
+//!
+//! Subscribe to stream channels and write every message to disk: gzip NDJSON
+//! files that roll over every hour, with receive timestamps, sequence numbers
+//! and a `manifest.json` (format: `hyperliquid_examples::recorder`).
+//!
+//! Channels: trades, l2_book, all_mids, book_updates (WebSocket, or gRPC with
+//! `--grpc`), blocks and l4_book (gRPC). Runs until Ctrl-C, or for `--duration`
+//! seconds (`HL_STREAM_SECONDS`).
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint/TOKEN"
+//! cargo run --bin recorder -- --channels trades,l2_book --coins BTC,ETH --out data/
+//! cargo run --bin recorder -- --channels l4_book,blocks --coins BTC --duration 3600
+//! ```
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use hyperliquid_examples::recorder::{Channel, Recorder, Source, MANIFEST};
+use hyperliquid_examples::{stream_duration, AssetRegistry, Config, Requires};
+use serde_json::Value;
+
+/// How often the manifest is brought up to date and a status line printed
+const STATUS_EVERY: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Parser)]
+#[command(name = "recorder", about = "Record Hyperliquid streams to compressed NDJSON files")]
+struct Args {
+    /// Channels to record, comma-separated
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = [Channel::Trades, Channel::L2Book])]
+    channels: Vec<Channel>,
+    /// Coins for the per-coin channels, comma-separated
+    #[arg(long, value_delimiter = ',', default_value = "BTC")]
+    coins: Vec<String>,
+    /// Recording directory
+    #[arg(long, default_value = "recordings")]
+    out: PathBuf,
+    /// Record trades, l2_book and book_updates over gRPC instead of WebSocket
+    #[arg(long)]
+    grpc: bool,
+    /// Stop after this many seconds
+    #[arg(long, value_name = "SECS")]
+    duration: Option<u64>,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("recorder", Requires::Endpoint);
+    let args = Args::parse_from(std::iter::once("recorder".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+
+    println!("Market Data Recorder");
+    println!("{}", "=".repeat(50));
+
+    // Canonical symbols, so files and manifest agree with the API
+    let registry = AssetRegistry::load(&sdk).await?;
+    let coins: Vec<String> = args
+        .coins
+        .iter()
+        .map(|coin| registry.resolve(coin).map(|m| m.symbol.clone()))
+        .collect::<Result<_, _>>()?;
+
+    let recorder = Recorder::create(&args.out, &args.channels, &coins)?;
+    println!("\n1. Recording to {}", recorder.dir().display());
+    let recorder = Arc::new(Mutex::new(recorder));
+
+    // Callback that records every message of one subscription
+    let sink = |channel: Channel, source: Source, coin: Option<&str>| {
+        let recorder = recorder.clone();
+        let coin = coin.map(str::to_string);
+        move |data: Value| {
+            if let Err(e) = recorder.lock().unwrap().record(channel, source, coin.as_deref(), data) {
+                eprintln!("   [Error] {}", e);
+            }
+        }
+    };
+
+    let mut stream = sdk.stream()
+        .on_open(|| {
+            println!("   [WebSocket connected]");
+        })
+        .on_error(|e| {
+            eprintln!("   [WebSocket error] {}", e);
+        });
+    let mut grpc = sdk.grpc()
+        .on_connect(|| {
+            println!("   [gRPC connected]");
+        })
+        .on_error(|e| {
+            eprintln!("   [gRPC error] {}", e);
+        });
+
+    println!("\n2. Subscribing:");
+    let (mut ws_subs, mut grpc_subs) = (Vec::new(), Vec::new());
+    for &channel in &args.channels {
+        let sources = channel.sources();
+        let source = if sources == [Source::Grpc] || (args.grpc && sources.contains(&Source::Grpc)) {
+            Source::Grpc
+        } else {
+            Source::Ws
+        };
+        let targets: Vec<Option<&str>> = if channel.per_coin() {
+            coins.iter().map(|c| Some(c.as_str())).collect()
+        } else {
+            vec![None]
+        };
+
+        for coin in targets {
+            let callback = sink(channel, source, coin);
+            let name = coin.unwrap_or_default();
+            match (source, channel) {
+                (Source::Ws, Channel::Trades) => ws_subs.push(stream.trades(&[name], callback)),
+                (Source::Ws, Channel::L2Book) => ws_subs.push(stream.l2_book(name, callback)),
+                (Source::Ws, Channel::AllMids) => ws_subs.push(stream.all_mids(callback)),
+                (Source::Ws, Channel::BookUpdates) => ws_subs.push(stream.book_updates(&[name], callback)),
+                (Source::Grpc, Channel::Trades) => grpc_subs.push(grpc.trades(&[name], callback)),
+                (Source::Grpc, Channel::L2Book) => grpc_subs.push(grpc.l2_book(name, callback)),
+                (Source::Grpc, Channel::BookUpdates) => grpc_subs.push(grpc.book_updates(&[name], callback)),
+                (Source::Grpc, Channel::Blocks) => grpc_subs.push(grpc.blocks(callback)),
+                (Source::Grpc, Channel::L4Book) => grpc_subs.push(grpc.l4_book(name, callback)),
+                (Source::Ws, _) | (Source::Grpc, Channel::AllMids) => unreachable!("{} is not on {:?}", channel, source),
+            }
+            let source = if source == Source::Ws { "WebSocket" } else { "gRPC" };
+            println!("   {} {} ({})", channel, name, source);
+        }
+    }
+
+    if !ws_subs.is_empty() {
+        stream.start()?;
+    }
+    if !grpc_subs.is_empty() {
+        grpc.start()?;
+    }
+
+    // Until Ctrl-C, or for --duration / HL_STREAM_SECONDS
+    let limit = match args.duration {
+        Some(secs) => Some(Duration::from_secs(secs)),
+        None if std::env::var_os("HL_STREAM_SECONDS").is_some() => Some(stream_duration(0)),
+        None => None,
+    };
+    match limit {
+        Some(limit) => println!("\n3. Recording for {} seconds (Ctrl-C stops)...", limit.as_secs()),
+        None => println!("\n3. Recording until Ctrl-C..."),
+    }
+
+    let started = Instant::now();
+    let deadline = async {
+        match limit {
+            Some(limit) => tokio::time::sleep(limit).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(deadline);
+    let mut status = tokio::time::interval(STATUS_EVERY);
+    status.tick().await;
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            _ = tokio::signal::ctrl_c() => break,
+            _ = status.tick() => {
+                let mut recorder = recorder.lock().unwrap();
+                if let Err(e) = recorder.flush() {
+                    eprintln!("   [Error] {}", e);
+                }
+                println!("   [{}s] {} messages", started.elapsed().as_secs(), recorder.messages());
+            }
+        }
+    }
+
+    stream.stop();
+    grpc.stop();
+
+    let manifest = recorder.lock().unwrap().close()?;
+    println!("\n4. Files:");
+    for file in &manifest.files {
+        let channels: Vec<String> = file.channels.iter().map(|(c, n)| format!("{} {}", c, n)).collect();
+        println!("   {}: {} messages (seq {}-{}) {}", file.file, file.messages, file.first_seq, file.last_seq, channels.join(", "));
+    }
+    println!("   Manifest: {}", args.out.join(MANIFEST).display());
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Resilient Feed Example
+//!
+//! Stream trades over gRPC and WebSocket at once and fail over between them:
+//! the primary's events are delivered, the other transport stands by, and the
+//! feed switches on a disconnect, reconnect or stall without losing or
+//! repeating trades (format: `hyperliquid_examples::feed`). Each trade is
+//! tagged with the transport that delivered it.
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint/TOKEN"
+//! cargo run --bin resilient_feed -- BTC,ETH
+//! cargo run --bin resilient_feed -- BTC --primary ws --stall 5 --book
+//! ```
+
+use std::time::Duration;
+
+use clap::Parser;
+use hyperliquid_examples::feed::{FeedEvent, ResilientFeed, DEFAULT_STALL_AFTER};
+use hyperliquid_examples::models::MarketEvent;
+use hyperliquid_examples::recorder::Source;
+use hyperliquid_examples::{stream_duration, AssetRegistry, Config, Requires};
+
+#[derive(Debug, Parser)]
+#[command(name = "resilient_feed", about = "Trades over gRPC and WebSocket with failover")]
+struct Args {
+    /// Coins, comma-separated
+    #[arg(default_value = "BTC")]
+    coins: String,
+    /// Transport to prefer; the other is the hot standby
+    #[arg(long, value_enum, default_value = "grpc")]
+    primary: Source,
+    /// Seconds without a message on the active transport that count as a stall
+    #[arg(long, default_value_t = DEFAULT_STALL_AFTER.as_secs())]
+    stall: u64,
+    /// Also stream the L2 book of the first coin
+    #[arg(long)]
+    book: bool,
+}
+
+fn print_event(e: FeedEvent) {
+    match e.event {
+        MarketEvent::Trade(t) => println!("   [{}] {} {} {} @ {} (tid {})", e.source, t.coin, t.side, t.sz, t.px, t.tid),
+        MarketEvent::BookSnapshot(b) => {
+            let bid = b.best_bid().map_or("-".to_string(), |l| l.px.to_string());
+            let ask = b.best_ask().map_or("-".to_string(), |l| l.px.to_string());
+            println!("   [{}] {} book bid {} / ask {}", e.source, b.coin, bid, ask);
+        }
+        _ => {}
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("resilient_feed", Requires::Endpoint);
+    let args = Args::parse_from(std::iter::once("resilient_feed".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+
+    println!("Resilient Feed Example");
+    println!("{}", "=".repeat(50));
+
+    let registry = AssetRegistry::load(&sdk).await?;
+    let coins: Vec<String> = args
+        .coins
+        .split(',')
+        .map(|coin| registry.resolve(coin.trim()).map(|m| m.symbol.clone()))
+        .collect::<Result<_, _>>()?;
+    let names: Vec<&str> = coins.iter().map(String::as_str).collect();
+
+    let mut feed = ResilientFeed::new(&sdk)
+        .primary(args.primary)
+        .stall_after(Duration::from_secs(args.stall))
+        .on_switch(|s| {
+            println!("   [Switch] {} -> {} ({})", s.from, s.to, s.reason);
+        })
+        .on_reconnect(|source, attempt| {
+            println!("   [Reconnect] {} attempt {}", source, attempt);
+        })
+        .on_error(|source, e| {
+            eprintln!("   [Error] {}: {}", source, e);
+        });
+
+    println!("\n1. Streaming {} (primary {}, stall after {}s):", coins.join(", "), args.primary, args.stall);
+    feed.trades(&names, print_event);
+    if args.book {
+        feed.l2_book(&coins[0], print_event);
+    }
+    feed.start()?;
+
+    tokio::select! {
+        _ = tokio::time::sleep(stream_duration(60)) => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+    feed.stop();
+
+    let stats = feed.stats();
+    println!("\n2. Summary:");
+    println!("   Delivered: {} over gRPC, {} over WebSocket", stats.delivered(Source::Grpc), stats.delivered(Source::Ws));
+    println!("   Duplicates dropped: {}", stats.duplicates);
+    println!("   Switches: {}, reconnects: {}", stats.switches, stats.reconnects);
+    println!("   Active transport: {}", feed.active());
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
@@ -1,45 +1,42 @@
 //! Roundtrip Example
 //!
-//! Complete trading flow: buy, monitor, sell.
+//! Complete trading flow: buy with an attached stop loss and take profit
+//! (`hyperliquid_examples::bracket`), keep them sized to the fill, sell.
 //!
 //! # Usage
 //! ```bash
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
 //! cargo run --example roundtrip
+//! cargo run --example roundtrip -- --paper   # simulated fills, nothing sent
 //! ```
 
-use hyperliquid_sdk::{HyperliquidSDK, TriggerOrder};
+use hyperliquid_examples::bracket::Bracket;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{paper, AssetRegistry, Config, Requires, Rounding};
+use rust_decimal::Decimal;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
-
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example roundtrip");
-        std::process::exit(1);
-    }
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("roundtrip", Requires::Signer);
+    let sdk = config.connect().await?;
 
     println!("Trading Roundtrip Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
+    // Check the symbol exists before sending anything
+    let registry = AssetRegistry::load(&sdk).await?;
+    registry.resolve("BTC")?;
+
     // Step 1: Get market data
     println!("\n{}", "─".repeat(50));
     println!("STEP 1: Market Data");
@@ -53,71 +50,60 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("STEP 2: Account State");
     println!("{}", "─".repeat(50));
 
-    let info = sdk.info();
+    let info = TypedInfo::from_sdk(&sdk);
     let address_str = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
 
     match info.clearinghouse_state(&address_str, None).await {
-        Ok(state) => {
-            if let Some(margin) = state.get("marginSummary") {
-                let value = margin.get("accountValue").and_then(|v| v.as_str()).unwrap_or("0");
-                println!("Account Value: ${}", value);
-            }
-        }
+        Ok(state) => println!("Account Value: ${}", state.margin_summary.account_value),
         Err(e) => println!("Error: {}", e),
     }
 
-    // Step 3: Place entry order
+    // Step 3: Enter with the stop loss and take profit attached
     println!("\n{}", "─".repeat(50));
-    println!("STEP 3: Entry Order");
+    println!("STEP 3: Bracket Entry");
     println!("{}", "─".repeat(50));
 
-    println!("Market buy $100 of BTC:");
-    match sdk.market_buy("BTC").await.notional(100.0).await {
+    // $100 of BTC at market; TP/SL are sized to whatever the entry fills
+    let btc = registry.resolve("BTC")?;
+    let mid_px = Decimal::try_from(mid)?;
+    let size = btc.precision().sz(Decimal::from(100) / mid_px, Rounding::Up)?;
+    let bracket = Bracket::buy("BTC", size.value())
+        .take_profit(mid_px * Decimal::new(105, 2))
+        .stop_loss(mid_px * Decimal::new(95, 2));
+
+    println!("Market buy $100 of BTC, TP +5%, SL -5%:");
+    let mut order = match bracket.place(&config, &sdk).await {
         Ok(order) => {
             println!("   Status: {}", order.status);
-            println!("   OID: {:?}", order.oid);
-            println!("   Filled: {:?} @ ${:?}", order.filled_size, order.avg_price);
+            println!("   OID: {:?}", order.entry.oid);
+            println!("   Filled: {} @ ${:?}", order.entry.filled, order.entry.avg_px);
+            for leg in order.take_profit.iter().chain(&order.stop_loss) {
+                println!("   {} at ${:?}: size {}, OID: {:?}", leg.kind, leg.trigger_px, leg.size, leg.oid);
+            }
+            Some(order)
         }
-        Err(e) => println!("   Error: {}", e),
-    }
-
-    // Step 4: Set stop loss
-    println!("\n{}", "─".repeat(50));
-    println!("STEP 4: Stop Loss");
-    println!("{}", "─".repeat(50));
-
-    let stop_loss = TriggerOrder::stop_loss("BTC")
-        .size(0.001)
-        .trigger_price(mid * 0.95);
-
-    match sdk.trigger_order(stop_loss).await {
-        Ok(order) => {
-            println!("Stop loss set at ${:.2}", mid * 0.95);
-            println!("   OID: {:?}", order.oid);
+        Err(e) => {
+            println!("   Error: {}", e);
+            None
         }
-        Err(e) => println!("   Error: {}", e),
-    }
+    };
 
-    // Step 5: Set take profit
+    // Step 4: Keep TP/SL in step with the entry
     println!("\n{}", "─".repeat(50));
-    println!("STEP 5: Take Profit");
+    println!("STEP 4: Sync TP/SL");
     println!("{}", "─".repeat(50));
 
-    let take_profit = TriggerOrder::take_profit("BTC")
-        .size(0.001)
-        .trigger_price(mid * 1.05);
-
-    match sdk.trigger_order(take_profit).await {
-        Ok(order) => {
-            println!("Take profit set at ${:.2}", mid * 1.05);
-            println!("   OID: {:?}", order.oid);
+    if let Some(order) = &mut order {
+        match order.sync(&config, &info, &address_str).await {
+            Ok(adjustments) if adjustments.is_empty() => println!("TP/SL match the fill ({})", order.status),
+            Ok(adjustments) => adjustments.iter().for_each(|a| println!("   {}", a)),
+            Err(e) => println!("   Error: {}", e),
         }
-        Err(e) => println!("   Error: {}", e),
     }
 
-    // Step 6: Close position
+    // Step 5: Close position
     println!("\n{}", "─".repeat(50));
-    println!("STEP 6: Close Position");
+    println!("STEP 5: Close Position");
     println!("{}", "─".repeat(50));
 
     match sdk.close_position("BTC").await {
@@ -138,6 +124,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => println!("   Error: {}", e),
     }
 
+    paper::report();
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
@@ -0,0 +1,282 @@
+//! Account state as Prometheus gauges.
+//!
+//! [`AccountSnapshot::fetch`] reads an address's clearinghouse state, open
+//! orders, vault equities and staking delegations from the Info API;
+//! [`AccountMetrics`] keeps the latest snapshot of each address and renders
+//! them for `/metrics`:
+//!
+//! | Metric | Labels |
+//! |--------|--------|
+//! | `hl_account_up`, `hl_account_last_update_timestamp_seconds` | `address` |
+//! | `hl_account_value_usd`, `hl_account_margin_used_usd`, `hl_account_maintenance_margin_usd`, `hl_account_withdrawable_usd`, `hl_account_notional_usd`, `hl_account_leverage` | `address` |
+//! | `hl_account_open_orders` | `address`, `coin` |
+//! | `hl_position_size`, `hl_position_value_usd`, `hl_position_entry_px`, `hl_position_unrealized_pnl_usd`, `hl_position_leverage`, `hl_position_liquidation_px`, `hl_position_liquidation_distance_ratio` | `address`, `coin` |
+//! | `hl_vault_equity_usd` | `address`, `vault` |
+//! | `hl_delegated_hype` | `address`, `validator` |
+//!
+//! Account leverage is total notional over account value. The liquidation
+//! distance is how far the mark (position value over size) has to move, as a
+//! fraction of the mark, to reach the liquidation price; positions without one
+//! (well-collateralized cross positions) have no sample.
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use rust_decimal::Decimal;
+
+use crate::error::Result;
+use crate::metrics::{self, header, labels, sample, MetricsServer};
+use crate::models::{ClearinghouseState, Delegation, OpenOrder, Position, TypedInfo, VaultEquity};
+use crate::recorder::now_ms;
+
+/// What the Info API says about one address at one time
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountSnapshot {
+    pub address: String,
+    /// Local time of the fetch (ms)
+    pub time_ms: u64,
+    pub state: ClearinghouseState,
+    pub open_orders: Vec<OpenOrder>,
+    pub vault_equities: Vec<VaultEquity>,
+    pub delegations: Vec<Delegation>,
+}
+
+impl AccountSnapshot {
+    /// Fetch the four queries for `address` at once
+    pub async fn fetch(info: &TypedInfo, address: &str) -> Result<Self> {
+        let (state, open_orders, vault_equities, delegations) = tokio::try_join!(
+            info.clearinghouse_state(address, None),
+            info.open_orders(address, None),
+            info.user_vault_equities(address),
+            info.delegations(address),
+        )?;
+        Ok(AccountSnapshot { address: address.to_string(), time_ms: now_ms(), state, open_orders, vault_equities, delegations })
+    }
+
+    /// Total notional over account value (0 for an empty account)
+    pub fn leverage(&self) -> Decimal {
+        let summary = &self.state.margin_summary;
+        match summary.account_value.is_zero() {
+            true => Decimal::ZERO,
+            false => summary.total_ntl_pos / summary.account_value,
+        }
+    }
+
+    /// Open orders per coin
+    pub fn orders_by_coin(&self) -> BTreeMap<&str, usize> {
+        let mut counts = BTreeMap::new();
+        for order in &self.open_orders {
+            *counts.entry(order.coin.as_str()).or_default() += 1;
+        }
+        counts
+    }
+}
+
+/// Mark price of a position: its value over its size
+pub fn mark_px(position: &Position) -> Option<Decimal> {
+    (!position.szi.is_zero()).then(|| position.position_value / position.szi.abs())
+}
+
+/// Fraction the mark has to move to reach the liquidation price
+pub fn liquidation_distance(position: &Position) -> Option<Decimal> {
+    let mark = mark_px(position).filter(|m| !m.is_zero())?;
+    Some(((position.liquidation_px? - mark) / mark).abs())
+}
+
+/// Name, help and value of a gauge
+type Gauge<T, V> = (&'static str, &'static str, fn(&T) -> V);
+
+#[derive(Debug, Default)]
+struct Account {
+    snapshot: Option<AccountSnapshot>,
+    /// Whether the last fetch succeeded
+    up: bool,
+    last_error: Option<String>,
+}
+
+/// Latest snapshot per address, rendered as Prometheus gauges; see the
+/// [module docs](self). Clones share the same accounts.
+#[derive(Clone, Default)]
+pub struct AccountMetrics {
+    accounts: Arc<Mutex<BTreeMap<String, Account>>>,
+}
+
+impl AccountMetrics {
+    /// Metrics for `addresses`, all down until their first fetch
+    pub fn new(addresses: &[String]) -> Self {
+        let metrics = AccountMetrics::default();
+        {
+            let mut accounts = metrics.accounts.lock().unwrap();
+            for address in addresses {
+                accounts.insert(address.to_lowercase(), Account::default());
+            }
+        }
+        metrics
+    }
+
+    /// Addresses being exported
+    pub fn addresses(&self) -> Vec<String> {
+        self.accounts.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Fetch and store a fresh snapshot of `address`. On error the previous
+    /// snapshot stays, and the account is down until a fetch succeeds.
+    pub async fn poll(&self, info: &TypedInfo, address: &str) -> Result<()> {
+        let result = AccountSnapshot::fetch(info, address).await;
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts.entry(address.to_lowercase()).or_default();
+        account.up = result.is_ok();
+        match result {
+            Ok(snapshot) => {
+                account.snapshot = Some(snapshot);
+                account.last_error = None;
+                Ok(())
+            }
+            Err(e) => {
+                account.last_error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// Store a snapshot fetched elsewhere
+    pub fn update(&self, snapshot: AccountSnapshot) {
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts.entry(snapshot.address.to_lowercase()).or_default();
+        account.up = true;
+        account.last_error = None;
+        account.snapshot = Some(snapshot);
+    }
+
+    /// Latest snapshot of `address`
+    pub fn snapshot(&self, address: &str) -> Option<AccountSnapshot> {
+        self.accounts.lock().unwrap().get(&address.to_lowercase()).and_then(|a| a.snapshot.clone())
+    }
+
+    /// Error of the last fetch of `address`, if it failed
+    pub fn last_error(&self, address: &str) -> Option<String> {
+        self.accounts.lock().unwrap().get(&address.to_lowercase()).and_then(|a| a.last_error.clone())
+    }
+
+    /// The gauges in the Prometheus text format
+    pub fn render(&self) -> String {
+        let accounts = self.accounts.lock().unwrap();
+        let snapshots: Vec<(&str, &AccountSnapshot)> =
+            accounts.iter().filter_map(|(address, a)| Some((address.as_str(), a.snapshot.as_ref()?))).collect();
+        let mut out = String::new();
+
+        header(&mut out, "hl_account_up", "gauge", "1 when the last fetch of the account succeeded");
+        for (address, account) in accounts.iter() {
+            sample(&mut out, "hl_account_up", &labels(&[("address", address)]), u8::from(account.up));
+        }
+        let gauges: [Gauge<AccountSnapshot, Decimal>; 6] = [
+            ("hl_account_value_usd", "Account value", |s| s.state.margin_summary.account_value),
+            ("hl_account_margin_used_usd", "Margin used", |s| s.state.margin_summary.total_margin_used),
+            ("hl_account_maintenance_margin_usd", "Cross maintenance margin used", |s| s.state.cross_maintenance_margin_used),
+            ("hl_account_withdrawable_usd", "Withdrawable USDC", |s| s.state.withdrawable),
+            ("hl_account_notional_usd", "Total position notional", |s| s.state.margin_summary.total_ntl_pos),
+            ("hl_account_leverage", "Total notional over account value", |s| s.leverage().round_dp(6)),
+        ];
+        for (name, help, value) in gauges {
+            header(&mut out, name, "gauge", help);
+            for (address, snapshot) in &snapshots {
+                sample(&mut out, name, &labels(&[("address", address)]), value(snapshot));
+            }
+        }
+        header(&mut out, "hl_account_last_update_timestamp_seconds", "gauge", "Time of the last successful fetch");
+        for (address, snapshot) in &snapshots {
+            sample(&mut out, "hl_account_last_update_timestamp_seconds", &labels(&[("address", address)]), snapshot.time_ms as f64 / 1000.0);
+        }
+        header(&mut out, "hl_account_open_orders", "gauge", "Resting orders");
+        for (address, snapshot) in &snapshots {
+            for (coin, count) in snapshot.orders_by_coin() {
+                sample(&mut out, "hl_account_open_orders", &labels(&[("address", address), ("coin", coin)]), count);
+            }
+        }
+
+        let positions: [Gauge<Position, Option<Decimal>>; 7] = [
+            ("hl_position_size", "Signed position size (negative short)", |p| Some(p.szi)),
+            ("hl_position_value_usd", "Position value at the mark", |p| Some(p.position_value)),
+            ("hl_position_entry_px", "Average entry price", |p| p.entry_px),
+            ("hl_position_unrealized_pnl_usd", "Unrealized PnL", |p| Some(p.unrealized_pnl)),
+            ("hl_position_leverage", "Leverage setting of the coin", |p| Some(p.leverage.value.into())),
+            ("hl_position_liquidation_px", "Liquidation price", |p| p.liquidation_px),
+            ("hl_position_liquidation_distance_ratio", "Move of the mark to liquidation, as a fraction of the mark", |p| {
+                liquidation_distance(p).map(|d| d.round_dp(6))
+            }),
+        ];
+        for (name, help, value) in positions {
+            header(&mut out, name, "gauge", help);
+            for (address, snapshot) in &snapshots {
+                for position in snapshot.state.asset_positions.iter().map(|p| &p.position) {
+                    if let Some(value) = value(position) {
+                        sample(&mut out, name, &labels(&[("address", address), ("coin", &position.coin)]), value);
+                    }
+                }
+            }
+        }
+
+        header(&mut out, "hl_vault_equity_usd", "gauge", "Equity in a vault");
+        for (address, snapshot) in &snapshots {
+            for vault in &snapshot.vault_equities {
+                sample(&mut out, "hl_vault_equity_usd", &labels(&[("address", address), ("vault", &vault.vault_address)]), vault.equity);
+            }
+        }
+        header(&mut out, "hl_delegated_hype", "gauge", "HYPE staked with a validator");
+        for (address, snapshot) in &snapshots {
+            for delegation in &snapshot.delegations {
+                sample(&mut out, "hl_delegated_hype", &labels(&[("address", address), ("validator", &delegation.validator)]), delegation.amount);
+            }
+        }
+        out
+    }
+
+    /// Serve [`render`](Self::render) at `http://addr/metrics` until the
+    /// returned server is dropped (port 0 picks a free port)
+    pub async fn serve(&self, addr: SocketAddr) -> Result<MetricsServer> {
+        let metrics = self.clone();
+        metrics::serve(addr, move || metrics.render()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::decode;
+
+    fn fixture<T: serde::de::DeserializeOwned>(text: &str) -> T {
+        decode(serde_json::from_str(text).unwrap()).unwrap()
+    }
+
+    fn snapshot(address: &str) -> AccountSnapshot {
+        AccountSnapshot {
+            address: address.to_string(),
+            time_ms: 1_737_412_345_678,
+            state: fixture(include_str!("models/fixtures/clearinghouse_state.json")),
+            open_orders: fixture(include_str!("models/fixtures/open_orders.json")),
+            vault_equities: Vec::new(),
+            delegations: fixture(include_str!("models/fixtures/delegations.json")),
+        }
+    }
+
+    #[test]
+    fn renders_account_and_position_gauges() {
+        let metrics = AccountMetrics::new(&["0xAAA".to_string(), "0xbbb".to_string()]);
+        metrics.update(snapshot("0xaaa"));
+        let text = metrics.render();
+
+        assert!(text.contains("hl_account_up{address=\"0xaaa\"} 1\n"), "{}", text);
+        assert!(text.contains("hl_account_up{address=\"0xbbb\"} 0\n"), "{}", text);
+        assert!(!text.contains("hl_account_value_usd{address=\"0xbbb\"}"), "{}", text);
+        assert!(text.contains("hl_account_value_usd{address=\"0xaaa\"} 13109.482328\n"), "{}", text);
+        assert!(text.contains("hl_account_leverage{address=\"0xaaa\"} 0.367081\n"), "{}", text);
+        assert!(text.contains("hl_account_last_update_timestamp_seconds{address=\"0xaaa\"} 1737412345.678\n"), "{}", text);
+        assert!(text.contains("hl_position_size{address=\"0xaaa\",coin=\"ETH\"} -1.2\n"), "{}", text);
+        assert!(text.contains("hl_position_leverage{address=\"0xaaa\",coin=\"BTC\"} 20\n"), "{}", text);
+        assert!(text.contains("hl_position_liquidation_px{address=\"0xaaa\",coin=\"ETH\"} 3943.12\n"), "{}", text);
+        assert!(!text.contains("hl_position_liquidation_px{address=\"0xaaa\",coin=\"BTC\"}"), "{}", text);
+        assert!(text.contains("hl_position_liquidation_distance_ratio{address=\"0xaaa\",coin=\"ETH\"} 0.154478\n"), "{}", text);
+        assert!(text.contains("hl_delegated_hype{address=\"0xaaa\",validator=\"0x5ac99df645f3414876c816caa18b2d234024b487\"} 1250.5\n"), "{}", text);
+    }
+}
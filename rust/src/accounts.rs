@@ -0,0 +1,419 @@
+//! Named accounts: main accounts, sub-accounts and vaults.
+//!
+//! The profile file (see [`config`](crate::config)) can list accounts under
+//! `[accounts.NAME]`, each with exactly one of:
+//!
+//! - `private_key`: a main account, trading with its own key
+//! - `sub_account`: a sub-account, trading with its master's key
+//! - `vault`: a vault we lead, trading with the leader's key
+//! - `address`: any account, read-only
+//!
+//! Sub-accounts and vaults name the account holding the key with `signer`
+//! (default: the configured `PRIVATE_KEY`). Their actions are signed by that
+//! key with the sub-account or vault as `vaultAddress`, which is how
+//! Hyperliquid lets one key trade for several accounts:
+//!
+//! ```toml
+//! [accounts.main]
+//! private_key = "0x..."
+//!
+//! [accounts.mm]
+//! sub_account = "0x..."
+//! signer = "main"
+//!
+//! [accounts.fund]
+//! vault = "0x..."
+//! signer = "main"
+//!
+//! [accounts.cold]
+//! address = "0x..."
+//! ```
+//!
+//! `--account NAME[,NAME...]` (or `HL_ACCOUNT`) selects accounts and `all`
+//! selects every one. Only binaries that say so act for them (`hl` and
+//! `account_exporter`); the other examples refuse to start with an account
+//! selected rather than ignore it. Views like [`open_orders`] and
+//! [`positions`] query each selected account and keep going when one of them
+//! fails:
+//!
+//! ```no_run
+//! use hyperliquid_examples::accounts::{self, Accounts};
+//! use hyperliquid_examples::models::TypedInfo;
+//! use hyperliquid_examples::{Config, Requires};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = Config::load()?.require_accounts("open_orders", Requires::Endpoint);
+//! let selected = Accounts::selected(&config)?;
+//! let info = TypedInfo::from_sdk(&config.connect().await?);
+//! for (account, orders) in accounts::open_orders(&info, &selected).await {
+//!     println!("{}: {} open orders", account.name, orders?.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::config::{profile_file_path, Config};
+use crate::error::{Error, Result};
+use crate::exchange;
+use crate::models::{ClearinghouseState, OpenOrder, TypedInfo};
+
+/// Selects every configured account
+pub const ALL: &str = "all";
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Accounts
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// How an account trades
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountKind {
+    /// Its own key
+    Main,
+    /// The master account's key, with the sub-account as `vaultAddress`
+    SubAccount,
+    /// The leader's key, with the vault as `vaultAddress`
+    Vault,
+    /// Not at all; queries only
+    ReadOnly,
+}
+
+impl AccountKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AccountKind::Main => "main",
+            AccountKind::SubAccount => "sub-account",
+            AccountKind::Vault => "vault",
+            AccountKind::ReadOnly => "read-only",
+        }
+    }
+}
+
+impl fmt::Display for AccountKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One named account
+#[derive(Clone)]
+pub struct Account {
+    pub name: String,
+    pub kind: AccountKind,
+    /// Address queries are made for, as the info API expects it
+    pub address: String,
+    /// Account whose key signs, for sub-accounts and vaults (`None`: `PRIVATE_KEY`)
+    pub signer: Option<String>,
+    /// Hex private key that signs, if the file provides one
+    private_key: Option<String>,
+}
+
+impl fmt::Debug for Account {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Account")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("address", &self.address)
+            .field("signer", &self.signer)
+            .field("private_key", &self.private_key.as_ref().map(|_| "****"))
+            .finish()
+    }
+}
+
+impl Account {
+    /// `vaultAddress` its actions are signed with (sub-accounts and vaults)
+    pub fn vault_address(&self) -> Option<Address> {
+        match self.kind {
+            AccountKind::SubAccount | AccountKind::Vault => Address::from_str(&self.address).ok(),
+            AccountKind::Main | AccountKind::ReadOnly => None,
+        }
+    }
+
+    /// `base` with this account's signing key, for connecting the SDK
+    pub fn config(&self, base: &Config) -> Config {
+        let mut config = base.clone();
+        if let Some(key) = &self.private_key {
+            config.private_key = Some(key.clone());
+        }
+        config
+    }
+
+    /// Fail unless this account can send exchange actions
+    pub fn check_can_trade(&self) -> Result<()> {
+        match self.kind {
+            AccountKind::ReadOnly => Err(Error::ConfigError(format!(
+                "account '{}' is read-only (it has an address but no key)",
+                self.name
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Accounts of the profile file, by name
+#[derive(Debug, Clone, Default)]
+pub struct Accounts {
+    accounts: BTreeMap<String, Account>,
+}
+
+/// `[accounts.NAME]` as written in the file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    private_key: Option<String>,
+    sub_account: Option<String>,
+    vault: Option<String>,
+    address: Option<String>,
+    signer: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AccountsFile {
+    #[serde(default)]
+    accounts: BTreeMap<String, Entry>,
+}
+
+impl Accounts {
+    /// Accounts of the profile file; none without a file
+    pub fn load() -> Result<Self> {
+        let Some(path) = profile_file_path() else {
+            return Ok(Self::default());
+        };
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| Error::ConfigError(format!("{}: {}", path.display(), e)))?;
+        Self::from_toml(&text).map_err(|e| Error::ConfigError(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Accounts of a profile file's text
+    pub fn from_toml(text: &str) -> Result<Self> {
+        let file: AccountsFile = toml::from_str(text).map_err(|e| Error::ConfigError(e.to_string()))?;
+
+        // Keys first, so sub-accounts and vaults can borrow their signer's
+        let mut keys = BTreeMap::new();
+        for (name, entry) in &file.accounts {
+            if let Some(key) = &entry.private_key {
+                let signer = PrivateKeySigner::from_str(key)
+                    .map_err(|e| Error::ConfigError(format!("account '{}': invalid private key: {}", name, e)))?;
+                keys.insert(name.clone(), (key.clone(), format!("{:?}", signer.address())));
+            }
+        }
+
+        let mut accounts = BTreeMap::new();
+        for (name, entry) in file.accounts {
+            let parse = |address: &str| {
+                Address::from_str(address.trim())
+                    .map(|a| format!("{:?}", a))
+                    .map_err(|e| Error::ConfigError(format!("account '{}': invalid address '{}': {}", name, address, e)))
+            };
+            let (kind, address) = match (&entry.private_key, &entry.sub_account, &entry.vault, &entry.address) {
+                (Some(_), None, None, None) => (AccountKind::Main, keys[&name].1.clone()),
+                (None, Some(sub), None, None) => (AccountKind::SubAccount, parse(sub)?),
+                (None, None, Some(vault), None) => (AccountKind::Vault, parse(vault)?),
+                (None, None, None, Some(address)) => (AccountKind::ReadOnly, parse(address)?),
+                _ => {
+                    return Err(Error::ConfigError(format!(
+                        "account '{}' needs exactly one of private_key, sub_account, vault or address",
+                        name
+                    )))
+                }
+            };
+
+            let private_key = match (&entry.signer, kind) {
+                (None, AccountKind::Main) => entry.private_key.clone(),
+                (None, _) => None,
+                (Some(signer), AccountKind::SubAccount | AccountKind::Vault) => {
+                    let (key, _) = keys.get(signer).ok_or_else(|| {
+                        Error::ConfigError(format!(
+                            "account '{}': signer '{}' is not an account with a private_key",
+                            name, signer
+                        ))
+                    })?;
+                    Some(key.clone())
+                }
+                (Some(_), _) => {
+                    return Err(Error::ConfigError(format!(
+                        "account '{}': only sub-accounts and vaults take a signer",
+                        name
+                    )))
+                }
+            };
+
+            let account = Account { name: name.clone(), kind, address, signer: entry.signer, private_key };
+            accounts.insert(name, account);
+        }
+        Ok(Accounts { accounts })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Account> {
+        self.accounts.get(name)
+    }
+
+    /// Every account, by name
+    pub fn iter(&self) -> impl Iterator<Item = &Account> {
+        self.accounts.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Accounts named by `selection`: `NAME[,NAME...]` or `all`
+    pub fn select(&self, selection: &str) -> Result<Vec<Account>> {
+        if selection.trim() == ALL {
+            if self.accounts.is_empty() {
+                return Err(Error::ConfigError("--account all: no [accounts] in the profile file".to_string()));
+            }
+            return Ok(self.accounts.values().cloned().collect());
+        }
+        let mut selected: Vec<Account> = Vec::new();
+        for name in selection.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let account = self.get(name).ok_or_else(|| {
+                let known: Vec<&str> = self.accounts.keys().map(String::as_str).collect();
+                Error::ConfigError(format!("unknown account '{}' (configured: {})", name, known.join(", ")))
+            })?;
+            if !selected.iter().any(|a| a.name == account.name) {
+                selected.push(account.clone());
+            }
+        }
+        if selected.is_empty() {
+            return Err(Error::ConfigError("--account needs a name".to_string()));
+        }
+        Ok(selected)
+    }
+
+    /// Accounts `config.account` selects from the profile file; empty
+    /// without `--account`, meaning the account of `PRIVATE_KEY`
+    pub fn selected(config: &Config) -> Result<Vec<Account>> {
+        match &config.account {
+            Some(selection) => Self::load()?.select(selection),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Views
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Open orders of each account (with trigger details), in order
+pub async fn open_orders<'a>(info: &TypedInfo, accounts: &'a [Account]) -> Vec<(&'a Account, Result<Vec<OpenOrder>>)> {
+    let mut results = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        results.push((account, info.frontend_open_orders(&account.address, None).await));
+    }
+    results
+}
+
+/// Perp account state of each account, in order
+pub async fn positions<'a>(
+    info: &TypedInfo,
+    accounts: &'a [Account],
+) -> Vec<(&'a Account, Result<ClearinghouseState>)> {
+    let mut results = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        results.push((account, info.clearinghouse_state(&account.address, None).await));
+    }
+    results
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Trading
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Sign `action` for a sub-account or vault and send it with its `vaultAddress`.
+///
+/// The SDK's own methods always act for the key's account, so actions for
+/// these accounts are built with [`dry_run::actions`](crate::dry_run::actions)
+/// and sent with [`exchange::send`]. `config` is the account's (see
+/// [`Account::config`]).
+pub async fn send(config: &Config, account: &Account, action: &Value) -> Result<Value> {
+    let Some(vault) = account.vault_address() else {
+        return Err(Error::ConfigError(format!("account '{}' is not a sub-account or vault", account.name)));
+    };
+    exchange::send(config, action, Some(vault)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"
+        default = "main"
+
+        [profiles.main]
+        endpoint = "https://example.invalid/TOKEN"
+
+        [accounts.main]
+        private_key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+
+        [accounts.mm]
+        sub_account = "0x1111111111111111111111111111111111111111"
+        signer = "main"
+
+        [accounts.fund]
+        vault = "0xDFC24B077BC1425AD1DEA75BCB6F8158E10DF303"
+
+        [accounts.cold]
+        address = "0x2222222222222222222222222222222222222222"
+    "#;
+
+    #[test]
+    fn parses_accounts_and_their_signers() {
+        let accounts = Accounts::from_toml(FILE).unwrap();
+        assert_eq!(accounts.len(), 4);
+
+        let main = accounts.get("main").unwrap();
+        assert_eq!(main.kind, AccountKind::Main);
+        assert_eq!(main.address, "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+        assert_eq!(main.vault_address(), None);
+
+        let mm = accounts.get("mm").unwrap();
+        assert_eq!(mm.kind, AccountKind::SubAccount);
+        assert_eq!(mm.vault_address(), Some(Address::from_str(&mm.address).unwrap()));
+        let base = Config { private_key: Some("0xother".to_string()), ..Config::default() };
+        assert_eq!(mm.config(&base).private_key, main.private_key);
+
+        // Without a signer the configured PRIVATE_KEY signs
+        let fund = accounts.get("fund").unwrap();
+        assert_eq!(fund.address, "0xdfc24b077bc1425ad1dea75bcb6f8158e10df303");
+        assert_eq!(fund.config(&base).private_key.as_deref(), Some("0xother"));
+
+        let cold = accounts.get("cold").unwrap();
+        assert!(cold.check_can_trade().is_err());
+        assert!(!format!("{:?}", main).contains("ac0974"));
+    }
+
+    #[test]
+    fn selects_by_name_or_all() {
+        let accounts = Accounts::from_toml(FILE).unwrap();
+        let names = |selection: &str| -> Vec<String> {
+            accounts.select(selection).unwrap().into_iter().map(|a| a.name).collect()
+        };
+        assert_eq!(names("mm, main,mm"), ["mm", "main"]);
+        assert_eq!(names("all"), ["cold", "fund", "main", "mm"]);
+        assert!(accounts.select("nope").is_err());
+        assert!(accounts.select(",").is_err());
+        assert!(Accounts::default().select("all").is_err());
+    }
+
+    #[test]
+    fn rejects_ambiguous_entries() {
+        let both = "[accounts.x]\naddress = \"0x1111111111111111111111111111111111111111\"\nvault = \"0x1111111111111111111111111111111111111111\"";
+        assert!(Accounts::from_toml(both).is_err());
+        let unknown_signer = "[accounts.x]\nvault = \"0x1111111111111111111111111111111111111111\"\nsigner = \"main\"";
+        assert!(Accounts::from_toml(unknown_signer).is_err());
+        assert!(Accounts::from_toml("[accounts.x]\naddress = \"nope\"").is_err());
+    }
+}
@@ -0,0 +1,421 @@
+//! Resample trades into bars the exchange doesn't offer.
+//!
+//! `info.candles` only has fixed time intervals. A [`BarBuilder`] turns a trade
+//! stream into bars of one [`BarKind`]:
+//!
+//! | Kind | Closes when | Written as |
+//! |------|-------------|------------|
+//! | time | the interval ends (aligned to the epoch, like candles) | `30s`, `1m`, `4h` |
+//! | tick | N trades are in the bar | `tick:500` |
+//! | volume | the traded size reaches N | `volume:10` |
+//! | dollar | the traded notional (px × sz) reaches N | `dollar:1000000` |
+//! | imbalance | buy size minus sell size reaches ±N | `imbalance:5` |
+//!
+//! Every [`Bar`] carries OHLC, volume, notional, VWAP and the volume split by
+//! the trade's aggressor `side`. A trade is never split across bars: the trade
+//! that crosses a threshold closes the bar with all of its size.
+//!
+//! The same builder works live and in batch. Hand it every message of
+//! `stream.trades` or `grpc.trades` with [`BarBuilder::ingest`] (and call
+//! [`BarBuilder::poll`] on a timer so time bars close without waiting for the
+//! next trade), or run a slice of trades through [`build`]; [`read_trades`]
+//! loads the trades of a [`recorder`](crate::recorder) directory:
+//!
+//! ```no_run
+//! use hyperliquid_examples::bars::{build, read_trades, BarKind};
+//!
+//! let trades = read_trades("recordings".as_ref())?;
+//! for bar in build("volume:10".parse()?, &trades) {
+//!     println!("{} {} close {} vwap {}", bar.coin, bar.close_time, bar.close, bar.vwap);
+//! }
+//! # Ok::<(), hyperliquid_examples::Error>(())
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+use crate::cli::Since;
+use crate::error::{Error, Result};
+use crate::models::{stream_items, Side, Trade};
+use crate::recorder::{read_file, recording_files, Channel};
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Bar kinds
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// What closes a bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BarKind {
+    /// Fixed interval (ms)
+    Time(u64),
+    /// Number of trades
+    Tick(u64),
+    /// Traded size
+    Volume(Decimal),
+    /// Traded notional (px × sz)
+    Dollar(Decimal),
+    /// Absolute difference between buy and sell size
+    Imbalance(Decimal),
+}
+
+impl fmt::Display for BarKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BarKind::Time(ms) => {
+                let units = [(86_400_000, "d"), (3_600_000, "h"), (60_000, "m"), (1_000, "s")];
+                match units.iter().find(|(unit, _)| ms.is_multiple_of(*unit)) {
+                    Some((unit, name)) => write!(f, "{}{}", ms / unit, name),
+                    None => write!(f, "{}ms", ms),
+                }
+            }
+            BarKind::Tick(n) => write!(f, "tick:{}", n),
+            BarKind::Volume(n) => write!(f, "volume:{}", n),
+            BarKind::Dollar(n) => write!(f, "dollar:{}", n),
+            BarKind::Imbalance(n) => write!(f, "imbalance:{}", n),
+        }
+    }
+}
+
+impl FromStr for BarKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bad = || Error::ValidationError(format!("bad bar '{}' (e.g. 1m, tick:500, volume:10, dollar:1000000, imbalance:5)", s));
+        let time = |value: &str| match value.trim().parse::<Since>() {
+            Ok(Since::Ago(interval)) if !interval.is_zero() => Ok(BarKind::Time(interval.as_millis() as u64)),
+            _ => Err(bad()),
+        };
+        let Some((kind, value)) = s.split_once(':') else {
+            return time(s);
+        };
+        let threshold = || Decimal::from_str(value.trim()).ok().filter(|n| n.is_sign_positive() && !n.is_zero()).ok_or_else(bad);
+        match kind.trim().to_ascii_lowercase().as_str() {
+            "time" => time(value),
+            "tick" | "ticks" => value.trim().parse().ok().filter(|n| *n > 0).map(BarKind::Tick).ok_or_else(bad),
+            "volume" => threshold().map(BarKind::Volume),
+            "dollar" | "notional" => threshold().map(BarKind::Dollar),
+            "imbalance" => threshold().map(BarKind::Imbalance),
+            _ => Err(bad()),
+        }
+    }
+}
+
+impl Serialize for BarKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Bar
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Trades of one coin aggregated into a bar
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bar {
+    pub coin: String,
+    pub kind: BarKind,
+    /// Time bars: start of the interval; others: time of the first trade (ms)
+    pub open_time: u64,
+    /// Time bars: last ms of the interval; others: time of the last trade (ms)
+    pub close_time: u64,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal,
+    /// Size bought by takers
+    pub buy_volume: Decimal,
+    /// Size sold by takers
+    pub sell_volume: Decimal,
+    /// Sum of px × sz
+    pub notional: Decimal,
+    /// Volume-weighted average price (`notional / volume`)
+    pub vwap: Decimal,
+    pub trades: u64,
+}
+
+impl Bar {
+    fn open(kind: BarKind, trade: &Trade) -> Self {
+        let (open_time, close_time) = match kind {
+            BarKind::Time(ms) => {
+                let start = trade.time - trade.time % ms;
+                (start, start + ms - 1)
+            }
+            _ => (trade.time, trade.time),
+        };
+        Bar {
+            coin: trade.coin.clone(),
+            kind,
+            open_time,
+            close_time,
+            open: trade.px,
+            high: trade.px,
+            low: trade.px,
+            close: trade.px,
+            volume: Decimal::ZERO,
+            buy_volume: Decimal::ZERO,
+            sell_volume: Decimal::ZERO,
+            notional: Decimal::ZERO,
+            vwap: trade.px,
+            trades: 0,
+        }
+    }
+
+    fn add(&mut self, trade: &Trade) {
+        self.high = self.high.max(trade.px);
+        self.low = self.low.min(trade.px);
+        self.close = trade.px;
+        self.volume += trade.sz;
+        match trade.side {
+            Side::Buy => self.buy_volume += trade.sz,
+            Side::Sell => self.sell_volume += trade.sz,
+        }
+        self.notional += trade.px * trade.sz;
+        if !self.volume.is_zero() {
+            self.vwap = self.notional / self.volume;
+        }
+        self.trades += 1;
+        if !matches!(self.kind, BarKind::Time(_)) {
+            self.close_time = self.close_time.max(trade.time);
+        }
+    }
+
+    /// Taker buy size minus taker sell size
+    pub fn imbalance(&self) -> Decimal {
+        self.buy_volume - self.sell_volume
+    }
+
+    /// Whether a threshold bar has reached its threshold (time bars close by the clock)
+    fn is_full(&self) -> bool {
+        match self.kind {
+            BarKind::Time(_) => false,
+            BarKind::Tick(n) => self.trades >= n,
+            BarKind::Volume(n) => self.volume >= n,
+            BarKind::Dollar(n) => self.notional >= n,
+            BarKind::Imbalance(n) => self.imbalance().abs() >= n,
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Builder
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Builds bars of one kind, per coin, from trades as they arrive
+#[derive(Debug, Clone)]
+pub struct BarBuilder {
+    kind: BarKind,
+    open: BTreeMap<String, Bar>,
+    closed: u64,
+}
+
+impl BarBuilder {
+    pub fn new(kind: BarKind) -> Self {
+        BarBuilder { kind, open: BTreeMap::new(), closed: 0 }
+    }
+
+    pub fn kind(&self) -> BarKind {
+        self.kind
+    }
+
+    /// Bars closed so far
+    pub fn closed(&self) -> u64 {
+        self.closed
+    }
+
+    /// The bar of `coin` still being built
+    pub fn open_bar(&self, coin: &str) -> Option<&Bar> {
+        self.open.get(coin)
+    }
+
+    /// Add one trade; returns the bar it closed, if any.
+    ///
+    /// For time bars that is the previous interval's bar, closed by the first
+    /// trade past its end (a trade older than the open bar is added to it).
+    /// For the others it is the bar this trade filled.
+    pub fn push(&mut self, trade: &Trade) -> Option<Bar> {
+        let mut done = None;
+        if let BarKind::Time(_) = self.kind {
+            if self.open.get(&trade.coin).is_some_and(|bar| trade.time > bar.close_time) {
+                done = self.open.remove(&trade.coin);
+            }
+        }
+        let kind = self.kind;
+        let bar = self.open.entry(trade.coin.clone()).or_insert_with(|| Bar::open(kind, trade));
+        bar.add(trade);
+        if bar.is_full() {
+            done = self.open.remove(&trade.coin);
+        }
+        if done.is_some() {
+            self.closed += 1;
+        }
+        done
+    }
+
+    /// Add every trade of a `trades` message from `stream.trades` or
+    /// `grpc.trades`; returns the bars they closed. Messages of other channels
+    /// are ignored.
+    pub fn ingest(&mut self, message: &Value) -> Result<Vec<Bar>> {
+        match stream_items::<Trade>(message, "trades") {
+            Some(trades) => Ok(trades?.iter().filter_map(|t| self.push(t)).collect()),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Close the time bars whose interval ended before `now_ms`
+    pub fn poll(&mut self, now_ms: u64) -> Vec<Bar> {
+        let ended: Vec<String> = self
+            .open
+            .values()
+            .filter(|bar| matches!(bar.kind, BarKind::Time(_)) && bar.close_time < now_ms)
+            .map(|bar| bar.coin.clone())
+            .collect();
+        self.closed += ended.len() as u64;
+        ended.iter().filter_map(|coin| self.open.remove(coin)).collect()
+    }
+
+    /// Take the bars still being built, complete or not
+    pub fn finish(&mut self) -> Vec<Bar> {
+        std::mem::take(&mut self.open).into_values().collect()
+    }
+}
+
+/// Closed bars of `kind` from trades in time order; the bar still being built
+/// after the last trade is left out.
+pub fn build(kind: BarKind, trades: &[Trade]) -> Vec<Bar> {
+    let mut builder = BarBuilder::new(kind);
+    trades.iter().filter_map(|t| builder.push(t)).collect()
+}
+
+/// Trades of a recording directory (or a single recorded file), in time
+/// order. A trade recorded from both WebSocket and gRPC is kept once.
+pub fn read_trades(path: &Path) -> Result<Vec<Trade>> {
+    let mut seen = HashSet::new();
+    let mut trades = Vec::new();
+    for file in recording_files(path)? {
+        for record in read_file(&file)? {
+            if record.channel != Channel::Trades {
+                continue;
+            }
+            if let Some(items) = stream_items::<Trade>(&record.data, "trades") {
+                trades.extend(items?.into_iter().filter(|t| seen.insert((t.coin.clone(), t.tid))));
+            }
+        }
+    }
+    trades.sort_by_key(|t| (t.time, t.tid));
+    Ok(trades)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn trade(time: u64, side: Side, px: &str, sz: &str) -> Trade {
+        Trade {
+            coin: "BTC".to_string(),
+            side,
+            px: dec(px),
+            sz: dec(sz),
+            time,
+            hash: "0x0".to_string(),
+            tid: time,
+            users: None,
+        }
+    }
+
+    #[test]
+    fn parses_and_prints_bar_kinds() {
+        for (text, kind) in [
+            ("1m", BarKind::Time(60_000)),
+            ("30s", BarKind::Time(30_000)),
+            ("time:4h", BarKind::Time(14_400_000)),
+            ("tick:500", BarKind::Tick(500)),
+            ("volume:10", BarKind::Volume(dec("10"))),
+            ("dollar:1000000", BarKind::Dollar(dec("1000000"))),
+            ("imbalance:2.5", BarKind::Imbalance(dec("2.5"))),
+        ] {
+            assert_eq!(text.parse::<BarKind>().unwrap(), kind, "{}", text);
+        }
+        assert_eq!(BarKind::Time(60_000).to_string(), "1m");
+        assert_eq!(BarKind::Volume(dec("10")).to_string(), "volume:10");
+        for bad in ["", "0s", "1737400000000", "tick:0", "volume:-1", "renko:10"] {
+            assert!(bad.parse::<BarKind>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn time_bars_align_to_the_interval_and_close_on_the_clock() {
+        let trades = [
+            trade(60_500, Side::Buy, "100", "1"),
+            trade(61_000, Side::Sell, "102", "2"),
+            trade(119_999, Side::Buy, "99", "1"),
+            trade(125_000, Side::Buy, "101", "3"),
+        ];
+        let bars = build(BarKind::Time(60_000), &trades);
+        assert_eq!(bars.len(), 1);
+        let bar = &bars[0];
+        assert_eq!((bar.open_time, bar.close_time), (60_000, 119_999));
+        assert_eq!((bar.open, bar.high, bar.low, bar.close), (dec("100"), dec("102"), dec("99"), dec("99")));
+        assert_eq!((bar.volume, bar.buy_volume, bar.sell_volume), (dec("4"), dec("2"), dec("2")));
+        assert_eq!(bar.vwap, dec("100.75"));
+        assert_eq!(bar.trades, 3);
+
+        let mut builder = BarBuilder::new(BarKind::Time(60_000));
+        for t in &trades {
+            builder.push(t);
+        }
+        assert!(builder.poll(179_999).is_empty());
+        let closed = builder.poll(180_000);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].open_time, 120_000);
+        assert_eq!(builder.closed(), 2);
+    }
+
+    #[test]
+    fn threshold_bars_close_on_the_trade_that_crosses() {
+        let trades = [
+            trade(1, Side::Buy, "100", "1"),
+            trade(2, Side::Buy, "100", "1"),
+            trade(3, Side::Sell, "100", "4"),
+            trade(4, Side::Buy, "100", "1"),
+        ];
+        let ticks = build(BarKind::Tick(2), &trades);
+        assert_eq!(ticks.iter().map(|b| b.trades).collect::<Vec<_>>(), [2, 2]);
+
+        // The 4 lot trade is not split: it closes the bar with 6
+        let volume = build(BarKind::Volume(dec("5")), &trades);
+        assert_eq!(volume.len(), 1);
+        assert_eq!((volume[0].volume, volume[0].open_time, volume[0].close_time), (dec("6"), 1, 3));
+
+        let dollar = build(BarKind::Dollar(dec("200")), &trades);
+        assert_eq!(dollar.iter().map(|b| b.notional).collect::<Vec<_>>(), [dec("200"), dec("400")]);
+
+        let imbalance = build(BarKind::Imbalance(dec("2")), &trades);
+        assert_eq!(imbalance.iter().map(|b| b.imbalance()).collect::<Vec<_>>(), [dec("2"), dec("-4")]);
+    }
+
+    #[test]
+    fn ingests_stream_messages_per_coin() {
+        let mut builder = BarBuilder::new(BarKind::Tick(1));
+        let message = json!({"channel": "trades", "data": [
+            {"coin": "BTC", "side": "B", "px": "96251.0", "sz": "0.01", "time": 1, "hash": "0x1", "tid": 1},
+            {"coin": "ETH", "side": "A", "px": "3300.5", "sz": "0.5", "time": 2, "hash": "0x2", "tid": 2},
+        ]});
+        let bars = builder.ingest(&message).unwrap();
+        assert_eq!(bars.iter().map(|b| b.coin.as_str()).collect::<Vec<_>>(), ["BTC", "ETH"]);
+        assert_eq!(bars[1].sell_volume, dec("0.5"));
+        assert!(builder.ingest(&json!({"channel": "l2Book", "data": {}})).unwrap().is_empty());
+    }
+}
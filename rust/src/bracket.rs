@@ -0,0 +1,694 @@
+//! Bracket orders: an entry with its take-profit and stop-loss attached.
+//!
+//! [`Bracket`] sends the entry and both triggers in one `order` action. With
+//! the default `normalTpsl` grouping the exchange holds the triggers back
+//! until the entry fills and cancels them with it; with `positionTpsl` they
+//! are attached to the position and close all of it when they fire.
+//!
+//! The exchange does not resize `normalTpsl` triggers to a partial fill, nor
+//! cancel one when the other fires. [`BracketOrder::sync`] does both: it
+//! reads the account's fills and open orders, works out the
+//! [`Adjustment`]s with [`BracketOrder::plan`] and sends them.
+//!
+//! ```no_run
+//! use hyperliquid_examples::bracket::Bracket;
+//! use hyperliquid_examples::models::TypedInfo;
+//! use hyperliquid_examples::{Config, Requires};
+//! use rust_decimal::Decimal;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = Config::load()?.require("bracket", Requires::Signer);
+//! let sdk = config.connect().await?;
+//! let mut order = Bracket::buy("BTC", Decimal::new(1, 3))
+//!     .take_profit(Decimal::from(101_000))
+//!     .stop_loss(Decimal::from(91_000))
+//!     .place(&config, &sdk)
+//!     .await?;
+//! let user = format!("{:?}", sdk.address().unwrap());
+//! let adjustments = order.sync(&config, &TypedInfo::from_sdk(&sdk), &user).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+
+use hyperliquid_sdk::HyperliquidSDK;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::dry_run::actions;
+use crate::error::{Error, Result};
+use crate::exchange::{self, rejection, OrderStatus};
+use crate::models::{Fill, OpenOrder, TypedInfo};
+use crate::price::{check_slippage, Precision, Rounding, DEFAULT_SLIPPAGE};
+use crate::registry::{AssetRegistry, Market};
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Bracket
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// How the take-profit and stop-loss are attached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Grouping {
+    /// Sized like the entry, active once it fills, cancelled with it
+    #[default]
+    NormalTpsl,
+    /// Attached to the position: close all of it when they fire
+    PositionTpsl,
+}
+
+impl Grouping {
+    /// Wire name
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Grouping::NormalTpsl => "normalTpsl",
+            Grouping::PositionTpsl => "positionTpsl",
+        }
+    }
+}
+
+impl fmt::Display for Grouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Builder of an entry order with a take-profit, a stop-loss, or both
+#[derive(Debug, Clone)]
+pub struct Bracket {
+    coin: String,
+    is_buy: bool,
+    size: Decimal,
+    limit_px: Option<Decimal>,
+    take_profit: Option<Decimal>,
+    stop_loss: Option<Decimal>,
+    grouping: Grouping,
+    slippage: Decimal,
+}
+
+impl Bracket {
+    /// Buy `size` of `coin`, at market unless given a [`limit`](Self::limit)
+    pub fn buy(coin: &str, size: Decimal) -> Self {
+        Self::new(coin, true, size)
+    }
+
+    /// Sell `size` of `coin`, at market unless given a [`limit`](Self::limit)
+    pub fn sell(coin: &str, size: Decimal) -> Self {
+        Self::new(coin, false, size)
+    }
+
+    fn new(coin: &str, is_buy: bool, size: Decimal) -> Self {
+        Self {
+            coin: coin.to_string(),
+            is_buy,
+            size,
+            limit_px: None,
+            take_profit: None,
+            stop_loss: None,
+            grouping: Grouping::NormalTpsl,
+            slippage: DEFAULT_SLIPPAGE,
+        }
+    }
+
+    /// Rest the entry at `px` (GTC) instead of crossing the spread
+    pub fn limit(mut self, px: Decimal) -> Self {
+        self.limit_px = Some(px);
+        self
+    }
+
+    /// Close at market once the price reaches `px` in our favour
+    pub fn take_profit(mut self, px: Decimal) -> Self {
+        self.take_profit = Some(px);
+        self
+    }
+
+    /// Close at market once the price reaches `px` against us
+    pub fn stop_loss(mut self, px: Decimal) -> Self {
+        self.stop_loss = Some(px);
+        self
+    }
+
+    /// Attach the take-profit and stop-loss to the position instead
+    pub fn position_tpsl(mut self) -> Self {
+        self.grouping = Grouping::PositionTpsl;
+        self
+    }
+
+    /// Worst price of market orders, as a fraction (default 3%)
+    pub fn slippage(mut self, fraction: Decimal) -> Self {
+        self.slippage = fraction;
+        self
+    }
+
+    /// Check the prices against the entry price (the limit, else `mid`) and
+    /// round everything onto `market`'s grid
+    pub fn build(&self, market: &Market, mid: Decimal) -> Result<BracketOrder> {
+        if !market.is_perp() {
+            return Err(Error::ValidationError(format!(
+                "{} is a spot market; brackets need a perp",
+                market.symbol
+            )));
+        }
+        if self.take_profit.is_none() && self.stop_loss.is_none() {
+            return Err(Error::ValidationError(
+                "A bracket needs a take profit, a stop loss or both".to_string(),
+            ));
+        }
+        check_slippage(self.slippage)?;
+
+        let precision = market.precision();
+        let size = precision.sz(self.size, Rounding::Down)?.value();
+        let entry_px = match self.limit_px {
+            Some(px) => precision.px_away_from(px, mid)?.value(),
+            None => precision.px_toward(self.worst(self.is_buy, mid), mid)?.value(),
+        };
+        let reference = self.limit_px.map(|_| entry_px).unwrap_or(mid);
+
+        // Above the entry is a profit for a long, a loss for a short
+        let above = |px: Decimal| px > reference;
+        let side = if self.is_buy { "buy" } else { "sell" };
+        if let Some(px) = self.take_profit.filter(|px| above(*px) != self.is_buy || *px == reference) {
+            return Err(Error::ValidationError(format!(
+                "Take profit {} must be {} the entry price {} for a {}",
+                px,
+                if self.is_buy { "above" } else { "below" },
+                reference,
+                side
+            )));
+        }
+        if let Some(px) = self.stop_loss.filter(|px| above(*px) == self.is_buy || *px == reference) {
+            return Err(Error::ValidationError(format!(
+                "Stop loss {} must be {} the entry price {} for a {}",
+                px,
+                if self.is_buy { "below" } else { "above" },
+                reference,
+                side
+            )));
+        }
+
+        let cloids = [exchange::cloid(), exchange::cloid(), exchange::cloid()];
+        let child = |kind: LegKind, trigger: Decimal, cloid: String| -> Result<Leg> {
+            let trigger_px = precision.px(trigger, Rounding::Nearest)?.value();
+            let limit_px = precision.px_toward(self.worst(!self.is_buy, trigger_px), trigger_px)?.value();
+            Ok(Leg::new(kind, cloid, limit_px, Some(trigger_px), size))
+        };
+        Ok(BracketOrder {
+            coin: market.symbol.clone(),
+            asset_id: market.asset_id,
+            precision,
+            is_buy: self.is_buy,
+            is_market: self.limit_px.is_none(),
+            grouping: self.grouping,
+            entry: Leg::new(LegKind::Entry, cloids[0].clone(), entry_px, None, size),
+            take_profit: self.take_profit.map(|px| child(LegKind::TakeProfit, px, cloids[1].clone())).transpose()?,
+            stop_loss: self.stop_loss.map(|px| child(LegKind::StopLoss, px, cloids[2].clone())).transpose()?,
+            status: BracketStatus::Pending,
+        })
+    }
+
+    /// Build the bracket at the current mid and send it as one action
+    pub async fn place(&self, config: &Config, sdk: &HyperliquidSDK) -> Result<BracketOrder> {
+        let registry = AssetRegistry::load(sdk).await?;
+        let market = registry.resolve(&self.coin)?;
+        let mid = TypedInfo::from_sdk(sdk).mid(&market.symbol).await?;
+        let mut order = self.build(market, mid)?;
+        let response = exchange::send(config, &order.action(), None).await?;
+        order.placed(&response)?;
+        Ok(order)
+    }
+
+    /// Worst acceptable price of a market order on a side
+    fn worst(&self, is_buy: bool, px: Decimal) -> Decimal {
+        match is_buy {
+            true => px * (Decimal::ONE + self.slippage),
+            false => px * (Decimal::ONE - self.slippage),
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// BracketOrder
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// One order of a bracket
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegKind {
+    Entry,
+    TakeProfit,
+    StopLoss,
+}
+
+impl LegKind {
+    /// `tp` or `sl` on the wire (the entry has none)
+    fn tpsl(self) -> &'static str {
+        match self {
+            LegKind::StopLoss => "sl",
+            _ => "tp",
+        }
+    }
+}
+
+impl fmt::Display for LegKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LegKind::Entry => "entry",
+            LegKind::TakeProfit => "take profit",
+            LegKind::StopLoss => "stop loss",
+        })
+    }
+}
+
+/// State of one order of a placed bracket, as of the last sync
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leg {
+    pub kind: LegKind,
+    pub cloid: String,
+    /// Known once the order rests (triggers waiting for the entry have none yet)
+    pub oid: Option<u64>,
+    /// Limit price; the worst fill price for the entry at market and the triggers
+    pub limit_px: Decimal,
+    pub trigger_px: Option<Decimal>,
+    /// Size the order is open for
+    pub size: Decimal,
+    pub filled: Decimal,
+    /// Average fill price, once filled
+    pub avg_px: Option<Decimal>,
+    /// On the book or waiting for its trigger
+    pub open: bool,
+}
+
+impl Leg {
+    fn new(kind: LegKind, cloid: String, limit_px: Decimal, trigger_px: Option<Decimal>, size: Decimal) -> Self {
+        Self {
+            kind,
+            cloid,
+            oid: None,
+            limit_px,
+            trigger_px,
+            size,
+            filled: Decimal::ZERO,
+            avg_px: None,
+            open: false,
+        }
+    }
+
+    fn is(&self, cloid: Option<&str>, oid: u64) -> bool {
+        cloid == Some(self.cloid.as_str()) || self.oid == Some(oid)
+    }
+}
+
+/// Where a bracket stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketStatus {
+    /// Entry open, nothing filled
+    Pending,
+    /// Entry (partly) filled, the take-profit and stop-loss protect it
+    Open,
+    /// Closed by this leg; the other one is cancelled
+    Closed(LegKind),
+    /// Entry cancelled or rejected before it filled
+    Cancelled,
+}
+
+impl fmt::Display for BracketStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BracketStatus::Pending => f.write_str("pending"),
+            BracketStatus::Open => f.write_str("open"),
+            BracketStatus::Closed(leg) => write!(f, "closed by {}", leg),
+            BracketStatus::Cancelled => f.write_str("cancelled"),
+        }
+    }
+}
+
+/// Change [`BracketOrder::sync`] sends to keep the legs consistent
+#[derive(Debug, Clone, PartialEq)]
+pub enum Adjustment {
+    /// Resize a trigger to the entry's filled size
+    Resize { leg: LegKind, oid: u64, from: Decimal, to: Decimal },
+    /// Cancel a trigger whose sibling fired, or whose entry is gone
+    Cancel { leg: LegKind, oid: u64 },
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Adjustment::Resize { leg, oid, from, to } => write!(f, "Resize {} (oid {}): {} -> {}", leg, oid, from, to),
+            Adjustment::Cancel { leg, oid } => write!(f, "Cancel {} (oid {})", leg, oid),
+        }
+    }
+}
+
+/// A bracket built by [`Bracket::build`], tracked by its legs' cloids
+#[derive(Debug, Clone)]
+pub struct BracketOrder {
+    pub coin: String,
+    pub asset_id: u32,
+    precision: Precision,
+    pub is_buy: bool,
+    /// Entry is an IOC at the worst price instead of a GTC limit
+    pub is_market: bool,
+    pub grouping: Grouping,
+    pub entry: Leg,
+    pub take_profit: Option<Leg>,
+    pub stop_loss: Option<Leg>,
+    pub status: BracketStatus,
+}
+
+impl BracketOrder {
+    /// The `order` action sending the entry and its triggers together
+    pub fn action(&self) -> Value {
+        let orders: Vec<Value> = self.legs().map(|leg| self.wire(leg, leg.size)).collect();
+        json!({
+            "type": "order",
+            "orders": orders,
+            "grouping": self.grouping.as_str(),
+        })
+    }
+
+    /// The entry, take-profit and stop-loss (those that are set)
+    pub fn legs(&self) -> impl Iterator<Item = &Leg> {
+        std::iter::once(&self.entry).chain(&self.take_profit).chain(&self.stop_loss)
+    }
+
+    fn legs_mut(&mut self) -> impl Iterator<Item = &mut Leg> {
+        std::iter::once(&mut self.entry).chain(&mut self.take_profit).chain(&mut self.stop_loss)
+    }
+
+    fn children(&self) -> impl Iterator<Item = &Leg> {
+        self.take_profit.iter().chain(&self.stop_loss)
+    }
+
+    /// Wire form of `leg` for `size`
+    fn wire(&self, leg: &Leg, size: Decimal) -> Value {
+        let order_type = match leg.trigger_px {
+            Some(px) => json!({"trigger": {"isMarket": true, "triggerPx": px.to_string(), "tpsl": leg.kind.tpsl()}}),
+            None => json!({"limit": {"tif": if self.is_market { "Ioc" } else { "Gtc" }}}),
+        };
+        let is_child = leg.kind != LegKind::Entry;
+        json!({
+            "a": self.asset_id,
+            "b": self.is_buy != is_child,
+            "p": leg.limit_px.to_string(),
+            "s": size.normalize().to_string(),
+            "r": is_child,
+            "t": order_type,
+            "c": leg.cloid,
+        })
+    }
+
+    /// Record the statuses the exchange answered [`action`](Self::action) with.
+    ///
+    /// Fails when the entry was rejected; a rejected trigger only stays closed.
+    pub fn placed(&mut self, response: &Value) -> Result<()> {
+        let statuses = OrderStatus::all(response)
+            .map_err(|reason| Error::ValidationError(format!("Bracket rejected: {}", reason)))?;
+        let is_market = self.is_market;
+        for (leg, status) in self.legs_mut().zip(&statuses) {
+            match status {
+                OrderStatus::Resting { oid } => {
+                    leg.oid = Some(*oid);
+                    leg.open = true;
+                }
+                OrderStatus::Filled { oid, total_sz, avg_px } => {
+                    leg.oid = Some(*oid);
+                    leg.filled = *total_sz;
+                    leg.avg_px = Some(*avg_px);
+                    leg.open = leg.filled < leg.size && leg.kind == LegKind::Entry && !is_market;
+                }
+                // Waiting for the entry: open, without an oid until it rests
+                OrderStatus::WaitingForFill => leg.open = true,
+                OrderStatus::Error(_) => {}
+            }
+        }
+        if let Some(OrderStatus::Error(reason)) = statuses.first() {
+            self.status = BracketStatus::Cancelled;
+            return Err(Error::ValidationError(format!("Bracket entry rejected: {}", reason)));
+        }
+        if self.entry.filled > Decimal::ZERO {
+            self.status = BracketStatus::Open;
+        }
+        Ok(())
+    }
+
+    /// Update the legs from the account's `fills` and `open` orders and work
+    /// out what to change:
+    ///
+    /// - once a trigger has filled, cancel the other one;
+    /// - while the entry fills, resize `normalTpsl` triggers to the filled
+    ///   size (`positionTpsl` ones are sized when they fire);
+    /// - when the entry is gone without filling, cancel the triggers.
+    pub fn plan(&mut self, fills: &[Fill], open: &[OpenOrder]) -> Vec<Adjustment> {
+        for leg in self.legs_mut() {
+            let mine: Vec<&Fill> = fills.iter().filter(|f| leg.is(f.cloid.as_deref(), f.oid)).collect();
+            if !mine.is_empty() {
+                leg.filled = mine.iter().map(|f| f.sz).sum();
+                let notional: Decimal = mine.iter().map(|f| f.px * f.sz).sum();
+                leg.avg_px = Some((notional / leg.filled).round_dp(8).normalize());
+            }
+            match open.iter().find(|o| leg.is(o.cloid.as_deref(), o.oid)) {
+                Some(order) => {
+                    leg.oid = Some(order.oid);
+                    leg.size = order.sz;
+                    leg.open = true;
+                }
+                None => leg.open = false,
+            }
+        }
+
+        let open_children = |order: &Self| -> Vec<(LegKind, u64)> {
+            order
+                .children()
+                .filter(|leg| leg.open)
+                .filter_map(|leg| leg.oid.map(|oid| (leg.kind, oid)))
+                .collect()
+        };
+        let closed_by = self.children().find(|leg| leg.filled > Decimal::ZERO).map(|leg| leg.kind);
+        if let Some(closed_by) = closed_by {
+            self.status = BracketStatus::Closed(closed_by);
+            return open_children(self)
+                .into_iter()
+                .filter(|(kind, _)| *kind != closed_by)
+                .map(|(leg, oid)| Adjustment::Cancel { leg, oid })
+                .collect();
+        }
+        if self.entry.filled.is_zero() {
+            if self.entry.open {
+                self.status = BracketStatus::Pending;
+                return Vec::new();
+            }
+            self.status = BracketStatus::Cancelled;
+            return open_children(self).into_iter().map(|(leg, oid)| Adjustment::Cancel { leg, oid }).collect();
+        }
+
+        self.status = BracketStatus::Open;
+        if self.grouping == Grouping::PositionTpsl {
+            return Vec::new();
+        }
+        let filled = self.entry.filled;
+        self.children()
+            .filter(|leg| leg.open && leg.size != filled)
+            .filter_map(|leg| leg.oid.map(|oid| Adjustment::Resize { leg: leg.kind, oid, from: leg.size, to: filled }))
+            .collect()
+    }
+
+    /// The action that makes `adjustment`
+    pub fn adjustment_action(&self, adjustment: &Adjustment) -> Value {
+        match adjustment {
+            Adjustment::Resize { leg, oid, to, .. } => {
+                let leg = self.children().find(|l| l.kind == *leg).unwrap_or(&self.entry);
+                json!({
+                    "type": "batchModify",
+                    "modifies": [{"oid": oid, "order": self.wire(leg, *to)}],
+                })
+            }
+            Adjustment::Cancel { oid, .. } => actions::cancel(&[(self.asset_id, *oid)]),
+        }
+    }
+
+    /// Read `user`'s fills and open orders, then send the
+    /// [`plan`](Self::plan)ned adjustments; returns those that were made
+    pub async fn sync(&mut self, config: &Config, info: &TypedInfo, user: &str) -> Result<Vec<Adjustment>> {
+        let fills = info.user_fills(user).await?;
+        let open = info.frontend_open_orders(user, None).await?;
+        let mut made = Vec::new();
+        for adjustment in self.plan(&fills, &open) {
+            let response = exchange::send(config, &self.adjustment_action(&adjustment), None).await?;
+            if let Some(reason) = rejection(&response) {
+                return Err(Error::ValidationError(format!("{} rejected: {}", adjustment, reason)));
+            }
+            self.adjusted(&adjustment);
+            made.push(adjustment);
+        }
+        Ok(made)
+    }
+
+    /// Record a made adjustment (the next sync reads the new state anyway)
+    fn adjusted(&mut self, adjustment: &Adjustment) {
+        let (kind, size) = match adjustment {
+            Adjustment::Resize { leg, to, .. } => (*leg, Some(*to)),
+            Adjustment::Cancel { leg, .. } => (*leg, None),
+        };
+        if let Some(leg) = self.legs_mut().find(|l| l.kind == kind) {
+            match size {
+                Some(size) => leg.size = size,
+                None => leg.open = false,
+            }
+        }
+    }
+
+    /// Lot size and tick of the bracket's market
+    pub fn precision(&self) -> Precision {
+        self.precision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::testing::{book, btc, dec};
+    use crate::mock::{MatchingEngine, Script};
+    use crate::models::{Side, Trade};
+
+    fn engine() -> MatchingEngine {
+        MatchingEngine::new(&Script::default_market()).unwrap()
+    }
+
+    /// `order` after syncing against the engine, with the adjustments made
+    fn sync(order: &mut BracketOrder, engine: &mut MatchingEngine) -> Vec<Adjustment> {
+        let adjustments = order.plan(&engine.user_fills(), &engine.open_orders());
+        for adjustment in &adjustments {
+            let response = engine.execute(&order.adjustment_action(adjustment));
+            assert_eq!(rejection(&response), None, "{}", adjustment);
+        }
+        adjustments
+    }
+
+    #[test]
+    fn builds_one_grouped_action_with_reduce_only_triggers() {
+        let engine = engine();
+        let order = Bracket::sell("BTC", dec("0.0101"))
+            .take_profit(dec("90000.4"))
+            .stop_loss(dec("101000"))
+            .build(&btc(&engine), dec("96250.5"))
+            .unwrap();
+        let action = order.action();
+
+        assert_eq!(action["grouping"], "normalTpsl");
+        let orders = action["orders"].as_array().unwrap();
+        assert_eq!(orders.len(), 3);
+        // Entry sells at market: IOC 3% under the mid
+        assert_eq!(orders[0]["b"], false);
+        assert_eq!(orders[0]["t"]["limit"]["tif"], "Ioc");
+        assert_eq!(orders[0]["p"], "93363");
+        assert_eq!(orders[0]["s"], "0.0101");
+        // Triggers buy back, reduce-only, with the same size
+        assert_eq!(orders[1]["b"], true);
+        assert_eq!(orders[1]["r"], true);
+        assert_eq!(orders[1]["t"]["trigger"]["tpsl"], "tp");
+        assert_eq!(orders[1]["t"]["trigger"]["triggerPx"], "90000");
+        assert_eq!(orders[2]["t"]["trigger"]["tpsl"], "sl");
+        assert_eq!(orders[2]["p"], "104030");
+        assert_eq!(orders[2]["s"], "0.0101");
+        let cloids: Vec<&Value> = orders.iter().map(|o| &o["c"]).collect();
+        assert!(cloids[0] != cloids[1] && cloids[1] != cloids[2]);
+    }
+
+    #[test]
+    fn rejects_triggers_on_the_wrong_side_of_the_entry() {
+        let engine = engine();
+        let market = btc(&engine);
+        let build = |bracket: Bracket| bracket.build(&market, dec("96250.5")).unwrap_err().to_string();
+
+        assert!(build(Bracket::buy("BTC", Decimal::ONE)).contains("needs a take profit, a stop loss or both"));
+        let tp = build(Bracket::buy("BTC", Decimal::ONE).take_profit(dec("95000")));
+        assert!(tp.contains("Take profit 95000 must be above the entry price 96250.5 for a buy"), "{}", tp);
+        let sl = build(Bracket::buy("BTC", Decimal::ONE).limit(dec("90000")).stop_loss(dec("92000")));
+        assert!(sl.contains("Stop loss 92000 must be below the entry price 90000 for a buy"), "{}", sl);
+        let short = build(Bracket::sell("BTC", Decimal::ONE).stop_loss(dec("95000")));
+        assert!(short.contains("must be above the entry price"), "{}", short);
+    }
+
+    #[test]
+    fn resizes_triggers_to_partial_fills_then_cancels_the_sibling() {
+        let mut engine = engine();
+        // Rests below the book: nothing fills yet
+        let mut order = Bracket::buy("BTC", Decimal::ONE)
+            .limit(dec("96240"))
+            .take_profit(dec("100000"))
+            .stop_loss(dec("95000"))
+            .build(&btc(&engine), dec("96250.5"))
+            .unwrap();
+        let response = engine.execute(&order.action());
+        order.placed(&response).unwrap();
+        assert_eq!(order.status, BracketStatus::Pending);
+        assert!(order.take_profit.as_ref().unwrap().open);
+        assert_eq!(order.take_profit.as_ref().unwrap().oid, None);
+        assert!(sync(&mut order, &mut engine).is_empty());
+        assert!(order.take_profit.as_ref().unwrap().oid.is_some());
+
+        // A trade through the entry fills 0.4 of it
+        engine.on_trade(&trade(&engine, "96239", "0.4"));
+        let adjustments = sync(&mut order, &mut engine);
+        assert_eq!(order.status, BracketStatus::Open);
+        assert_eq!(order.entry.filled, dec("0.4"));
+        assert_eq!(adjustments.len(), 2);
+        assert!(matches!(adjustments[0], Adjustment::Resize { leg: LegKind::TakeProfit, to, .. } if to == dec("0.4")));
+        assert_eq!(trigger_sizes(&engine), vec![dec("0.4"), dec("0.4")]);
+        assert!(sync(&mut order, &mut engine).is_empty());
+
+        // Then the rest
+        engine.on_trade(&trade(&engine, "96239", "1"));
+        assert_eq!(sync(&mut order, &mut engine).len(), 2);
+        assert_eq!(trigger_sizes(&engine), vec![Decimal::ONE, Decimal::ONE]);
+
+        // The stop fires and closes the position: the take-profit goes
+        engine.set_book(&book(&engine, "94000", "94010"));
+        let adjustments = sync(&mut order, &mut engine);
+        assert_eq!(order.status, BracketStatus::Closed(LegKind::StopLoss));
+        assert!(matches!(adjustments[..], [Adjustment::Cancel { leg: LegKind::TakeProfit, .. }]));
+        assert!(engine.open_orders().is_empty());
+        assert!(engine.clearinghouse_state().asset_positions.is_empty());
+    }
+
+    #[test]
+    fn cancels_position_triggers_when_the_entry_is_cancelled() {
+        let mut engine = engine();
+        let mut order = Bracket::buy("BTC", Decimal::ONE)
+            .limit(dec("96240"))
+            .stop_loss(dec("95000"))
+            .position_tpsl()
+            .build(&btc(&engine), dec("96250.5"))
+            .unwrap();
+        let response = engine.execute(&order.action());
+        order.placed(&response).unwrap();
+        assert_eq!(order.action()["grouping"], "positionTpsl");
+        assert!(sync(&mut order, &mut engine).is_empty());
+
+        let entry = order.entry.oid.unwrap();
+        engine.execute(&actions::cancel(&[(order.asset_id, entry)]));
+        let adjustments = sync(&mut order, &mut engine);
+        assert_eq!(order.status, BracketStatus::Cancelled);
+        assert!(matches!(adjustments[..], [Adjustment::Cancel { leg: LegKind::StopLoss, .. }]));
+        assert!(engine.open_orders().is_empty());
+    }
+
+    fn trigger_sizes(engine: &MatchingEngine) -> Vec<Decimal> {
+        engine.open_orders().iter().filter(|o| o.is_trigger == Some(true)).map(|o| o.sz).collect()
+    }
+
+    /// A public sell at `px`
+    fn trade(engine: &MatchingEngine, px: &str, sz: &str) -> Trade {
+        Trade {
+            coin: "BTC".to_string(),
+            side: Side::Sell,
+            px: dec(px),
+            sz: dec(sz),
+            time: engine.now() + 1000,
+            hash: String::new(),
+            tid: 1,
+            users: None,
+        }
+    }
+}
@@ -0,0 +1,398 @@
+//! Local candle history in SQLite.
+//!
+//! [`CandleStore`] keeps one row per `(coin, interval, open_time)` with the
+//! prices as exact decimal text. [`backfill`] fills it for any coin and
+//! interval: first forward from the newest stored candle to now, then
+//! backwards one [`CANDLE_PAGE`](crate::export::CANDLE_PAGE) window at a time
+//! until the requested start, or until the API has nothing older (it only
+//! serves the most recent candles of each interval). Pages are stored as
+//! they arrive, so an interrupted backfill resumes where it stopped.
+//!
+//! [`CandleStore::gaps`] lists missing bars between the oldest and newest
+//! stored candle and [`refill_gaps`] requests them again; bars the API still
+//! does not return (no trades in that interval) stay listed. The open candle
+//! is kept current from the `candle` WebSocket channel with
+//! [`CandleStore::apply_stream`]; every candle is marked closed once its
+//! close time has passed or a newer one arrives.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use rust_decimal::Decimal;
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::export::{self, interval_ms, CANDLE_PAGE};
+use crate::models::{stream_items, Candle, TypedInfo};
+
+/// Intervals the store accepts; each bar starts on a multiple of its length (UTC)
+pub const INTERVALS: [&str; 7] = ["1m", "5m", "15m", "30m", "1h", "4h", "1d"];
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS candles (
+        coin       TEXT    NOT NULL,
+        interval   TEXT    NOT NULL,
+        open_time  INTEGER NOT NULL,
+        close_time INTEGER NOT NULL,
+        open       TEXT    NOT NULL,
+        high       TEXT    NOT NULL,
+        low        TEXT    NOT NULL,
+        close      TEXT    NOT NULL,
+        volume     TEXT    NOT NULL,
+        trades     INTEGER NOT NULL,
+        closed     INTEGER NOT NULL,
+        PRIMARY KEY (coin, interval, open_time)
+    ) WITHOUT ROWID;
+";
+
+const COLUMNS: &str = "open_time, close_time, coin, interval, open, high, low, close, volume, trades";
+
+/// Stored candles of one coin and interval
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Series {
+    pub coin: String,
+    pub interval: String,
+    pub candles: u64,
+    /// Open time of the oldest and newest candle (ms)
+    pub first: u64,
+    pub last: u64,
+}
+
+/// Run of missing bars, by open time (ms)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gap {
+    /// Open time of the first missing bar
+    pub from: u64,
+    /// Open time of the last missing bar
+    pub to: u64,
+    pub bars: u64,
+}
+
+/// What a [`backfill`] did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Backfill {
+    pub requests: u32,
+    /// Candles received and stored (new or updated)
+    pub stored: usize,
+    /// The API had nothing older before the requested start was reached
+    pub exhausted: bool,
+}
+
+/// Candles by coin and interval in a SQLite file
+pub struct CandleStore {
+    db: Connection,
+}
+
+impl CandleStore {
+    /// Open (or create) the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Database that lives only as long as the store
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(db: Connection) -> Result<Self> {
+        db.execute_batch(SCHEMA)?;
+        Ok(CandleStore { db })
+    }
+
+    /// Insert or replace candles; those whose close time is before `now_ms`
+    /// are marked closed. Returns the number written.
+    pub fn upsert(&mut self, candles: &[Candle], now_ms: u64) -> Result<usize> {
+        let tx = self.db.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(&format!(
+                "INSERT OR REPLACE INTO candles ({}, closed) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                COLUMNS
+            ))?;
+            for c in candles {
+                check_interval(&c.interval)?;
+                insert.execute(params![
+                    c.open_time as i64,
+                    c.close_time as i64,
+                    c.coin,
+                    c.interval,
+                    c.open.to_string(),
+                    c.high.to_string(),
+                    c.low.to_string(),
+                    c.close.to_string(),
+                    c.volume.to_string(),
+                    c.trades as i64,
+                    c.close_time < now_ms,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(candles.len())
+    }
+
+    /// Upsert the candles of a `candle` stream message; a newer candle closes
+    /// the ones before it. Returns the candles written (none for other channels).
+    pub fn apply_stream(&mut self, message: &Value, now_ms: u64) -> Result<Vec<Candle>> {
+        let candles: Vec<Candle> = match stream_items(message, "candle") {
+            Some(candles) => candles?,
+            None => return Ok(Vec::new()),
+        };
+        self.upsert(&candles, now_ms)?;
+        for c in &candles {
+            self.db.execute(
+                "UPDATE candles SET closed = 1 WHERE coin = ?1 AND interval = ?2 AND open_time < ?3 AND closed = 0",
+                params![c.coin, c.interval, c.open_time as i64],
+            )?;
+        }
+        Ok(candles)
+    }
+
+    /// Candles that open between `from` and `to` (ms), oldest first
+    pub fn candles(&self, coin: &str, interval: &str, from: u64, to: u64) -> Result<Vec<Candle>> {
+        let mut query = self.db.prepare_cached(&format!(
+            "SELECT {} FROM candles WHERE coin = ?1 AND interval = ?2 AND open_time BETWEEN ?3 AND ?4 ORDER BY open_time",
+            COLUMNS
+        ))?;
+        let rows = query.query_map(params![coin, interval, from as i64, to.min(i64::MAX as u64) as i64], candle)?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Oldest stored candle
+    pub fn first(&self, coin: &str, interval: &str) -> Result<Option<Candle>> {
+        self.one(coin, interval, "ORDER BY open_time LIMIT 1")
+    }
+
+    /// Newest stored candle
+    pub fn last(&self, coin: &str, interval: &str) -> Result<Option<Candle>> {
+        self.one(coin, interval, "ORDER BY open_time DESC LIMIT 1")
+    }
+
+    /// Newest candle that has not closed yet
+    pub fn open_candle(&self, coin: &str, interval: &str) -> Result<Option<Candle>> {
+        self.one(coin, interval, "AND closed = 0 ORDER BY open_time DESC LIMIT 1")
+    }
+
+    fn one(&self, coin: &str, interval: &str, clause: &str) -> Result<Option<Candle>> {
+        let sql = format!("SELECT {} FROM candles WHERE coin = ?1 AND interval = ?2 {}", COLUMNS, clause);
+        Ok(self.db.query_row(&sql, params![coin, interval], candle).optional()?)
+    }
+
+    /// Every stored coin and interval
+    pub fn series(&self) -> Result<Vec<Series>> {
+        let mut query = self.db.prepare(
+            "SELECT coin, interval, count(*), min(open_time), max(open_time) FROM candles GROUP BY coin, interval ORDER BY coin, interval",
+        )?;
+        let rows = query.query_map([], |row| {
+            Ok(Series {
+                coin: row.get(0)?,
+                interval: row.get(1)?,
+                candles: row.get::<_, i64>(2)? as u64,
+                first: row.get::<_, i64>(3)? as u64,
+                last: row.get::<_, i64>(4)? as u64,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Missing bars between the oldest and newest stored candle
+    pub fn gaps(&self, coin: &str, interval: &str) -> Result<Vec<Gap>> {
+        let step = check_interval(interval)?;
+        let mut query = self
+            .db
+            .prepare_cached("SELECT open_time FROM candles WHERE coin = ?1 AND interval = ?2 ORDER BY open_time")?;
+        let times = query.query_map(params![coin, interval], |row| row.get::<_, i64>(0))?;
+
+        let mut gaps = Vec::new();
+        let mut expected: Option<u64> = None;
+        for time in times {
+            let time = time? as u64;
+            if let Some(next) = expected.filter(|next| time > *next) {
+                let to = time - step;
+                gaps.push(Gap { from: next, to, bars: (to - next) / step + 1 });
+            }
+            expected = Some(time + step);
+        }
+        Ok(gaps)
+    }
+}
+
+/// Length of `interval` in ms, if the store accepts it
+fn check_interval(interval: &str) -> Result<u64> {
+    if !INTERVALS.contains(&interval) {
+        return Err(Error::ValidationError(format!(
+            "unsupported interval '{}' (use {})",
+            interval,
+            INTERVALS.join(", ")
+        )));
+    }
+    interval_ms(interval)
+}
+
+fn candle(row: &Row) -> rusqlite::Result<Candle> {
+    let decimal = |i: usize| -> rusqlite::Result<Decimal> {
+        let text: String = row.get(i)?;
+        Decimal::from_str(&text).map_err(|e| rusqlite::Error::FromSqlConversionFailure(i, rusqlite::types::Type::Text, Box::new(e)))
+    };
+    Ok(Candle {
+        open_time: row.get::<_, i64>(0)? as u64,
+        close_time: row.get::<_, i64>(1)? as u64,
+        coin: row.get(2)?,
+        interval: row.get(3)?,
+        open: decimal(4)?,
+        high: decimal(5)?,
+        low: decimal(6)?,
+        close: decimal(7)?,
+        volume: decimal(8)?,
+        trades: row.get::<_, i64>(9)? as u64,
+    })
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Backfill
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Fill `coin`/`interval` between `since_ms` and `until_ms`: forward from the
+/// newest stored candle, then backwards from the oldest. `now_ms` decides
+/// which candles are closed.
+pub async fn backfill(
+    store: &mut CandleStore,
+    info: &TypedInfo,
+    coin: &str,
+    interval: &str,
+    since_ms: u64,
+    until_ms: u64,
+    now_ms: u64,
+) -> Result<Backfill> {
+    let step = check_interval(interval)?;
+    let window = step * CANDLE_PAGE as u64;
+    let mut report = Backfill::default();
+
+    // Forward: from the newest stored candle (it may still have been open)
+    if let Some(last) = store.last(coin, interval)?.filter(|c| c.open_time < until_ms) {
+        let candles = export::candles(info, coin, interval, last.open_time, until_ms).await?;
+        report.requests += (until_ms.saturating_sub(last.open_time) / window + 1) as u32;
+        report.stored += store.upsert(&candles, now_ms)?;
+    }
+
+    // Backwards: one window before the oldest stored candle at a time
+    let mut end = match store.first(coin, interval)? {
+        Some(first) => first.open_time.saturating_sub(1).min(until_ms),
+        None => until_ms,
+    };
+    while end >= since_ms && end > 0 {
+        let start = end.saturating_sub(window - 1).max(since_ms);
+        let candles: Vec<Candle> = info
+            .candles(coin, interval, start, Some(end))
+            .await?
+            .into_iter()
+            .filter(|c| (start..=end).contains(&c.open_time))
+            .collect();
+        report.requests += 1;
+        if candles.is_empty() {
+            report.exhausted = true;
+            break;
+        }
+        report.stored += store.upsert(&candles, now_ms)?;
+        end = start.saturating_sub(1);
+    }
+    Ok(report)
+}
+
+/// Request every gap of `coin`/`interval` again; returns the gaps that remain
+pub async fn refill_gaps(store: &mut CandleStore, info: &TypedInfo, coin: &str, interval: &str, now_ms: u64) -> Result<Vec<Gap>> {
+    let step = check_interval(interval)?;
+    for gap in store.gaps(coin, interval)? {
+        let candles = export::candles(info, coin, interval, gap.from, gap.to + step - 1).await?;
+        store.upsert(&candles, now_ms)?;
+    }
+    store.gaps(coin, interval)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const HOUR: u64 = 3_600_000;
+    /// 2025-01-20T21:00:00Z
+    const T0: u64 = 1_737_406_800_000;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn candle(open_time: u64, close: &str) -> Candle {
+        Candle {
+            open_time,
+            close_time: open_time + HOUR - 1,
+            coin: "BTC".to_string(),
+            interval: "1h".to_string(),
+            open: dec("95870.0"),
+            high: dec("96410.0"),
+            low: dec("95702.0"),
+            close: dec(close),
+            volume: dec("1532.48291"),
+            trades: 18234,
+        }
+    }
+
+    #[test]
+    fn upserts_and_reads_back_exact_decimals() {
+        let mut store = CandleStore::in_memory().unwrap();
+        let candles = [candle(T0, "96301.0"), candle(T0 + HOUR, "96250.123456789")];
+        assert_eq!(store.upsert(&candles, T0 + HOUR).unwrap(), 2);
+        assert_eq!(store.candles("BTC", "1h", 0, u64::MAX).unwrap(), candles);
+
+        // The second candle is still open; a later update replaces it
+        assert_eq!(store.open_candle("BTC", "1h").unwrap(), Some(candles[1].clone()));
+        store.upsert(&[candle(T0 + HOUR, "96260.0")], T0 + 2 * HOUR).unwrap();
+        assert_eq!(store.last("BTC", "1h").unwrap().unwrap().close, dec("96260.0"));
+        assert_eq!(store.open_candle("BTC", "1h").unwrap(), None);
+        assert_eq!(store.series().unwrap(), [Series {
+            coin: "BTC".to_string(),
+            interval: "1h".to_string(),
+            candles: 2,
+            first: T0,
+            last: T0 + HOUR,
+        }]);
+
+        let mut weekly = candle(T0, "1");
+        weekly.interval = "1w".to_string();
+        assert!(store.upsert(&[weekly], T0).is_err());
+    }
+
+    #[test]
+    fn finds_missing_bars() {
+        let mut store = CandleStore::in_memory().unwrap();
+        let times = [T0, T0 + HOUR, T0 + 4 * HOUR, T0 + 6 * HOUR];
+        let candles: Vec<Candle> = times.iter().map(|t| candle(*t, "96000.0")).collect();
+        store.upsert(&candles, T0 + 7 * HOUR).unwrap();
+
+        assert_eq!(
+            store.gaps("BTC", "1h").unwrap(),
+            [
+                Gap { from: T0 + 2 * HOUR, to: T0 + 3 * HOUR, bars: 2 },
+                Gap { from: T0 + 5 * HOUR, to: T0 + 5 * HOUR, bars: 1 },
+            ]
+        );
+        assert!(store.gaps("ETH", "1h").unwrap().is_empty());
+    }
+
+    #[test]
+    fn stream_updates_the_open_candle_and_closes_older_ones() {
+        let mut store = CandleStore::in_memory().unwrap();
+        store.upsert(&[candle(T0, "96301.0")], T0 + 10).unwrap();
+        assert!(store.open_candle("BTC", "1h").unwrap().is_some());
+
+        let message = json!({"channel": "candle", "data": {
+            "t": T0 + HOUR, "T": T0 + 2 * HOUR - 1, "s": "BTC", "i": "1h",
+            "o": "96301.0", "h": "96388.0", "l": "96112.0", "c": "96250.0", "v": "402.1", "n": 5120
+        }});
+        assert_eq!(store.apply_stream(&message, T0 + HOUR + 10).unwrap().len(), 1);
+        assert_eq!(store.open_candle("BTC", "1h").unwrap().unwrap().open_time, T0 + HOUR);
+        assert_eq!(store.candles("BTC", "1h", 0, u64::MAX).unwrap().len(), 2);
+
+        let trades = json!({"channel": "trades", "data": []});
+        assert!(store.apply_stream(&trades, T0).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,123 @@
+//! `hl orders`, `hl positions`, `hl accounts`
+
+use clap::Args;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use super::{address, ByAccount, Context, Report};
+use crate::accounts::{self, Accounts};
+use crate::config::Requires;
+use crate::error::Result;
+use crate::models::{ClearinghouseState, OpenOrder, TypedInfo};
+use crate::registry::AssetRegistry;
+
+#[derive(Debug, Args)]
+pub struct OrdersArgs {
+    /// Only orders for this symbol
+    pub coin: Option<String>,
+}
+
+pub(super) async fn orders(ctx: &Context, args: &OrdersArgs) -> Result<Report> {
+    let requires = if ctx.accounts.is_empty() { Requires::Signer } else { Requires::Endpoint };
+    let sdk = ctx.connect(requires).await?;
+    if let Some(coin) = &args.coin {
+        AssetRegistry::load(&sdk).await?.resolve(coin)?;
+    }
+    if !ctx.accounts.is_empty() {
+        let mut by_account = ByAccount::default();
+        for (account, orders) in accounts::open_orders(&TypedInfo::from_sdk(&sdk), &ctx.accounts).await {
+            by_account.add(&account.name, orders.map(|mut orders| {
+                orders.retain(|o| args.coin.as_deref().is_none_or(|coin| o.coin == coin));
+                let lines = order_lines(&orders);
+                (json!(orders), lines)
+            }));
+        }
+        return Ok(by_account.report());
+    }
+    let mut orders = TypedInfo::from_sdk(&sdk)
+        .frontend_open_orders(&address(&sdk), None)
+        .await?;
+    orders.retain(|o| args.coin.as_deref().is_none_or(|coin| o.coin == coin));
+
+    let mut report = Report::new(&orders);
+    for line in order_lines(&orders) {
+        report.line(line);
+    }
+    Ok(report)
+}
+
+fn order_lines(orders: &[OpenOrder]) -> Vec<String> {
+    if orders.is_empty() {
+        return vec!["No open orders".to_string()];
+    }
+    orders
+        .iter()
+        .map(|o| {
+            let cloid = o.cloid.as_deref().map(|c| format!(" cloid={}", c)).unwrap_or_default();
+            format!("{} {} {} @ {} oid={}{}", o.coin, o.side.as_str(), o.sz, o.limit_px, o.oid, cloid)
+        })
+        .collect()
+}
+
+pub(super) async fn positions(ctx: &Context) -> Result<Report> {
+    if !ctx.accounts.is_empty() {
+        let sdk = ctx.connect(Requires::Endpoint).await?;
+        let mut by_account = ByAccount::default();
+        let mut total = Decimal::ZERO;
+        for (account, state) in accounts::positions(&TypedInfo::from_sdk(&sdk), &ctx.accounts).await {
+            if let Ok(state) = &state {
+                total += state.margin_summary.account_value;
+            }
+            by_account.add(&account.name, state.map(|state| (json!(state), position_lines(&state))));
+        }
+        let mut report = by_account.report();
+        report.line(format!("Total account value: ${}", total));
+        return Ok(report);
+    }
+
+    let sdk = ctx.connect(Requires::Signer).await?;
+    let state = TypedInfo::from_sdk(&sdk)
+        .clearinghouse_state(&address(&sdk), None)
+        .await?;
+
+    let mut report = Report::new(&state);
+    for line in position_lines(&state) {
+        report.line(line);
+    }
+    Ok(report)
+}
+
+fn position_lines(state: &ClearinghouseState) -> Vec<String> {
+    let mut lines = vec![
+        format!("Account value: ${}", state.margin_summary.account_value),
+        format!("Withdrawable: ${}", state.withdrawable),
+    ];
+    if state.asset_positions.is_empty() {
+        lines.push("No open positions".to_string());
+    }
+    for p in state.asset_positions.iter().map(|a| &a.position) {
+        let entry = p.entry_px.map(|px| px.to_string()).unwrap_or_else(|| "-".to_string());
+        lines.push(format!(
+            "{} {} @ {} ({}x {}) uPnL ${}",
+            p.coin, p.szi, entry, p.leverage.value, p.leverage.kind, p.unrealized_pnl
+        ));
+    }
+    lines
+}
+
+pub(super) fn accounts() -> Result<Report> {
+    let accounts = Accounts::load()?;
+    let list: Vec<Value> = accounts
+        .iter()
+        .map(|a| json!({"name": a.name, "kind": a.kind.as_str(), "address": a.address, "signer": a.signer}))
+        .collect();
+    let mut report = Report::new(list);
+    if accounts.is_empty() {
+        report.line("No [accounts] in the profile file");
+    }
+    for a in accounts.iter() {
+        let signer = a.signer.as_deref().map(|s| format!(" (signed by {})", s)).unwrap_or_default();
+        report.line(format!("{} {} {}{}", a.name, a.kind, a.address, signer));
+    }
+    Ok(report)
+}
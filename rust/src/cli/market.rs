@@ -0,0 +1,78 @@
+//! `hl book`, `hl candles`
+
+use clap::Args;
+use serde_json::json;
+
+use super::{now_ms, Context, Report, Since};
+use crate::config::Requires;
+use crate::error::Result;
+use crate::models::TypedInfo;
+use crate::registry::AssetRegistry;
+
+/// Intervals `candleSnapshot` accepts
+const INTERVALS: [&str; 14] = ["1m", "3m", "5m", "15m", "30m", "1h", "2h", "4h", "8h", "12h", "1d", "3d", "1w", "1M"];
+
+#[derive(Debug, Args)]
+pub struct BookArgs {
+    pub coin: String,
+    /// Levels per side
+    #[arg(long, default_value_t = 10)]
+    pub depth: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct CandlesArgs {
+    pub coin: String,
+    #[arg(value_parser = INTERVALS)]
+    pub interval: String,
+    /// Start: a duration ago (30m, 24h, 7d) or a time in ms
+    #[arg(long, default_value = "24h")]
+    pub since: Since,
+}
+
+pub(super) async fn book(ctx: &Context, args: &BookArgs) -> Result<Report> {
+    let sdk = ctx.connect(Requires::Endpoint).await?;
+    let market = AssetRegistry::load(&sdk).await?.resolve(&args.coin)?.clone();
+    let book = TypedInfo::from_sdk(&sdk).l2_book(&market.symbol).await?;
+
+    let bids = &book.bids()[..book.bids().len().min(args.depth)];
+    let asks = &book.asks()[..book.asks().len().min(args.depth)];
+    let mut report = Report::new(json!({
+        "coin": book.coin,
+        "time": book.time,
+        "bids": bids,
+        "asks": asks,
+    }));
+
+    report.line(format!("{} order book", book.coin));
+    for level in asks.iter().rev() {
+        report.line(format!("  ask {:>14} {:>14} ({})", level.px, level.sz, level.n));
+    }
+    match (book.spread(), book.mid()) {
+        (Some(spread), Some(mid)) => report.line(format!("  --- mid {} spread {} ---", mid, spread)),
+        _ => report.line("  ---"),
+    }
+    for level in bids {
+        report.line(format!("  bid {:>14} {:>14} ({})", level.px, level.sz, level.n));
+    }
+    Ok(report)
+}
+
+pub(super) async fn candles(ctx: &Context, args: &CandlesArgs) -> Result<Report> {
+    let sdk = ctx.connect(Requires::Endpoint).await?;
+    let market = AssetRegistry::load(&sdk).await?.resolve(&args.coin)?.clone();
+    let now = now_ms();
+    let candles = TypedInfo::from_sdk(&sdk)
+        .candles(&market.symbol, &args.interval, args.since.start_ms(now), Some(now))
+        .await?;
+
+    let mut report = Report::new(&candles);
+    report.line(format!("{} {} candles: {}", market.symbol, args.interval, candles.len()));
+    for c in &candles {
+        report.line(format!(
+            "  t={} O={} H={} L={} C={} V={}",
+            c.open_time, c.open, c.high, c.low, c.close, c.volume
+        ));
+    }
+    Ok(report)
+}
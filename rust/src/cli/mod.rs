@@ -0,0 +1,578 @@
+//! The `hl` command-line tool.
+//!
+//! The example binaries each demonstrate one SDK call with BTC and fixed sizes
+//! baked in. `hl` exposes the same operations with everything on the command
+//! line, built from the same helpers ([`AssetRegistry`](crate::AssetRegistry)
+//! checks, [`Precision`](crate::Precision) rounding, [`TypedInfo`](crate::models::TypedInfo)
+//! queries, [`dry_run`](crate::dry_run) and [`paper`](crate::paper)):
+//!
+//! ```bash
+//! hl order buy BTC 0.01 --px 60000 --tif alo
+//! hl cancel --cloid 0x0123456789abcdef0123456789abcdef
+//! hl book ETH --depth 20
+//! hl candles BTC 1h --since 24h
+//! hl withdraw 100 --to 0x... --dry-run
+//! ```
+//!
+//! The shared flags (`--endpoint`, `--private-key`, `--profile`, `--testnet`,
+//! `--paper`, `--dry-run`) work as in every example and fall back to the
+//! environment and profile file the same way (see [`config`](crate::config)).
+//! `--account NAME` acts for a named account of the profile file, including
+//! sub-accounts and vaults (see [`accounts`](crate::accounts)). `order`,
+//! `cancel`, `orders` and `positions` also take several names or `all` and run
+//! for each account in turn (`order` and `cancel` skip the read-only ones of
+//! `all`); `leverage` and `withdraw` act for one account and exit with 2 when
+//! `--account` selects more.
+//! `--json` prints one JSON document on stdout instead of text; errors are
+//! then printed as `{"error": ..., "exit": ...}`.
+//!
+//! The exit status tells scripts what happened (see [`Exit`]): 0 success,
+//! 1 request failed, 2 bad arguments or unknown symbol, 3 missing or invalid
+//! configuration, 4 rejected by the exchange.
+
+mod account;
+mod market;
+mod trade;
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::builder::BoolishValueParser;
+use clap::{Args, Parser, Subcommand};
+use hyperliquid_sdk::HyperliquidSDK;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::accounts::{Account, AccountKind, Accounts, ALL};
+use crate::config::{Config, Requires};
+use crate::error::{Error, Result};
+use crate::exchange::rejection;
+
+pub use account::OrdersArgs;
+pub use market::{BookArgs, CandlesArgs};
+pub use trade::{CancelArgs, LeverageArgs, OrderArgs, OrderSide, Tif, WithdrawArgs};
+
+/// Command line of the `hl` binary
+#[derive(Debug, Parser)]
+#[command(name = "hl", version, about = "Trade and query Hyperliquid from the command line")]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Flags accepted before or after any subcommand
+#[derive(Debug, Default, Args)]
+pub struct GlobalArgs {
+    /// Print one JSON document instead of text
+    #[arg(long, global = true)]
+    pub json: bool,
+    /// Endpoint URL (default: ENDPOINT)
+    #[arg(long, global = true, value_name = "URL")]
+    pub endpoint: Option<String>,
+    /// Hex private key (default: PRIVATE_KEY)
+    #[arg(long, global = true, value_name = "KEY")]
+    pub private_key: Option<String>,
+    /// Profile from the profile file (default: HL_PROFILE)
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+    /// Use testnet (default: TESTNET, the profile or the endpoint)
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true",
+          value_parser = BoolishValueParser::new())]
+    pub testnet: Option<bool>,
+    /// Trade on a local simulated exchange (default: PAPER)
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true",
+          value_parser = BoolishValueParser::new())]
+    pub paper: Option<bool>,
+    /// Sign and print exchange actions without sending them (default: DRY_RUN)
+    #[arg(long, global = true, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true",
+          value_parser = BoolishValueParser::new())]
+    pub dry_run: Option<bool>,
+    /// Named account(s) from the profile file, or `all`; leverage and withdraw take one (default: HL_ACCOUNT)
+    #[arg(long, global = true, value_name = "NAME[,NAME]")]
+    pub account: Option<String>,
+}
+
+impl GlobalArgs {
+    /// Resolve the config, with these flags taking precedence
+    pub fn config(&self) -> Result<Config> {
+        let mut args = Vec::new();
+        let values = [
+            ("--endpoint", &self.endpoint),
+            ("--private-key", &self.private_key),
+            ("--profile", &self.profile),
+            ("--account", &self.account),
+        ];
+        for (flag, value) in values {
+            if let Some(value) = value {
+                args.extend([flag.to_string(), value.clone()]);
+            }
+        }
+        let switches = [("--testnet", self.testnet), ("--paper", self.paper), ("--dry-run", self.dry_run)];
+        for (flag, value) in switches {
+            if let Some(value) = value {
+                args.push(format!("{}={}", flag, value));
+            }
+        }
+        Config::from_args(args)
+    }
+}
+
+/// `hl` subcommands
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Place a limit order, or a market order without --px
+    Order(OrderArgs),
+    /// Cancel orders by oid, by cloid, or all of them
+    Cancel(CancelArgs),
+    /// List open orders
+    Orders(OrdersArgs),
+    /// Show perp positions and margin
+    Positions,
+    /// Show the L2 order book
+    Book(BookArgs),
+    /// Fetch candles
+    Candles(CandlesArgs),
+    /// Set leverage for a perp
+    Leverage(LeverageArgs),
+    /// Withdraw USDC to Arbitrum
+    Withdraw(WithdrawArgs),
+    /// List the named accounts of the profile file
+    Accounts,
+}
+
+impl Command {
+    /// Whether the command sends exchange actions
+    fn is_trading(&self) -> bool {
+        matches!(
+            self,
+            Command::Order(_) | Command::Cancel(_) | Command::Leverage(_) | Command::Withdraw(_)
+        )
+    }
+}
+
+/// Run a parsed command line, print its output and return the exit status
+pub async fn run(cli: Cli) -> Exit {
+    let json = cli.global.json;
+    let result = match cli.global.config().and_then(Context::new) {
+        Ok(ctx) => dispatch(&ctx, &cli.command).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(report) => {
+            report.print(json);
+            if !json && cli.command.is_trading() {
+                crate::paper::report();
+            }
+            report.exit
+        }
+        Err(e) => {
+            let exit = Exit::from(&e);
+            if json {
+                println!("{}", json!({"error": e.to_string(), "exit": exit.code()}));
+            } else {
+                eprintln!("error: {}", e);
+            }
+            exit
+        }
+    }
+}
+
+async fn dispatch(ctx: &Context, command: &Command) -> Result<Report> {
+    if ctx.accounts.len() > 1 && matches!(command, Command::Order(_) | Command::Cancel(_)) {
+        let mut by_account = ByAccount::default();
+        for ctx in ctx.each_account() {
+            let name = ctx.accounts[0].name.clone();
+            by_account.add_report(&name, dispatch_one(&ctx, command).await);
+        }
+        return Ok(by_account.report());
+    }
+    dispatch_one(ctx, command).await
+}
+
+async fn dispatch_one(ctx: &Context, command: &Command) -> Result<Report> {
+    match command {
+        Command::Order(args) => trade::order(ctx, args).await,
+        Command::Cancel(args) => trade::cancel(ctx, args).await,
+        Command::Orders(args) => account::orders(ctx, args).await,
+        Command::Positions => account::positions(ctx).await,
+        Command::Book(args) => market::book(ctx, args).await,
+        Command::Candles(args) => market::candles(ctx, args).await,
+        Command::Leverage(args) => trade::leverage(ctx, args).await,
+        Command::Withdraw(args) => trade::withdraw(ctx, args).await,
+        Command::Accounts => account::accounts(),
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Context
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// What every subcommand gets
+struct Context {
+    /// With one account selected, carries that account's signing key
+    config: Config,
+    /// Accounts `--account` selects; empty for the account of `PRIVATE_KEY`
+    accounts: Vec<Account>,
+}
+
+impl Context {
+    fn new(config: Config) -> Result<Self> {
+        let accounts = Accounts::selected(&config)?;
+        let config = match &accounts[..] {
+            [account] => account.config(&config),
+            _ => config,
+        };
+        Ok(Context { config, accounts })
+    }
+
+    /// A context for each selected account, to run a trading command for them
+    /// in turn; `all` leaves out the read-only accounts
+    fn each_account(&self) -> Vec<Context> {
+        let all = self.config.account.as_deref().map(str::trim) == Some(ALL);
+        self.accounts
+            .iter()
+            .filter(|account| !(all && account.kind == AccountKind::ReadOnly))
+            .map(|account| Context { config: account.config(&self.config), accounts: vec![account.clone()] })
+            .collect()
+    }
+
+    /// The account a trading command acts for; `None` for the key's own
+    fn account(&self) -> Result<Option<&Account>> {
+        match &self.accounts[..] {
+            [] => Ok(None),
+            [account] => {
+                account.check_can_trade()?;
+                Ok(Some(account))
+            }
+            accounts => Err(Error::ValidationError(format!(
+                "this command acts for one account, --account selects {}",
+                accounts.len()
+            ))),
+        }
+    }
+
+    /// The sub-account or vault a trading command acts for, if any.
+    ///
+    /// Its actions are built and signed here and sent with its `vaultAddress`
+    /// instead of through the SDK. Paper trading has a single account, so it
+    /// cannot act for one.
+    fn vault(&self) -> Result<Option<&Account>> {
+        let vault = self.account()?.filter(|a| a.vault_address().is_some());
+        match vault {
+            Some(account) if self.config.paper => Err(Error::ValidationError(format!(
+                "paper trading has a single account; it cannot act for {} '{}'",
+                account.kind, account.name
+            ))),
+            vault => Ok(vault),
+        }
+    }
+
+    /// Address a trading command acts for
+    fn address(&self, sdk: &HyperliquidSDK) -> Result<String> {
+        Ok(match self.account()? {
+            Some(account) => account.address.clone(),
+            None => address(sdk),
+        })
+    }
+
+    /// Build the SDK, failing with a config error when `requires` is not met
+    async fn connect(&self, requires: Requires) -> Result<HyperliquidSDK> {
+        if self.config.endpoint.is_none() {
+            return Err(Error::ConfigError(
+                "no endpoint: set ENDPOINT, pass --endpoint or select a --profile".to_string(),
+            ));
+        }
+        let missing_key = self.config.private_key.is_none() && !self.config.paper;
+        if requires == Requires::Signer && missing_key {
+            return Err(Error::ConfigError(
+                "no private key: set PRIVATE_KEY, pass --private-key or select a --profile".to_string(),
+            ));
+        }
+        if requires == Requires::Signer {
+            // Fail on the account before paper trading starts up
+            self.vault()?;
+        }
+        self.config.connect().await
+    }
+}
+
+/// Address of the SDK's signer, as the info API expects it
+fn address(sdk: &HyperliquidSDK) -> String {
+    sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default()
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Output
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Exit status of `hl`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Exit {
+    /// Everything worked
+    #[default]
+    Success = 0,
+    /// A request failed (network, unexpected response)
+    Failed = 1,
+    /// Bad arguments: unknown symbol, size below the lot, ... (clap also uses 2)
+    Usage = 2,
+    /// No endpoint or key, unreadable profile file, unknown profile
+    Config = 3,
+    /// The exchange answered with an error (order rejected, nothing to cancel, ...)
+    Rejected = 4,
+}
+
+impl Exit {
+    /// Process exit code
+    pub fn code(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<&Error> for Exit {
+    fn from(error: &Error) -> Self {
+        use hyperliquid_sdk::Error as Sdk;
+        match error {
+            Error::ConfigError(_) => Exit::Config,
+            Error::ValidationError(_) => Exit::Usage,
+            Error::SdkError(Sdk::ConfigError(_)) => Exit::Config,
+            Error::SdkError(Sdk::ValidationError(_)) => Exit::Usage,
+            Error::SdkError(Sdk::ApiError { .. } | Sdk::OrderError(_) | Sdk::ApprovalRequired { .. }) => {
+                Exit::Rejected
+            }
+            Error::SdkError(_) | Error::JsonError(_) | Error::IoError(_) | Error::DatabaseError(_) => Exit::Failed,
+        }
+    }
+}
+
+/// What a subcommand produced: JSON for `--json`, lines of text otherwise
+#[derive(Debug, Clone)]
+struct Report {
+    json: Value,
+    lines: Vec<String>,
+    exit: Exit,
+}
+
+impl Report {
+    fn new(json: impl Serialize) -> Self {
+        Report {
+            json: serde_json::to_value(json).unwrap_or(Value::Null),
+            lines: Vec::new(),
+            exit: Exit::Success,
+        }
+    }
+
+    fn line(&mut self, line: impl fmt::Display) {
+        self.lines.push(line.to_string());
+    }
+
+    /// Mark the report as an exchange rejection
+    fn rejected(mut self, reason: impl fmt::Display) -> Self {
+        self.line(format!("Rejected: {}", reason));
+        self.exit = Exit::Rejected;
+        self
+    }
+
+    fn print(&self, json: bool) {
+        if json {
+            println!("{}", serde_json::to_string_pretty(&self.json).unwrap_or_default());
+        } else {
+            for line in &self.lines {
+                println!("{}", line);
+            }
+        }
+    }
+}
+
+/// Results of several accounts: JSON keyed by account name, lines prefixed
+/// with it. A failed account shows its error and fails the command, without
+/// hiding the others.
+#[derive(Default)]
+struct ByAccount {
+    json: Map<String, Value>,
+    lines: Vec<String>,
+    exit: Exit,
+}
+
+impl ByAccount {
+    fn add(&mut self, name: &str, result: Result<(Value, Vec<String>)>) {
+        match result {
+            Ok((json, lines)) => {
+                self.json.insert(name.to_string(), json);
+                self.lines.extend(lines.into_iter().map(|line| format!("[{}] {}", name, line)));
+            }
+            Err(e) => {
+                self.json.insert(name.to_string(), json!({"error": e.to_string()}));
+                self.lines.push(format!("[{}] Error: {}", name, e));
+                self.exit = Exit::Failed;
+            }
+        }
+    }
+
+    /// Add the report of a command run for one account; the command exits
+    /// with the status of the last account that didn't succeed
+    fn add_report(&mut self, name: &str, result: Result<Report>) {
+        let exit = match &result {
+            Ok(report) => report.exit,
+            Err(e) => Exit::from(e),
+        };
+        self.add(name, result.map(|report| (report.json, report.lines)));
+        if exit != Exit::Success {
+            self.exit = exit;
+        }
+    }
+
+    fn report(self) -> Report {
+        let mut report = Report::new(Value::Object(self.json));
+        for line in self.lines {
+            report.line(line);
+        }
+        report.exit = self.exit;
+        report
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Time
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Start of a time range: `24h`, `30m`, `7d` ago, or an absolute time in ms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Since {
+    Ago(Duration),
+    At(u64),
+}
+
+impl Since {
+    /// Start time in ms, relative to `now_ms`
+    pub fn start_ms(self, now_ms: u64) -> u64 {
+        match self {
+            Since::Ago(ago) => now_ms.saturating_sub(ago.as_millis() as u64),
+            Since::At(ms) => ms,
+        }
+    }
+}
+
+impl FromStr for Since {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        let value = value.trim();
+        if let Ok(ms) = value.parse::<u64>() {
+            return Ok(Since::At(ms));
+        }
+        let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+        let (count, unit) = value.split_at(split);
+        let count: u64 = count
+            .parse()
+            .map_err(|_| format!("expected a duration like 24h or a time in ms, got '{}'", value))?;
+        let secs = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => return Err(format!("unknown unit '{}' (use s, m, h, d or w)", unit)),
+        };
+        Ok(Since::Ago(Duration::from_secs(count * secs)))
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    fn parse(line: &str) -> Cli {
+        Cli::try_parse_from(line.split_whitespace()).unwrap()
+    }
+
+    #[test]
+    fn parses_the_documented_commands() {
+        let Command::Order(order) = parse("hl order buy BTC 0.01 --px 60000 --tif alo").command else {
+            panic!("not an order");
+        };
+        assert_eq!(order.side, OrderSide::Buy);
+        assert_eq!(order.coin, "BTC");
+        assert_eq!(order.size, Decimal::new(1, 2));
+        assert_eq!(order.px, Some(Decimal::from(60000)));
+        assert_eq!(order.tif, Tif::Alo);
+
+        let Command::Cancel(cancel) = parse("hl cancel --cloid 0xabc").command else {
+            panic!("not a cancel");
+        };
+        assert_eq!(cancel.cloid.as_deref(), Some("0xabc"));
+        assert!(cancel.coin.is_none());
+
+        let Command::Book(book) = parse("hl book ETH --depth 20").command else {
+            panic!("not a book");
+        };
+        assert_eq!(book.depth, 20);
+
+        let Command::Candles(candles) = parse("hl candles BTC 1h --since 24h").command else {
+            panic!("not candles");
+        };
+        assert_eq!(candles.since, Since::Ago(Duration::from_secs(86_400)));
+
+        let Command::Withdraw(withdraw) = parse("hl withdraw 100 --to 0xabc").command else {
+            panic!("not a withdraw");
+        };
+        assert_eq!(withdraw.to.as_deref(), Some("0xabc"));
+    }
+
+    #[test]
+    fn global_flags_go_anywhere() {
+        let cli = parse("hl --json book ETH --testnet --dry-run=false");
+        assert!(cli.global.json);
+        assert_eq!(cli.global.testnet, Some(true));
+        assert_eq!(cli.global.dry_run, Some(false));
+        assert_eq!(parse("hl positions --account main,fund").global.account.as_deref(), Some("main,fund"));
+
+        assert!(Cli::try_parse_from(["hl", "cancel", "BTC", "--oid", "1", "--all"]).is_err());
+        assert!(Cli::try_parse_from(["hl", "candles", "BTC", "7m"]).is_err());
+    }
+
+    #[test]
+    fn parses_since() {
+        assert_eq!("90s".parse(), Ok(Since::Ago(Duration::from_secs(90))));
+        assert_eq!("7d".parse(), Ok(Since::Ago(Duration::from_secs(604_800))));
+        assert_eq!("1737412345000".parse(), Ok(Since::At(1_737_412_345_000)));
+        assert!("24x".parse::<Since>().is_err());
+        assert!("h".parse::<Since>().is_err());
+        assert_eq!(Since::Ago(Duration::from_secs(1)).start_ms(5_000), 4_000);
+    }
+
+    #[test]
+    fn maps_errors_to_exit_codes() {
+        assert_eq!(Exit::from(&Error::ConfigError(String::new())), Exit::Config);
+        assert_eq!(Exit::from(&Error::ValidationError(String::new())), Exit::Usage);
+        let network = Error::SdkError(hyperliquid_sdk::Error::NetworkError(String::new()));
+        assert_eq!(Exit::from(&network), Exit::Failed);
+        let order = Error::SdkError(hyperliquid_sdk::Error::OrderError(String::new()));
+        assert_eq!(Exit::from(&order), Exit::Rejected);
+    }
+
+    #[test]
+    fn finds_rejections_in_responses() {
+        let rejected = json!({"status": "ok", "response": {"type": "order", "data": {"statuses": [
+            {"error": "Order must have minimum value of $10."}
+        ]}}});
+        assert_eq!(rejection(&rejected).as_deref(), Some("Order must have minimum value of $10."));
+
+        let cancel = json!({"status": "ok", "response": {"type": "cancel", "data": {"statuses": ["success"]}}});
+        assert_eq!(rejection(&cancel), None);
+
+        let err = json!({"status": "err", "response": "User or API Wallet does not exist."});
+        assert_eq!(rejection(&err).as_deref(), Some("User or API Wallet does not exist."));
+    }
+}
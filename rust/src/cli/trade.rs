@@ -0,0 +1,330 @@
+//! `hl order`, `hl cancel`, `hl leverage`, `hl withdraw`
+
+use clap::{ArgGroup, Args, ValueEnum};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use super::{address, rejection, Context, Report};
+use crate::accounts::{self, Account};
+use crate::config::Requires;
+use crate::dry_run::{self, actions};
+use crate::error::{Error, Result};
+use crate::exchange::{self, OrderStatus};
+use crate::models::TypedInfo;
+use crate::price::{Px, Rounding, Sz, DEFAULT_SLIPPAGE};
+use crate::registry::{AssetRegistry, Market};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Tif {
+    /// Good until cancelled
+    #[default]
+    Gtc,
+    /// Immediate or cancel
+    Ioc,
+    /// Add liquidity only (post-only)
+    Alo,
+}
+
+impl Tif {
+    fn wire(self) -> &'static str {
+        match self {
+            Tif::Gtc => "Gtc",
+            Tif::Ioc => "Ioc",
+            Tif::Alo => "Alo",
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct OrderArgs {
+    pub side: OrderSide,
+    /// Symbol, e.g. BTC, PURR/USDC, xyz:XYZ100
+    pub coin: String,
+    /// Size in the base asset (rounded down to the lot)
+    pub size: Decimal,
+    /// Limit price (rounded to the tick, away from the market); market order without it
+    #[arg(long)]
+    pub px: Option<Decimal>,
+    /// Time in force of a limit order
+    #[arg(long, value_enum, default_value_t)]
+    pub tif: Tif,
+    /// Only reduce an open position
+    #[arg(long)]
+    pub reduce_only: bool,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("target").required(true).args(["oid", "cloid", "all"])))]
+pub struct CancelArgs {
+    /// Symbol of the order(s); looked up from open orders when omitted
+    pub coin: Option<String>,
+    /// Order id
+    #[arg(long)]
+    pub oid: Option<u64>,
+    /// Client order id
+    #[arg(long)]
+    pub cloid: Option<String>,
+    /// Every open order (of COIN, if given)
+    #[arg(long)]
+    pub all: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct LeverageArgs {
+    /// Perp symbol
+    pub coin: String,
+    pub leverage: u32,
+    /// Isolated margin instead of cross
+    #[arg(long)]
+    pub isolated: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct WithdrawArgs {
+    /// USDC amount
+    pub amount: Decimal,
+    /// Destination on Arbitrum (default: your own address)
+    #[arg(long, value_name = "ADDRESS")]
+    pub to: Option<String>,
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Order
+// ══════════════════════════════════════════════════════════════════════════════
+
+pub(super) async fn order(ctx: &Context, args: &OrderArgs) -> Result<Report> {
+    let sdk = ctx.connect(Requires::Signer).await?;
+    let registry = AssetRegistry::load(&sdk).await?;
+    let market = registry.resolve(&args.coin)?;
+    let precision = market.precision();
+    let size = precision.sz(args.size, Rounding::Down)?;
+    let is_buy = args.side == OrderSide::Buy;
+
+    // A limit price rounds away from the market so it never crosses by accident;
+    // a market order is an IOC at the mid ± slippage, rounded toward the mid
+    let (px, tif) = match args.px {
+        Some(px) => (precision.px(px, if is_buy { Rounding::Down } else { Rounding::Up })?, args.tif),
+        None => {
+            let mid = TypedInfo::from_sdk(&sdk).mid(&market.symbol).await?;
+            let slippage = if is_buy { Decimal::ONE + DEFAULT_SLIPPAGE } else { Decimal::ONE - DEFAULT_SLIPPAGE };
+            (precision.px_toward(mid * slippage, mid)?, Tif::Ioc)
+        }
+    };
+    let action = actions::order(market.asset_id, is_buy, px, size, tif.wire(), args.reduce_only);
+    if ctx.config.dry_run || ctx.vault()?.is_some() {
+        return signed(ctx, &action).await;
+    }
+    let response = exchange::send(&ctx.config, &action, None).await?;
+    Ok(placed(market, is_buy, px, size, response))
+}
+
+/// Report for the exchange's `response` to an order of `size` at `px`
+fn placed(market: &Market, is_buy: bool, px: Px, size: Sz, response: Value) -> Report {
+    let status = OrderStatus::first(&response);
+    let (name, filled, avg_px, error) = match &status {
+        OrderStatus::Resting { .. } => ("resting", None, None, None),
+        OrderStatus::Filled { total_sz, avg_px, .. } => ("filled", Some(total_sz), Some(avg_px), None),
+        OrderStatus::WaitingForFill => ("waitingForFill", None, None, None),
+        OrderStatus::Error(reason) => ("error", None, None, Some(reason)),
+    };
+    let mut report = Report::new(json!({
+        "status": name,
+        "oid": status.oid(),
+        "coin": market.symbol,
+        "side": if is_buy { "buy" } else { "sell" },
+        "size": size,
+        "price": px,
+        "filledSize": filled,
+        "avgPrice": avg_px,
+        "error": error,
+        "response": response,
+    }));
+    if let Some(error) = error {
+        return report.rejected(error);
+    }
+    report.line(format!("Status: {}", name));
+    if let Some(oid) = status.oid() {
+        report.line(format!("OID: {}", oid));
+    }
+    if let (Some(filled), Some(avg)) = (filled, avg_px) {
+        report.line(format!("Filled: {} @ {}", filled, avg));
+    }
+    report
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Cancel
+// ══════════════════════════════════════════════════════════════════════════════
+
+pub(super) async fn cancel(ctx: &Context, args: &CancelArgs) -> Result<Report> {
+    let sdk = ctx.connect(Requires::Signer).await?;
+    let registry = AssetRegistry::load(&sdk).await?;
+    if let Some(coin) = &args.coin {
+        registry.resolve(coin)?;
+    }
+    let open = TypedInfo::from_sdk(&sdk)
+        .frontend_open_orders(&ctx.address(&sdk)?, None)
+        .await?;
+    let by_action = ctx.config.dry_run || ctx.vault()?.is_some();
+
+    // (market, oid, cloid) of every open order the arguments select
+    let selected: Vec<(&Market, u64, Option<&str>)> = open
+        .iter()
+        .filter(|o| args.coin.as_deref().is_none_or(|coin| o.coin == coin))
+        .filter(|o| args.oid.is_none_or(|oid| o.oid == oid))
+        .filter(|o| args.cloid.is_none() || o.cloid.as_deref() == args.cloid.as_deref())
+        .filter_map(|o| Some((registry.get(&o.coin)?, o.oid, o.cloid.as_deref())))
+        .collect();
+
+    if selected.is_empty() {
+        if args.all {
+            let mut report = Report::new(json!({"cancelled": []}));
+            report.line("No open orders");
+            return Ok(report);
+        }
+        // Not open (any more); let the exchange say so when we know the coin
+        let Some(coin) = &args.coin else {
+            return Ok(Report::new(json!({"cancelled": []})).rejected("no open order matches"));
+        };
+        let market = registry.resolve(coin)?;
+        return match (&args.oid, &args.cloid) {
+            (Some(oid), _) if by_action => signed(ctx, &actions::cancel(&[(market.asset_id, *oid)])).await,
+            (_, Some(cloid)) if by_action => {
+                signed(ctx, &actions::cancel_by_cloid(market.asset_id, cloid)).await
+            }
+            (Some(oid), _) => Ok(sent(sdk.cancel(*oid, &market.symbol).await?)),
+            (_, Some(cloid)) => Ok(sent(sdk.cancel_by_cloid(cloid, &market.symbol).await?)),
+            _ => unreachable!("clap requires --oid, --cloid or --all"),
+        };
+    }
+
+    let cancels: Vec<(u32, u64)> = selected.iter().map(|(m, oid, _)| (m.asset_id, *oid)).collect();
+    if by_action {
+        return signed(ctx, &actions::cancel(&cancels)).await;
+    }
+    let response = match &selected[..] {
+        [(market, oid, _)] if !args.all => sdk.cancel(*oid, &market.symbol).await?,
+        _ => sdk.cancel_all(args.coin.as_deref()).await?,
+    };
+    let mut report = sent(response);
+    if report.exit == super::Exit::Success {
+        for (market, oid, cloid) in &selected {
+            let cloid = cloid.map(|c| format!(" ({})", c)).unwrap_or_default();
+            report.line(format!("Cancelled {} {}{}", market.symbol, oid, cloid));
+        }
+    }
+    Ok(report)
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Leverage, withdraw
+// ══════════════════════════════════════════════════════════════════════════════
+
+pub(super) async fn leverage(ctx: &Context, args: &LeverageArgs) -> Result<Report> {
+    let sdk = ctx.connect(Requires::Signer).await?;
+    let registry = AssetRegistry::load(&sdk).await?;
+    let market = registry.resolve(&args.coin)?;
+    if !market.is_perp() {
+        return Err(Error::ValidationError(format!("{} is not a perp", market.symbol)));
+    }
+    if let Some(max) = market.max_leverage.filter(|max| args.leverage > *max) {
+        return Err(Error::ValidationError(format!("{} allows at most {}x", market.symbol, max)));
+    }
+    if market.only_isolated && !args.isolated {
+        return Err(Error::ValidationError(format!("{} is isolated margin only", market.symbol)));
+    }
+
+    let leverage = args.leverage as i32;
+    if ctx.config.dry_run || ctx.vault()?.is_some() {
+        return signed(ctx, &actions::update_leverage(market.asset_id, leverage, !args.isolated)).await;
+    }
+    let mut report = sent(sdk.update_leverage(&market.symbol, leverage, !args.isolated).await?);
+    if report.exit == super::Exit::Success {
+        let mode = if args.isolated { "isolated" } else { "cross" };
+        report.line(format!("{} leverage set to {}x ({})", market.symbol, args.leverage, mode));
+    }
+    Ok(report)
+}
+
+pub(super) async fn withdraw(ctx: &Context, args: &WithdrawArgs) -> Result<Report> {
+    let sdk = ctx.connect(Requires::Signer).await?;
+    let amount = args.amount.to_f64().unwrap_or_default();
+    if amount <= 0.0 {
+        return Err(Error::ValidationError(format!("Amount must be positive: {}", args.amount)));
+    }
+    if let Some(account) = ctx.vault()? {
+        return Err(Error::ValidationError(format!(
+            "{} is a {}; withdrawals are signed for the key's own account",
+            account.name, account.kind
+        )));
+    }
+
+    if ctx.config.dry_run {
+        let destination = args.to.clone().unwrap_or_else(|| address(&sdk));
+        return signed(ctx, &actions::withdraw(sdk.chain(), amount, &destination)).await;
+    }
+    let mut report = sent(sdk.withdraw(amount, args.to.as_deref()).await?);
+    if report.exit == super::Exit::Success {
+        let destination = args.to.as_deref().unwrap_or("your address");
+        report.line(format!("Withdrawing ${} to {}", args.amount, destination));
+    }
+    Ok(report)
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Responses
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Report for an exchange response
+fn sent(response: Value) -> Report {
+    let reason = rejection(&response);
+    let report = Report::new(&response);
+    match reason {
+        Some(reason) => report.rejected(reason),
+        None => report,
+    }
+}
+
+/// Report for an exchange response to an action sent for `account`
+fn sent_for(account: &Account, response: Value) -> Report {
+    let mut report = sent(response.clone());
+    if report.exit != super::Exit::Success {
+        return report;
+    }
+    report.line(format!("Sent for {} ({} {})", account.name, account.kind, account.address));
+    let statuses = response.pointer("/response/data/statuses").and_then(|s| s.as_array());
+    for status in statuses.into_iter().flatten() {
+        if let Some(oid) = status.pointer("/resting/oid") {
+            report.line(format!("OID: {}", oid));
+        }
+        if let Some(filled) = status.get("filled") {
+            let field = |name: &str| filled.get(name).and_then(|v| v.as_str()).unwrap_or("?").to_string();
+            report.line(format!("OID: {}", filled.get("oid").cloned().unwrap_or_default()));
+            report.line(format!("Filled: {} @ {}", field("totalSz"), field("avgPx")));
+        }
+    }
+    report
+}
+
+/// Report for a dry run of `action`, or for sending it as the selected
+/// sub-account or vault
+async fn signed(ctx: &Context, action: &Value) -> Result<Report> {
+    let vault = ctx.vault()?;
+    if let (Some(account), false) = (vault, ctx.config.dry_run) {
+        let response = accounts::send(&ctx.config, account, action).await?;
+        return Ok(sent_for(account, response));
+    }
+    let signed = dry_run::sign_for(&ctx.config, action, vault.and_then(Account::vault_address)).await?;
+    let mut report = Report::new(&signed);
+    for line in signed.lines() {
+        report.line(line);
+    }
+    Ok(report)
+}
@@ -0,0 +1,677 @@
+//! Endpoint and key resolution shared by every example.
+//!
+//! Settings are resolved from four sources, highest precedence first:
+//!
+//! 1. Command-line flags: `--endpoint URL`, `--private-key KEY`, `--profile NAME`, `--testnet`,
+//!    `--paper`, `--dry-run`, `--account NAME`
+//! 2. Environment variables: `ENDPOINT`, `PRIVATE_KEY`, `HL_PROFILE`, `TESTNET`, `PAPER`,
+//!    `DRY_RUN`, `HL_ACCOUNT`
+//! 3. A `.env` file in the working directory (same keys as the environment)
+//! 4. A TOML profile file: `$HL_CONFIG`, else `./hyperliquid.toml`, else
+//!    `~/.config/hyperliquid/config.toml`
+//!
+//! Each setting is taken from the first source that provides it, so an
+//! `ENDPOINT` in the environment overrides the endpoint of the selected
+//! profile while the profile's key is still used. `default` names the profile
+//! to use when neither `--profile` nor `HL_PROFILE` is set:
+//!
+//! ```toml
+//! default = "main"
+//!
+//! [profiles.main]
+//! endpoint = "https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
+//! private_key = "0x..."
+//!
+//! [profiles.test]
+//! endpoint = "https://your-endpoint.hype-testnet.quiknode.pro/TOKEN"
+//! private_key = "0x..."
+//! testnet = true
+//! ```
+//!
+//! The same file lists named accounts (sub-accounts, vaults) that `--account`
+//! selects; see [`accounts`](crate::accounts).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use hyperliquid_sdk::{Chain, HyperliquidSDK, HyperliquidSDKBuilder};
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+
+/// Profile file name looked up in the working directory
+const LOCAL_PROFILE_FILE: &str = "hyperliquid.toml";
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Network
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Hyperliquid network an endpoint points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Network {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+impl Network {
+    /// Detect the network from an endpoint URL.
+    ///
+    /// QuickNode testnet hosts look like `name.hype-testnet.quiknode.pro`, the
+    /// public API is `api.hyperliquid-testnet.xyz`; anything else is mainnet.
+    pub fn detect(endpoint: &str) -> Self {
+        let host = url::Url::parse(endpoint)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_lowercase))
+            .unwrap_or_else(|| endpoint.to_lowercase());
+        if host.contains("testnet") {
+            Network::Testnet
+        } else {
+            Network::Mainnet
+        }
+    }
+
+    /// Whether this is testnet
+    pub fn is_testnet(self) -> bool {
+        self == Network::Testnet
+    }
+
+    /// Network name as a string
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Network::Mainnet => "mainnet",
+            Network::Testnet => "testnet",
+        }
+    }
+}
+
+impl From<Chain> for Network {
+    fn from(chain: Chain) -> Self {
+        if chain.is_mainnet() {
+            Network::Mainnet
+        } else {
+            Network::Testnet
+        }
+    }
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Endpoint Redaction
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Redact an endpoint URL for display.
+///
+/// QuickNode endpoints carry their auth token in the path
+/// (`https://name.hype-mainnet.quiknode.pro/TOKEN/evm`). Path segments that
+/// look like tokens, user info and query strings are masked; the host and
+/// well-known suffixes (`/evm`, `/info`, ...) are kept.
+///
+/// ```
+/// use hyperliquid_examples::redact_endpoint;
+///
+/// assert_eq!(
+///     redact_endpoint("https://x.hype-mainnet.quiknode.pro/0123456789abcdef0123/evm"),
+///     "https://x.hype-mainnet.quiknode.pro/0123****/evm",
+/// );
+/// ```
+pub fn redact_endpoint(endpoint: &str) -> String {
+    let Ok(url) = url::Url::parse(endpoint) else {
+        // Not a URL; show only a short prefix
+        let prefix: String = endpoint.chars().take(8).collect();
+        return format!("{}****", prefix);
+    };
+
+    let mut out = format!("{}://", url.scheme());
+    if !url.username().is_empty() || url.password().is_some() {
+        out.push_str("****@");
+    }
+    out.push_str(url.host_str().unwrap_or_default());
+    if let Some(port) = url.port() {
+        out.push_str(&format!(":{}", port));
+    }
+    if let Some(segments) = url.path_segments() {
+        for segment in segments.filter(|s| !s.is_empty()) {
+            out.push('/');
+            if looks_like_token(segment) {
+                out.push_str(&segment[..4]);
+                out.push_str("****");
+            } else {
+                out.push_str(segment);
+            }
+        }
+    }
+    if url.query().is_some() {
+        out.push_str("?****");
+    }
+    out
+}
+
+fn looks_like_token(segment: &str) -> bool {
+    segment.len() >= 16 && segment.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Config
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// What a binary needs before it can run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Requires {
+    /// An endpoint (read-only examples)
+    Endpoint,
+    /// An endpoint and a private key (examples that sign actions)
+    Signer,
+}
+
+/// Resolved settings for one run of an example
+#[derive(Clone, Default)]
+pub struct Config {
+    /// Endpoint URL
+    pub endpoint: Option<String>,
+    /// Hex private key
+    pub private_key: Option<String>,
+    /// Profile the settings came from, if any
+    pub profile: Option<String>,
+    /// Network, from `--testnet`/`TESTNET`/the profile or detected from the endpoint
+    pub network: Network,
+    /// Trade on the local [`paper`](crate::paper) exchange instead of Hyperliquid
+    pub paper: bool,
+    /// Sign exchange actions and print them instead of sending them (see [`dry_run`](crate::dry_run))
+    pub dry_run: bool,
+    /// Named accounts to act on: `NAME[,NAME...]` or `all` (see [`accounts`](crate::accounts))
+    pub account: Option<String>,
+    /// Command-line arguments left after the shared flags were removed
+    pub args: Vec<String>,
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("endpoint", &self.endpoint.as_deref().map(redact_endpoint))
+            .field("private_key", &self.private_key.as_ref().map(|_| "****"))
+            .field("profile", &self.profile)
+            .field("network", &self.network)
+            .field("paper", &self.paper)
+            .field("dry_run", &self.dry_run)
+            .field("account", &self.account)
+            .field("args", &self.args)
+            .finish()
+    }
+}
+
+impl Config {
+    /// Resolve settings from the process arguments, environment, `.env` and profile file
+    pub fn load() -> Result<Self> {
+        Self::from_args(std::env::args().skip(1))
+    }
+
+    /// Resolve settings from the given arguments (without the program name)
+    pub fn from_args<I>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let (cli, args) = parse_flags(args)?;
+        let env = Layer::from_vars(|key| std::env::var(key).ok());
+        let dotenv = read_dotenv(Path::new(".env"))?;
+        let file = match profile_file_path() {
+            Some(path) => Some((read_profile_file(&path)?, path)),
+            None => None,
+        };
+        Self::resolve(cli.or(env).or(dotenv), file, args)
+    }
+
+    /// Settings from `layer` (flags, environment and `.env`, merged), completed
+    /// by the selected profile of `file`
+    fn resolve(layer: Layer, file: Option<(ProfileFile, PathBuf)>, args: Vec<String>) -> Result<Self> {
+        let profile = layer
+            .profile
+            .clone()
+            .or_else(|| file.as_ref().and_then(|(f, _)| f.default.clone()));
+        let layer = match &profile {
+            Some(name) => {
+                let Some((file, path)) = &file else {
+                    return Err(Error::ConfigError(format!(
+                        "profile '{}' requested but no {} found",
+                        name, LOCAL_PROFILE_FILE
+                    )));
+                };
+                let found = file.profiles.get(name).cloned().ok_or_else(|| {
+                    Error::ConfigError(format!(
+                        "profile '{}' not found in {}",
+                        name,
+                        path.display()
+                    ))
+                })?;
+                layer.or(found)
+            }
+            None => layer,
+        };
+
+        let network = match layer.testnet {
+            Some(true) => Network::Testnet,
+            Some(false) => Network::Mainnet,
+            None => layer
+                .endpoint
+                .as_deref()
+                .map(Network::detect)
+                .unwrap_or_default(),
+        };
+
+        Ok(Config {
+            endpoint: layer.endpoint,
+            private_key: layer.private_key,
+            profile,
+            network,
+            paper: layer.paper.unwrap_or(false),
+            dry_run: layer.dry_run.unwrap_or(false),
+            account: layer.account,
+            args,
+        })
+    }
+
+    /// Check that everything `requires` asks for is present.
+    ///
+    /// Prints usage for `name` and exits with status 1 otherwise, like the
+    /// examples always have. Paper trading signs with a dev key when none is set.
+    /// The examples act for the key's own account, so an `--account` they
+    /// would ignore is refused the same way.
+    pub fn require(self, name: &str, requires: Requires) -> Self {
+        if let Some(account) = &self.account {
+            eprintln!("{} acts for the account of PRIVATE_KEY only, not --account/HL_ACCOUNT '{}'.", name, account);
+            eprintln!("Named accounts work with `hl` and account_exporter; unset HL_ACCOUNT to run {}.", name);
+            std::process::exit(1);
+        }
+        self.require_accounts(name, requires)
+    }
+
+    /// [`require`](Self::require) for binaries that act for the accounts
+    /// `--account` selects (see [`Accounts::selected`](crate::accounts::Accounts::selected))
+    pub fn require_accounts(self, name: &str, requires: Requires) -> Self {
+        let missing_key = requires == Requires::Signer && self.private_key.is_none() && !self.paper;
+        if self.endpoint.is_none() || missing_key {
+            print_usage(name, requires);
+            std::process::exit(1);
+        }
+        self
+    }
+
+    /// Endpoint for display, with the token masked
+    pub fn redacted_endpoint(&self) -> String {
+        self.endpoint
+            .as_deref()
+            .map(redact_endpoint)
+            .unwrap_or_else(|| "(none)".to_string())
+    }
+
+    /// SDK builder preloaded with this config, for examples that tune other options
+    pub fn builder(&self) -> HyperliquidSDKBuilder {
+        let mut builder = HyperliquidSDK::new().testnet(self.network.is_testnet());
+        if let Some(ep) = &self.endpoint {
+            builder = builder.endpoint(ep);
+        }
+        if let Some(pk) = &self.private_key {
+            builder = builder.private_key(pk);
+        }
+        builder
+    }
+
+    /// Build an SDK from this config: trading on the paper exchange with
+    /// `paper`, and unable to send exchange actions with `dry_run`
+    pub async fn connect(&self) -> Result<HyperliquidSDK> {
+        if self.paper {
+            return crate::paper::connect(self).await;
+        }
+        if self.dry_run {
+            return crate::dry_run::connect(self).await;
+        }
+        Ok(self.builder().build().await?)
+    }
+
+    /// Route the process's HTTPS through the local port [`connect`](Self::connect)
+    /// starts the paper exchange (with `paper`) or the dry-run guard (with
+    /// `dry_run`) on; see [`prepare`]
+    pub fn prepare(&self) {
+        if self.paper {
+            crate::paper::install();
+        } else if self.dry_run {
+            crate::dry_run::install();
+        }
+    }
+}
+
+/// Set up what `--paper` and `--dry-run` need, first thing in `main`.
+///
+/// Both put a local proxy between the SDK and the API. The SDK
+/// can't be handed a proxy or trusted roots, so they go in the environment
+/// (`HTTPS_PROXY`, `SSL_CERT_FILE`), which its HTTP client reads when built.
+/// Changing the environment is only sound while the process has one thread,
+/// so examples call this before the tokio runtime starts:
+///
+/// ```no_run
+/// use hyperliquid_examples::{connect, Requires};
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     hyperliquid_examples::prepare();
+///     run()
+/// }
+///
+/// #[tokio::main]
+/// async fn run() -> Result<(), Box<dyn std::error::Error>> {
+///     let sdk = connect("place_order", Requires::Signer).await?;
+///     Ok(())
+/// }
+/// ```
+///
+/// Settings that fail to load are left for the example's own [`Config::load`]
+/// to report, and a proxy that fails to start for [`Config::connect`].
+pub fn prepare() {
+    if let Ok(config) = Config::load() {
+        config.prepare();
+    }
+}
+
+/// How long a streaming example runs: `HL_STREAM_SECONDS`, else `default_secs`.
+///
+/// Lets tests and quick checks run the streaming examples for a second or two.
+pub fn stream_duration(default_secs: u64) -> Duration {
+    let secs = std::env::var("HL_STREAM_SECONDS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(default_secs);
+    Duration::from_secs(secs)
+}
+
+/// Resolve the config, exit with usage if something is missing, and build the SDK.
+///
+/// This is the one-liner every example starts with.
+pub async fn connect(name: &str, requires: Requires) -> Result<HyperliquidSDK> {
+    Config::load()?.require(name, requires).connect().await
+}
+
+fn print_usage(name: &str, requires: Requires) {
+    eprintln!("Usage:");
+    eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
+    if requires == Requires::Signer {
+        eprintln!("  export PRIVATE_KEY='0x...'");
+    }
+    eprintln!("  cargo run --bin {}", name);
+    eprintln!();
+    eprintln!("Settings can also come from --endpoint/--private-key flags, a .env file,");
+    eprintln!("or a profile in {} (select with --profile NAME).", LOCAL_PROFILE_FILE);
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Sources
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Settings from one source; missing fields fall through to the next source
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Layer {
+    endpoint: Option<String>,
+    private_key: Option<String>,
+    #[serde(skip)]
+    profile: Option<String>,
+    testnet: Option<bool>,
+    paper: Option<bool>,
+    dry_run: Option<bool>,
+    account: Option<String>,
+}
+
+impl Layer {
+    fn from_vars(get: impl Fn(&str) -> Option<String>) -> Self {
+        let get = |key: &str| get(key).filter(|v| !v.trim().is_empty());
+        Layer {
+            endpoint: get("ENDPOINT"),
+            private_key: get("PRIVATE_KEY"),
+            profile: get("HL_PROFILE"),
+            testnet: get("TESTNET").map(|v| parse_bool(&v)),
+            paper: get("PAPER").map(|v| parse_bool(&v)),
+            dry_run: get("DRY_RUN").map(|v| parse_bool(&v)),
+            account: get("HL_ACCOUNT"),
+        }
+    }
+
+    fn or(self, other: Layer) -> Layer {
+        Layer {
+            endpoint: self.endpoint.or(other.endpoint),
+            private_key: self.private_key.or(other.private_key),
+            profile: self.profile.or(other.profile),
+            testnet: self.testnet.or(other.testnet),
+            paper: self.paper.or(other.paper),
+            dry_run: self.dry_run.or(other.dry_run),
+            account: self.account.or(other.account),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProfileFile {
+    default: Option<String>,
+    #[serde(default)]
+    profiles: HashMap<String, Layer>,
+}
+
+fn parse_bool(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "true" | "yes" | "on"
+    )
+}
+
+/// Pull the shared flags out of `args`, returning them and whatever is left
+fn parse_flags<I>(args: I) -> Result<(Layer, Vec<String>)>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut layer = Layer::default();
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None),
+        };
+        let slot = match flag.as_str() {
+            "--endpoint" => &mut layer.endpoint,
+            "--private-key" => &mut layer.private_key,
+            "--profile" => &mut layer.profile,
+            "--account" => &mut layer.account,
+            "--testnet" => {
+                layer.testnet = Some(inline.as_deref().map(parse_bool).unwrap_or(true));
+                continue;
+            }
+            "--paper" => {
+                layer.paper = Some(inline.as_deref().map(parse_bool).unwrap_or(true));
+                continue;
+            }
+            "--dry-run" => {
+                layer.dry_run = Some(inline.as_deref().map(parse_bool).unwrap_or(true));
+                continue;
+            }
+            _ => {
+                rest.push(arg);
+                continue;
+            }
+        };
+        let value = match inline {
+            Some(value) => value,
+            None => args
+                .next()
+                .ok_or_else(|| Error::ConfigError(format!("{} needs a value", flag)))?,
+        };
+        *slot = Some(value);
+    }
+
+    Ok((layer, rest))
+}
+
+fn read_dotenv(path: &Path) -> Result<Layer> {
+    if !path.exists() {
+        return Ok(Layer::default());
+    }
+    let vars = dotenvy::from_path_iter(path)
+        .map_err(|e| Error::ConfigError(format!("{}: {}", path.display(), e)))?
+        .collect::<std::result::Result<HashMap<_, _>, _>>()
+        .map_err(|e| Error::ConfigError(format!("{}: {}", path.display(), e)))?;
+    Ok(Layer::from_vars(|key| vars.get(key).cloned()))
+}
+
+pub(crate) fn profile_file_path() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("HL_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let local = PathBuf::from(LOCAL_PROFILE_FILE);
+    if local.exists() {
+        return Some(local);
+    }
+    let home = std::env::var_os("HOME")?;
+    let global = PathBuf::from(home).join(".config/hyperliquid/config.toml");
+    global.exists().then_some(global)
+}
+
+fn read_profile_file(path: &Path) -> Result<ProfileFile> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| Error::ConfigError(format!("{}: {}", path.display(), e)))?;
+    toml::from_str(&text).map_err(|e| Error::ConfigError(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"
+        default = "main"
+
+        [profiles.main]
+        endpoint = "https://main.hype-mainnet.quiknode.pro/0123456789abcdef0123"
+        private_key = "0xmain"
+
+        [profiles.test]
+        endpoint = "https://test.hype-testnet.quiknode.pro/0123456789abcdef0123"
+        private_key = "0xtest"
+        dry_run = true
+    "#;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> Layer {
+        let vars: HashMap<String, String> = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Layer::from_vars(|key| vars.get(key).cloned())
+    }
+
+    fn file(text: &str) -> Option<(ProfileFile, PathBuf)> {
+        Some((toml::from_str(text).unwrap(), PathBuf::from(LOCAL_PROFILE_FILE)))
+    }
+
+    /// Resolve `line` over the environment `env`, a `.env` of `dotenv` and `profiles`
+    fn resolve(line: &str, env: &[(&str, &str)], dotenv: &[(&str, &str)], profiles: Option<&str>) -> Result<Config> {
+        let (cli, rest) = parse_flags(args(line))?;
+        Config::resolve(cli.or(vars(env)).or(vars(dotenv)), profiles.and_then(file), rest)
+    }
+
+    #[test]
+    fn flags_beat_env_beat_dotenv_beat_profile() {
+        let config = resolve(
+            "--endpoint https://flag.invalid",
+            &[("ENDPOINT", "https://env.invalid"), ("PRIVATE_KEY", "0xenv")],
+            &[("PRIVATE_KEY", "0xdotenv"), ("HL_ACCOUNT", "fund")],
+            Some(FILE),
+        )
+        .unwrap();
+        assert_eq!(config.endpoint.as_deref(), Some("https://flag.invalid"));
+        assert_eq!(config.private_key.as_deref(), Some("0xenv"));
+        assert_eq!(config.account.as_deref(), Some("fund"));
+        assert_eq!(config.profile.as_deref(), Some("main"));
+
+        // Whatever no earlier source sets comes from the profile
+        let config = resolve("", &[("ENDPOINT", "https://env.invalid")], &[], Some(FILE)).unwrap();
+        assert_eq!(config.endpoint.as_deref(), Some("https://env.invalid"));
+        assert_eq!(config.private_key.as_deref(), Some("0xmain"));
+    }
+
+    #[test]
+    fn selects_the_default_or_requested_profile() {
+        let config = resolve("", &[], &[], Some(FILE)).unwrap();
+        assert_eq!(config.profile.as_deref(), Some("main"));
+        assert_eq!(config.network, Network::Mainnet);
+        assert!(!config.dry_run);
+
+        let config = resolve("", &[("HL_PROFILE", "test")], &[], Some(FILE)).unwrap();
+        assert_eq!(config.profile.as_deref(), Some("test"));
+        assert_eq!(config.private_key.as_deref(), Some("0xtest"));
+        assert_eq!(config.network, Network::Testnet);
+        assert!(config.dry_run);
+
+        let config = resolve("--profile=main", &[("HL_PROFILE", "test")], &[], Some(FILE)).unwrap();
+        assert_eq!(config.profile.as_deref(), Some("main"));
+
+        // No `default` and no selection: nothing comes from the file
+        let config = resolve("", &[], &[], Some("[profiles.main]\nprivate_key = \"0xmain\"")).unwrap();
+        assert_eq!(config.profile, None);
+        assert_eq!(config.private_key, None);
+    }
+
+    #[test]
+    fn reports_missing_profiles() {
+        let error = resolve("--profile main", &[], &[], None).unwrap_err().to_string();
+        assert!(error.contains("profile 'main' requested but no hyperliquid.toml found"), "{}", error);
+
+        let error = resolve("", &[("HL_PROFILE", "nope")], &[], Some(FILE)).unwrap_err().to_string();
+        assert!(error.contains("profile 'nope' not found in hyperliquid.toml"), "{}", error);
+    }
+
+    #[test]
+    fn parses_flags_and_keeps_the_rest() {
+        let line = "buy --endpoint=https://a.invalid/x=y BTC --testnet=false --paper --dry-run=0 --account main,fund 1";
+        let (layer, rest) = parse_flags(args(line)).unwrap();
+        assert_eq!(layer.endpoint.as_deref(), Some("https://a.invalid/x=y"));
+        assert_eq!(layer.testnet, Some(false));
+        assert_eq!(layer.paper, Some(true));
+        assert_eq!(layer.dry_run, Some(false));
+        assert_eq!(layer.account.as_deref(), Some("main,fund"));
+        assert_eq!(rest, ["buy", "BTC", "1"]);
+
+        let error = parse_flags(args("--private-key")).unwrap_err().to_string();
+        assert!(error.contains("--private-key needs a value"), "{}", error);
+
+        // Blank variables are unset; booleans take the usual spellings
+        let layer = vars(&[("ENDPOINT", "  "), ("TESTNET", "yes"), ("PAPER", "off")]);
+        assert_eq!((layer.endpoint, layer.testnet, layer.paper), (None, Some(true), Some(false)));
+    }
+
+    #[test]
+    fn network_comes_from_the_flag_or_the_endpoint() {
+        assert_eq!(Network::detect("https://x.hype-testnet.quiknode.pro/TOKEN"), Network::Testnet);
+        assert_eq!(Network::detect("https://api.hyperliquid-testnet.xyz"), Network::Testnet);
+        assert_eq!(Network::detect("https://x.hype-mainnet.quiknode.pro/testnet"), Network::Mainnet);
+        assert_eq!(Network::detect("not a url with TESTNET"), Network::Testnet);
+
+        let config = resolve("--testnet=false", &[("ENDPOINT", "https://api.hyperliquid-testnet.xyz")], &[], None).unwrap();
+        assert_eq!(config.network, Network::Mainnet);
+        let config = resolve("--endpoint https://api.hyperliquid-testnet.xyz", &[], &[], None).unwrap();
+        assert_eq!(config.network, Network::Testnet);
+        assert_eq!(resolve("", &[], &[], None).unwrap().network, Network::Mainnet);
+    }
+
+    #[test]
+    fn redacts_tokens_and_credentials() {
+        assert_eq!(
+            redact_endpoint("https://user:pw@x.hype-mainnet.quiknode.pro:8443/0123456789abcdef0123/info?key=1"),
+            "https://****@x.hype-mainnet.quiknode.pro:8443/0123****/info?****",
+        );
+        assert_eq!(redact_endpoint("0123456789abcdef"), "01234567****");
+        let config = Config { private_key: Some("0xsecret".to_string()), ..Config::default() };
+        assert!(!format!("{:?}", config).contains("secret"));
+    }
+}
@@ -0,0 +1,677 @@
+//! Dry runs: build and sign an exchange action without sending it.
+//!
+//! The SDK sends every action in two steps: the worker *builds* it (returning
+//! the canonical action, its nonce and the hash to sign) and a second request
+//! *sends* it with the signature. With `--dry-run` (or `DRY_RUN=1`) the
+//! mutating examples stop after the first step. [`sign`] builds the action
+//! with the worker, rebuilds the EIP-712 typed data locally, checks that it
+//! hashes to what the worker asked for, and signs it with the configured key.
+//! [`show`] prints the result. Nothing reaches `/exchange` as a signed action.
+//!
+//! Examples without a dry run of their own are covered too: with `dry_run`,
+//! [`prepare`](crate::prepare) routes the process's HTTPS through a local
+//! port before the runtime starts, and
+//! [`Config::connect`](crate::Config::connect) builds the SDK through
+//! [`connect`], whose proxy on that port lets the build half of each action reach the
+//! worker and answers the signed send itself, printing the action and
+//! refusing it with [`NOT_SENT`]. [`exchange::send`](crate::exchange::send)
+//! prints and refuses the same way.
+//!
+//! [`actions`] builds the same action bodies as the SDK's mutating methods,
+//! so an example can dry-run exactly what it would otherwise send:
+//!
+//! ```no_run
+//! use hyperliquid_examples::{dry_run, Config, Requires};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = Config::load()?.require("withdraw", Requires::Signer);
+//! let sdk = config.connect().await?;
+//! if config.dry_run {
+//!     let action = dry_run::actions::withdraw(sdk.chain(), 100.0, "0x...");
+//!     dry_run::show(&config, &action).await;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::{keccak256, Address, B256, U256};
+use alloy::signers::local::PrivateKeySigner;
+use axum::extract::{Request, State};
+use axum::http::{Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use hyperliquid_sdk::signing::{recover_signer, rmp_hash, sign_hash};
+use hyperliquid_sdk::{Chain, HyperliquidSDK, Signature};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::mock::proxy::{self, Intercept, Tunnel};
+
+/// Where the SDK builds and sends actions
+pub(crate) const EXCHANGE_URL: &str = "https://send.hyperliquidapi.com/exchange";
+
+/// Error a dry run answers signed actions with
+pub const NOT_SENT: &str = "Dry run: action not sent";
+
+static GUARD: OnceLock<Guard> = OnceLock::new();
+
+/// EIP-712 struct fields: `(name, type)`
+type Fields = &'static [(&'static str, &'static str)];
+
+/// EIP-712 type of each user-signed action: `(action type, primary type, fields)`.
+///
+/// Every other action is an L1 action, signed as an `Agent` whose
+/// `connectionId` is the MessagePack hash of the action and nonce.
+const USER_SIGNED: &[(&str, &str, Fields)] = &[
+    (
+        "usdSend",
+        "HyperliquidTransaction:UsdSend",
+        &[("hyperliquidChain", "string"), ("destination", "string"), ("amount", "string"), ("time", "uint64")],
+    ),
+    (
+        "spotSend",
+        "HyperliquidTransaction:SpotSend",
+        &[
+            ("hyperliquidChain", "string"),
+            ("destination", "string"),
+            ("token", "string"),
+            ("amount", "string"),
+            ("time", "uint64"),
+        ],
+    ),
+    (
+        "withdraw3",
+        "HyperliquidTransaction:Withdraw",
+        &[("hyperliquidChain", "string"), ("destination", "string"), ("amount", "string"), ("time", "uint64")],
+    ),
+    (
+        "usdClassTransfer",
+        "HyperliquidTransaction:UsdClassTransfer",
+        &[("hyperliquidChain", "string"), ("amount", "string"), ("toPerp", "bool"), ("nonce", "uint64")],
+    ),
+    (
+        "cDeposit",
+        "HyperliquidTransaction:CDeposit",
+        &[("hyperliquidChain", "string"), ("wei", "uint64"), ("nonce", "uint64")],
+    ),
+    (
+        "cWithdraw",
+        "HyperliquidTransaction:CWithdraw",
+        &[("hyperliquidChain", "string"), ("wei", "uint64"), ("nonce", "uint64")],
+    ),
+    (
+        "tokenDelegate",
+        "HyperliquidTransaction:TokenDelegate",
+        &[
+            ("hyperliquidChain", "string"),
+            ("validator", "address"),
+            ("wei", "uint64"),
+            ("isUndelegate", "bool"),
+            ("nonce", "uint64"),
+        ],
+    ),
+    (
+        "approveBuilderFee",
+        "HyperliquidTransaction:ApproveBuilderFee",
+        &[("hyperliquidChain", "string"), ("maxFeeRate", "string"), ("builder", "address"), ("nonce", "uint64")],
+    ),
+    (
+        "approveAgent",
+        "HyperliquidTransaction:ApproveAgent",
+        &[("hyperliquidChain", "string"), ("agentAddress", "address"), ("agentName", "string"), ("nonce", "uint64")],
+    ),
+];
+
+const DOMAIN_FIELDS: Fields = &[
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+];
+
+const AGENT_FIELDS: Fields = &[("source", "string"), ("connectionId", "bytes32")];
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Signing
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// An exchange action, built and signed but not sent
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedAction {
+    /// Canonical action, as the worker built it
+    pub action: Value,
+    pub nonce: u64,
+    /// Sub-account or vault the action is signed for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vault_address: Option<Address>,
+    /// EIP-712 typed data, when the action type is known
+    pub typed_data: Option<Value>,
+    /// Hash the worker asked to sign
+    pub hash: B256,
+    /// Hash of `typed_data`, if it differs from `hash`
+    pub mismatch: Option<B256>,
+    pub signature: Signature,
+    /// Address recovered from the signature
+    pub signer: Address,
+}
+
+/// Build `action` with the worker and sign it, without sending it
+pub async fn sign(config: &Config, action: &Value) -> Result<SignedAction> {
+    sign_for(config, action, None).await
+}
+
+/// [`sign`] on behalf of a sub-account or vault led by the configured key.
+///
+/// The worker's hash does not commit to a `vaultAddress`, so with one the
+/// hash is computed locally from the typed data instead.
+pub async fn sign_for(config: &Config, action: &Value, vault_address: Option<Address>) -> Result<SignedAction> {
+    let key = match (&config.private_key, config.paper) {
+        (Some(key), _) => key.as_str(),
+        (None, true) => crate::paper::PAPER_KEY,
+        (None, false) => return Err(Error::ConfigError("a dry run needs PRIVATE_KEY".to_string())),
+    };
+    let signer = PrivateKeySigner::from_str(key)
+        .map_err(|e| Error::ConfigError(format!("invalid private key: {}", e)))?;
+    let chain = if config.network.is_testnet() { Chain::Testnet } else { Chain::Mainnet };
+
+    let built: Value = reqwest::Client::new()
+        .post(EXCHANGE_URL)
+        .json(&json!({"action": action}))
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| Error::ValidationError(format!("build request failed: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| Error::ValidationError(format!("build response unreadable: {}", e)))?;
+    if let Some(error) = built.get("error") {
+        return Err(Error::ValidationError(format!("build rejected: {}", error)));
+    }
+    let action = built.get("action").cloned().unwrap_or_else(|| action.clone());
+    let nonce = built
+        .get("nonce")
+        .and_then(|n| n.as_u64())
+        .ok_or_else(|| Error::ValidationError(format!("build response has no nonce: {}", built)))?;
+    let built_hash = built
+        .get("hash")
+        .and_then(|h| h.as_str())
+        .and_then(|h| B256::from_str(h).ok());
+
+    let typed_data = typed_data_for(&action, nonce, chain, vault_address).ok();
+    let local = typed_data.as_ref().and_then(|t| signing_hash(t).ok());
+    let hash = match vault_address {
+        Some(_) => local.ok_or_else(|| Error::ValidationError(format!("cannot hash {} for a vault", action)))?,
+        None => built_hash
+            .ok_or_else(|| Error::ValidationError(format!("build response has no hash: {}", built)))?,
+    };
+    let signature = sign_hash(&signer, hash).await?;
+    let signer = recover_signer(hash, &signature)?;
+
+    Ok(SignedAction {
+        action,
+        nonce,
+        vault_address,
+        typed_data,
+        hash,
+        mismatch: local.filter(|local| *local != hash),
+        signature,
+        signer,
+    })
+}
+
+/// [`sign`] and print the result (or the error) in the examples' format
+pub async fn show(config: &Config, action: &Value) {
+    match sign(config, action).await {
+        Ok(signed) => signed.print(),
+        Err(e) => println!("   Error: {}", e),
+    }
+}
+
+impl SignedAction {
+    /// Print everything a reviewer needs to check the action
+    pub fn print(&self) {
+        for line in self.lines() {
+            println!("   {}", line);
+        }
+    }
+
+    /// [`print`](Self::print)'s lines, unindented
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            "Dry run (not sent):".to_string(),
+            format!("Action: {}", self.action),
+            format!("Nonce: {}", self.nonce),
+        ];
+        if let Some(vault) = self.vault_address {
+            lines.push(format!("Vault address: {:?}", vault));
+        }
+        match &self.typed_data {
+            Some(typed_data) => {
+                let pretty = serde_json::to_string_pretty(typed_data).unwrap_or_default();
+                lines.push("Typed data:".to_string());
+                lines.extend(pretty.lines().map(|line| format!("  {}", line)));
+            }
+            None => lines.push("Typed data: (not available for this action)".to_string()),
+        }
+        lines.push(format!("Hash: {}", self.hash));
+        if let Some(local) = self.mismatch {
+            lines.push(format!("Warning: the typed data hashes to {}", local));
+        }
+        lines.push(format!("Signature: {}", json!(self.signature)));
+        lines.push(format!("Signer: {}", self.signer));
+        lines
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Guard
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Build an SDK that cannot send exchange actions (started on first use).
+///
+/// Its HTTPS traffic goes through a local proxy that forwards everything
+/// except signed `/exchange` requests: those are printed and answered with an
+/// `"err"` status of [`NOT_SENT`], so the SDK reports the action as rejected.
+pub async fn connect(config: &Config) -> Result<HyperliquidSDK> {
+    if GUARD.get().is_none() {
+        let guard = Guard::start(Intercept::take()?)?;
+        GUARD.get_or_init(|| guard);
+    }
+    Ok(config.builder().build().await?)
+}
+
+/// Route the process's HTTPS through the guard's port; see [`prepare`](crate::prepare)
+pub(crate) fn install() {
+    proxy::install("dry-run");
+}
+
+/// The proxy between a dry-run SDK and the exchange
+struct Guard {
+    _task: JoinHandle<()>,
+}
+
+impl Guard {
+    /// Start on the port the process's HTTPS goes through. Its forwarding
+    /// client was built before the environment changed, so it keeps the
+    /// proxy settings and trusted roots the process started with.
+    fn start(intercept: Intercept) -> Result<Self> {
+        let app = Router::new().fallback(guard_request).with_state(intercept.client.clone());
+        Ok(Guard { _task: intercept.serve(app)? })
+    }
+}
+
+/// Print and refuse a signed `/exchange` action; forward everything else
+async fn guard_request(State(client): State<reqwest::Client>, request: Request) -> Response {
+    let (parts, body) = request.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    if parts.method == Method::POST && parts.uri.path().ends_with("/exchange") {
+        let request = serde_json::from_slice::<Value>(&body).unwrap_or_default();
+        if let (Some(action), Some(_)) = (request.get("action"), request.get("signature")) {
+            println!("   Dry run (not sent): {}", action);
+            return Json(json!({"status": "err", "response": NOT_SENT})).into_response();
+        }
+    }
+    let tunnel = parts.extensions.get::<Tunnel>();
+    proxy::forward(&client, tunnel, parts.method.clone(), &parts.uri, parts.headers.clone(), body).await
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// EIP-712
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// EIP-712 typed data a wallet signs for `action` at `nonce`
+pub fn typed_data(action: &Value, nonce: u64, chain: Chain) -> Result<Value> {
+    typed_data_for(action, nonce, chain, None)
+}
+
+/// [`typed_data`] for an action sent on behalf of a sub-account or vault.
+///
+/// Only L1 actions can carry a `vaultAddress`; user-signed actions (transfers,
+/// withdrawals, approvals) are always signed for the key's own account.
+pub fn typed_data_for(action: &Value, nonce: u64, chain: Chain, vault_address: Option<Address>) -> Result<Value> {
+    let kind = action.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+    let fields = |list: &[(&str, &str)]| {
+        list.iter()
+            .map(|(name, kind)| json!({"name": name, "type": kind}))
+            .collect::<Vec<_>>()
+    };
+
+    if let Some((_, primary, list)) = USER_SIGNED.iter().find(|(k, _, _)| *k == kind) {
+        if vault_address.is_some() {
+            return Err(Error::ValidationError(format!("{} cannot be signed for a vault or sub-account", kind)));
+        }
+        let chain_id = action
+            .get("signatureChainId")
+            .and_then(|c| c.as_str())
+            .and_then(|c| u64::from_str_radix(c.trim_start_matches("0x"), 16).ok())
+            .ok_or_else(|| Error::ValidationError("action has no signatureChainId".to_string()))?;
+        let mut message = serde_json::Map::new();
+        for (name, _) in *list {
+            let value = action
+                .get(*name)
+                .ok_or_else(|| Error::ValidationError(format!("action has no '{}'", name)))?;
+            message.insert(name.to_string(), value.clone());
+        }
+        return Ok(json!({
+            "types": {"EIP712Domain": fields(DOMAIN_FIELDS), *primary: fields(list)},
+            "primaryType": primary,
+            "domain": {
+                "name": "HyperliquidSignTransaction",
+                "version": "1",
+                "chainId": chain_id,
+                "verifyingContract": Address::ZERO,
+            },
+            "message": message,
+        }));
+    }
+
+    let connection_id = rmp_hash(action, nonce, vault_address, None)
+        .map_err(|e| Error::ValidationError(format!("cannot encode action: {}", e)))?;
+    Ok(json!({
+        "types": {"EIP712Domain": fields(DOMAIN_FIELDS), "Agent": fields(AGENT_FIELDS)},
+        "primaryType": "Agent",
+        "domain": {
+            "name": "Exchange",
+            "version": "1",
+            "chainId": 1337,
+            "verifyingContract": Address::ZERO,
+        },
+        "message": {
+            "source": if chain.is_mainnet() { "a" } else { "b" },
+            "connectionId": connection_id,
+        },
+    }))
+}
+
+/// Hash a wallet signs for `action` at `nonce`, if the action type is known
+pub fn action_hash(action: &Value, nonce: u64, chain: Chain) -> Option<B256> {
+    typed_data(action, nonce, chain).and_then(|t| signing_hash(&t)).ok()
+}
+
+/// EIP-712 signing hash of typed data built by [`typed_data`]
+pub fn signing_hash(typed_data: &Value) -> Result<B256> {
+    let primary = typed_data["primaryType"].as_str().unwrap_or_default();
+    let domain = struct_hash(typed_data, "EIP712Domain", &typed_data["domain"])?;
+    let message = struct_hash(typed_data, primary, &typed_data["message"])?;
+
+    let mut bytes = vec![0x19, 0x01];
+    bytes.extend_from_slice(domain.as_slice());
+    bytes.extend_from_slice(message.as_slice());
+    Ok(keccak256(bytes))
+}
+
+/// `hashStruct` of a flat struct (every field an atomic or string type)
+fn struct_hash(typed_data: &Value, name: &str, value: &Value) -> Result<B256> {
+    let fields: Vec<(&str, &str)> = typed_data["types"][name]
+        .as_array()
+        .ok_or_else(|| Error::ValidationError(format!("typed data has no type '{}'", name)))?
+        .iter()
+        .map(|f| (f["name"].as_str().unwrap_or_default(), f["type"].as_str().unwrap_or_default()))
+        .collect();
+    let signature = fields
+        .iter()
+        .map(|(field, kind)| format!("{} {}", kind, field))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let mut bytes = keccak256(format!("{}({})", name, signature)).to_vec();
+    for (field, kind) in fields {
+        let word = encode_value(kind, &value[field])
+            .map_err(|e| Error::ValidationError(format!("{}.{}: {}", name, field, e)))?;
+        bytes.extend_from_slice(word.as_slice());
+    }
+    Ok(keccak256(bytes))
+}
+
+fn encode_value(kind: &str, value: &Value) -> std::result::Result<B256, String> {
+    let text = value.as_str();
+    match kind {
+        "string" => Ok(keccak256(text.ok_or("expected a string")?)),
+        "bytes32" => B256::from_str(text.ok_or("expected hex")?).map_err(|e| e.to_string()),
+        "address" => {
+            let address = Address::from_str(text.ok_or("expected an address")?).map_err(|e| e.to_string())?;
+            Ok(address.into_word())
+        }
+        "bool" => Ok(B256::from(U256::from(value.as_bool().ok_or("expected a bool")? as u8))),
+        _ if kind.starts_with("uint") => {
+            let number = match (value.as_u64(), text) {
+                (Some(n), _) => U256::from(n),
+                (None, Some(s)) => U256::from_str(s).map_err(|e| e.to_string())?,
+                _ => return Err("expected an integer".to_string()),
+            };
+            Ok(B256::from(number))
+        }
+        other => Err(format!("unsupported type {}", other)),
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Actions
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Action bodies of the SDK's mutating methods, built the same way
+pub mod actions {
+    use hyperliquid_sdk::Chain;
+    use serde_json::{json, Value};
+
+    use super::now_ms;
+    use crate::price::{Px, Sz};
+
+    /// `sdk.withdraw(amount, Some(destination))`
+    pub fn withdraw(chain: Chain, amount: f64, destination: &str) -> Value {
+        json!({
+            "type": "withdraw3",
+            "hyperliquidChain": chain.to_string(),
+            "signatureChainId": chain.signature_chain_id(),
+            "destination": destination,
+            "amount": format!("{}", amount),
+            "time": now_ms(),
+        })
+    }
+
+    /// `sdk.transfer_usd(destination, amount)`
+    pub fn transfer_usd(chain: Chain, destination: &str, amount: f64) -> Value {
+        json!({
+            "type": "usdSend",
+            "hyperliquidChain": chain.to_string(),
+            "signatureChainId": chain.signature_chain_id(),
+            "destination": destination,
+            "amount": format!("{}", amount),
+            "time": now_ms(),
+        })
+    }
+
+    /// `sdk.transfer_spot(token, destination, amount)`
+    pub fn transfer_spot(chain: Chain, token: &str, destination: &str, amount: f64) -> Value {
+        json!({
+            "type": "spotSend",
+            "hyperliquidChain": chain.to_string(),
+            "signatureChainId": chain.signature_chain_id(),
+            "token": token,
+            "destination": destination,
+            "amount": format!("{}", amount),
+            "time": now_ms(),
+        })
+    }
+
+    /// `sdk.transfer_spot_to_perp(amount)` / `sdk.transfer_perp_to_spot(amount)`
+    pub fn usd_class_transfer(chain: Chain, amount: f64, to_perp: bool) -> Value {
+        json!({
+            "type": "usdClassTransfer",
+            "hyperliquidChain": chain.to_string(),
+            "signatureChainId": chain.signature_chain_id(),
+            "amount": format!("{}", amount),
+            "toPerp": to_perp,
+            "nonce": now_ms(),
+        })
+    }
+
+    /// `sdk.vault_deposit(vault, usd)` / `sdk.vault_withdraw(vault, usd)`
+    pub fn vault_transfer(vault_address: &str, is_deposit: bool, usd: f64) -> Value {
+        json!({
+            "type": "vaultTransfer",
+            "vaultAddress": vault_address,
+            "isDeposit": is_deposit,
+            "usd": usd,
+        })
+    }
+
+    /// `sdk.stake(amount)` / `sdk.unstake(amount)`
+    pub fn stake(chain: Chain, amount_tokens: f64, unstake: bool) -> Value {
+        json!({
+            "type": if unstake { "cWithdraw" } else { "cDeposit" },
+            "hyperliquidChain": chain.to_string(),
+            "signatureChainId": chain.signature_chain_id(),
+            "wei": ((amount_tokens * 1e18) as u128).to_string(),
+            "nonce": now_ms(),
+        })
+    }
+
+    /// `sdk.delegate(validator, amount)` / `sdk.undelegate(validator, amount)`
+    pub fn token_delegate(chain: Chain, validator: &str, amount_tokens: f64, is_undelegate: bool) -> Value {
+        json!({
+            "type": "tokenDelegate",
+            "hyperliquidChain": chain.to_string(),
+            "signatureChainId": chain.signature_chain_id(),
+            "validator": validator,
+            "isUndelegate": is_undelegate,
+            "wei": ((amount_tokens * 1e18) as u128).to_string(),
+            "nonce": now_ms(),
+        })
+    }
+
+    /// `sdk.update_leverage(asset, leverage, is_cross)`, with the asset's id
+    pub fn update_leverage(asset_id: u32, leverage: i32, is_cross: bool) -> Value {
+        json!({
+            "type": "updateLeverage",
+            "asset": asset_id,
+            "isCross": is_cross,
+            "leverage": leverage,
+        })
+    }
+
+    /// `sdk.order(..)` for one limit order, with a fresh cloid like the SDK's.
+    ///
+    /// `px` and `sz` go on the wire as rounded for the asset; the SDK would
+    /// round the price to a whole number instead.
+    pub fn order(asset_id: u32, is_buy: bool, px: Px, sz: Sz, tif: &str, reduce_only: bool) -> Value {
+        json!({
+            "type": "order",
+            "orders": [{
+                "a": asset_id,
+                "b": is_buy,
+                "p": px.to_string(),
+                "s": sz.to_string(),
+                "r": reduce_only,
+                "t": {"limit": {"tif": tif}},
+                "c": crate::exchange::cloid(),
+            }],
+            "grouping": "na",
+        })
+    }
+
+    /// `sdk.cancel(oid, asset)` / `sdk.cancel_all(..)`, for `(asset id, oid)` pairs
+    pub fn cancel(orders: &[(u32, u64)]) -> Value {
+        let cancels: Vec<Value> = orders.iter().map(|(a, o)| json!({"a": a, "o": o})).collect();
+        json!({
+            "type": "cancel",
+            "cancels": cancels,
+        })
+    }
+
+    /// `sdk.cancel_by_cloid(cloid, asset)`
+    pub fn cancel_by_cloid(asset_id: u32, cloid: &str) -> Value {
+        json!({
+            "type": "cancelByCloid",
+            "cancels": [{"asset": asset_id, "cloid": cloid}],
+        })
+    }
+
+    /// `sdk.approve_builder_fee(Some(max_fee))` (the worker adds the builder and nonce)
+    pub fn approve_builder_fee(max_fee: &str) -> Value {
+        json!({
+            "type": "approveBuilderFee",
+            "maxFeeRate": max_fee,
+        })
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyperliquid_sdk::signing::agent_signing_hash;
+
+    #[test]
+    fn agent_hash_matches_the_sdk() {
+        let action = actions::update_leverage(0, 10, true);
+        let typed = typed_data(&action, 1_737_412_345_000, Chain::Mainnet).unwrap();
+        let connection_id = rmp_hash(&action, 1_737_412_345_000, None, None).unwrap();
+
+        assert_eq!(typed["primaryType"], "Agent");
+        assert_eq!(typed["message"]["source"], "a");
+        assert_eq!(signing_hash(&typed).unwrap(), agent_signing_hash(Chain::Mainnet, connection_id));
+
+        let testnet = typed_data(&action, 1_737_412_345_000, Chain::Testnet).unwrap();
+        assert_eq!(testnet["message"]["source"], "b");
+    }
+
+    #[test]
+    fn user_signed_actions_use_their_own_domain() {
+        let action = actions::withdraw(Chain::Testnet, 100.0, "0x0000000000000000000000000000000000000001");
+        let typed = typed_data(&action, 0, Chain::Testnet).unwrap();
+
+        assert_eq!(typed["primaryType"], "HyperliquidTransaction:Withdraw");
+        assert_eq!(typed["domain"]["chainId"], 0x66eee);
+        assert_eq!(typed["message"]["amount"], "100");
+        assert_eq!(typed["message"].as_object().unwrap().len(), 4);
+        assert!(signing_hash(&typed).is_ok());
+
+        // The worker fills in the builder; without it there is nothing to sign
+        assert!(typed_data(&actions::approve_builder_fee("1%"), 0, Chain::Mainnet).is_err());
+    }
+
+    #[test]
+    fn vault_actions_commit_to_the_vault_address() {
+        let vault = Address::from_str("0xdfc24b077bc1425ad1dea75bcb6f8158e10df303").unwrap();
+        let action = actions::cancel(&[(0, 42)]);
+        let typed = typed_data_for(&action, 7, Chain::Mainnet, Some(vault)).unwrap();
+        let connection_id = rmp_hash(&action, 7, Some(vault), None).unwrap();
+
+        assert_eq!(signing_hash(&typed).unwrap(), agent_signing_hash(Chain::Mainnet, connection_id));
+        assert_ne!(action_hash(&action, 7, Chain::Mainnet), Some(signing_hash(&typed).unwrap()));
+
+        let withdraw = actions::withdraw(Chain::Mainnet, 1.0, "0x0000000000000000000000000000000000000001");
+        assert!(typed_data_for(&withdraw, 0, Chain::Mainnet, Some(vault)).is_err());
+    }
+
+    #[test]
+    fn encodes_eip712_atoms() {
+        let word = |kind: &str, value: Value| encode_value(kind, &value).unwrap();
+        assert_eq!(word("uint64", json!(1)), B256::from(U256::from(1)));
+        assert_eq!(word("uint64", json!("100000000000000000000")), B256::from(U256::from(100_000_000_000_000_000_000u128)));
+        assert_eq!(word("bool", json!(true)), B256::from(U256::from(1)));
+        assert_eq!(word("string", json!("Mainnet")), keccak256("Mainnet"));
+        assert_eq!(
+            word("address", json!("0x0000000000000000000000000000000000000001")),
+            B256::from(U256::from(1))
+        );
+        assert!(encode_value("uint64", &json!(true)).is_err());
+    }
+}
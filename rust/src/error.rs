@@ -0,0 +1,34 @@
+//! Error types shared by the example helpers.
+
+use thiserror::Error;
+
+/// Result type for the example helpers
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors raised by the shared helpers (the SDK's own errors pass through)
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Configuration error (bad flag, unreadable profile file, unknown profile)
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    /// Invalid order parameter (price off the tick grid, unknown asset, ...)
+    #[error("Validation error: {0}")]
+    ValidationError(String),
+
+    /// Error returned by the Hyperliquid SDK
+    #[error(transparent)]
+    SdkError(#[from] hyperliquid_sdk::Error),
+
+    /// Response did not match the expected model
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    /// Filesystem error
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// Local database error (candle store)
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] rusqlite::Error),
+}
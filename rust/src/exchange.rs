@@ -0,0 +1,171 @@
+//! Exchange actions the SDK has no method for.
+//!
+//! The SDK's methods each send one kind of action for the key's own account.
+//! Grouped orders (an entry with its TP/SL, see [`bracket`](crate::bracket))
+//! and actions for a sub-account or vault (see [`accounts`](crate::accounts))
+//! are built with [`dry_run::actions`](crate::dry_run::actions) or by hand,
+//! signed with [`dry_run::sign_for`] and sent here, through the same worker
+//! (and the same [`paper`](crate::paper) exchange with `--paper`).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::Address;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use crate::config::Config;
+use crate::dry_run::{self, EXCHANGE_URL};
+use crate::error::{Error, Result};
+
+/// Sign `action` with `config`'s key and send it, for `vault_address` if given.
+///
+/// Returns the exchange's response as is; a rejected action is an `"err"`
+/// status or an error in `response.data.statuses`, not an `Err`. With
+/// `config.dry_run` the signed action is printed instead and answered with
+/// an `"err"` status of [`dry_run::NOT_SENT`].
+pub async fn send(config: &Config, action: &Value, vault_address: Option<Address>) -> Result<Value> {
+    let signed = dry_run::sign_for(config, action, vault_address).await?;
+    if config.dry_run {
+        signed.print();
+        return Ok(json!({"status": "err", "response": dry_run::NOT_SENT}));
+    }
+    let mut body = json!({
+        "action": signed.action,
+        "nonce": signed.nonce,
+        "signature": signed.signature,
+    });
+    if let Some(vault) = signed.vault_address {
+        body["vaultAddress"] = json!(format!("{:?}", vault));
+    }
+    reqwest::Client::new()
+        .post(EXCHANGE_URL)
+        .json(&body)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| Error::ValidationError(format!("send request failed: {}", e)))?
+        .json()
+        .await
+        .map_err(|e| Error::ValidationError(format!("send response unreadable: {}", e)))
+}
+
+/// Error message of an exchange response, if it reports one
+pub fn rejection(response: &Value) -> Option<String> {
+    if response.get("status").and_then(|s| s.as_str()) == Some("err") {
+        let message = response.get("response").cloned().unwrap_or_default();
+        return Some(message.as_str().map(str::to_string).unwrap_or_else(|| message.to_string()));
+    }
+    response
+        .pointer("/response/data/statuses")
+        .and_then(|s| s.as_array())
+        .into_iter()
+        .flatten()
+        .find_map(|status| status.get("error"))
+        .map(|error| error.as_str().map(str::to_string).unwrap_or_else(|| error.to_string()))
+}
+
+/// What the exchange did with one order of an `order` or `batchModify` action
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderStatus {
+    Resting { oid: u64 },
+    /// Filled right away, in full or in part (an IOC's rest is cancelled)
+    Filled { oid: u64, total_sz: Decimal, avg_px: Decimal },
+    /// A TP/SL of a grouped order, waiting for its entry to fill
+    WaitingForFill,
+    Error(String),
+}
+
+impl OrderStatus {
+    /// Status of each order of `response`, or why the whole action was rejected
+    pub fn all(response: &Value) -> std::result::Result<Vec<OrderStatus>, String> {
+        match response.pointer("/response/data/statuses").and_then(|s| s.as_array()) {
+            Some(statuses) => Ok(statuses.iter().map(Self::parse).collect()),
+            None => Err(rejection(response).unwrap_or_else(|| response.to_string())),
+        }
+    }
+
+    /// Status of the first (or only) order of `response`; a rejected
+    /// action is an [`Error`](Self::Error)
+    pub fn first(response: &Value) -> OrderStatus {
+        match Self::all(response) {
+            Ok(statuses) => statuses
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| OrderStatus::Error(response.to_string())),
+            Err(reason) => OrderStatus::Error(reason),
+        }
+    }
+
+    /// Oid of a resting or filled order
+    pub fn oid(&self) -> Option<u64> {
+        match self {
+            OrderStatus::Resting { oid } | OrderStatus::Filled { oid, .. } => Some(*oid),
+            _ => None,
+        }
+    }
+
+    /// Status of one order; a resting or filled status missing its oid, size
+    /// or price is an [`Error`](Self::Error) rather than an order of zero
+    fn parse(status: &Value) -> OrderStatus {
+        let oid = |v: &Value| v.get("oid").and_then(|o| o.as_u64());
+        let decimal = |v: &Value, key: &str| {
+            v.get(key)
+                .and_then(|v| v.as_str())
+                .and_then(|v| v.parse::<Decimal>().ok())
+        };
+        let malformed = || OrderStatus::Error(format!("malformed order status: {}", status));
+        if let Some(resting) = status.get("resting") {
+            return oid(resting).map_or_else(malformed, |oid| OrderStatus::Resting { oid });
+        }
+        if let Some(filled) = status.get("filled") {
+            return match (oid(filled), decimal(filled, "totalSz"), decimal(filled, "avgPx")) {
+                (Some(oid), Some(total_sz), Some(avg_px)) => OrderStatus::Filled { oid, total_sz, avg_px },
+                _ => malformed(),
+            };
+        }
+        if status.as_str() == Some("waitingForFill") {
+            return OrderStatus::WaitingForFill;
+        }
+        let error = status.get("error").unwrap_or(status);
+        OrderStatus::Error(error.as_str().map(str::to_string).unwrap_or_else(|| error.to_string()))
+    }
+}
+
+/// A fresh client order id, different on every call
+pub fn cloid() -> String {
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    let n = CALLS.fetch_add(1, Ordering::Relaxed);
+    format!("0x{:016x}{:016x}", nanos, nanos.wrapping_mul(0x517cc1b727220a95) ^ n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_statuses_need_their_oid_size_and_price() {
+        let response = json!({"status": "ok", "response": {"type": "order", "data": {"statuses": [
+            {"resting": {"oid": 7}},
+            {"filled": {"oid": 8, "totalSz": "0.01", "avgPx": "96251.0"}},
+            "waitingForFill",
+            {"error": "Order has invalid price."},
+            {"resting": {}},
+            {"filled": {"oid": 9, "totalSz": "0.01"}},
+        ]}}});
+        let statuses = OrderStatus::all(&response).unwrap();
+
+        assert_eq!(statuses[0], OrderStatus::Resting { oid: 7 });
+        let filled = OrderStatus::Filled { oid: 8, total_sz: "0.01".parse().unwrap(), avg_px: "96251.0".parse().unwrap() };
+        assert_eq!(statuses[1], filled);
+        assert_eq!(statuses[2], OrderStatus::WaitingForFill);
+        assert_eq!(statuses[3], OrderStatus::Error("Order has invalid price.".to_string()));
+        for malformed in &statuses[4..] {
+            assert!(matches!(malformed, OrderStatus::Error(e) if e.starts_with("malformed")), "{:?}", malformed);
+        }
+    }
+}
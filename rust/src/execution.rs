@@ -0,0 +1,1025 @@
+//! Client-side execution algorithms: a parent order worked into child orders
+//! over time.
+//!
+//! [`Algo`] describes the parent order and its schedule:
+//!
+//! - TWAP: equal slices over the duration;
+//! - VWAP: slices weighted by how much the coin usually trades at that time
+//!   of day, a [`VolumeProfile`] built from past `info.candles`;
+//! - POV: a share of the volume the market trades from the start (fed from
+//!   `stream.trades` with [`Execution::on_trade`]), until the size is done.
+//!
+//! [`Execution::step`] runs every tick. It sends the child order the
+//! schedule calls for, either an IOC across the spread or a GTC at the touch
+//! that lives until the next step, never beyond the price limit, and reports
+//! what it did as [`Activity`]. A [`Control`] handle pauses, resumes or
+//! cancels the execution from another task. [`Progress`] reads the same for
+//! an [`Execution`] as for a [`NativeTwap`], the exchange's own TWAP, so the
+//! two can be compared.
+//!
+//! ```no_run
+//! use std::time::{Duration, Instant};
+//!
+//! use hyperliquid_examples::execution::{Algo, Control, ExecStatus};
+//! use hyperliquid_examples::models::TypedInfo;
+//! use hyperliquid_examples::{AssetRegistry, Config, Requires};
+//! use rust_decimal::Decimal;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = Config::load()?.require("algo", Requires::Signer);
+//! let sdk = config.connect().await?;
+//! let info = TypedInfo::from_sdk(&sdk);
+//! let user = format!("{:?}", sdk.address().unwrap());
+//! let registry = AssetRegistry::load(&sdk).await?;
+//! let mut execution = Algo::buy("BTC", Decimal::new(1, 2))
+//!     .twap(Duration::from_secs(600), 10)
+//!     .start(registry.resolve("BTC")?, &user, Instant::now())?;
+//! let control = Control::default();
+//! while matches!(execution.status, ExecStatus::Running | ExecStatus::Paused) {
+//!     for activity in execution.step(&config, &info, &control, Instant::now()).await? {
+//!         println!("{}", activity);
+//!     }
+//!     tokio::time::sleep(Duration::from_secs(1)).await;
+//! }
+//! println!("{}", execution.progress());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use hyperliquid_sdk::Stream;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use crate::config::Config;
+use crate::dry_run::actions;
+use crate::error::{Error, Result};
+use crate::exchange::{self, rejection, OrderStatus};
+use crate::export::{self, interval_ms};
+use crate::feed;
+use crate::models::{Candle, L2Book, MarketEvent, Trade, TypedInfo};
+use crate::price::{check_slippage, Precision, Rounding, DEFAULT_SLIPPAGE};
+use crate::registry::Market;
+
+/// Smallest order value the exchange accepts
+pub const MIN_NOTIONAL: Decimal = Decimal::from_parts(10, 0, 0, false, 0);
+
+const DAY_MS: u64 = 86_400_000;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Schedules
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// How the size is spread over time
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    /// Equal slices
+    Twap,
+    /// Slices weighted by the usual volume
+    Vwap(VolumeProfile),
+    /// This share (0.1 = 10%) of the volume traded since the start
+    Pov(Decimal),
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Schedule::Twap => f.write_str("TWAP"),
+            Schedule::Vwap(_) => f.write_str("VWAP"),
+            Schedule::Pov(rate) => write!(f, "POV {}%", (rate * Decimal::ONE_HUNDRED).normalize()),
+        }
+    }
+}
+
+/// Share of the day's volume in each slice of a VWAP schedule
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeProfile {
+    /// One weight per slice, summing to 1
+    pub weights: Vec<Decimal>,
+}
+
+impl VolumeProfile {
+    /// The same weight for every slice (what a VWAP without history falls back to)
+    pub fn flat(slices: usize) -> Self {
+        let slices = slices.max(1);
+        VolumeProfile { weights: vec![Decimal::ONE / Decimal::from(slices); slices] }
+    }
+
+    /// Average volume of `candles` at the time of day of each slice, for
+    /// `slices` slices of `slice_ms` from `start_ms`
+    pub fn from_candles(candles: &[Candle], start_ms: u64, slice_ms: u64, slices: usize) -> Self {
+        let slice_ms = slice_ms.max(1);
+        let mut sums = vec![(Decimal::ZERO, 0u32); slices.max(1)];
+        for candle in candles {
+            let since_start = (candle.open_time % DAY_MS + DAY_MS - start_ms % DAY_MS) % DAY_MS;
+            if let Some((sum, count)) = sums.get_mut((since_start / slice_ms) as usize) {
+                *sum += candle.volume;
+                *count += 1;
+            }
+        }
+        let averages: Vec<Decimal> = sums
+            .iter()
+            .map(|(sum, count)| if *count == 0 { Decimal::ZERO } else { sum / Decimal::from(*count) })
+            .collect();
+        let total: Decimal = averages.iter().sum();
+        if total.is_zero() {
+            return Self::flat(slices);
+        }
+        VolumeProfile { weights: averages.iter().map(|v| v / total).collect() }
+    }
+
+    /// Profile from the last `days` days of `coin`'s candles, at the
+    /// longest interval (up to 1h) that fits in a slice
+    pub async fn load(info: &TypedInfo, coin: &str, start_ms: u64, slice_ms: u64, slices: usize, days: u64) -> Result<Self> {
+        let interval = ["1h", "30m", "15m", "5m"]
+            .into_iter()
+            .find(|i| interval_ms(i).is_ok_and(|ms| ms <= slice_ms))
+            .unwrap_or("1m");
+        let candles = export::candles(info, coin, interval, start_ms.saturating_sub(days * DAY_MS), start_ms).await?;
+        Ok(Self::from_candles(&candles, start_ms, slice_ms, slices))
+    }
+
+    /// Share of the size due by the end of slice `i`
+    fn cumulative(&self, i: usize) -> Decimal {
+        self.weights.iter().take(i + 1).sum::<Decimal>().min(Decimal::ONE)
+    }
+}
+
+/// Child orders: taking liquidity, or resting at the touch for one step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChildStyle {
+    Ioc,
+    Limit,
+}
+
+impl FromStr for ChildStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ioc" => Ok(ChildStyle::Ioc),
+            "limit" | "gtc" => Ok(ChildStyle::Limit),
+            other => Err(Error::ValidationError(format!("Unknown child order style '{}': ioc or limit", other))),
+        }
+    }
+}
+
+impl fmt::Display for ChildStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChildStyle::Ioc => "IOC",
+            ChildStyle::Limit => "GTC",
+        })
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Algo
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Builder of a parent order and its schedule
+#[derive(Debug, Clone)]
+pub struct Algo {
+    pub coin: String,
+    pub is_buy: bool,
+    pub size: Decimal,
+    pub schedule: Schedule,
+    /// Time over which the size is spread (POV: the longest it runs)
+    pub duration: Duration,
+    pub slices: usize,
+    /// Never buy above (sell below) this price
+    pub limit_px: Option<Decimal>,
+    pub child: ChildStyle,
+    slippage: Decimal,
+}
+
+impl Algo {
+    pub fn buy(coin: &str, size: Decimal) -> Self {
+        Self::new(coin, true, size)
+    }
+
+    pub fn sell(coin: &str, size: Decimal) -> Self {
+        Self::new(coin, false, size)
+    }
+
+    fn new(coin: &str, is_buy: bool, size: Decimal) -> Self {
+        Self {
+            coin: coin.to_string(),
+            is_buy,
+            size,
+            schedule: Schedule::Twap,
+            duration: Duration::from_secs(600),
+            slices: 10,
+            limit_px: None,
+            child: ChildStyle::Ioc,
+            slippage: DEFAULT_SLIPPAGE,
+        }
+    }
+
+    /// `slices` equal slices over `duration` (the default: 10 over 10 minutes)
+    pub fn twap(mut self, duration: Duration, slices: usize) -> Self {
+        self.schedule = Schedule::Twap;
+        self.duration = duration;
+        self.slices = slices;
+        self
+    }
+
+    /// One slice per weight of `profile` over `duration`
+    pub fn vwap(mut self, duration: Duration, profile: VolumeProfile) -> Self {
+        self.slices = profile.weights.len();
+        self.schedule = Schedule::Vwap(profile);
+        self.duration = duration;
+        self
+    }
+
+    /// `rate` (0.1 = 10%) of the market's volume, for at most `duration`
+    pub fn pov(mut self, rate: Decimal, duration: Duration) -> Self {
+        self.schedule = Schedule::Pov(rate);
+        self.duration = duration;
+        self
+    }
+
+    /// Never buy above (sell below) `px`; rounded onto the tick inside it
+    /// when the execution starts
+    pub fn limit_px(mut self, px: Decimal) -> Self {
+        self.limit_px = Some(px);
+        self
+    }
+
+    /// IOC children (the default) or GTC children at the touch
+    pub fn child(mut self, style: ChildStyle) -> Self {
+        self.child = style;
+        self
+    }
+
+    /// How far past the touch IOC children may fill, as a fraction of it
+    pub fn slippage(mut self, fraction: Decimal) -> Self {
+        self.slippage = fraction;
+        self
+    }
+
+    /// Start working the order on `market` for `user` at `now`
+    pub fn start(&self, market: &Market, user: &str, now: Instant) -> Result<Execution> {
+        let precision = market.precision();
+        let size = precision.sz(self.size, Rounding::Down)?.value();
+        if size.is_zero() {
+            return Err(Error::ValidationError(format!("Size {} rounds down to nothing", self.size)));
+        }
+        if self.duration.is_zero() || self.slices == 0 {
+            return Err(Error::ValidationError("An execution needs a duration and at least one slice".to_string()));
+        }
+        match &self.schedule {
+            Schedule::Pov(rate) if *rate <= Decimal::ZERO || *rate > Decimal::ONE => {
+                return Err(Error::ValidationError(format!("POV rate must be above 0 and at most 1: {}", rate)));
+            }
+            Schedule::Vwap(profile) if profile.weights.len() != self.slices => {
+                return Err(Error::ValidationError("A VWAP profile needs one weight per slice".to_string()));
+            }
+            _ => {}
+        }
+        check_slippage(self.slippage)?;
+        let inside = if self.is_buy { Rounding::Down } else { Rounding::Up };
+        let limit_px = match self.limit_px {
+            Some(limit) => Some(precision.px(limit, inside)?.value()),
+            None => None,
+        };
+        Ok(Execution {
+            algo: Algo { coin: market.symbol.clone(), size, limit_px, ..self.clone() },
+            asset_id: market.asset_id,
+            precision,
+            user: user.to_lowercase(),
+            status: ExecStatus::Running,
+            children: Vec::new(),
+            market_volume: Decimal::ZERO,
+            started: now,
+            started_ms: now_ms(),
+            paused_at: None,
+            paused_for: Duration::ZERO,
+        })
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Control
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Wanted {
+    #[default]
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// Pauses, resumes or cancels an [`Execution`] at its next step; clones
+/// share the same state
+#[derive(Debug, Clone, Default)]
+pub struct Control(Arc<Mutex<Wanted>>);
+
+impl Control {
+    pub fn pause(&self) {
+        self.set(Wanted::Pause);
+    }
+
+    pub fn resume(&self) {
+        self.set(Wanted::Run);
+    }
+
+    /// Cancel for good: a later `resume` is ignored
+    pub fn cancel(&self) {
+        self.set(Wanted::Cancel);
+    }
+
+    fn set(&self, wanted: Wanted) {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if *state != Wanted::Cancel {
+            *state = wanted;
+        }
+    }
+
+    fn wanted(&self) -> Wanted {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Execution
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Where an execution (or a native TWAP) stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecStatus {
+    Running,
+    Paused,
+    Cancelled,
+    /// The schedule is over (the size may not all have filled)
+    Done,
+}
+
+impl fmt::Display for ExecStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExecStatus::Running => "running",
+            ExecStatus::Paused => "paused",
+            ExecStatus::Cancelled => "cancelled",
+            ExecStatus::Done => "done",
+        })
+    }
+}
+
+/// One child order of an execution
+#[derive(Debug, Clone, PartialEq)]
+pub struct Child {
+    /// Slice it was sent in (1-based)
+    pub slice: usize,
+    pub cloid: String,
+    pub oid: Option<u64>,
+    pub is_buy: bool,
+    pub px: Decimal,
+    pub size: Decimal,
+    pub style: ChildStyle,
+    pub filled: Decimal,
+    pub avg_px: Option<Decimal>,
+    /// Resting on the book
+    pub open: bool,
+}
+
+impl fmt::Display for Child {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{} {} {} @ {} {}",
+            self.slice,
+            if self.is_buy { "Buy" } else { "Sell" },
+            self.size,
+            self.px,
+            self.style
+        )?;
+        match (self.avg_px, self.open) {
+            (Some(avg), _) => write!(f, ": filled {} @ {}", self.filled, avg),
+            (None, true) => write!(f, ": resting (oid {})", self.oid.unwrap_or_default()),
+            (None, false) => f.write_str(": nothing filled"),
+        }
+    }
+}
+
+/// What a step did
+#[derive(Debug, Clone, PartialEq)]
+pub enum Activity {
+    /// A child order was sent (with what filled right away)
+    Sent(Child),
+    /// A resting child was cancelled at the next step (with what it filled)
+    Expired(Child),
+    Paused,
+    Resumed,
+    Cancelled,
+    Done,
+}
+
+impl fmt::Display for Activity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Activity::Sent(child) => write!(f, "{}", child),
+            Activity::Expired(child) => write!(f, "{} (expired)", child),
+            Activity::Paused => f.write_str("Paused"),
+            Activity::Resumed => f.write_str("Resumed"),
+            Activity::Cancelled => f.write_str("Cancelled"),
+            Activity::Done => f.write_str("Schedule complete"),
+        }
+    }
+}
+
+/// How much of a parent order has filled, and at what price
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    pub coin: String,
+    pub is_buy: bool,
+    pub size: Decimal,
+    pub filled: Decimal,
+    pub avg_px: Option<Decimal>,
+    /// Child orders sent (slices filled, for a native TWAP)
+    pub children: usize,
+    pub status: String,
+}
+
+impl Progress {
+    /// Share of the size filled, from 0 to 1
+    pub fn fraction(&self) -> Decimal {
+        if self.size.is_zero() {
+            return Decimal::ZERO;
+        }
+        self.filled / self.size
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}/{} {} ({}%)",
+            if self.is_buy { "Buy" } else { "Sell" },
+            self.filled,
+            self.size,
+            self.coin,
+            (self.fraction() * Decimal::ONE_HUNDRED).round_dp(1).normalize()
+        )?;
+        if let Some(avg) = self.avg_px {
+            write!(f, ", avg {}", avg)?;
+        }
+        write!(f, ", {} child orders, {}", self.children, self.status)
+    }
+}
+
+/// An [`Algo`] being worked, one [`step`](Self::step) at a time
+#[derive(Debug, Clone)]
+pub struct Execution {
+    pub algo: Algo,
+    asset_id: u32,
+    precision: Precision,
+    user: String,
+    pub status: ExecStatus,
+    pub children: Vec<Child>,
+    /// Volume other traders traded since the start (POV)
+    pub market_volume: Decimal,
+    started: Instant,
+    started_ms: u64,
+    paused_at: Option<Instant>,
+    paused_for: Duration,
+}
+
+impl Execution {
+    pub fn filled(&self) -> Decimal {
+        self.children.iter().map(|c| c.filled).sum()
+    }
+
+    pub fn avg_px(&self) -> Option<Decimal> {
+        let filled = self.filled();
+        if filled.is_zero() {
+            return None;
+        }
+        let notional: Decimal = self.children.iter().filter_map(|c| c.avg_px.map(|px| px * c.filled)).sum();
+        Some((notional / filled).round_dp(8).normalize())
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            coin: self.algo.coin.clone(),
+            is_buy: self.algo.is_buy,
+            size: self.algo.size,
+            filled: self.filled(),
+            avg_px: self.avg_px(),
+            children: self.children.len(),
+            status: self.status.to_string(),
+        }
+    }
+
+    /// Time on the schedule's clock at `now`: time since the start, less time paused
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        let paused = self.paused_for + self.paused_at.map(|at| now.saturating_duration_since(at)).unwrap_or_default();
+        now.saturating_duration_since(self.started).saturating_sub(paused)
+    }
+
+    /// Slice (0-based) the schedule is in after `elapsed`
+    fn slice(&self, elapsed: Duration) -> usize {
+        let slice = self.algo.duration.as_secs_f64() / self.algo.slices as f64;
+        ((elapsed.as_secs_f64() / slice) as usize).min(self.algo.slices - 1)
+    }
+
+    /// Size the schedule calls for in total after `elapsed`
+    pub fn due(&self, elapsed: Duration) -> Decimal {
+        let size = self.algo.size;
+        let due = match &self.algo.schedule {
+            Schedule::Twap => size * Decimal::from(self.slice(elapsed) + 1) / Decimal::from(self.algo.slices),
+            Schedule::Vwap(profile) => size * profile.cumulative(self.slice(elapsed)),
+            Schedule::Pov(rate) => size.min(rate * self.market_volume),
+        };
+        if elapsed >= self.algo.duration && !matches!(self.algo.schedule, Schedule::Pov(_)) {
+            return size;
+        }
+        due.min(size)
+    }
+
+    /// Count a public trade of the coin towards the market's volume (POV);
+    /// trades from before the start and our own are left out
+    pub fn on_trade(&mut self, trade: &Trade) {
+        let ours = trade
+            .users
+            .as_ref()
+            .is_some_and(|(buyer, seller)| buyer.eq_ignore_ascii_case(&self.user) || seller.eq_ignore_ascii_case(&self.user));
+        if trade.coin == self.algo.coin && trade.time >= self.started_ms && !ours {
+            self.market_volume += trade.sz;
+        }
+    }
+
+    /// The child order the schedule calls for at `now` against `book`, if
+    /// any: nothing while a child rests or while the size due is below the
+    /// $10 minimum (it carries over to later slices). Once time is up the
+    /// rest goes out as an IOC.
+    pub fn next_child(&self, now: Instant, book: &L2Book) -> Option<Child> {
+        if self.status != ExecStatus::Running || self.children.iter().any(|c| c.open) {
+            return None;
+        }
+        let elapsed = self.elapsed(now);
+        let filled = self.filled();
+        let size = self.precision.sz(self.due(elapsed) - filled, Rounding::Down).ok()?.value();
+        if size <= Decimal::ZERO {
+            return None;
+        }
+
+        let is_buy = self.algo.is_buy;
+        let (bids, asks) = &book.levels;
+        let style = if elapsed >= self.algo.duration { ChildStyle::Ioc } else { self.algo.child };
+        let px = match (style, is_buy) {
+            (ChildStyle::Limit, true) => self.precision.px(bids.first()?.px, Rounding::Down).ok()?.value(),
+            (ChildStyle::Limit, false) => self.precision.px(asks.first()?.px, Rounding::Up).ok()?.value(),
+            (ChildStyle::Ioc, true) => {
+                let touch = asks.first()?.px;
+                self.precision.px_toward(touch * (Decimal::ONE + self.algo.slippage), touch).ok()?.value()
+            }
+            (ChildStyle::Ioc, false) => {
+                let touch = bids.first()?.px;
+                self.precision.px_toward(touch * (Decimal::ONE - self.algo.slippage), touch).ok()?.value()
+            }
+        };
+        let px = match (self.algo.limit_px, is_buy) {
+            (Some(limit), true) => px.min(limit),
+            (Some(limit), false) => px.max(limit),
+            (None, _) => px,
+        };
+        if size * px < MIN_NOTIONAL {
+            return None;
+        }
+        Some(Child {
+            slice: self.slice(elapsed) + 1,
+            cloid: exchange::cloid(),
+            oid: None,
+            is_buy,
+            px,
+            size,
+            style,
+            filled: Decimal::ZERO,
+            avg_px: None,
+            open: false,
+        })
+    }
+
+    /// The `order` action sending `child`
+    pub fn child_action(&self, child: &Child) -> Value {
+        let tif = match child.style {
+            ChildStyle::Ioc => "Ioc",
+            ChildStyle::Limit => "Gtc",
+        };
+        json!({
+            "type": "order",
+            "orders": [{
+                "a": self.asset_id,
+                "b": child.is_buy,
+                "p": child.px.normalize().to_string(),
+                "s": child.size.normalize().to_string(),
+                "r": false,
+                "t": {"limit": {"tif": tif}},
+                "c": child.cloid,
+            }],
+            "grouping": "na",
+        })
+    }
+
+    /// Record `child` with the exchange's `response` to its action
+    pub fn sent(&mut self, mut child: Child, response: &Value) -> Result<Child> {
+        match OrderStatus::first(response) {
+            OrderStatus::Resting { oid } => {
+                child.oid = Some(oid);
+                child.open = true;
+            }
+            OrderStatus::Filled { oid, total_sz, avg_px } => {
+                child.oid = Some(oid);
+                child.filled = total_sz;
+                child.avg_px = Some(avg_px);
+            }
+            // IOCs that find nothing to match are cancelled: not an error
+            OrderStatus::Error(reason) if !reason.contains("could not immediately match") => {
+                return Err(Error::ValidationError(format!("Child #{} rejected: {}", child.slice, reason)));
+            }
+            _ => {}
+        }
+        self.children.push(child.clone());
+        Ok(child)
+    }
+
+    /// Apply `control`, then send what the schedule calls for at `now`.
+    ///
+    /// Resting children are cancelled first (they live for one step), and
+    /// what they filled is read back from `info`. Once time is up and the
+    /// last child went out, the execution is done.
+    pub async fn step(&mut self, config: &Config, info: &TypedInfo, control: &Control, now: Instant) -> Result<Vec<Activity>> {
+        let mut activities = Vec::new();
+        match (control.wanted(), self.status) {
+            (Wanted::Cancel, ExecStatus::Running | ExecStatus::Paused) => {
+                activities.extend(self.expire(config, info).await?);
+                self.status = ExecStatus::Cancelled;
+                activities.push(Activity::Cancelled);
+            }
+            (Wanted::Pause, ExecStatus::Running) => {
+                activities.extend(self.expire(config, info).await?);
+                self.paused_at = Some(now);
+                self.status = ExecStatus::Paused;
+                activities.push(Activity::Paused);
+            }
+            (Wanted::Run, ExecStatus::Paused) => {
+                if let Some(at) = self.paused_at.take() {
+                    self.paused_for += now.saturating_duration_since(at);
+                }
+                self.status = ExecStatus::Running;
+                activities.push(Activity::Resumed);
+            }
+            _ => {}
+        }
+        if self.status != ExecStatus::Running {
+            return Ok(activities);
+        }
+
+        activities.extend(self.expire(config, info).await?);
+        let book = info.l2_book(&self.algo.coin).await?;
+        if let Some(child) = self.next_child(now, &book) {
+            let response = exchange::send(config, &self.child_action(&child), None).await?;
+            activities.push(Activity::Sent(self.sent(child, &response)?));
+        }
+        let time_up = self.elapsed(now) >= self.algo.duration;
+        if self.filled() >= self.algo.size || (time_up && !self.children.iter().any(|c| c.open)) {
+            self.status = ExecStatus::Done;
+            activities.push(Activity::Done);
+        }
+        Ok(activities)
+    }
+
+    /// Cancel resting children and read back what they filled
+    async fn expire(&mut self, config: &Config, info: &TypedInfo) -> Result<Vec<Activity>> {
+        let open: Vec<u64> = self.children.iter().filter(|c| c.open).filter_map(|c| c.oid).collect();
+        if open.is_empty() {
+            return Ok(Vec::new());
+        }
+        let orders: Vec<(u32, u64)> = open.iter().map(|oid| (self.asset_id, *oid)).collect();
+        // Rejected for the children that filled in the meantime
+        exchange::send(config, &actions::cancel(&orders), None).await?;
+        let fills = info.user_fills(&self.user).await?;
+
+        let mut expired = Vec::new();
+        for child in self.children.iter_mut().filter(|c| c.open) {
+            let mine: Vec<_> = fills.iter().filter(|f| Some(f.oid) == child.oid).collect();
+            if !mine.is_empty() {
+                child.filled = mine.iter().map(|f| f.sz).sum();
+                let notional: Decimal = mine.iter().map(|f| f.px * f.sz).sum();
+                child.avg_px = Some((notional / child.filled).round_dp(8).normalize());
+            }
+            child.open = false;
+            expired.push(Activity::Expired(child.clone()));
+        }
+        Ok(expired)
+    }
+
+    /// Subscribe `stream` to the coin's trades for [`on_trade`](Self::on_trade);
+    /// they (or the items that failed to decode) arrive on the returned
+    /// channel once the stream is started
+    pub fn watch_trades(&self, stream: &mut Stream) -> UnboundedReceiver<Result<Trade>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (events, errors) = (tx.clone(), tx);
+        let trades = feed::handler(
+            "trades",
+            move |event| {
+                if let MarketEvent::Trade(trade) = event {
+                    let _ = events.send(Ok(trade));
+                }
+            },
+            move |e| {
+                let _ = errors.send(Err(e));
+            },
+        );
+        stream.trades(&[self.algo.coin.as_str()], trades);
+        rx
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Native TWAP
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// A TWAP the exchange runs itself (`twapOrder`), tracked through
+/// `userTwapHistory` and `userTwapSliceFills`
+#[derive(Debug, Clone)]
+pub struct NativeTwap {
+    pub coin: String,
+    asset_id: u32,
+    pub is_buy: bool,
+    pub size: Decimal,
+    pub minutes: u64,
+    pub randomize: bool,
+    /// Set once placed
+    pub twap_id: Option<u64>,
+}
+
+impl NativeTwap {
+    /// A TWAP of `size` (rounded down to the lot) over `minutes`, 5 to 1440
+    pub fn new(market: &Market, is_buy: bool, size: Decimal, minutes: u64, randomize: bool) -> Result<Self> {
+        let size = market.precision().sz(size, Rounding::Down)?.value();
+        if size.is_zero() {
+            return Err(Error::ValidationError("TWAP size rounds down to nothing".to_string()));
+        }
+        if !(5..=1440).contains(&minutes) {
+            return Err(Error::ValidationError(format!("A TWAP runs 5 to 1440 minutes, not {}", minutes)));
+        }
+        Ok(NativeTwap {
+            coin: market.symbol.clone(),
+            asset_id: market.asset_id,
+            is_buy,
+            size,
+            minutes,
+            randomize,
+            twap_id: None,
+        })
+    }
+
+    /// The `twapOrder` action starting it
+    pub fn action(&self) -> Value {
+        json!({
+            "type": "twapOrder",
+            "twap": {
+                "a": self.asset_id,
+                "b": self.is_buy,
+                "s": self.size.normalize().to_string(),
+                "r": false,
+                "m": self.minutes,
+                "t": self.randomize,
+            },
+        })
+    }
+
+    /// Send the TWAP and keep its id
+    pub async fn place(&mut self, config: &Config) -> Result<u64> {
+        let response = exchange::send(config, &self.action(), None).await?;
+        let status = response.pointer("/response/data/status");
+        match status.and_then(|s| s.pointer("/running/twapId")).and_then(|id| id.as_u64()) {
+            Some(twap_id) => {
+                self.twap_id = Some(twap_id);
+                Ok(twap_id)
+            }
+            None => {
+                let reason = status
+                    .and_then(|s| s.get("error"))
+                    .and_then(|e| e.as_str())
+                    .map(str::to_string)
+                    .or_else(|| rejection(&response))
+                    .unwrap_or_else(|| response.to_string());
+                Err(Error::ValidationError(format!("TWAP rejected: {}", reason)))
+            }
+        }
+    }
+
+    /// Progress as the exchange reports it for `user`
+    pub async fn progress(&self, info: &TypedInfo, user: &str) -> Result<Progress> {
+        let twap_id = self.placed()?;
+        let history = info.user_twap_history(user).await?;
+        let entry = history
+            .iter()
+            .rev()
+            .find(|h| h.twap_id == Some(twap_id))
+            .ok_or_else(|| Error::ValidationError(format!("TWAP {} is not in the history yet", twap_id)))?;
+        let slices = info
+            .user_twap_slice_fills(user)
+            .await?
+            .iter()
+            .filter(|f| f.twap_id == twap_id)
+            .count();
+        let state = &entry.state;
+        Ok(Progress {
+            coin: state.coin.clone(),
+            is_buy: state.side.is_buy(),
+            size: state.sz,
+            filled: state.executed_sz,
+            avg_px: (!state.executed_sz.is_zero())
+                .then(|| (state.executed_ntl / state.executed_sz).round_dp(8).normalize()),
+            children: slices,
+            status: match &entry.status.description {
+                Some(description) => format!("{} ({})", entry.status.status, description),
+                None => entry.status.status.clone(),
+            },
+        })
+    }
+
+    /// The `twapCancel` action stopping it
+    pub fn cancel_action(&self) -> Result<Value> {
+        Ok(json!({"type": "twapCancel", "a": self.asset_id, "t": self.placed()?}))
+    }
+
+    /// Stop the TWAP
+    pub async fn cancel(&self, config: &Config) -> Result<()> {
+        let response = exchange::send(config, &self.cancel_action()?, None).await?;
+        let error = response
+            .pointer("/response/data/status/error")
+            .and_then(|e| e.as_str())
+            .map(str::to_string);
+        match error.or_else(|| rejection(&response)) {
+            Some(reason) => Err(Error::ValidationError(format!("TWAP cancel rejected: {}", reason))),
+            None => Ok(()),
+        }
+    }
+
+    fn placed(&self) -> Result<u64> {
+        self.twap_id
+            .ok_or_else(|| Error::ValidationError("The TWAP has not been placed".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::testing::{self, btc, dec};
+    use crate::mock::{MatchingEngine, Script};
+    use crate::models::Side;
+
+    fn book(engine: &MatchingEngine) -> L2Book {
+        engine.l2_book("BTC").unwrap()
+    }
+
+    fn candle(open_time: u64, volume: &str) -> Candle {
+        Candle {
+            open_time,
+            close_time: open_time + 3_599_999,
+            coin: "BTC".to_string(),
+            interval: "1h".to_string(),
+            open: dec("1"),
+            high: dec("1"),
+            low: dec("1"),
+            close: dec("1"),
+            volume: dec(volume),
+            trades: 1,
+        }
+    }
+
+    #[test]
+    fn schedules_spread_the_size_over_time() {
+        let engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let t0 = Instant::now();
+        let secs = Duration::from_secs;
+
+        let twap = Algo::buy("BTC", dec("0.4")).twap(secs(100), 4).start(&btc(&engine), "0xabc", t0).unwrap();
+        let due: Vec<Decimal> = [0, 24, 25, 99, 100].iter().map(|s| twap.due(secs(*s))).collect();
+        assert_eq!(due, [dec("0.1"), dec("0.1"), dec("0.2"), dec("0.4"), dec("0.4")]);
+
+        // Yesterday's volume: 1 in the first hour from the start, 3 in the second
+        let start_ms = 1_737_417_600_000;
+        let candles = [candle(start_ms - DAY_MS, "1"), candle(start_ms - DAY_MS + 3_600_000, "3")];
+        let profile = VolumeProfile::from_candles(&candles, start_ms, 3_600_000, 2);
+        assert_eq!(profile.weights, [dec("0.25"), dec("0.75")]);
+        assert_eq!(VolumeProfile::from_candles(&[], start_ms, 3_600_000, 2), VolumeProfile::flat(2));
+        let vwap = Algo::sell("BTC", dec("1")).vwap(secs(7200), profile).start(&btc(&engine), "0xabc", t0).unwrap();
+        assert_eq!((vwap.due(secs(0)), vwap.due(secs(3600))), (dec("0.25"), dec("1")));
+
+        // POV follows other traders' volume, not ours
+        let mut pov = Algo::buy("BTC", dec("1")).pov(dec("0.1"), secs(600)).start(&btc(&engine), "0xabc", t0).unwrap();
+        let trade = |sz: &str, users: Option<(String, String)>| Trade {
+            coin: "BTC".to_string(),
+            side: Side::Buy,
+            px: dec("96251"),
+            sz: dec(sz),
+            time: now_ms() + 1000,
+            hash: String::new(),
+            tid: 1,
+            users,
+        };
+        pov.on_trade(&trade("2", None));
+        pov.on_trade(&trade("5", Some(("0xABC".to_string(), "0xdef".to_string()))));
+        assert_eq!((pov.market_volume, pov.due(secs(1))), (dec("2"), dec("0.2")));
+    }
+
+    #[test]
+    fn children_respect_the_limit_the_minimum_and_pauses() {
+        let mut engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let t0 = Instant::now();
+        let secs = Duration::from_secs;
+        let mut execution = Algo::buy("BTC", dec("0.4"))
+            .twap(secs(100), 4)
+            .limit_px(dec("96300"))
+            .start(&btc(&engine), "0xabc", t0)
+            .unwrap();
+
+        // An IOC capped at the limit, filled against the asks
+        let child = execution.next_child(t0, &book(&engine)).unwrap();
+        assert_eq!((child.slice, child.size, child.px, child.style), (1, dec("0.1"), dec("96300"), ChildStyle::Ioc));
+        let response = engine.execute(&execution.child_action(&child));
+        let child = execution.sent(child, &response).unwrap();
+        assert_eq!((child.filled, child.avg_px), (dec("0.1"), Some(dec("96251"))));
+        assert_eq!(execution.next_child(t0 + secs(10), &book(&engine)), None);
+
+        // Paused 50s in the second slice: the schedule clock stops
+        execution.paused_at = Some(t0 + secs(30));
+        assert_eq!(execution.elapsed(t0 + secs(80)), secs(30));
+        execution.paused_at = None;
+        execution.paused_for = secs(50);
+        assert_eq!(execution.next_child(t0 + secs(80), &book(&engine)).map(|c| c.slice), Some(2));
+
+        // Below $10 waits for later slices; limit children rest at the bid
+        let mut small = Algo::sell("BTC", dec("0.0004"))
+            .twap(secs(100), 4)
+            .child(ChildStyle::Limit)
+            .start(&btc(&engine), "0xabc", t0)
+            .unwrap();
+        assert_eq!(small.next_child(t0, &book(&engine)), None);
+        let child = small.next_child(t0 + secs(25), &book(&engine)).unwrap();
+        assert_eq!((child.size, child.px, child.style), (dec("0.0002"), dec("96251"), ChildStyle::Limit));
+        let child = small.sent(child.clone(), &engine.execute(&small.child_action(&child))).unwrap();
+        assert!(child.open && child.oid.is_some());
+        assert_eq!(small.next_child(t0 + secs(75), &book(&engine)), None);
+        assert_eq!(small.progress().to_string(), "Sell 0/0.0004 BTC (0%), 1 child orders, running");
+
+        // A book quoted with trailing zeros still sends the price as the wire writes it
+        let resting = Algo::buy("BTC", dec("0.4"))
+            .twap(secs(100), 4)
+            .child(ChildStyle::Limit)
+            .start(&btc(&engine), "0xabc", t0)
+            .unwrap();
+        let child = resting.next_child(t0, &testing::book(&engine, "96250.0", "96251.0")).unwrap();
+        assert_eq!((child.px, child.style), (dec("96250"), ChildStyle::Limit));
+        assert_eq!(resting.child_action(&child)["orders"][0]["p"], "96250");
+    }
+
+    #[test]
+    fn an_off_tick_limit_rounds_inside_itself() {
+        let engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let t0 = Instant::now();
+        let limited = |algo: Algo| algo.twap(Duration::from_secs(100), 4).limit_px(dec("96250.55"));
+
+        // BTC prices at 96250 take whole dollars: a buy rounds down, a sell up
+        let buy = limited(Algo::buy("BTC", dec("0.4"))).start(&btc(&engine), "0xabc", t0).unwrap();
+        assert_eq!(buy.algo.limit_px, Some(dec("96250")));
+        let child = buy.next_child(t0, &book(&engine)).unwrap();
+        assert_eq!(child.px, dec("96250"));
+        assert_eq!(buy.child_action(&child)["orders"][0]["p"], "96250");
+
+        let sell = limited(Algo::sell("BTC", dec("0.4"))).start(&btc(&engine), "0xabc", t0).unwrap();
+        assert_eq!(sell.algo.limit_px, Some(dec("96251")));
+    }
+
+    #[test]
+    fn control_cancel_is_final() {
+        let control = Control::default();
+        control.pause();
+        assert_eq!(control.wanted(), Wanted::Pause);
+        control.resume();
+        assert_eq!(control.wanted(), Wanted::Run);
+        control.clone().cancel();
+        control.resume();
+        assert_eq!(control.wanted(), Wanted::Cancel);
+    }
+}
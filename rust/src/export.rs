@@ -0,0 +1,431 @@
+//! Export candles, fills and trades to Parquet.
+//!
+//! The info API caps each response ([`CANDLE_PAGE`] candles, [`FILL_PAGE`]
+//! fills), so [`candles`] and [`user_fills`] split a date range into requests
+//! that each fit under the cap and stitch the pages back together without
+//! duplicates. [`latest_trades`] collects the trades of the most recent
+//! HyperCore blocks, which is as far back as that API goes.
+//!
+//! [`write_candles`] and [`write_fills`] write one Parquet file with a fixed
+//! schema ([`candle_schema`], [`fill_schema`]), so files from different runs
+//! can be read as one dataset:
+//!
+//! - prices, sizes, fees and P&L are `Decimal128(38, 10)`, exact to 10 places
+//! - times are `Timestamp(ms, UTC)`
+//! - coin, interval, side and other repeated strings are dictionary encoded
+//!
+//! ```python
+//! pd.read_parquet("BTC_1h.parquet")
+//! duckdb.sql("SELECT coin, sum(fee) FROM 'fills.parquet' GROUP BY coin")
+//! ```
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::types::Int32Type;
+use arrow_array::{
+    ArrayRef, BooleanArray, Decimal128Array, DictionaryArray, RecordBatch, StringArray, TimestampMillisecondArray,
+    UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use hyperliquid_sdk::HyperCore;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use parquet::format::KeyValue;
+use rust_decimal::Decimal;
+
+use crate::error::{Error, Result};
+use crate::models::{decode, Candle, Fill, TypedInfo};
+
+/// Most candles `candleSnapshot` returns per request
+pub const CANDLE_PAGE: usize = 5000;
+
+/// Most fills `userFillsByTime` returns per request
+pub const FILL_PAGE: usize = 2000;
+
+/// Precision and scale of every decimal column
+pub const DECIMAL_PRECISION: u8 = 38;
+pub const DECIMAL_SCALE: i8 = 10;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Fetching
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Length of a candle interval (`1m` ... `1M`) in ms; a month counts as 31 days
+pub fn interval_ms(interval: &str) -> Result<u64> {
+    const MINUTE: u64 = 60_000;
+    let ms = match interval {
+        "1m" => MINUTE,
+        "3m" => 3 * MINUTE,
+        "5m" => 5 * MINUTE,
+        "15m" => 15 * MINUTE,
+        "30m" => 30 * MINUTE,
+        "1h" => 60 * MINUTE,
+        "2h" => 120 * MINUTE,
+        "4h" => 240 * MINUTE,
+        "8h" => 480 * MINUTE,
+        "12h" => 720 * MINUTE,
+        "1d" => 1440 * MINUTE,
+        "3d" => 3 * 1440 * MINUTE,
+        "1w" => 7 * 1440 * MINUTE,
+        "1M" => 31 * 1440 * MINUTE,
+        _ => return Err(Error::ValidationError(format!("unknown candle interval '{}'", interval))),
+    };
+    Ok(ms)
+}
+
+/// Candles that open between `start_ms` and `end_ms`, oldest first.
+///
+/// The range is requested in windows of [`CANDLE_PAGE`] intervals, so no
+/// response is truncated however long the range is.
+pub async fn candles(info: &TypedInfo, coin: &str, interval: &str, start_ms: u64, end_ms: u64) -> Result<Vec<Candle>> {
+    let window = interval_ms(interval)? * CANDLE_PAGE as u64;
+    let mut all: Vec<Candle> = Vec::new();
+    let mut from = start_ms;
+    while from <= end_ms {
+        let to = from.saturating_add(window - 1).min(end_ms);
+        for candle in info.candles(coin, interval, from, Some(to)).await? {
+            let in_window = (from..=to).contains(&candle.open_time);
+            if in_window && all.last().is_none_or(|last| candle.open_time > last.open_time) {
+                all.push(candle);
+            }
+        }
+        from = to + 1;
+    }
+    Ok(all)
+}
+
+/// Fills of `user` between `start_ms` and `end_ms`, oldest first.
+///
+/// A full page is followed by a request starting at its last fill's time;
+/// fills seen twice at the boundary are dropped by `(tid, oid)`.
+pub async fn user_fills(info: &TypedInfo, user: &str, start_ms: u64, end_ms: u64) -> Result<Vec<Fill>> {
+    let mut all: Vec<Fill> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut from = start_ms;
+    loop {
+        let page = info.user_fills_by_time(user, from, Some(end_ms)).await?;
+        let full = page.len() >= FILL_PAGE;
+        let before = all.len();
+        for fill in page {
+            if (start_ms..=end_ms).contains(&fill.time) && seen.insert((fill.tid, fill.oid)) {
+                all.push(fill);
+            }
+        }
+        if !full || all.len() == before {
+            break;
+        }
+        from = all[before..].iter().map(|f| f.time).max().unwrap_or(end_ms);
+    }
+    all.sort_by_key(|f| (f.time, f.tid));
+    Ok(all)
+}
+
+/// Trades in the last `blocks` HyperCore blocks, oldest first.
+///
+/// Each trade appears once per side, with the user of that side.
+pub async fn latest_trades(hc: &HyperCore, blocks: u32, coin: Option<&str>) -> Result<Vec<Fill>> {
+    let trades: Vec<Fill> = decode(hc.latest_trades(Some(blocks), coin).await?)?;
+    let mut seen = HashSet::new();
+    let mut trades: Vec<Fill> = trades
+        .into_iter()
+        .filter(|t| seen.insert((t.tid, t.oid, t.user.clone())))
+        .collect();
+    trades.sort_by_key(|t| (t.time, t.tid));
+    Ok(trades)
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Schemas
+// ══════════════════════════════════════════════════════════════════════════════
+
+fn timestamp(name: &str) -> Field {
+    Field::new(name, DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())), false)
+}
+
+fn decimal(name: &str, nullable: bool) -> Field {
+    Field::new(name, DataType::Decimal128(DECIMAL_PRECISION, DECIMAL_SCALE), nullable)
+}
+
+fn dictionary(name: &str) -> Field {
+    Field::new(name, DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)), false)
+}
+
+/// Columns of a candles file
+pub fn candle_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        timestamp("open_time"),
+        timestamp("close_time"),
+        dictionary("coin"),
+        dictionary("interval"),
+        decimal("open", false),
+        decimal("high", false),
+        decimal("low", false),
+        decimal("close", false),
+        decimal("volume", false),
+        Field::new("trades", DataType::UInt64, false),
+    ]))
+}
+
+/// Columns of a fills (or HyperCore trades) file
+pub fn fill_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        timestamp("time"),
+        dictionary("coin"),
+        dictionary("side"),
+        decimal("px", false),
+        decimal("sz", false),
+        dictionary("dir"),
+        decimal("start_position", false),
+        decimal("closed_pnl", false),
+        decimal("fee", false),
+        dictionary("fee_token"),
+        decimal("builder_fee", true),
+        Field::new("crossed", DataType::Boolean, false),
+        Field::new("oid", DataType::UInt64, false),
+        Field::new("tid", DataType::UInt64, false),
+        Field::new("hash", DataType::Utf8, false),
+        Field::new("cloid", DataType::Utf8, true),
+        Field::new("user", DataType::Utf8, true),
+    ]))
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Record batches
+// ══════════════════════════════════════════════════════════════════════════════
+
+fn timestamps(values: impl Iterator<Item = u64>) -> ArrayRef {
+    Arc::new(TimestampMillisecondArray::from_iter_values(values.map(|ms| ms as i64)).with_timezone("UTC"))
+}
+
+fn dictionary_of<'a>(values: impl Iterator<Item = &'a str>) -> ArrayRef {
+    Arc::new(values.collect::<DictionaryArray<Int32Type>>())
+}
+
+/// Decimals at [`DECIMAL_SCALE`]; values with more places are rounded
+fn decimals(values: impl Iterator<Item = Option<Decimal>>) -> Result<ArrayRef> {
+    let mantissas = values
+        .map(|value| value.map(scaled).transpose())
+        .collect::<Result<Vec<Option<i128>>>>()?;
+    let array = Decimal128Array::from(mantissas)
+        .with_precision_and_scale(DECIMAL_PRECISION, DECIMAL_SCALE)
+        .map_err(file_error)?;
+    Ok(Arc::new(array))
+}
+
+fn scaled(value: Decimal) -> Result<i128> {
+    let scale = DECIMAL_SCALE as u32;
+    let mut value = value.round_dp(scale);
+    value.rescale(scale);
+    if value.scale() != scale {
+        return Err(Error::ValidationError(format!("{} does not fit in a decimal with {} places", value, scale)));
+    }
+    Ok(value.mantissa())
+}
+
+/// Candles as one record batch of [`candle_schema`]
+pub fn candle_batch(candles: &[Candle]) -> Result<RecordBatch> {
+    let column = |f: fn(&Candle) -> Decimal| decimals(candles.iter().map(|c| Some(f(c))));
+    let columns = vec![
+        timestamps(candles.iter().map(|c| c.open_time)),
+        timestamps(candles.iter().map(|c| c.close_time)),
+        dictionary_of(candles.iter().map(|c| c.coin.as_str())),
+        dictionary_of(candles.iter().map(|c| c.interval.as_str())),
+        column(|c| c.open)?,
+        column(|c| c.high)?,
+        column(|c| c.low)?,
+        column(|c| c.close)?,
+        column(|c| c.volume)?,
+        Arc::new(UInt64Array::from_iter_values(candles.iter().map(|c| c.trades))),
+    ];
+    RecordBatch::try_new(candle_schema(), columns).map_err(file_error)
+}
+
+/// Fills as one record batch of [`fill_schema`]
+pub fn fill_batch(fills: &[Fill]) -> Result<RecordBatch> {
+    let column = |f: fn(&Fill) -> Decimal| decimals(fills.iter().map(|x| Some(f(x))));
+    let columns = vec![
+        timestamps(fills.iter().map(|f| f.time)),
+        dictionary_of(fills.iter().map(|f| f.coin.as_str())),
+        dictionary_of(fills.iter().map(|f| f.side.as_str())),
+        column(|f| f.px)?,
+        column(|f| f.sz)?,
+        dictionary_of(fills.iter().map(|f| f.dir.as_str())),
+        column(|f| f.start_position)?,
+        column(|f| f.closed_pnl)?,
+        column(|f| f.fee)?,
+        dictionary_of(fills.iter().map(|f| f.fee_token.as_str())),
+        decimals(fills.iter().map(|f| f.builder_fee))?,
+        Arc::new(BooleanArray::from_iter(fills.iter().map(|f| Some(f.crossed)))),
+        Arc::new(UInt64Array::from_iter_values(fills.iter().map(|f| f.oid))),
+        Arc::new(UInt64Array::from_iter_values(fills.iter().map(|f| f.tid))),
+        Arc::new(StringArray::from_iter_values(fills.iter().map(|f| f.hash.as_str()))),
+        Arc::new(StringArray::from_iter(fills.iter().map(|f| f.cloid.as_deref()))),
+        Arc::new(StringArray::from_iter(fills.iter().map(|f| f.user.as_deref()))),
+    ];
+    RecordBatch::try_new(fill_schema(), columns).map_err(file_error)
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Writing
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Write candles to a Parquet file; returns its size in bytes
+pub fn write_candles(path: &Path, candles: &[Candle]) -> Result<u64> {
+    write_parquet(path, &candle_batch(candles)?, "candles")
+}
+
+/// Write fills (or HyperCore trades) to a Parquet file; returns its size in bytes
+pub fn write_fills(path: &Path, fills: &[Fill]) -> Result<u64> {
+    write_parquet(path, &fill_batch(fills)?, "fills")
+}
+
+/// Write one batch, Snappy compressed, through a temporary file so a failed
+/// export never leaves half a file behind
+pub fn write_parquet(path: &Path, batch: &RecordBatch, dataset: &str) -> Result<u64> {
+    let metadata = vec![KeyValue::new("hyperliquid.dataset".to_string(), dataset.to_string())];
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .set_key_value_metadata(Some(metadata))
+        .build();
+
+    let tmp = path.with_extension("parquet.tmp");
+    let mut writer = ArrowWriter::try_new(File::create(&tmp)?, batch.schema(), Some(properties)).map_err(file_error)?;
+    writer.write(batch).map_err(file_error)?;
+    writer.close().map_err(file_error)?;
+    fs::rename(&tmp, path)?;
+    Ok(fs::metadata(path)?.len())
+}
+
+/// Arrow and Parquet failures surface as file errors
+fn file_error(e: impl std::error::Error + Send + Sync + 'static) -> Error {
+    Error::IoError(std::io::Error::other(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Side;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Decimal128Type, TimestampMillisecondType};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    fn candle(open_time: u64, close: Decimal) -> Candle {
+        Candle {
+            open_time,
+            close_time: open_time + 3_599_999,
+            coin: "BTC".to_string(),
+            interval: "1h".to_string(),
+            open: dec("95870.0"),
+            high: dec("96410.0"),
+            low: dec("95702.0"),
+            close,
+            volume: dec("1532.48291"),
+            trades: 18234,
+        }
+    }
+
+    fn fill(tid: u64, coin: &str, fee: Decimal) -> Fill {
+        Fill {
+            coin: coin.to_string(),
+            px: dec("96251.0"),
+            sz: dec("0.01"),
+            side: Side::Buy,
+            time: 1_737_412_345_000 + tid,
+            start_position: dec("0.0"),
+            dir: "Open Long".to_string(),
+            closed_pnl: dec("-1.5"),
+            hash: format!("0x{:064x}", tid),
+            oid: 98_765_432_101,
+            crossed: true,
+            fee,
+            tid,
+            fee_token: "USDC".to_string(),
+            cloid: None,
+            builder_fee: None,
+            user: Some("0x1111111111111111111111111111111111111111".to_string()),
+        }
+    }
+
+    fn read_back(path: &Path) -> RecordBatch {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap().build().unwrap();
+        let mut batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(batches.len(), 1, "one batch expected");
+        batches.remove(0)
+    }
+
+    fn scratch(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("hl-export-test-{}-{}.parquet", std::process::id(), name))
+    }
+
+    #[test]
+    fn knows_candle_intervals() {
+        assert_eq!(interval_ms("1m").unwrap(), 60_000);
+        assert_eq!(interval_ms("4h").unwrap(), 14_400_000);
+        assert_eq!(interval_ms("1M").unwrap(), 31 * 86_400_000);
+        assert!(interval_ms("2m").is_err());
+    }
+
+    #[test]
+    fn scales_decimals_exactly() {
+        assert_eq!(scaled(dec("96251.5")).unwrap(), 962_515_000_000_000);
+        assert_eq!(scaled(dec("0.00000000005")).unwrap(), 0);
+        assert_eq!(scaled(dec("-1.5")).unwrap(), -15_000_000_000);
+        assert!(scaled(Decimal::MAX).is_err());
+    }
+
+    #[test]
+    fn writes_candles_with_typed_columns() {
+        let path = scratch("candles");
+        let candles = [candle(1_737_406_800_000, dec("96301.0")), candle(1_737_410_400_000, dec("96250.123456789012"))];
+        assert!(write_candles(&path, &candles).unwrap() > 0);
+
+        let batch = read_back(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(batch.schema(), candle_schema());
+        assert_eq!(batch.num_rows(), 2);
+
+        let open_time = batch.column(0).as_primitive::<TimestampMillisecondType>();
+        assert_eq!(open_time.value(1), 1_737_410_400_000);
+        let close = batch.column(7).as_primitive::<Decimal128Type>();
+        assert_eq!(close.value_as_string(0), "96301.0000000000");
+        assert_eq!(close.value_as_string(1), "96250.1234567890");
+        let coin = batch.column(2).as_dictionary::<Int32Type>();
+        assert_eq!(coin.values().len(), 1, "one dictionary entry for both rows");
+    }
+
+    #[test]
+    fn writes_fills_with_nullable_columns() {
+        let path = scratch("fills");
+        let mut fills = vec![fill(1, "BTC", dec("0.433129")), fill(2, "ETH", dec("0.1"))];
+        fills[1].cloid = Some("0x1234".to_string());
+        fills[1].builder_fee = Some(dec("0.01"));
+        write_fills(&path, &fills).unwrap();
+
+        let batch = read_back(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(batch.schema(), fill_schema());
+        let column = |name: &str| batch.column(batch.schema().index_of(name).unwrap()).clone();
+
+        let fee = column("fee");
+        assert_eq!(fee.as_primitive::<Decimal128Type>().value_as_string(0), "0.4331290000");
+        let builder_fee = column("builder_fee");
+        assert!(builder_fee.is_null(0));
+        assert_eq!(builder_fee.as_primitive::<Decimal128Type>().value_as_string(1), "0.0100000000");
+        let cloid = column("cloid");
+        assert!(cloid.is_null(0));
+        assert_eq!(cloid.as_string::<i32>().value(1), "0x1234");
+        let side = column("side");
+        let side = side.as_dictionary::<Int32Type>();
+        let sides = side.values().as_string::<i32>();
+        assert_eq!(sides.value(side.keys().value(0) as usize), "BUY");
+    }
+}
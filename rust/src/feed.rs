@@ -0,0 +1,853 @@
+//! Market data over gRPC and WebSocket at once, with failover between them.
+//!
+//! [`ResilientFeed`] subscribes every channel on both transports. Events of
+//! the active transport (the primary, gRPC by default) are delivered; the
+//! other runs as a hot standby whose latest events are held back. The feed
+//! switches over when the active transport disconnects or starts
+//! reconnecting (from `on_state_change` / `on_reconnect`), or stalls: no
+//! message for [`ResilientFeed::stall_after`] while the standby is still
+//! receiving. On a switch the held-back events are replayed, so nothing is
+//! lost in the gap, and events are deduplicated (trades by coin and trade id,
+//! books by time, blocks by number) so nothing arrives twice. Once the primary
+//! delivers again the feed switches back.
+//!
+//! Every [`FeedEvent`] says which transport delivered it:
+//!
+//! ```no_run
+//! use hyperliquid_examples::feed::ResilientFeed;
+//! use hyperliquid_examples::models::MarketEvent;
+//!
+//! # fn run(sdk: &hyperliquid_sdk::HyperliquidSDK) -> hyperliquid_examples::Result<()> {
+//! let mut feed = ResilientFeed::new(sdk).on_switch(|s| println!("{} -> {} ({})", s.from, s.to, s.reason));
+//! feed.trades(&["BTC"], |e| {
+//!     if let MarketEvent::Trade(trade) = e.event {
+//!         println!("[{}] {} {} @ {}", e.source, trade.side, trade.sz, trade.px);
+//!     }
+//! });
+//! feed.start()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Callbacks run with the feed locked, so events stay in order across a
+//! switch; don't call [`ResilientFeed::active`] from one. A callback that
+//! panics is stopped at the feed: the panic is reported as usual and the
+//! feed carries on with the next event.
+//!
+//! Book deltas of the two transports are not interchangeable; a consumer
+//! keeping a book from `book_updates` should resync from a snapshot after a
+//! switch.
+//!
+//! Consumers of a single `sdk.stream()` build their callbacks with
+//! [`handler`] or [`forward`] instead.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use hyperliquid_sdk::stream::{ConnectionState, Stream};
+use hyperliquid_sdk::{GRPCStream, HyperliquidSDK};
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::error::{Error, Result};
+use crate::models::MarketEvent;
+use crate::recorder::{now_ms, Channel, Source};
+
+/// How long the active transport may go quiet, while the standby is not,
+/// before the feed switches
+pub const DEFAULT_STALL_AFTER: Duration = Duration::from_secs(10);
+
+/// Standby events held back per subscription
+const STANDBY_BUFFER: usize = 1_000;
+
+/// Trade ids remembered per subscription for deduplication
+const DEDUP_WINDOW: usize = 10_000;
+
+/// How often the watchdog looks for a stall
+const CHECK_EVERY: Duration = Duration::from_millis(250);
+
+type Callback = Arc<dyn Fn(FeedEvent) + Send + Sync>;
+type SwitchCallback = Arc<dyn Fn(Switch) + Send + Sync>;
+type ErrorCallback = Arc<dyn Fn(Source, String) + Send + Sync>;
+
+/// An event and the transport that delivered it
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEvent {
+    pub source: Source,
+    /// Local receive time (ms)
+    pub recv_ms: u64,
+    pub event: MarketEvent,
+}
+
+/// Why the feed changed transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwitchReason {
+    Disconnected,
+    Reconnecting,
+    /// No message for this long while the standby was receiving
+    Stalled(Duration),
+    /// The primary is delivering again
+    Recovered,
+}
+
+impl fmt::Display for SwitchReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwitchReason::Disconnected => f.write_str("disconnected"),
+            SwitchReason::Reconnecting => f.write_str("reconnecting"),
+            SwitchReason::Stalled(quiet) => write!(f, "no messages for {:.1}s", quiet.as_secs_f64()),
+            SwitchReason::Recovered => f.write_str("primary recovered"),
+        }
+    }
+}
+
+/// A change of the active transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Switch {
+    pub from: Source,
+    pub to: Source,
+    pub reason: SwitchReason,
+}
+
+/// Counters of a [`ResilientFeed`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedStats {
+    pub ws_delivered: u64,
+    pub grpc_delivered: u64,
+    /// Events dropped because they had already been delivered
+    pub duplicates: u64,
+    /// Standby events held back (most are duplicates by the time of a switch)
+    pub held_back: u64,
+    pub switches: u64,
+    pub reconnects: u64,
+}
+
+impl FeedStats {
+    /// Events delivered from `source`
+    pub fn delivered(&self, source: Source) -> u64 {
+        match source {
+            Source::Ws => self.ws_delivered,
+            Source::Grpc => self.grpc_delivered,
+        }
+    }
+}
+
+fn other(source: Source) -> Source {
+    match source {
+        Source::Ws => Source::Grpc,
+        Source::Grpc => Source::Ws,
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Failover
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// What the feed knows about one transport
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    state: ConnectionState,
+    /// Last message received
+    last: Option<Instant>,
+    /// Set on a disconnect or reconnect, cleared by the next message or a
+    /// successful connect (the SDK's gRPC client never reports `Connected`)
+    failure: Option<SwitchReason>,
+}
+
+impl Link {
+    fn new() -> Self {
+        Link { state: ConnectionState::Connecting, last: None, failure: None }
+    }
+}
+
+/// Which transport is active and when to change it
+#[derive(Debug)]
+struct Failover {
+    primary: Source,
+    active: Source,
+    stall_after: Duration,
+    failback: bool,
+    ws: Link,
+    grpc: Link,
+    switched_at: Instant,
+}
+
+impl Failover {
+    fn new(primary: Source, now: Instant) -> Self {
+        Failover {
+            primary,
+            active: primary,
+            stall_after: DEFAULT_STALL_AFTER,
+            failback: true,
+            ws: Link::new(),
+            grpc: Link::new(),
+            switched_at: now,
+        }
+    }
+
+    fn link(&self, source: Source) -> &Link {
+        match source {
+            Source::Ws => &self.ws,
+            Source::Grpc => &self.grpc,
+        }
+    }
+
+    fn link_mut(&mut self, source: Source) -> &mut Link {
+        match source {
+            Source::Ws => &mut self.ws,
+            Source::Grpc => &mut self.grpc,
+        }
+    }
+
+    fn recent(&self, source: Source, now: Instant) -> bool {
+        self.link(source).last.is_some_and(|t| now.duration_since(t) < self.stall_after)
+    }
+
+    /// Whether `source` could take over: not failed, and connected or receiving
+    fn usable(&self, source: Source, now: Instant) -> bool {
+        let link = self.link(source);
+        link.failure.is_none() && (link.state == ConnectionState::Connected || self.recent(source, now))
+    }
+
+    /// A message arrived on `source`; returns whether it is the active transport
+    fn message(&mut self, source: Source, now: Instant) -> bool {
+        let link = self.link_mut(source);
+        link.last = Some(now);
+        link.failure = None;
+        source == self.active
+    }
+
+    fn state_change(&mut self, source: Source, state: ConnectionState, now: Instant) -> Option<Switch> {
+        let link = self.link_mut(source);
+        link.state = state;
+        match state {
+            ConnectionState::Connected => link.failure = None,
+            ConnectionState::Disconnected => link.failure = Some(SwitchReason::Disconnected),
+            ConnectionState::Reconnecting => link.failure = Some(SwitchReason::Reconnecting),
+            ConnectionState::Connecting => {}
+        }
+        self.check(now)
+    }
+
+    fn reconnect(&mut self, source: Source, now: Instant) -> Option<Switch> {
+        self.link_mut(source).failure = Some(SwitchReason::Reconnecting);
+        self.check(now)
+    }
+
+    /// Switch if the active transport failed or stalled, or the primary is back
+    fn check(&mut self, now: Instant) -> Option<Switch> {
+        let standby = other(self.active);
+        if !self.usable(standby, now) {
+            return None;
+        }
+        let active = *self.link(self.active);
+        let reason = if let Some(failure) = active.failure {
+            failure
+        } else if self.failback && standby == self.primary && now.duration_since(self.switched_at) >= self.stall_after
+            && self.link(standby).last.is_some_and(|t| t > self.switched_at)
+        {
+            SwitchReason::Recovered
+        } else {
+            let quiet = now.duration_since(active.last.map_or(self.switched_at, |t| t.max(self.switched_at)));
+            if quiet < self.stall_after || !self.recent(standby, now) {
+                return None;
+            }
+            SwitchReason::Stalled(quiet)
+        };
+        let from = self.active;
+        self.active = standby;
+        self.switched_at = now;
+        Some(Switch { from, to: standby, reason })
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Deduplication
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Events already delivered to one subscription
+#[derive(Debug, Default)]
+struct Dedup {
+    trades: HashSet<(String, u64)>,
+    order: VecDeque<(String, u64)>,
+    /// Newest book time delivered per coin
+    books: HashMap<String, u64>,
+    block: Option<u64>,
+}
+
+impl Dedup {
+    /// Whether `event` has not been delivered yet (and remember it)
+    fn fresh(&mut self, event: &MarketEvent) -> bool {
+        match event {
+            MarketEvent::Trade(trade) => {
+                let key = (trade.coin.clone(), trade.tid);
+                if !self.trades.insert(key.clone()) {
+                    return false;
+                }
+                self.order.push_back(key);
+                if self.order.len() > DEDUP_WINDOW {
+                    if let Some(old) = self.order.pop_front() {
+                        self.trades.remove(&old);
+                    }
+                }
+                true
+            }
+            MarketEvent::BookSnapshot(book) => self.newer(&book.coin, book.time, false),
+            MarketEvent::BookDelta(update) => update.time.is_none_or(|time| self.newer(&update.coin, time, true)),
+            MarketEvent::Block(block) => {
+                if self.block.is_some_and(|last| block.number <= last) {
+                    return false;
+                }
+                self.block = Some(block.number);
+                true
+            }
+            _ => true,
+        }
+    }
+
+    /// Book events must move forward in time; deltas of one block share it
+    fn newer(&mut self, coin: &str, time: u64, same_time: bool) -> bool {
+        let last = self.books.get(coin).copied();
+        if last.is_some_and(|last| time < last || (time == last && !same_time)) {
+            return false;
+        }
+        self.books.insert(coin.to_string(), time);
+        true
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Feed
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// One subscription, on both transports
+struct Target {
+    channel: Channel,
+    coins: Vec<String>,
+    callback: Callback,
+    state: Mutex<TargetState>,
+}
+
+#[derive(Default)]
+struct TargetState {
+    dedup: Dedup,
+    standby: VecDeque<FeedEvent>,
+}
+
+impl Target {
+    fn wants(&self, event: &MarketEvent) -> bool {
+        let coin = event.coin().is_some_and(|c| self.coins.iter().any(|x| x == c));
+        match (self.channel, event) {
+            (Channel::Trades, MarketEvent::Trade(_)) => coin,
+            (Channel::L2Book, MarketEvent::BookSnapshot(_)) => coin,
+            (Channel::BookUpdates, MarketEvent::BookDelta(_) | MarketEvent::BookSnapshot(_)) => coin,
+            _ => false,
+        }
+    }
+
+    fn deliver(&self, state: &mut TargetState, event: FeedEvent, stats: &Mutex<FeedStats>) {
+        if !state.dedup.fresh(&event.event) {
+            stats.lock().unwrap().duplicates += 1;
+            return;
+        }
+        {
+            let mut stats = stats.lock().unwrap();
+            match event.source {
+                Source::Ws => stats.ws_delivered += 1,
+                Source::Grpc => stats.grpc_delivered += 1,
+            }
+        }
+        call(|| (self.callback)(event));
+    }
+}
+
+#[derive(Default)]
+struct Callbacks {
+    on_switch: Option<SwitchCallback>,
+    on_error: Option<ErrorCallback>,
+    on_reconnect: Option<Arc<dyn Fn(Source, u32) + Send + Sync>>,
+}
+
+struct Shared {
+    failover: Mutex<Failover>,
+    targets: Mutex<Vec<Arc<Target>>>,
+    callbacks: Mutex<Callbacks>,
+    stats: Mutex<FeedStats>,
+    running: AtomicBool,
+}
+
+impl Shared {
+    fn error(&self, source: Source, message: String) {
+        let callback = self.callbacks.lock().unwrap().on_error.clone();
+        if let Some(callback) = callback {
+            call(|| callback(source, message));
+        }
+    }
+
+    /// Run a failover decision; on a switch, replay what the new transport
+    /// held back and tell the `on_switch` callback
+    fn decide(&self, decide: impl FnOnce(&mut Failover, Instant) -> Option<Switch>) {
+        if !self.running.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut failover = self.failover.lock().unwrap();
+        let Some(switch) = decide(&mut failover, Instant::now()) else {
+            return;
+        };
+        self.stats.lock().unwrap().switches += 1;
+        let callback = self.callbacks.lock().unwrap().on_switch.clone();
+        if let Some(callback) = callback {
+            call(|| callback(switch));
+        }
+        for target in self.targets.lock().unwrap().iter() {
+            let mut state = target.state.lock().unwrap();
+            for event in std::mem::take(&mut state.standby) {
+                target.deliver(&mut state, event, &self.stats);
+            }
+        }
+    }
+
+    /// A message of `target` arrived on `source`
+    fn receive(&self, target: &Target, source: Source, data: &Value) {
+        if !self.running.load(Ordering::SeqCst) {
+            return;
+        }
+        let mut events = Vec::new();
+        for event in MarketEvent::parse_each(data) {
+            match event {
+                Ok(event) => events.push(event),
+                Err(e) => self.error(source, format!("bad payload: {}", e)),
+            }
+        }
+        // Held across delivery, so a switch can't slip between the check and the buffer
+        let mut failover = self.failover.lock().unwrap();
+        let active = failover.message(source, Instant::now());
+        let recv_ms = now_ms();
+        let mut state = target.state.lock().unwrap();
+        for event in events.into_iter().filter(|e| target.wants(e)) {
+            let event = FeedEvent { source, recv_ms, event };
+            if active {
+                target.deliver(&mut state, event, &self.stats);
+            } else {
+                state.standby.push_back(event);
+                if state.standby.len() > STANDBY_BUFFER {
+                    state.standby.pop_front();
+                }
+                self.stats.lock().unwrap().held_back += 1;
+            }
+        }
+        drop(state);
+        drop(failover);
+    }
+}
+
+/// Run a consumer's callback, stopping a panic here: unwinding further would
+/// poison the locks held around the call and take the stream's thread with it.
+/// The panic hook has already reported the panic.
+fn call(callback: impl FnOnce()) {
+    let _ = panic::catch_unwind(AssertUnwindSafe(callback));
+}
+
+/// Trades and books from gRPC and WebSocket with failover; see the
+/// [module docs](self)
+pub struct ResilientFeed {
+    stream: Stream,
+    grpc: GRPCStream,
+    shared: Arc<Shared>,
+    watchdog: Option<JoinHandle<()>>,
+}
+
+impl ResilientFeed {
+    /// Feed over the SDK's WebSocket and gRPC streams
+    pub fn new(sdk: &HyperliquidSDK) -> Self {
+        Self::from_streams(sdk.stream(), sdk.grpc())
+    }
+
+    /// Feed over streams you configured; their `on_state_change`,
+    /// `on_reconnect` and `on_error` callbacks are replaced by the feed's
+    pub fn from_streams(stream: Stream, grpc: GRPCStream) -> Self {
+        let shared = Arc::new(Shared {
+            failover: Mutex::new(Failover::new(Source::Grpc, Instant::now())),
+            targets: Mutex::new(Vec::new()),
+            callbacks: Mutex::new(Callbacks::default()),
+            stats: Mutex::new(FeedStats::default()),
+            running: AtomicBool::new(false),
+        });
+        let hooks = |source: Source| {
+            let (on_state, on_reconnect, on_error) = (shared.clone(), shared.clone(), shared.clone());
+            (
+                move |state: ConnectionState| on_state.decide(|f, now| f.state_change(source, state, now)),
+                move |attempt: u32| {
+                    on_reconnect.stats.lock().unwrap().reconnects += 1;
+                    let callback = on_reconnect.callbacks.lock().unwrap().on_reconnect.clone();
+                    if let Some(callback) = callback {
+                        call(|| callback(source, attempt));
+                    }
+                    on_reconnect.decide(|f, now| f.reconnect(source, now));
+                },
+                move |e: String| on_error.error(source, e),
+            )
+        };
+        let (state, reconnect, error) = hooks(Source::Ws);
+        let stream = stream.on_state_change(state).on_reconnect(reconnect).on_error(error);
+        let (state, reconnect, error) = hooks(Source::Grpc);
+        let grpc = grpc.on_state_change(state).on_reconnect(reconnect).on_error(error);
+        ResilientFeed { stream, grpc, shared, watchdog: None }
+    }
+
+    /// Transport to prefer (default gRPC)
+    pub fn primary(self, source: Source) -> Self {
+        let mut failover = self.shared.failover.lock().unwrap();
+        failover.primary = source;
+        failover.active = source;
+        drop(failover);
+        self
+    }
+
+    /// Silence on the active transport that counts as a stall (default
+    /// [`DEFAULT_STALL_AFTER`])
+    pub fn stall_after(self, stall: Duration) -> Self {
+        self.shared.failover.lock().unwrap().stall_after = stall;
+        self
+    }
+
+    /// Switch back to the primary once it delivers again (default on)
+    pub fn failback(self, failback: bool) -> Self {
+        self.shared.failover.lock().unwrap().failback = failback;
+        self
+    }
+
+    /// Called on every change of the active transport
+    pub fn on_switch<F>(self, f: F) -> Self
+    where
+        F: Fn(Switch) + Send + Sync + 'static,
+    {
+        self.shared.callbacks.lock().unwrap().on_switch = Some(Arc::new(f));
+        self
+    }
+
+    /// Called with errors of either transport
+    pub fn on_error<F>(self, f: F) -> Self
+    where
+        F: Fn(Source, String) + Send + Sync + 'static,
+    {
+        self.shared.callbacks.lock().unwrap().on_error = Some(Arc::new(f));
+        self
+    }
+
+    /// Called when either transport starts a reconnect attempt
+    pub fn on_reconnect<F>(self, f: F) -> Self
+    where
+        F: Fn(Source, u32) + Send + Sync + 'static,
+    {
+        self.shared.callbacks.lock().unwrap().on_reconnect = Some(Arc::new(f));
+        self
+    }
+
+    fn target<F>(&mut self, channel: Channel, coins: &[&str], callback: F) -> Arc<Target>
+    where
+        F: Fn(FeedEvent) + Send + Sync + 'static,
+    {
+        let target = Arc::new(Target {
+            channel,
+            coins: coins.iter().map(|c| c.to_string()).collect(),
+            callback: Arc::new(callback),
+            state: Mutex::new(TargetState::default()),
+        });
+        self.shared.targets.lock().unwrap().push(target.clone());
+        target
+    }
+
+    fn handler(&self, target: &Arc<Target>, source: Source) -> impl Fn(Value) + Send + Sync + 'static {
+        let (shared, target) = (self.shared.clone(), target.clone());
+        move |data| shared.receive(&target, source, &data)
+    }
+
+    /// Subscribe to trades of `coins` on both transports
+    pub fn trades<F>(&mut self, coins: &[&str], callback: F)
+    where
+        F: Fn(FeedEvent) + Send + Sync + 'static,
+    {
+        let target = self.target(Channel::Trades, coins, callback);
+        self.stream.trades(coins, self.handler(&target, Source::Ws));
+        self.grpc.trades(coins, self.handler(&target, Source::Grpc));
+    }
+
+    /// Subscribe to L2 snapshots of `coin` on both transports
+    pub fn l2_book<F>(&mut self, coin: &str, callback: F)
+    where
+        F: Fn(FeedEvent) + Send + Sync + 'static,
+    {
+        let target = self.target(Channel::L2Book, &[coin], callback);
+        self.stream.l2_book(coin, self.handler(&target, Source::Ws));
+        self.grpc.l2_book(coin, self.handler(&target, Source::Grpc));
+    }
+
+    /// Subscribe to book level changes of `coins` on both transports
+    pub fn book_updates<F>(&mut self, coins: &[&str], callback: F)
+    where
+        F: Fn(FeedEvent) + Send + Sync + 'static,
+    {
+        let target = self.target(Channel::BookUpdates, coins, callback);
+        self.stream.book_updates(coins, self.handler(&target, Source::Ws));
+        self.grpc.book_updates(coins, self.handler(&target, Source::Grpc));
+    }
+
+    /// Connect both transports and start watching for stalls
+    pub fn start(&mut self) -> Result<()> {
+        if self.shared.running.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        {
+            let mut failover = self.shared.failover.lock().unwrap();
+            let primary = failover.primary;
+            *failover = Failover { ws: Link::new(), grpc: Link::new(), active: primary, switched_at: Instant::now(), ..*failover };
+        }
+        self.grpc.start()?;
+        self.stream.start()?;
+
+        let shared = self.shared.clone();
+        self.watchdog = Some(
+            std::thread::Builder::new()
+                .name("feed-watchdog".to_string())
+                .spawn(move || {
+                    while shared.running.load(Ordering::SeqCst) {
+                        std::thread::sleep(CHECK_EVERY);
+                        shared.decide(|f, now| f.check(now));
+                    }
+                })?,
+        );
+        Ok(())
+    }
+
+    /// Disconnect both transports
+    pub fn stop(&mut self) {
+        if !self.shared.running.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        self.stream.stop();
+        self.grpc.stop();
+        if let Some(watchdog) = self.watchdog.take() {
+            let _ = watchdog.join();
+        }
+    }
+
+    /// Transport whose events are being delivered
+    pub fn active(&self) -> Source {
+        self.shared.failover.lock().unwrap().active
+    }
+
+    pub fn stats(&self) -> FeedStats {
+        self.shared.stats.lock().unwrap().clone()
+    }
+}
+
+impl Drop for ResilientFeed {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Stream callbacks
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Stream callback handing `on_event` the events of each `channel` message
+/// (`"userFills"`, `"allMids"`, ...) and `on_error` each item that fails to
+/// decode; the other items of that message are still delivered.
+///
+/// Every callback of a stream sees every channel's messages, so each keeps
+/// only its own: messages tagged with another channel are skipped, and of an
+/// untagged (gRPC) payload only the events of `channel` are kept.
+pub fn handler(
+    channel: &'static str,
+    on_event: impl Fn(MarketEvent) + Send + Sync + 'static,
+    on_error: impl Fn(Error) + Send + Sync + 'static,
+) -> impl Fn(Value) + Send + Sync + 'static {
+    move |message| {
+        let tagged = match message.get("channel").and_then(|c| c.as_str()) {
+            Some(tag) if !same_channel(tag, channel) => return,
+            tag => tag.is_some(),
+        };
+        for event in MarketEvent::parse_each(&message) {
+            match event {
+                Ok(event) if tagged || same_channel(event.channel(), channel) => on_event(event),
+                Ok(_) => {}
+                Err(e) => on_error(e),
+            }
+        }
+    }
+}
+
+/// [`handler`] sending the events, and the items that failed to decode, to `tx`
+pub fn forward(channel: &'static str, tx: &UnboundedSender<Result<MarketEvent>>) -> impl Fn(Value) + Send + Sync + 'static {
+    let (events, errors) = (tx.clone(), tx.clone());
+    handler(
+        channel,
+        move |event| {
+            let _ = events.send(Ok(event));
+        },
+        move |e| {
+            let _ = errors.send(Err(e));
+        },
+    )
+}
+
+/// Whether two channel names are the same channel (`l2_book` on gRPC is `l2Book`)
+fn same_channel(a: &str, b: &str) -> bool {
+    let key = |name: &str| -> String { name.chars().filter(|c| *c != '_').map(|c| c.to_ascii_lowercase()).collect() };
+    key(a) == key(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const STALL: Duration = Duration::from_secs(10);
+
+    fn failover(now: Instant) -> Failover {
+        Failover { stall_after: STALL, ..Failover::new(Source::Grpc, now) }
+    }
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn switches_when_the_active_transport_goes_down() {
+        let t0 = Instant::now();
+        let mut f = failover(t0);
+        // gRPC fails before the WebSocket is up: nothing to switch to yet
+        assert_eq!(f.state_change(Source::Grpc, ConnectionState::Reconnecting, t0), None);
+        let switch = f.state_change(Source::Ws, ConnectionState::Connected, t0 + secs(1)).unwrap();
+        assert_eq!(switch, Switch { from: Source::Grpc, to: Source::Ws, reason: SwitchReason::Reconnecting });
+        assert!(f.message(Source::Ws, t0 + secs(2)));
+        assert!(!f.message(Source::Grpc, t0 + secs(3)));
+
+        // gRPC delivers again; failback waits one stall period after the switch
+        assert_eq!(f.check(t0 + secs(5)), None);
+        f.message(Source::Grpc, t0 + secs(11));
+        let back = f.check(t0 + secs(11)).unwrap();
+        assert_eq!((back.to, back.reason), (Source::Grpc, SwitchReason::Recovered));
+    }
+
+    #[test]
+    fn switches_on_a_stall_only_while_the_standby_receives() {
+        let t0 = Instant::now();
+        let mut f = failover(t0);
+        f.message(Source::Grpc, t0);
+        f.state_change(Source::Ws, ConnectionState::Connected, t0);
+        // Both quiet: a slow market, not a stall
+        assert_eq!(f.check(t0 + secs(30)), None);
+
+        f.message(Source::Ws, t0 + secs(31));
+        let switch = f.check(t0 + secs(32)).unwrap();
+        assert_eq!(switch.reason, SwitchReason::Stalled(secs(32)));
+        assert_eq!(f.active, Source::Ws);
+
+        // Without failback the feed stays on the standby
+        let mut f = Failover { failback: false, ..failover(t0) };
+        f.state_change(Source::Grpc, ConnectionState::Disconnected, t0);
+        f.message(Source::Ws, t0);
+        assert_eq!(f.check(t0).unwrap().to, Source::Ws);
+        f.message(Source::Grpc, t0 + secs(20));
+        f.message(Source::Ws, t0 + secs(20));
+        assert_eq!(f.check(t0 + secs(21)), None);
+    }
+
+    #[test]
+    fn deduplicates_trades_books_and_blocks() {
+        let mut dedup = Dedup::default();
+        let trade = |tid: u64| {
+            MarketEvent::parse(&json!({"channel": "trades", "data": [
+                {"coin": "BTC", "side": "B", "px": "96251.0", "sz": "0.01", "time": 1, "hash": "0x1", "tid": tid}
+            ]}))
+            .unwrap()
+            .remove(0)
+        };
+        assert!(dedup.fresh(&trade(1)));
+        assert!(!dedup.fresh(&trade(1)));
+        assert!(dedup.fresh(&trade(2)));
+
+        let book = |time: u64| {
+            MarketEvent::parse(&json!({"channel": "l2Book", "data": {"coin": "BTC", "time": time, "levels": [[], []]}}))
+                .unwrap()
+                .remove(0)
+        };
+        assert!(dedup.fresh(&book(10)));
+        assert!(!dedup.fresh(&book(10)));
+        assert!(!dedup.fresh(&book(9)));
+        assert!(dedup.fresh(&book(11)));
+
+        let block = |n: u64| MarketEvent::parse(&json!({"block_number": n})).unwrap().remove(0);
+        assert!(dedup.fresh(&block(5)));
+        assert!(!dedup.fresh(&block(5)));
+        assert!(dedup.fresh(&block(6)));
+    }
+
+    #[test]
+    fn a_panicking_callback_does_not_stop_delivery() {
+        let shared = Shared {
+            failover: Mutex::new(Failover::new(Source::Grpc, Instant::now())),
+            targets: Mutex::new(Vec::new()),
+            callbacks: Mutex::new(Callbacks::default()),
+            stats: Mutex::new(FeedStats::default()),
+            running: AtomicBool::new(true),
+        };
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let target = Target {
+            channel: Channel::Trades,
+            coins: vec!["BTC".to_string()],
+            callback: Arc::new({
+                let seen = seen.clone();
+                move |e: FeedEvent| {
+                    let tid = match e.event {
+                        MarketEvent::Trade(trade) => trade.tid,
+                        _ => 0,
+                    };
+                    seen.lock().unwrap().push(tid);
+                    assert_ne!(tid, 1, "callback panics on the first trade");
+                }
+            }),
+            state: Mutex::new(TargetState::default()),
+        };
+        let trade = |tid: u64| {
+            json!({"channel": "trades", "data": [
+                {"coin": "BTC", "side": "B", "px": "96251.0", "sz": "0.01", "time": 1, "hash": "0x1", "tid": tid}
+            ]})
+        };
+
+        shared.receive(&target, Source::Grpc, &trade(1));
+        shared.receive(&target, Source::Grpc, &trade(2));
+        assert_eq!(*seen.lock().unwrap(), vec![1, 2]);
+        assert!(!shared.failover.is_poisoned() && !target.state.is_poisoned());
+        assert_eq!(shared.stats.lock().unwrap().grpc_delivered, 2);
+    }
+
+    #[test]
+    fn callbacks_keep_their_channel_and_report_bad_items() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let trade = json!({"coin": "BTC", "side": "B", "px": "96251.0", "sz": "0.01", "time": 1, "hash": "0x1", "tid": 1});
+        let message = json!({"channel": "trades", "data": [trade.clone(), {"coin": "BTC"}, trade]});
+
+        forward("userFills", &tx)(message.clone());
+        assert!(rx.try_recv().is_err());
+
+        // The bad trade is an error in its place; its neighbours still arrive
+        forward("trades", &tx)(message);
+        let received: Vec<Result<MarketEvent>> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+        assert_eq!(received.len(), 3);
+        assert!(matches!(received[0], Ok(MarketEvent::Trade(_))));
+        assert!(received[1].is_err());
+        assert!(matches!(received[2], Ok(MarketEvent::Trade(_))));
+
+        assert!(same_channel("l2_book", "l2Book"));
+        assert!(!same_channel("l2Book", "bookUpdates"));
+    }
+}
@@ -0,0 +1,599 @@
+//! Stream health: latency, gaps, reconnects and staleness as Prometheus metrics.
+//!
+//! A [`HealthMonitor`] sits between a stream and your callbacks. Wrap each
+//! subscription with [`HealthMonitor::watch`] and call
+//! [`reconnected`](HealthMonitor::reconnected) / [`error`](HealthMonitor::error)
+//! from the stream's `on_reconnect` / `on_error`, and it keeps, per transport,
+//! channel and coin:
+//!
+//! - how late messages arrive (receive time minus exchange time),
+//! - the time between consecutive messages,
+//! - when the last one arrived, and whether that is longer ago than
+//!   [`stale_after`](HealthMonitor::stale_after),
+//!
+//! plus reconnect and error counts per transport and gaps in the block numbers
+//! of `grpc.blocks`. [`render`](HealthMonitor::render) writes them in the
+//! Prometheus text format and [`serve`](HealthMonitor::serve) exposes them at
+//! `/metrics`. [`check`](HealthMonitor::check) raises an [`Alert`] when a feed
+//! goes stale (and when it recovers); call it every second or so.
+//!
+//! ```no_run
+//! use hyperliquid_examples::health::HealthMonitor;
+//! use hyperliquid_examples::recorder::{Channel, Source};
+//!
+//! # async fn run(sdk: &hyperliquid_sdk::HyperliquidSDK) -> hyperliquid_examples::Result<()> {
+//! let monitor = HealthMonitor::new().on_alert(|alert| eprintln!("{}", alert));
+//! let server = monitor.serve("127.0.0.1:9184".parse().unwrap()).await?;
+//! let mut stream = sdk.stream();
+//! stream.trades(&["BTC"], monitor.watch(Source::Ws, Channel::Trades, &["BTC"], |data| println!("{}", data)));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Latency compares the exchange's timestamps with the local clock, so it is
+//! only as good as your clock sync. Quiet coins trade less often than
+//! [`DEFAULT_STALE_AFTER`]; raise it for them.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use hyperliquid_sdk::stream::ConnectionState;
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::metrics::{self, header, sample, MetricsServer};
+use crate::models::MarketEvent;
+use crate::recorder::{now_ms, Channel, Source};
+
+/// How long a feed may go without a message before it is stale
+pub const DEFAULT_STALE_AFTER: Duration = Duration::from_secs(30);
+
+/// Histogram bucket bounds (seconds) for latencies and gaps
+const BUCKETS: [f64; 12] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+type AlertCallback = Arc<dyn Fn(Alert) + Send + Sync>;
+
+/// One feed: a channel of one coin over one transport (`coin` is `None` for
+/// channels without coins, like `blocks`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FeedKey {
+    pub source: Source,
+    pub channel: Channel,
+    pub coin: Option<String>,
+}
+
+impl fmt::Display for FeedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.source, self.channel)?;
+        match &self.coin {
+            Some(coin) => write!(f, " {}", coin),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Something worth telling a human about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Alert {
+    /// No message on the feed for `quiet`
+    Stale { key: FeedKey, quiet: Duration },
+    /// A stale feed delivered again
+    Recovered { key: FeedKey, quiet: Duration },
+    /// Blocks between `from` and `to` (both exclusive) never arrived
+    BlockGap { from: u64, to: u64 },
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Alert::Stale { key, quiet } => write!(f, "{} stale: no message for {:.1}s", key, quiet.as_secs_f64()),
+            Alert::Recovered { key, quiet } => write!(f, "{} recovered after {:.1}s", key, quiet.as_secs_f64()),
+            Alert::BlockGap { from, to } => write!(f, "blocks {} -> {}: {} missed", from, to, to - from - 1),
+        }
+    }
+}
+
+/// Summary of one feed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedHealth {
+    pub key: FeedKey,
+    pub messages: u64,
+    pub latency_mean: Option<Duration>,
+    pub latency_max: Option<Duration>,
+    pub gap_max: Option<Duration>,
+    /// Local receive time of the last message (ms)
+    pub last_recv_ms: Option<u64>,
+    pub stale: bool,
+}
+
+/// Reconnects, errors and blocks of one transport
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkHealth {
+    pub reconnects: u64,
+    pub errors: u64,
+    /// Last state reported by `on_state_change`
+    pub state: Option<ConnectionState>,
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Metrics
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+    max: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, secs: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+            if secs <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += secs;
+        self.max = self.max.max(secs);
+    }
+
+    fn mean(&self) -> Option<Duration> {
+        (self.count > 0).then(|| Duration::from_secs_f64(self.sum / self.count as f64))
+    }
+
+    fn max(&self) -> Option<Duration> {
+        (self.count > 0).then(|| Duration::from_secs_f64(self.max))
+    }
+}
+
+#[derive(Debug)]
+struct Feed {
+    messages: u64,
+    latency: Histogram,
+    gaps: Histogram,
+    /// Registration time, for feeds that never delivered
+    since_ms: u64,
+    last_recv_ms: Option<u64>,
+    stale: bool,
+}
+
+impl Feed {
+    fn new(since_ms: u64) -> Self {
+        Feed { messages: 0, latency: Histogram::default(), gaps: Histogram::default(), since_ms, last_recv_ms: None, stale: false }
+    }
+
+    fn quiet(&self, now_ms: u64) -> Duration {
+        Duration::from_millis(now_ms.saturating_sub(self.last_recv_ms.unwrap_or(self.since_ms)))
+    }
+}
+
+#[derive(Debug, Default)]
+struct Blocks {
+    last: Option<u64>,
+    gaps: u64,
+    missed: u64,
+}
+
+struct State {
+    stale_after: Duration,
+    feeds: HashMap<FeedKey, Feed>,
+    links: HashMap<Source, LinkHealth>,
+    blocks: Blocks,
+}
+
+struct Inner {
+    state: Mutex<State>,
+    on_alert: Mutex<Option<AlertCallback>>,
+}
+
+/// Stream health metrics and stale-feed alerts; see the [module docs](self).
+///
+/// Clones share the same metrics.
+#[derive(Clone)]
+pub struct HealthMonitor {
+    inner: Arc<Inner>,
+}
+
+impl Default for HealthMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthMonitor {
+    pub fn new() -> Self {
+        HealthMonitor {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State {
+                    stale_after: DEFAULT_STALE_AFTER,
+                    feeds: HashMap::new(),
+                    links: HashMap::new(),
+                    blocks: Blocks::default(),
+                }),
+                on_alert: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// Silence after which a feed is stale (default [`DEFAULT_STALE_AFTER`])
+    pub fn stale_after(self, stale_after: Duration) -> Self {
+        self.state().stale_after = stale_after;
+        self
+    }
+
+    /// Called with every alert
+    pub fn on_alert<F>(self, f: F) -> Self
+    where
+        F: Fn(Alert) + Send + Sync + 'static,
+    {
+        *self.inner.on_alert.lock().unwrap() = Some(Arc::new(f));
+        self
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.inner.state.lock().unwrap()
+    }
+
+    fn alert(&self, mut alerts: Vec<Alert>) -> Vec<Alert> {
+        alerts.sort_by_key(|a| a.to_string());
+        let callback = self.inner.on_alert.lock().unwrap().clone();
+        if let Some(callback) = callback {
+            alerts.iter().cloned().for_each(|alert| callback(alert));
+        }
+        alerts
+    }
+
+    /// Stream callback that records each message, then passes it to
+    /// `callback`. The feeds of `coins` count as stale from now on if they
+    /// never deliver.
+    pub fn watch<F>(&self, source: Source, channel: Channel, coins: &[&str], callback: F) -> impl Fn(Value) + Send + Sync + 'static
+    where
+        F: Fn(Value) + Send + Sync + 'static,
+    {
+        let now = now_ms();
+        {
+            let mut state = self.state();
+            let coins: Vec<Option<String>> = match channel.per_coin() {
+                true => coins.iter().map(|c| Some(c.to_string())).collect(),
+                false => vec![None],
+            };
+            for coin in coins {
+                state.feeds.entry(FeedKey { source, channel, coin }).or_insert_with(|| Feed::new(now));
+            }
+        }
+        let monitor = self.clone();
+        move |data| {
+            monitor.record(source, channel, &data);
+            callback(data);
+        }
+    }
+
+    /// Record a message received now
+    pub fn record(&self, source: Source, channel: Channel, data: &Value) -> Vec<Alert> {
+        self.record_at(source, channel, data, now_ms())
+    }
+
+    /// Record a message received at `recv_ms`
+    pub fn record_at(&self, source: Source, channel: Channel, data: &Value, recv_ms: u64) -> Vec<Alert> {
+        // Newest exchange time per coin of the message
+        let mut times: HashMap<Option<String>, Option<u64>> = HashMap::new();
+        let mut blocks = Vec::new();
+        let events = MarketEvent::parse(data).unwrap_or_default();
+        for event in events.into_iter().filter(|e| belongs(channel, e)) {
+            if let MarketEvent::Block(block) = &event {
+                blocks.push(block.number);
+            }
+            let coin = if channel.per_coin() { event.coin().map(str::to_string) } else { None };
+            let time = times.entry(coin).or_default();
+            *time = (*time).max(event.time());
+        }
+        // WebSocket callbacks also see other channels and subscription acks;
+        // only L4 books have no events to tell them apart by
+        if times.is_empty() {
+            let tag = data.get("channel").and_then(|c| c.as_str());
+            if channel != Channel::L4Book || !matches!(tag, None | Some("l4Book" | "l4_book")) {
+                return Vec::new();
+            }
+            times.insert(None, None);
+        }
+
+        let mut alerts = Vec::new();
+        let mut state = self.state();
+        for (coin, time) in times {
+            let key = FeedKey { source, channel, coin };
+            let feed = state.feeds.entry(key.clone()).or_insert_with(|| Feed::new(recv_ms));
+            if feed.stale {
+                feed.stale = false;
+                alerts.push(Alert::Recovered { key, quiet: feed.quiet(recv_ms) });
+            }
+            if let Some(last) = feed.last_recv_ms {
+                feed.gaps.observe(recv_ms.saturating_sub(last) as f64 / 1000.0);
+            }
+            if let Some(time) = time {
+                // Clocks drift; a message can't arrive before it was sent
+                feed.latency.observe(recv_ms.saturating_sub(time) as f64 / 1000.0);
+            }
+            feed.messages += 1;
+            feed.last_recv_ms = Some(recv_ms);
+        }
+        for number in blocks {
+            let seen = &mut state.blocks;
+            match seen.last {
+                Some(last) if number <= last => continue,
+                Some(last) if number > last + 1 => {
+                    seen.gaps += 1;
+                    seen.missed += number - last - 1;
+                    alerts.push(Alert::BlockGap { from: last, to: number });
+                }
+                _ => {}
+            }
+            seen.last = Some(number);
+        }
+        drop(state);
+        self.alert(alerts)
+    }
+
+    /// Count a reconnect attempt (from `on_reconnect`)
+    pub fn reconnected(&self, source: Source) {
+        self.state().links.entry(source).or_default().reconnects += 1;
+    }
+
+    /// Count an error (from `on_error`)
+    pub fn error(&self, source: Source) {
+        self.state().links.entry(source).or_default().errors += 1;
+    }
+
+    /// Remember the connection state (from `on_state_change`). The SDK's
+    /// gRPC client never reports `Connected`, only the way down and back.
+    pub fn state_changed(&self, source: Source, state: ConnectionState) {
+        self.state().links.entry(source).or_default().state = Some(state);
+    }
+
+    /// Mark feeds stale that have been quiet too long; returns (and passes to
+    /// `on_alert`) the feeds that just went stale
+    pub fn check(&self) -> Vec<Alert> {
+        self.check_at(now_ms())
+    }
+
+    pub fn check_at(&self, now_ms: u64) -> Vec<Alert> {
+        let mut state = self.state();
+        let stale_after = state.stale_after;
+        let mut alerts = Vec::new();
+        for (key, feed) in state.feeds.iter_mut() {
+            let quiet = feed.quiet(now_ms);
+            if !feed.stale && quiet >= stale_after {
+                feed.stale = true;
+                alerts.push(Alert::Stale { key: key.clone(), quiet });
+            }
+        }
+        drop(state);
+        self.alert(alerts)
+    }
+
+    /// Every feed, by transport, channel and coin
+    pub fn feeds(&self) -> Vec<FeedHealth> {
+        let state = self.state();
+        let mut feeds: Vec<FeedHealth> = state
+            .feeds
+            .iter()
+            .map(|(key, feed)| FeedHealth {
+                key: key.clone(),
+                messages: feed.messages,
+                latency_mean: feed.latency.mean(),
+                latency_max: feed.latency.max(),
+                gap_max: feed.gaps.max(),
+                last_recv_ms: feed.last_recv_ms,
+                stale: feed.stale,
+            })
+            .collect();
+        feeds.sort_by_key(|f| sort_key(&f.key));
+        feeds
+    }
+
+    /// Reconnects, errors and state of `source`
+    pub fn link(&self, source: Source) -> LinkHealth {
+        self.state().links.get(&source).cloned().unwrap_or_default()
+    }
+
+    /// `(last block, gaps, blocks missed)` of `grpc.blocks`
+    pub fn blocks(&self) -> (Option<u64>, u64, u64) {
+        let state = self.state();
+        (state.blocks.last, state.blocks.gaps, state.blocks.missed)
+    }
+
+    /// The metrics in the Prometheus text format
+    pub fn render(&self) -> String {
+        self.render_at(now_ms())
+    }
+
+    pub fn render_at(&self, now_ms: u64) -> String {
+        let state = self.state();
+        let mut feeds: Vec<(&FeedKey, &Feed)> = state.feeds.iter().collect();
+        feeds.sort_by_key(|(key, _)| sort_key(key));
+        let mut links: Vec<(&Source, &LinkHealth)> = state.links.iter().collect();
+        links.sort_by_key(|(source, _)| source.as_str());
+
+        let mut out = String::new();
+        header(&mut out, "hl_stream_messages_total", "counter", "Stream messages received");
+        for (key, feed) in &feeds {
+            sample(&mut out, "hl_stream_messages_total", &labels(key), feed.messages);
+        }
+        header(&mut out, "hl_stream_latency_seconds", "histogram", "Receive time minus exchange time of each message");
+        for (key, feed) in &feeds {
+            histogram(&mut out, "hl_stream_latency_seconds", &labels(key), &feed.latency);
+        }
+        header(&mut out, "hl_stream_gap_seconds", "histogram", "Time between consecutive messages");
+        for (key, feed) in &feeds {
+            histogram(&mut out, "hl_stream_gap_seconds", &labels(key), &feed.gaps);
+        }
+        header(&mut out, "hl_stream_last_message_age_seconds", "gauge", "Time since the last message");
+        for (key, feed) in &feeds {
+            sample(&mut out, "hl_stream_last_message_age_seconds", &labels(key), feed.quiet(now_ms).as_secs_f64());
+        }
+        header(&mut out, "hl_stream_stale", "gauge", "1 when the feed has been quiet longer than the stale threshold");
+        for (key, feed) in &feeds {
+            sample(&mut out, "hl_stream_stale", &labels(key), u8::from(feed.stale));
+        }
+
+        header(&mut out, "hl_stream_reconnects_total", "counter", "Reconnect attempts");
+        for (source, link) in &links {
+            sample(&mut out, "hl_stream_reconnects_total", &source_label(**source), link.reconnects);
+        }
+        header(&mut out, "hl_stream_errors_total", "counter", "Stream errors");
+        for (source, link) in &links {
+            sample(&mut out, "hl_stream_errors_total", &source_label(**source), link.errors);
+        }
+        header(&mut out, "hl_stream_up", "gauge", "0 while the transport is disconnected or reconnecting");
+        for (source, link) in &links {
+            let down = matches!(link.state, Some(ConnectionState::Disconnected | ConnectionState::Reconnecting));
+            sample(&mut out, "hl_stream_up", &source_label(**source), u8::from(!down));
+        }
+
+        if let Some(last) = state.blocks.last {
+            header(&mut out, "hl_block_height", "gauge", "Last block number from the blocks stream");
+            sample(&mut out, "hl_block_height", "", last);
+            header(&mut out, "hl_block_gaps_total", "counter", "Jumps in the block number");
+            sample(&mut out, "hl_block_gaps_total", "", state.blocks.gaps);
+            header(&mut out, "hl_blocks_missed_total", "counter", "Blocks skipped by those jumps");
+            sample(&mut out, "hl_blocks_missed_total", "", state.blocks.missed);
+        }
+        out
+    }
+
+    /// Serve [`render`](Self::render) at `http://addr/metrics` until the
+    /// returned server is dropped (port 0 picks a free port)
+    pub async fn serve(&self, addr: SocketAddr) -> Result<MetricsServer> {
+        let monitor = self.clone();
+        metrics::serve(addr, move || monitor.render()).await
+    }
+}
+
+/// Whether `event` is one the `channel` subscription delivers
+fn belongs(channel: Channel, event: &MarketEvent) -> bool {
+    matches!(
+        (channel, event),
+        (Channel::Trades, MarketEvent::Trade(_))
+            | (Channel::L2Book, MarketEvent::BookSnapshot(_))
+            | (Channel::BookUpdates, MarketEvent::BookDelta(_) | MarketEvent::BookSnapshot(_))
+            | (Channel::AllMids, MarketEvent::Mid(_))
+            | (Channel::Blocks, MarketEvent::Block(_))
+    )
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Text format
+// ══════════════════════════════════════════════════════════════════════════════
+
+fn sort_key(key: &FeedKey) -> (&'static str, &'static str, String) {
+    (key.source.as_str(), key.channel.as_str(), key.coin.clone().unwrap_or_default())
+}
+
+fn labels(key: &FeedKey) -> String {
+    let mut pairs = vec![("source", key.source.as_str()), ("channel", key.channel.as_str())];
+    if let Some(coin) = &key.coin {
+        pairs.push(("coin", coin));
+    }
+    metrics::labels(&pairs)
+}
+
+fn source_label(source: Source) -> String {
+    metrics::labels(&[("source", source.as_str())])
+}
+
+fn histogram(out: &mut String, name: &str, labels: &str, histogram: &Histogram) {
+    for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+        sample(out, &format!("{}_bucket", name), &format!("{},le=\"{}\"", labels, bound), count);
+    }
+    sample(out, &format!("{}_bucket", name), &format!("{},le=\"+Inf\"", labels), histogram.count);
+    sample(out, &format!("{}_sum", name), labels, histogram.sum);
+    sample(out, &format!("{}_count", name), labels, histogram.count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn trades(time: u64) -> Value {
+        json!({"channel": "trades", "data": [
+            {"coin": "BTC", "side": "B", "px": "96251.0", "sz": "0.01", "time": time, "hash": "0x1", "tid": 1},
+            {"coin": "ETH", "side": "A", "px": "3612.4", "sz": "0.5", "time": time - 100, "hash": "0x2", "tid": 2}
+        ]})
+    }
+
+    #[test]
+    fn measures_latency_and_gaps_per_coin() {
+        let monitor = HealthMonitor::new();
+        monitor.record_at(Source::Ws, Channel::Trades, &trades(1_000), 1_250);
+        monitor.record_at(Source::Ws, Channel::Trades, &trades(2_000), 3_000);
+        monitor.reconnected(Source::Ws);
+
+        let feeds = monitor.feeds();
+        assert_eq!(feeds.len(), 2);
+        let btc = &feeds[0];
+        assert_eq!(btc.key.to_string(), "ws trades BTC");
+        assert_eq!(btc.messages, 2);
+        assert_eq!(btc.latency_mean, Some(Duration::from_millis(625)));
+        assert_eq!(btc.latency_max, Some(Duration::from_secs(1)));
+        assert_eq!(btc.gap_max, Some(Duration::from_millis(1_750)));
+        assert_eq!(feeds[1].latency_max, Some(Duration::from_millis(1_100)));
+
+        let text = monitor.render_at(4_000);
+        assert!(text.contains("# TYPE hl_stream_latency_seconds histogram\n"), "{}", text);
+        assert!(text.contains("hl_stream_messages_total{source=\"ws\",channel=\"trades\",coin=\"BTC\"} 2\n"), "{}", text);
+        assert!(text.contains("hl_stream_latency_seconds_bucket{source=\"ws\",channel=\"trades\",coin=\"BTC\",le=\"0.25\"} 1\n"), "{}", text);
+        assert!(text.contains("hl_stream_latency_seconds_bucket{source=\"ws\",channel=\"trades\",coin=\"BTC\",le=\"+Inf\"} 2\n"), "{}", text);
+        assert!(text.contains("hl_stream_latency_seconds_sum{source=\"ws\",channel=\"trades\",coin=\"BTC\"} 1.25\n"), "{}", text);
+        assert!(text.contains("hl_stream_last_message_age_seconds{source=\"ws\",channel=\"trades\",coin=\"ETH\"} 1\n"), "{}", text);
+        assert!(text.contains("hl_stream_reconnects_total{source=\"ws\"} 1\n"), "{}", text);
+        assert!(!text.contains("hl_block_height"), "{}", text);
+    }
+
+    #[test]
+    fn alerts_on_stale_feeds_and_block_gaps() {
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let seen = alerts.clone();
+        let monitor = HealthMonitor::new()
+            .stale_after(Duration::from_secs(5))
+            .on_alert(move |alert| seen.lock().unwrap().push(alert.to_string()));
+        monitor.record_at(Source::Ws, Channel::Trades, &trades(1_000), 1_000);
+
+        assert!(monitor.check_at(5_999).is_empty());
+        assert_eq!(monitor.check_at(6_000).len(), 2);
+        assert!(monitor.check_at(7_000).is_empty());
+        monitor.record_at(Source::Ws, Channel::Trades, &trades(8_000), 8_000);
+
+        for number in [100u64, 101, 104, 103, 105] {
+            monitor.record_at(Source::Grpc, Channel::Blocks, &json!({"block_number": number, "time": 9_000}), 9_000);
+        }
+        assert_eq!(monitor.blocks(), (Some(105), 1, 2));
+        assert!(monitor.render_at(9_000).contains("hl_blocks_missed_total 2\n"));
+
+        assert_eq!(
+            *alerts.lock().unwrap(),
+            [
+                "ws trades BTC stale: no message for 5.0s",
+                "ws trades ETH stale: no message for 5.0s",
+                "ws trades BTC recovered after 7.0s",
+                "ws trades ETH recovered after 7.0s",
+                "blocks 101 -> 104: 2 missed",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn serves_the_metrics_endpoint() {
+        let monitor = HealthMonitor::new();
+        monitor.record(Source::Grpc, Channel::Trades, &trades(now_ms()));
+        let server = monitor.serve(SocketAddr::from(([127, 0, 0, 1], 0))).await.unwrap();
+        let response = reqwest::get(format!("http://{}/metrics", server.addr())).await.unwrap();
+        assert!(response.headers()[reqwest::header::CONTENT_TYPE].to_str().unwrap().starts_with("text/plain"));
+        let text = response.text().await.unwrap();
+        assert!(text.contains("hl_stream_messages_total{source=\"grpc\",channel=\"trades\",coin=\"ETH\"} 1\n"), "{}", text);
+    }
+}
@@ -0,0 +1,672 @@
+//! Iceberg (reserve) orders: a large resting order shown a clip at a time.
+//!
+//! An [`Iceberg`] takes an SDK `Order` with the full size and price, a
+//! `gtc()` or `alo()` limit order, and rests only a clip of it on the book.
+//! When the clip has filled, the next one is placed, until the whole size is
+//! done. Each clip's size can vary randomly around the clip size, and its
+//! price can sit a random number of ticks behind the limit, so the refills
+//! are harder to spot.
+//!
+//! Clips fill from the `userFills` stream ([`IcebergOrder::watch`] and
+//! [`on_event`](IcebergOrder::on_event)), with `sdk.order_status` polled as a
+//! fallback ([`sync`](IcebergOrder::sync)). [`IcebergOrder::progress`] gives
+//! the filled size and average price.
+//!
+//! ```no_run
+//! use hyperliquid_examples::iceberg::Iceberg;
+//! use hyperliquid_examples::{Config, Requires};
+//! use hyperliquid_sdk::Order;
+//! use rust_decimal::Decimal;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = Config::load()?.require("iceberg", Requires::Signer);
+//! let sdk = config.connect().await?;
+//! let order = Order::buy("BTC").size(1.0).price(95000.0).alo();
+//! let mut iceberg = Iceberg::new(order, Decimal::new(5, 2))
+//!     .clip_variance(Decimal::new(2, 1))
+//!     .price_offset(3)
+//!     .place(&config, &sdk)
+//!     .await?;
+//! while iceberg.is_working() {
+//!     iceberg.sync(&sdk).await?;
+//!     if let Some(clip) = iceberg.replenish(&config).await? {
+//!         println!("{} / {}", clip, iceberg.progress());
+//!     }
+//!     tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use hyperliquid_sdk::{HyperliquidSDK, Order, Stream, TIF};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use crate::config::Config;
+use crate::dry_run::actions;
+use crate::error::{Error, Result};
+use crate::exchange::{self, rejection, OrderStatus};
+use crate::execution::{Progress, MIN_NOTIONAL};
+use crate::feed;
+use crate::models::{Fill, MarketEvent, OrderUpdate};
+use crate::price::{Precision, Rounding};
+use crate::registry::{AssetRegistry, Market};
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Iceberg
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Builder of an iceberg order
+#[derive(Debug, Clone)]
+pub struct Iceberg {
+    order: Order,
+    clip: Decimal,
+    clip_variance: Decimal,
+    price_offset: u32,
+    reduce_only: bool,
+    seed: Option<u64>,
+}
+
+impl Iceberg {
+    /// Show `order` (a `gtc()` or `alo()` limit order with its full size and
+    /// price) `clip` at a time
+    pub fn new(order: Order, clip: Decimal) -> Self {
+        Self { order, clip, clip_variance: Decimal::ZERO, price_offset: 0, reduce_only: false, seed: None }
+    }
+
+    /// Vary each clip's size randomly by up to this fraction of the clip (0.2 = ±20%)
+    pub fn clip_variance(mut self, fraction: Decimal) -> Self {
+        self.clip_variance = fraction;
+        self
+    }
+
+    /// Rest each clip up to this many ticks behind the limit, at random
+    pub fn price_offset(mut self, ticks: u32) -> Self {
+        self.price_offset = ticks;
+        self
+    }
+
+    /// Send the clips reduce-only. The clips take the order's side, size,
+    /// price and time in force; its other settings don't carry over.
+    pub fn reduce_only(mut self) -> Self {
+        self.reduce_only = true;
+        self
+    }
+
+    /// Seed of the randomization, for clips that repeat from run to run
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Check the order against `market` and round it onto its grid
+    pub fn build(&self, market: &Market) -> Result<IcebergOrder> {
+        let order = &self.order;
+        let tif = order.get_tif();
+        if order.is_market() || !matches!(tif, TIF::Gtc | TIF::Alo) {
+            return Err(Error::ValidationError(format!("An iceberg rests a gtc() or alo() order, not {}", tif)));
+        }
+        let (Some(size), Some(px)) = (order.get_size(), order.get_price()) else {
+            return Err(Error::ValidationError("An iceberg needs a size and a price".to_string()));
+        };
+        // `Order::price`/`size` keep the f64's binary expansion (95000.1 is 95000.09999...)
+        let (size, px) = (size.round_dp(10), px.round_dp(10));
+        let is_buy = order.get_side().is_buy();
+        let precision = market.precision();
+        // Rounded away from the market, like any resting order
+        let px = precision
+            .px(px, if is_buy { Rounding::Down } else { Rounding::Up })?
+            .value();
+        let size = precision.sz(size, Rounding::Down)?.value();
+        let clip = precision.sz(self.clip, Rounding::Down)?.value().min(size);
+        if clip.is_zero() {
+            return Err(Error::ValidationError(format!("Clip {} rounds down to nothing", self.clip)));
+        }
+        if clip * px < MIN_NOTIONAL {
+            return Err(Error::ValidationError(format!(
+                "A clip of {} at {} is below the ${} minimum",
+                clip, px, MIN_NOTIONAL
+            )));
+        }
+        if self.clip_variance < Decimal::ZERO || self.clip_variance >= Decimal::ONE {
+            return Err(Error::ValidationError(format!(
+                "Clip variance must be at least 0 and below 1: {}",
+                self.clip_variance
+            )));
+        }
+        let seed = self.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or_default()
+        });
+        Ok(IcebergOrder {
+            coin: market.symbol.clone(),
+            asset_id: market.asset_id,
+            precision,
+            tif: if matches!(tif, TIF::Alo) { "Alo" } else { "Gtc" },
+            reduce_only: self.reduce_only,
+            is_buy,
+            size,
+            px,
+            clip,
+            clip_variance: self.clip_variance,
+            price_offset: self.price_offset,
+            rng: seed | 1,
+            clips: Vec::new(),
+            seen: HashSet::new(),
+            status: IcebergStatus::Working,
+        })
+    }
+
+    /// Build the iceberg and rest its first clip
+    pub async fn place(&self, config: &Config, sdk: &HyperliquidSDK) -> Result<IcebergOrder> {
+        let registry = AssetRegistry::load(sdk).await?;
+        let mut iceberg = self.build(registry.resolve(self.order.get_asset())?)?;
+        iceberg.replenish(config).await?;
+        Ok(iceberg)
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// IcebergOrder
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Where a clip stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipState {
+    Resting,
+    Filled,
+    Cancelled,
+}
+
+/// One visible part of an iceberg
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clip {
+    /// 1-based
+    pub n: usize,
+    pub cloid: String,
+    pub oid: Option<u64>,
+    pub px: Decimal,
+    pub size: Decimal,
+    pub filled: Decimal,
+    /// Σ px × size of the fills
+    pub notional: Decimal,
+    pub state: ClipState,
+}
+
+impl Clip {
+    /// Record `sz` more filled at `px`, up to the clip's size; returns what was added
+    fn fill(&mut self, sz: Decimal, px: Decimal) -> Decimal {
+        let sz = sz.min(self.size - self.filled).max(Decimal::ZERO);
+        self.filled += sz;
+        self.notional += sz * px;
+        if self.filled >= self.size {
+            self.state = ClipState::Filled;
+        }
+        sz
+    }
+}
+
+impl fmt::Display for Clip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Clip #{}: {} @ {}", self.n, self.size, self.px)?;
+        match self.state {
+            ClipState::Resting if self.filled.is_zero() => f.write_str(" resting")?,
+            ClipState::Resting => write!(f, " resting, {} filled", self.filled)?,
+            ClipState::Filled => f.write_str(" filled")?,
+            ClipState::Cancelled => write!(f, " cancelled, {} filled", self.filled)?,
+        }
+        match self.oid {
+            Some(oid) => write!(f, " (oid {})", oid),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IcebergStatus {
+    /// A clip rests, or the next one is due
+    Working,
+    Filled,
+    /// Cancelled by us, or a clip was cancelled from outside
+    Cancelled,
+}
+
+impl fmt::Display for IcebergStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            IcebergStatus::Working => "working",
+            IcebergStatus::Filled => "filled",
+            IcebergStatus::Cancelled => "cancelled",
+        })
+    }
+}
+
+/// An iceberg being worked, one clip at a time
+#[derive(Debug, Clone)]
+pub struct IcebergOrder {
+    pub coin: String,
+    asset_id: u32,
+    precision: Precision,
+    /// Time in force of the clips, as sent
+    tif: &'static str,
+    reduce_only: bool,
+    pub is_buy: bool,
+    pub size: Decimal,
+    /// Limit price (clips rest here or behind it)
+    pub px: Decimal,
+    pub clip: Decimal,
+    pub clip_variance: Decimal,
+    pub price_offset: u32,
+    rng: u64,
+    pub clips: Vec<Clip>,
+    /// Trade ids of the fills counted
+    seen: HashSet<u64>,
+    pub status: IcebergStatus,
+}
+
+impl IcebergOrder {
+    pub fn is_working(&self) -> bool {
+        self.status == IcebergStatus::Working
+    }
+
+    pub fn filled(&self) -> Decimal {
+        self.clips.iter().map(|c| c.filled).sum()
+    }
+
+    pub fn remaining(&self) -> Decimal {
+        self.size - self.filled()
+    }
+
+    pub fn avg_px(&self) -> Option<Decimal> {
+        let filled = self.filled();
+        if filled.is_zero() {
+            return None;
+        }
+        let notional: Decimal = self.clips.iter().map(|c| c.notional).sum();
+        Some((notional / filled).round_dp(8).normalize())
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            coin: self.coin.clone(),
+            is_buy: self.is_buy,
+            size: self.size,
+            filled: self.filled(),
+            avg_px: self.avg_px(),
+            children: self.clips.len(),
+            status: self.status.to_string(),
+        }
+    }
+
+    /// The clip on the book, if any
+    pub fn resting(&self) -> Option<&Clip> {
+        self.clips.iter().find(|c| c.state == ClipState::Resting)
+    }
+
+    /// The next clip to show, while none rests: the clip size ± the
+    /// variance (the rest of the size when less, or when what would be left
+    /// is under the $10 minimum), up to the offset behind the limit
+    pub fn next_clip(&mut self) -> Option<Clip> {
+        if !self.is_working() || self.resting().is_some() {
+            return None;
+        }
+        let remaining = self.remaining();
+        if remaining <= Decimal::ZERO {
+            return None;
+        }
+        let jitter = self.clip_variance * (Decimal::TWO * self.random() - Decimal::ONE);
+        let mut size = self
+            .precision
+            .sz(self.clip * (Decimal::ONE + jitter), Rounding::Down)
+            .map(|s| s.value())
+            .unwrap_or(self.clip)
+            .max(self.precision.lot());
+        if size * self.px < MIN_NOTIONAL {
+            size = self.clip;
+        }
+        if (remaining - size) * self.px < MIN_NOTIONAL {
+            size = remaining;
+        }
+        let size = size.min(remaining);
+
+        let ticks = (self.random() * Decimal::from(self.price_offset + 1)).floor().min(Decimal::from(self.price_offset));
+        let offset = ticks * self.precision.tick(self.px);
+        let px = if self.is_buy { self.px - offset } else { self.px + offset };
+        // Ticks widen past a power of ten: 9.9999 + 3 ticks goes to 10.001
+        let px = self
+            .precision
+            .px(px, if self.is_buy { Rounding::Down } else { Rounding::Up })
+            .map(|px| px.value())
+            .unwrap_or(self.px);
+        Some(Clip {
+            n: self.clips.len() + 1,
+            cloid: exchange::cloid(),
+            oid: None,
+            px,
+            size,
+            filled: Decimal::ZERO,
+            notional: Decimal::ZERO,
+            state: ClipState::Resting,
+        })
+    }
+
+    /// The `order` action resting `clip`
+    pub fn clip_action(&self, clip: &Clip) -> Value {
+        json!({
+            "type": "order",
+            "orders": [{
+                "a": self.asset_id,
+                "b": self.is_buy,
+                "p": clip.px.normalize().to_string(),
+                "s": clip.size.normalize().to_string(),
+                "r": self.reduce_only,
+                "t": {"limit": {"tif": self.tif}},
+                "c": clip.cloid,
+            }],
+            "grouping": "na",
+        })
+    }
+
+    /// Record `clip` with the exchange's `response` to its action
+    pub fn sent(&mut self, mut clip: Clip, response: &Value) -> Result<Clip> {
+        match OrderStatus::first(response) {
+            OrderStatus::Resting { oid } => clip.oid = Some(oid),
+            // A gtc() clip that crossed
+            OrderStatus::Filled { oid, total_sz, avg_px } => {
+                clip.oid = Some(oid);
+                clip.fill(total_sz, avg_px);
+            }
+            OrderStatus::Error(reason) => {
+                return Err(Error::ValidationError(format!("Clip #{} rejected: {}", clip.n, reason)));
+            }
+            status => return Err(Error::ValidationError(format!("Clip #{}: unexpected status {:?}", clip.n, status))),
+        }
+        self.clips.push(clip.clone());
+        self.settle();
+        Ok(clip)
+    }
+
+    /// Rest the next clip once the last one has filled; `None` while one rests
+    pub async fn replenish(&mut self, config: &Config) -> Result<Option<Clip>> {
+        let Some(clip) = self.next_clip() else {
+            return Ok(None);
+        };
+        let response = exchange::send(config, &self.clip_action(&clip), None).await?;
+        self.sent(clip, &response).map(Some)
+    }
+
+    /// Apply a `userFills` or `orderUpdates` event of one of the clips;
+    /// returns whether anything changed
+    pub fn on_event(&mut self, event: &MarketEvent) -> bool {
+        match event {
+            MarketEvent::Fill(fill) => self.on_fill(fill),
+            MarketEvent::OrderUpdate(update) => self.on_update(update),
+            _ => false,
+        }
+    }
+
+    fn on_fill(&mut self, fill: &Fill) -> bool {
+        let Some(clip) = self.clip_mut(fill.oid, fill.cloid.as_deref()) else {
+            return false;
+        };
+        let clip_n = clip.n;
+        if !self.seen.insert(fill.tid) {
+            return false;
+        }
+        let clip = &mut self.clips[clip_n - 1];
+        let added = clip.fill(fill.sz, fill.px);
+        self.settle();
+        !added.is_zero()
+    }
+
+    /// Fill a clip to what `update` reports filled (a resting clip fills at
+    /// its own price); a clip cancelled from outside stops the iceberg
+    fn on_update(&mut self, update: &OrderUpdate) -> bool {
+        let order = &update.order;
+        let Some(clip) = self.clip_mut(order.oid, order.cloid.as_deref()) else {
+            return false;
+        };
+        clip.oid = Some(order.oid);
+        let filled = match update.status.as_str() {
+            "filled" => order.orig_sz,
+            _ => order.orig_sz - order.sz,
+        };
+        let px = clip.px;
+        let mut changed = !clip.fill(filled - clip.filled, px).is_zero();
+        if !matches!(update.status.as_str(), "open" | "filled") && clip.state == ClipState::Resting {
+            clip.state = ClipState::Cancelled;
+            self.status = IcebergStatus::Cancelled;
+            changed = true;
+        }
+        self.settle();
+        changed
+    }
+
+    /// Ask `sdk.order_status` about the resting clip, for fills the stream missed
+    pub async fn sync(&mut self, sdk: &HyperliquidSDK) -> Result<bool> {
+        let Some(oid) = self.resting().and_then(|c| c.oid) else {
+            return Ok(false);
+        };
+        let response = sdk.order_status(oid, None).await?;
+        match response.get("order") {
+            Some(order) => {
+                let update: OrderUpdate = serde_json::from_value(order.clone())
+                    .map_err(|e| Error::ValidationError(format!("orderStatus of {} unreadable: {}", oid, e)))?;
+                Ok(self.on_update(&update))
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Cancel the resting clip and stop
+    pub async fn cancel(&mut self, config: &Config) -> Result<()> {
+        if let Some(oid) = self.resting().and_then(|c| c.oid) {
+            let response = exchange::send(config, &actions::cancel(&[(self.asset_id, oid)]), None).await?;
+            if let Some(reason) = rejection(&response) {
+                // Likely filled meanwhile: keep the iceberg as it is
+                return Err(Error::ValidationError(format!("Cancel of clip {} rejected: {}", oid, reason)));
+            }
+            if let Some(clip) = self.clips.iter_mut().find(|c| c.oid == Some(oid)) {
+                clip.state = ClipState::Cancelled;
+            }
+        }
+        if self.is_working() {
+            self.status = IcebergStatus::Cancelled;
+        }
+        Ok(())
+    }
+
+    /// Subscribe `stream` to `user`'s `userFills` and `orderUpdates`; the
+    /// events (or the items that failed to decode) arrive on the returned
+    /// channel once the stream is started
+    pub fn watch(&self, stream: &mut Stream, user: &str) -> UnboundedReceiver<Result<MarketEvent>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        stream.user_fills(user, feed::forward("userFills", &tx));
+        stream.order_updates(user, feed::forward("orderUpdates", &tx));
+        rx
+    }
+
+    fn clip_mut(&mut self, oid: u64, cloid: Option<&str>) -> Option<&mut Clip> {
+        self.clips.iter_mut().find(|c| {
+            c.oid == Some(oid) || cloid.is_some_and(|cloid| cloid.eq_ignore_ascii_case(&c.cloid))
+        })
+    }
+
+    fn settle(&mut self) {
+        if self.is_working() && self.remaining() <= Decimal::ZERO {
+            self.status = IcebergStatus::Filled;
+        }
+    }
+
+    /// Uniform in [0, 1) (xorshift64*)
+    fn random(&mut self) -> Decimal {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        let bits = self.rng.wrapping_mul(0x2545f4914f6cdd1d) >> 11;
+        Decimal::from_f64_retain(bits as f64 / (1u64 << 53) as f64).unwrap_or_default().round_dp(6)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::testing::{btc, dec};
+    use crate::mock::{MatchingEngine, Script};
+    use crate::models::{Side, Trade};
+
+    fn sell_through(engine: &mut MatchingEngine, px: &str, sz: &str) {
+        engine.on_trade(&Trade {
+            coin: "BTC".to_string(),
+            side: Side::Sell,
+            px: dec(px),
+            sz: dec(sz),
+            time: engine.now(),
+            hash: String::new(),
+            tid: 0,
+            users: None,
+        });
+    }
+
+    #[test]
+    fn clips_behind_the_limit_stay_on_the_tick_past_a_power_of_ten() {
+        let engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let sol = engine.registry().resolve("SOL").unwrap().clone();
+        let precision = sol.precision();
+        let mut order = Iceberg::new(Order::sell("SOL").size(40.0).price(9.9999).alo(), dec("2"))
+            .price_offset(3)
+            .seed(7)
+            .build(&sol)
+            .unwrap();
+        let (mut prices, mut sent) = (HashSet::new(), Vec::new());
+        while let Some(mut clip) = order.next_clip() {
+            assert!(precision.is_valid_px(clip.px), "{}", clip);
+            assert!(clip.px >= dec("9.9999") && clip.px <= dec("10.001"), "{}", clip);
+            prices.insert(clip.px);
+            sent.push(order.clip_action(&clip)["orders"][0]["p"].clone());
+            clip.fill(clip.size, clip.px);
+            order.clips.push(clip);
+            order.settle();
+        }
+        // 10.0001 and 10.0002 are a tick too fine: both go up to 10.001
+        assert!(prices.contains(&dec("10.001")), "{:?}", prices);
+        assert!(sent.contains(&json!("10.001")), "{:?}", sent);
+    }
+
+    #[test]
+    fn clips_vary_around_the_clip_and_rest_behind_the_limit() {
+        let engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let iceberg = Iceberg::new(Order::buy("BTC").size(0.1).price(96000.0).alo(), dec("0.02"))
+            .clip_variance(dec("0.2"))
+            .price_offset(3)
+            .seed(7);
+        let clips = |iceberg: &Iceberg| {
+            let mut order = iceberg.build(&btc(&engine)).unwrap();
+            let mut clips = Vec::new();
+            while let Some(mut clip) = order.next_clip() {
+                assert!(clip.px <= dec("96000") && clip.px >= dec("95997"), "{}", clip);
+                clip.fill(clip.size, clip.px);
+                order.clips.push(clip.clone());
+                order.settle();
+                clips.push((clip.size, clip.px));
+            }
+            assert_eq!(order.status, IcebergStatus::Filled);
+            clips
+        };
+        let first = clips(&iceberg);
+        assert_eq!(first, clips(&iceberg));
+        assert_eq!(first.iter().map(|(size, _)| size).sum::<Decimal>(), dec("0.1"));
+        let (last, rest) = first.split_last().unwrap();
+        assert!(rest.iter().all(|(size, _)| *size >= dec("0.016") && *size <= dec("0.024")), "{:?}", first);
+        assert!(last.0 * last.1 >= MIN_NOTIONAL);
+        assert!(first.iter().any(|(size, _)| *size != dec("0.02")), "{:?}", first);
+
+        let mut order = iceberg.build(&btc(&engine)).unwrap();
+        let clip = order.next_clip().unwrap();
+        let action = order.clip_action(&clip);
+        assert_eq!(action["orders"][0]["t"], json!({"limit": {"tif": "Alo"}}));
+        assert_eq!(action["orders"][0]["c"], clip.cloid);
+        let mut plain = Iceberg::new(Order::buy("BTC").size(0.1).price(96000.0).gtc(), dec("0.02"))
+            .reduce_only()
+            .build(&btc(&engine))
+            .unwrap();
+        let clip = plain.next_clip().unwrap();
+        let order = &plain.clip_action(&clip)["orders"][0];
+        assert_eq!((&order["p"], &order["s"], &order["r"]), (&json!("96000"), &json!("0.02"), &json!(true)));
+        assert_eq!(order["t"], json!({"limit": {"tif": "Gtc"}}));
+
+        let ioc = Iceberg::new(Order::buy("BTC").size(0.1).price(96000.0).ioc(), dec("0.02"));
+        assert!(ioc.build(&btc(&engine)).is_err());
+        let tiny = Iceberg::new(Order::buy("BTC").size(0.1).price(96000.0).gtc(), dec("0.0001"));
+        assert!(tiny.build(&btc(&engine)).is_err());
+    }
+
+    #[test]
+    fn fills_count_once_whichever_way_they_arrive() {
+        let mut engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let mut order = Iceberg::new(Order::sell("BTC").size_decimal(dec("0.0021")).price(96300.0).gtc(), dec("0.002"))
+            .build(&btc(&engine))
+            .unwrap();
+
+        let clip = order.next_clip().unwrap();
+        // What would be left is under $10: the whole size goes in one clip
+        assert_eq!(clip.size, dec("0.0021"));
+        let clip = order.sent(clip.clone(), &engine.execute(&order.clip_action(&clip))).unwrap();
+        let oid = clip.oid.unwrap();
+        assert_eq!(order.next_clip(), None);
+
+        engine.on_trade(&Trade {
+            coin: "BTC".to_string(),
+            side: Side::Buy,
+            px: dec("96301"),
+            sz: dec("0.001"),
+            time: engine.now(),
+            hash: String::new(),
+            tid: 0,
+            users: None,
+        });
+        let fill = MarketEvent::Fill(engine.user_fills().pop().unwrap());
+        assert!(order.on_event(&fill));
+        assert!(!order.on_event(&fill));
+        let update: OrderUpdate = serde_json::from_value(engine.order_status(oid)["order"].clone()).unwrap();
+        assert!(!order.on_event(&MarketEvent::OrderUpdate(update)));
+        assert_eq!(
+            order.progress().to_string(),
+            "Sell 0.001/0.0021 BTC (47.6%), avg 96300, 1 child orders, working"
+        );
+
+        // Cancelled from outside: the iceberg stops
+        let mut update: OrderUpdate = serde_json::from_value(engine.order_status(oid)["order"].clone()).unwrap();
+        update.status = "canceled".to_string();
+        assert!(order.on_event(&MarketEvent::OrderUpdate(update)));
+        assert_eq!(order.status, IcebergStatus::Cancelled);
+        assert_eq!(order.next_clip(), None);
+    }
+
+    #[test]
+    fn the_next_clip_follows_a_filled_one() {
+        let mut engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let mut order = Iceberg::new(Order::buy("BTC").size(0.002).price(96000.0).alo(), dec("0.001"))
+            .build(&btc(&engine))
+            .unwrap();
+        for n in 1..=2 {
+            let clip = order.next_clip().unwrap();
+            let clip = order.sent(clip.clone(), &engine.execute(&order.clip_action(&clip))).unwrap();
+            assert_eq!((clip.n, clip.size, clip.px), (n, dec("0.001"), dec("96000")));
+            sell_through(&mut engine, "95999", "0.001");
+            let update: OrderUpdate =
+                serde_json::from_value(engine.order_status(clip.oid.unwrap())["order"].clone()).unwrap();
+            assert!(order.on_event(&MarketEvent::OrderUpdate(update)));
+        }
+        assert_eq!(order.status, IcebergStatus::Filled);
+        assert_eq!((order.filled(), order.avg_px()), (dec("0.002"), Some(dec("96000"))));
+        assert_eq!(order.next_clip(), None);
+    }
+}
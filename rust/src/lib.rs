@@ -0,0 +1,46 @@
+//! Shared helpers for the Hyperliquid SDK examples.
+//!
+//! Every binary in this crate gets its endpoint and signing key from
+//! [`config`], so switching between mainnet, testnet and named profiles works
+//! the same way everywhere:
+//!
+//! ```no_run
+//! use hyperliquid_examples::{connect, Requires};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let sdk = connect("place_order", Requires::Signer).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod account_metrics;
+pub mod accounts;
+pub mod bars;
+pub mod bracket;
+pub mod candles;
+pub mod cli;
+pub mod config;
+pub mod dry_run;
+pub mod error;
+pub mod exchange;
+pub mod execution;
+pub mod export;
+pub mod feed;
+pub mod health;
+pub mod iceberg;
+pub mod metrics;
+pub mod mock;
+pub mod models;
+pub mod oco;
+pub mod orderbook;
+pub mod paper;
+pub mod price;
+pub mod recorder;
+pub mod registry;
+pub mod replay;
+pub mod trailing;
+
+pub use config::{connect, prepare, redact_endpoint, stream_duration, Config, Network, Requires};
+pub use error::{Error, Result};
+pub use price::{Precision, Px, Rounding, Sz};
+pub use registry::{AssetRegistry, Market, MarketKind};
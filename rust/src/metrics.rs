@@ -0,0 +1,81 @@
+//! Prometheus text format and the `/metrics` endpoint.
+//!
+//! Shared by [`crate::health`] and [`crate::account_metrics`]; each renders its
+//! own metrics with these helpers and serves them with [`serve`].
+
+use std::fmt::{self, Write};
+use std::net::SocketAddr;
+
+use axum::http::header;
+use axum::routing::get;
+use axum::Router;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use crate::error::Result;
+
+/// Content type of the text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// A running `/metrics` endpoint; stops when dropped
+pub struct MetricsServer {
+    addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl MetricsServer {
+    /// Address the endpoint listens on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Serve `render()` at `http://addr/metrics` until the returned server is
+/// dropped (port 0 picks a free port)
+pub async fn serve<F>(addr: SocketAddr, render: F) -> Result<MetricsServer>
+where
+    F: Fn() -> String + Clone + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(addr).await?;
+    let addr = listener.local_addr()?;
+    let app = Router::new().route(
+        "/metrics",
+        get(move || {
+            let render = render.clone();
+            async move { ([(header::CONTENT_TYPE, CONTENT_TYPE)], render()) }
+        }),
+    );
+    let task = tokio::spawn(async move {
+        let _ = axum::serve(listener, app).await;
+    });
+    Ok(MetricsServer { addr, task })
+}
+
+/// `name="value",...` with the values escaped
+pub(crate) fn labels(pairs: &[(&str, &str)]) -> String {
+    let mut labels = String::new();
+    for (i, (name, value)) in pairs.iter().enumerate() {
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+        let _ = write!(labels, "{}{}=\"{}\"", if i == 0 { "" } else { "," }, name, value);
+    }
+    labels
+}
+
+/// `# HELP` and `# TYPE` lines of a metric
+pub(crate) fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind);
+}
+
+/// One sample; `labels` from [`labels`], empty for none
+pub(crate) fn sample(out: &mut String, name: &str, labels: &str, value: impl fmt::Display) {
+    let _ = match labels.is_empty() {
+        true => writeln!(out, "{} {}", name, value),
+        false => writeln!(out, "{}{{{}}} {}", name, labels, value),
+    };
+}
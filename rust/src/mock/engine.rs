@@ -0,0 +1,1590 @@
+//! Deterministic matching engine behind the mock `/exchange` endpoint.
+//!
+//! Orders from the scripted account match against the seeded books (and its
+//! own resting orders) with price-time priority. Order ids, trade ids, hashes
+//! and timestamps all come from counters, so the same requests always get the
+//! same responses. Fills use the base fee tier (0.045% taker, 0.015% maker)
+//! and every market, spot included, is booked as a one-way position.
+//!
+//! Orders are checked the way the exchange checks them: tick and lot size
+//! (via [`Precision`](crate::Precision)), the $10 minimum, reduce-only
+//! direction and post-only crossing, with the exchange's error strings.
+//!
+//! The engine can also follow a live market: [`MatchingEngine::set_book`]
+//! swaps in a book snapshot and [`MatchingEngine::on_trade`] replays a public
+//! trade. Both fill our resting orders the market went through and fire
+//! trigger orders whose price was reached.
+//!
+//! Order groupings work like the exchange's TP/SL attachments: with
+//! `normalTpsl` the trigger orders after the entry wait for it to fill and
+//! are cancelled with it, and `positionTpsl` triggers close the whole
+//! position and are cancelled when it closes.
+
+use std::collections::{BTreeMap, HashMap};
+
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use crate::error::Result;
+use crate::models::{
+    AssetPosition, ClearinghouseState, CumFunding, Fill, L2Book, Level, Leverage,
+    MarginSummary, OpenOrder, OrderUpdate, Position, Side, Trade, TwapHistory, TwapSliceFill, TwapState,
+    TwapStatus,
+};
+use crate::registry::{AssetRegistry, Market};
+
+use super::script::Script;
+
+/// Clock start when the script does not set one
+pub const DEFAULT_TIME: u64 = 1_737_412_345_000;
+
+/// Account that owns the seeded liquidity
+pub const MAKER_ADDRESS: &str = "0x0000000000000000000000000000000000000001";
+
+const FIRST_OID: u64 = 1_000_001;
+const FIRST_TID: u64 = 7_000_001;
+const DEFAULT_LEVERAGE: u32 = 20;
+const MIN_NOTIONAL: Decimal = Decimal::from_parts(10, 0, 0, false, 0);
+const TAKER_FEE: Decimal = Decimal::from_parts(45, 0, 0, false, 5);
+const MAKER_FEE: Decimal = Decimal::from_parts(15, 0, 0, false, 5);
+const ZERO_CLOID: &str = "0x00000000000000000000000000000000";
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Orders
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Time in force of a limit order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tif {
+    Gtc,
+    Ioc,
+    Alo,
+}
+
+/// Trigger of a stop-loss / take-profit order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    pub px: Decimal,
+    pub is_market: bool,
+    /// `tp` or `sl`
+    pub tpsl: String,
+}
+
+impl Trigger {
+    /// Has the market reached this trigger for an order on `side`?
+    ///
+    /// Stop-losses on sells and take-profits on buys fire at or below the
+    /// trigger price, the other two at or above it.
+    fn fires(&self, side: Side, px: Decimal) -> bool {
+        let below = matches!((side, self.tpsl.as_str()), (Side::Sell, "sl") | (Side::Buy, "tp"));
+        if below {
+            px <= self.px
+        } else {
+            px >= self.px
+        }
+    }
+}
+
+/// An order as sent in `order` and `modify` actions
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRequest {
+    pub asset: u32,
+    pub side: Side,
+    pub px: Decimal,
+    pub sz: Decimal,
+    pub reduce_only: bool,
+    pub tif: Tif,
+    pub trigger: Option<Trigger>,
+    pub cloid: Option<String>,
+}
+
+impl OrderRequest {
+    /// Parse the wire form (`{"a", "b", "p", "s", "r", "t", "c"}`)
+    pub fn from_wire(order: &Value) -> std::result::Result<Self, String> {
+        let field = |key: &str| order.get(key).ok_or_else(|| format!("Order is missing '{}'", key));
+        let decimal = |key: &str| -> std::result::Result<Decimal, String> {
+            field(key)?
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| format!("Order has invalid '{}'", key))
+        };
+
+        let asset = field("a")?.as_u64().ok_or("Order has invalid 'a'")? as u32;
+        let side = if field("b")?.as_bool().ok_or("Order has invalid 'b'")? {
+            Side::Buy
+        } else {
+            Side::Sell
+        };
+        let kind = field("t")?;
+        let (tif, trigger) = if let Some(limit) = kind.get("limit") {
+            let tif = match limit.get("tif").and_then(|t| t.as_str()) {
+                Some("Gtc") => Tif::Gtc,
+                Some("Ioc") => Tif::Ioc,
+                Some("Alo") => Tif::Alo,
+                other => return Err(format!("Invalid TIF {:?}", other.unwrap_or_default())),
+            };
+            (tif, None)
+        } else if let Some(trigger) = kind.get("trigger") {
+            let px = trigger
+                .get("triggerPx")
+                .and_then(|p| p.as_str())
+                .and_then(|p| p.parse().ok())
+                .ok_or("Order has invalid 'triggerPx'")?;
+            let trigger = Trigger {
+                px,
+                is_market: trigger.get("isMarket").and_then(|m| m.as_bool()).unwrap_or(true),
+                tpsl: trigger.get("tpsl").and_then(|t| t.as_str()).unwrap_or("sl").to_string(),
+            };
+            (Tif::Gtc, Some(trigger))
+        } else {
+            return Err("Order type must be limit or trigger".to_string());
+        };
+
+        Ok(OrderRequest {
+            asset,
+            side,
+            px: decimal("p")?,
+            sz: decimal("s")?,
+            reduce_only: order.get("r").and_then(|r| r.as_bool()).unwrap_or(false),
+            tif,
+            trigger,
+            cloid: order
+                .get("c")
+                .and_then(|c| c.as_str())
+                .filter(|c| *c != ZERO_CLOID)
+                .map(str::to_string),
+        })
+    }
+}
+
+/// Order resting on a book or waiting for its trigger
+#[derive(Debug, Clone)]
+struct Resting {
+    oid: u64,
+    coin: String,
+    side: Side,
+    px: Decimal,
+    sz: Decimal,
+    orig_sz: Decimal,
+    time: u64,
+    cloid: Option<String>,
+    reduce_only: bool,
+    tif: Tif,
+    trigger: Option<Trigger>,
+    /// Placed by the scripted account (not seeded liquidity)
+    ours: bool,
+    /// `normalTpsl` child waiting for this entry order to fill
+    parent: Option<u64>,
+    /// `positionTpsl` trigger: sized to the whole position when it fires
+    position_tpsl: bool,
+}
+
+impl Resting {
+    fn to_open_order(&self) -> OpenOrder {
+        let order_type = match &self.trigger {
+            None => "Limit",
+            Some(t) => match (t.tpsl.as_str(), t.is_market) {
+                ("tp", true) => "Take Profit Market",
+                ("tp", false) => "Take Profit Limit",
+                (_, true) => "Stop Market",
+                (_, false) => "Stop Limit",
+            },
+        };
+        OpenOrder {
+            coin: self.coin.clone(),
+            side: self.side,
+            limit_px: self.px,
+            sz: self.sz,
+            oid: self.oid,
+            timestamp: self.time,
+            orig_sz: self.orig_sz,
+            cloid: self.cloid.clone(),
+            order_type: Some(order_type.to_string()),
+            tif: self.trigger.is_none().then(|| format!("{:?}", self.tif)),
+            reduce_only: Some(self.reduce_only),
+            is_trigger: Some(self.trigger.is_some()),
+            trigger_px: self.trigger.as_ref().map(|t| t.px),
+            is_position_tpsl: Some(self.position_tpsl),
+        }
+    }
+}
+
+/// One coin's resting orders, best price first on each side
+#[derive(Debug, Clone, Default)]
+struct Book {
+    bids: Vec<Resting>,
+    asks: Vec<Resting>,
+}
+
+impl Book {
+    fn side(&self, side: Side) -> &Vec<Resting> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    fn side_mut(&mut self, side: Side) -> &mut Vec<Resting> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    /// Rest behind every order at the same or a better price
+    fn insert(&mut self, order: Resting) {
+        let orders = self.side_mut(order.side);
+        let at = orders
+            .iter()
+            .position(|o| match order.side {
+                Side::Buy => o.px < order.px,
+                Side::Sell => o.px > order.px,
+            })
+            .unwrap_or(orders.len());
+        orders.insert(at, order);
+    }
+
+    fn best(&self, side: Side) -> Option<Decimal> {
+        self.side(side).first().map(|o| o.px)
+    }
+
+    /// Take up to `max` of the outside orders on `side` that a `limit` order
+    /// from the other side would cross; returns the size taken
+    fn take_outside(&mut self, side: Side, limit: Decimal, max: Decimal) -> Decimal {
+        let mut taken = Decimal::ZERO;
+        let orders = self.side_mut(side);
+        for order in orders.iter_mut().filter(|o| !o.ours) {
+            if taken == max || !crosses(opposite(side), limit, order.px) {
+                break;
+            }
+            let sz = order.sz.min(max - taken);
+            order.sz -= sz;
+            taken += sz;
+        }
+        orders.retain(|o| !o.sz.is_zero());
+        taken
+    }
+
+    fn mid(&self) -> Option<Decimal> {
+        Some((self.best(Side::Buy)? + self.best(Side::Sell)?) / Decimal::TWO)
+    }
+
+    fn levels(&self, side: Side) -> Vec<Level> {
+        let mut levels: Vec<Level> = Vec::new();
+        for order in self.side(side) {
+            match levels.last_mut() {
+                Some(level) if level.px == order.px => {
+                    level.sz += order.sz;
+                    level.n += 1;
+                }
+                _ => levels.push(Level {
+                    px: order.px,
+                    sz: order.sz,
+                    n: 1,
+                }),
+            }
+        }
+        levels
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Engine
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Something the WebSocket feeds should push
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Trade(Trade),
+    Fill(Fill),
+    OrderUpdate(OrderUpdate),
+    /// The book of this coin changed
+    Book(String),
+}
+
+#[derive(Debug, Clone, Default)]
+struct PositionState {
+    szi: Decimal,
+    entry_px: Decimal,
+}
+
+/// Order book, positions and history of the mock exchange
+#[derive(Debug)]
+pub struct MatchingEngine {
+    registry: AssetRegistry,
+    address: String,
+    books: BTreeMap<String, Book>,
+    triggers: Vec<Resting>,
+    mids: BTreeMap<String, Decimal>,
+    history: HashMap<u64, OrderUpdate>,
+    positions: BTreeMap<String, PositionState>,
+    leverage: HashMap<String, (u32, bool)>,
+    usdc: Decimal,
+    builder_fee: Option<String>,
+    fills: Vec<Fill>,
+    tape: Vec<Trade>,
+    events: Vec<Event>,
+    clock: u64,
+    next_oid: u64,
+    next_tid: u64,
+    next_twap: u64,
+    twaps: Vec<TwapHistory>,
+}
+
+impl MatchingEngine {
+    /// Engine seeded from a script's books, mids and account
+    pub fn new(script: &Script) -> Result<Self> {
+        let registry = AssetRegistry::from_snapshot(&script.snapshot()?);
+        let account = script.account.clone().unwrap_or_default();
+        let mut engine = Self::with_registry(
+            registry,
+            account.address.as_deref().unwrap_or(MAKER_ADDRESS),
+            account.usdc.unwrap_or_default(),
+            script.time.unwrap_or(DEFAULT_TIME),
+        );
+        engine.mids = script.mids.clone();
+
+        for (coin, seed) in &script.books {
+            let book = engine.books.entry(coin.clone()).or_default();
+            for (side, levels) in [(Side::Buy, &seed.bids), (Side::Sell, &seed.asks)] {
+                for level in levels {
+                    book.insert(Resting {
+                        oid: engine.next_oid,
+                        coin: coin.clone(),
+                        side,
+                        px: level.0,
+                        sz: level.1,
+                        orig_sz: level.1,
+                        time: engine.clock,
+                        cloid: None,
+                        reduce_only: false,
+                        tif: Tif::Gtc,
+                        trigger: None,
+                        ours: false,
+                        parent: None,
+                        position_tpsl: false,
+                    });
+                    engine.next_oid += 1;
+                }
+            }
+        }
+        Ok(engine)
+    }
+
+    /// Engine with empty books for `registry`'s markets, trading as `address`
+    /// with `usdc` of collateral, its clock starting at `time` (ms)
+    pub fn with_registry(registry: AssetRegistry, address: &str, usdc: Decimal, time: u64) -> Self {
+        MatchingEngine {
+            registry,
+            address: address.to_string(),
+            books: BTreeMap::new(),
+            triggers: Vec::new(),
+            mids: BTreeMap::new(),
+            history: HashMap::new(),
+            positions: BTreeMap::new(),
+            leverage: HashMap::new(),
+            usdc,
+            builder_fee: None,
+            fills: Vec::new(),
+            tape: Vec::new(),
+            events: Vec::new(),
+            clock: time,
+            next_oid: FIRST_OID,
+            next_tid: FIRST_TID,
+            next_twap: 1,
+            twaps: Vec::new(),
+        }
+    }
+
+    /// Address of the scripted account
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Markets the engine accepts orders for
+    pub fn registry(&self) -> &AssetRegistry {
+        &self.registry
+    }
+
+    /// Current mock time (ms)
+    pub fn now(&self) -> u64 {
+        self.clock
+    }
+
+    /// Advance the clock by one millisecond and return it
+    pub fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Events since the last call
+    pub fn drain_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Actions
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Apply a signed `/exchange` action and build the response body
+    pub fn execute(&mut self, action: &Value) -> Value {
+        self.tick();
+        let kind = action.get("type").and_then(|t| t.as_str()).unwrap_or_default();
+        let result = match kind {
+            "order" => Ok(self.order_action(action)),
+            "cancel" => Ok(self.cancel_action(action, false)),
+            "cancelByCloid" => Ok(self.cancel_action(action, true)),
+            "modify" => Ok(self.modify_action(std::slice::from_ref(action))),
+            "batchModify" => Ok(self.modify_action(
+                action
+                    .get("modifies")
+                    .and_then(|m| m.as_array())
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+            )),
+            "updateLeverage" => self.update_leverage(action),
+            "twapOrder" => Ok(self.twap_order(action.get("twap").unwrap_or(&Value::Null))),
+            "twapCancel" => Ok(self.twap_cancel(action)),
+            "usdSend" | "withdraw3" => self.debit(action),
+            "approveBuilderFee" => {
+                self.builder_fee = action.get("maxFeeRate").and_then(|r| r.as_str()).map(str::to_string);
+                Ok(json!({"type": "default"}))
+            }
+            "scheduleCancel" | "updateIsolatedMargin" | "topUpIsolatedOnlyMargin"
+            | "usdClassTransfer" | "spotSend" | "sendAsset" | "vaultTransfer" | "cDeposit"
+            | "cWithdraw" | "tokenDelegate" | "approveAgent"
+            | "reserveRequestWeight" | "noop" | "userSetAbstraction"
+            | "agentSetAbstraction" | "validatorL1Stream" | "createSubAccount"
+            | "subAccountTransfer" | "setReferrer" => Ok(json!({"type": "default"})),
+            other => Err(format!("Unknown action type: {}", other)),
+        };
+        match result {
+            Ok(response) => json!({"status": "ok", "response": response}),
+            Err(message) => json!({"status": "err", "response": message}),
+        }
+    }
+
+    fn order_action(&mut self, action: &Value) -> Value {
+        let orders = action.get("orders").and_then(|o| o.as_array()).cloned().unwrap_or_default();
+        let grouping = action.get("grouping").and_then(|g| g.as_str()).unwrap_or("na");
+        // (entry oid, filled yet) of a normalTpsl group, once the entry is placed
+        let mut entry: Option<std::result::Result<(u64, bool), ()>> = None;
+        let statuses: Vec<Value> = orders
+            .iter()
+            .map(|order| {
+                let request = match OrderRequest::from_wire(order) {
+                    Ok(request) => request,
+                    Err(error) => return json!({"error": error}),
+                };
+                match (grouping, &entry) {
+                    ("normalTpsl", None) if request.trigger.is_none() => {
+                        let status = self.place(request);
+                        entry = Some(match (status.pointer("/resting/oid"), status.pointer("/filled/oid")) {
+                            (Some(oid), _) => Ok((oid.as_u64().unwrap_or_default(), self.has_filled(oid.as_u64()))),
+                            (_, Some(oid)) => Ok((oid.as_u64().unwrap_or_default(), true)),
+                            _ => Err(()),
+                        });
+                        status
+                    }
+                    ("normalTpsl", Some(Err(()))) => json!({"error": "Entry order was rejected"}),
+                    ("normalTpsl", Some(Ok((oid, false)))) if request.trigger.is_some() => {
+                        let oid = *oid;
+                        self.place_with(request, Some(oid), false)
+                    }
+                    ("positionTpsl", _) if request.trigger.is_some() => self.place_with(request, None, true),
+                    _ => self.place(request),
+                }
+            })
+            .collect();
+        json!({"type": "order", "data": {"statuses": statuses}})
+    }
+
+    /// Whether one of our orders has any fills
+    fn has_filled(&self, oid: Option<u64>) -> bool {
+        oid.is_some_and(|oid| self.fills.iter().any(|f| f.oid == oid))
+    }
+
+    fn cancel_action(&mut self, action: &Value, by_cloid: bool) -> Value {
+        let cancels = action.get("cancels").and_then(|c| c.as_array()).cloned().unwrap_or_default();
+        let statuses: Vec<Value> = cancels
+            .iter()
+            .map(|cancel| {
+                let asset = cancel
+                    .get("a")
+                    .or_else(|| cancel.get("asset"))
+                    .and_then(|a| a.as_u64())
+                    .unwrap_or(u64::MAX) as u32;
+                let found = if by_cloid {
+                    let cloid = cancel.get("cloid").and_then(|c| c.as_str()).unwrap_or_default();
+                    self.find(asset, |o| o.cloid.as_deref() == Some(cloid))
+                } else {
+                    let oid = cancel.get("o").and_then(|o| o.as_u64()).unwrap_or_default();
+                    self.find(asset, |o| o.oid == oid)
+                };
+                match found.and_then(|oid| self.remove(oid)) {
+                    Some(order) => {
+                        self.update(&order, "canceled");
+                        self.cancel_children(order.oid);
+                        self.events.push(Event::Book(order.coin));
+                        json!("success")
+                    }
+                    None => json!({
+                        "error": format!(
+                            "Order was never placed, already canceled, or filled. asset={}",
+                            asset
+                        )
+                    }),
+                }
+            })
+            .collect();
+        json!({"type": "cancel", "data": {"statuses": statuses}})
+    }
+
+    fn modify_action(&mut self, modifies: &[Value]) -> Value {
+        let statuses: Vec<Value> = modifies
+            .iter()
+            .map(|modify| {
+                let request = match modify.get("order").map(OrderRequest::from_wire) {
+                    Some(Ok(request)) => request,
+                    Some(Err(error)) => return json!({"error": error}),
+                    None => return json!({"error": "Modify is missing 'order'"}),
+                };
+                // By oid, or by cloid
+                let found = match modify.get("oid") {
+                    Some(Value::String(cloid)) => self.find(request.asset, |o| o.cloid.as_deref() == Some(cloid)),
+                    oid => {
+                        let oid = oid.and_then(|o| o.as_u64()).unwrap_or_default();
+                        self.find(request.asset, |o| o.oid == oid)
+                    }
+                };
+                let Some(old) = found.and_then(|oid| self.remove(oid)) else {
+                    return json!({"error": "Cannot modify canceled or filled order"});
+                };
+                if let Err(error) = self.check(&Self::attached(&request, old.parent, old.position_tpsl)) {
+                    // Put the order back untouched, as the exchange does
+                    self.rest(old);
+                    return json!({"error": error});
+                }
+                self.update(&old, "canceled");
+                self.place_with(request, old.parent, old.position_tpsl)
+            })
+            .collect();
+        json!({"type": "batchModify", "data": {"statuses": statuses}})
+    }
+
+    fn update_leverage(&mut self, action: &Value) -> std::result::Result<Value, String> {
+        let asset = action.get("asset").and_then(|a| a.as_u64()).unwrap_or(u64::MAX) as u32;
+        let market = self
+            .registry
+            .by_id(asset)
+            .ok_or_else(|| format!("Unknown asset {}", asset))?;
+        let leverage = action.get("leverage").and_then(|l| l.as_u64()).unwrap_or_default() as u32;
+        let is_cross = action.get("isCross").and_then(|c| c.as_bool()).unwrap_or(true);
+        if leverage == 0 || market.max_leverage.is_some_and(|max| leverage > max) {
+            return Err("Invalid leverage value".to_string());
+        }
+        if is_cross && market.only_isolated {
+            return Err("Cross margin is not allowed for this asset.".to_string());
+        }
+        self.leverage.insert(market.symbol.clone(), (leverage, is_cross));
+        Ok(json!({"type": "default"}))
+    }
+
+    /// Start a native TWAP; it stays `activated` (nothing executes) until cancelled
+    fn twap_order(&mut self, twap: &Value) -> Value {
+        let status = match self.twap_state(twap) {
+            Ok(state) => {
+                let twap_id = self.next_twap;
+                self.next_twap += 1;
+                self.twaps.push(TwapHistory {
+                    time: self.clock / 1000,
+                    state,
+                    status: TwapStatus { status: "activated".to_string(), description: None },
+                    twap_id: Some(twap_id),
+                });
+                json!({"running": {"twapId": twap_id}})
+            }
+            Err(error) => json!({"error": error}),
+        };
+        json!({"type": "twapOrder", "data": {"status": status}})
+    }
+
+    /// TWAP parameters (`a` by index, or by name as the SDK sends it)
+    fn twap_state(&self, twap: &Value) -> std::result::Result<TwapState, String> {
+        let market = match twap.get("a") {
+            Some(Value::String(coin)) => self.registry.get(coin),
+            asset => asset.and_then(|a| a.as_u64()).and_then(|a| self.registry.by_id(a as u32)),
+        }
+        .ok_or("Invalid asset")?;
+        let sz: Decimal = twap.get("s").and_then(|s| s.as_str()).and_then(|s| s.parse().ok()).unwrap_or_default();
+        let minutes = twap.get("m").and_then(|m| m.as_u64()).unwrap_or_default();
+        if !(5..=1440).contains(&minutes) {
+            return Err("TWAP duration must be between 5 minutes and 24 hours.".to_string());
+        }
+        if sz <= Decimal::ZERO || !market.precision().is_valid_sz(sz) {
+            return Err("Order has invalid size.".to_string());
+        }
+        Ok(TwapState {
+            coin: market.symbol.clone(),
+            user: self.address.clone(),
+            side: if twap.get("b").and_then(|b| b.as_bool()).unwrap_or(false) { Side::Buy } else { Side::Sell },
+            sz,
+            executed_sz: Decimal::ZERO,
+            executed_ntl: Decimal::ZERO,
+            minutes,
+            reduce_only: twap.get("r").and_then(|r| r.as_bool()).unwrap_or(false),
+            randomize: twap.get("t").and_then(|t| t.as_bool()).unwrap_or(false),
+            timestamp: self.clock,
+        })
+    }
+
+    fn twap_cancel(&mut self, action: &Value) -> Value {
+        let twap_id = action.get("t").and_then(|t| t.as_u64());
+        let clock = self.clock;
+        let running = self
+            .twaps
+            .iter_mut()
+            .find(|t| t.twap_id.is_some() && t.twap_id == twap_id && t.status.status == "activated");
+        let status = match running {
+            Some(twap) => {
+                twap.time = clock / 1000;
+                twap.status.status = "terminated".to_string();
+                json!("success")
+            }
+            None => json!({"error": "TWAP was never placed, already canceled, or filled."}),
+        };
+        json!({"type": "twapCancel", "data": {"status": status}})
+    }
+
+    fn debit(&mut self, action: &Value) -> std::result::Result<Value, String> {
+        let amount: Decimal = action
+            .get("amount")
+            .and_then(|a| a.as_str())
+            .and_then(|a| a.parse().ok())
+            .ok_or("Invalid amount")?;
+        if amount > self.withdrawable() {
+            return Err("Insufficient balance for withdrawal".to_string());
+        }
+        self.usdc -= amount;
+        Ok(json!({"type": "default"}))
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Matching
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Check an order the way the exchange would, without placing it
+    pub fn check(&self, request: &OrderRequest) -> std::result::Result<&Market, String> {
+        let market = self
+            .registry
+            .by_id(request.asset)
+            .ok_or_else(|| format!("Unknown asset {}", request.asset))?;
+        if market.is_delisted {
+            return Err(format!("Trading is halted for {}.", market.symbol));
+        }
+        let precision = market.precision();
+        let trigger_ok = request.trigger.as_ref().is_none_or(|t| precision.is_valid_px(t.px));
+        if !precision.is_valid_px(request.px) || !trigger_ok {
+            return Err("Order has invalid price.".to_string());
+        }
+        if !precision.is_valid_sz(request.sz) {
+            return Err("Order has invalid size.".to_string());
+        }
+        let position = self.position(&market.symbol);
+        if request.reduce_only {
+            let reduces = match request.side {
+                Side::Buy => position < Decimal::ZERO,
+                Side::Sell => position > Decimal::ZERO,
+            };
+            if !reduces {
+                return Err("Reduce only order would increase position.".to_string());
+            }
+        } else if request.px * request.sz < MIN_NOTIONAL {
+            return Err(format!("Order must have minimum value of ${}.", MIN_NOTIONAL));
+        }
+        if request.tif == Tif::Alo && request.trigger.is_none() {
+            let book = self.books.get(&market.symbol);
+            let crosses = book.and_then(|b| b.best(opposite(request.side))).is_some_and(|best| {
+                match request.side {
+                    Side::Buy => best <= request.px,
+                    Side::Sell => best >= request.px,
+                }
+            });
+            if crosses {
+                let bbo = |side| {
+                    book.and_then(|b| b.best(side))
+                        .map(|px: Decimal| px.normalize().to_string())
+                        .unwrap_or_default()
+                };
+                return Err(format!(
+                    "Post only order would have immediately matched, bbo was {}@{}. asset={}",
+                    bbo(Side::Buy),
+                    bbo(Side::Sell),
+                    request.asset
+                ));
+            }
+        }
+        Ok(market)
+    }
+
+    /// Place one order and return its status entry
+    pub fn place(&mut self, request: OrderRequest) -> Value {
+        self.place_with(request, None, false)
+    }
+
+    /// [`place`](Self::place) a trigger waiting for `parent` to fill, or one
+    /// attached to the position
+    fn place_with(&mut self, request: OrderRequest, parent: Option<u64>, position_tpsl: bool) -> Value {
+        let (coin, position) = match self.check(&Self::attached(&request, parent, position_tpsl)) {
+            Ok(market) => (market.symbol.clone(), self.position(&market.symbol)),
+            Err(error) => return json!({"error": error}),
+        };
+        let time = self.tick();
+        let oid = self.next_oid;
+        self.next_oid += 1;
+
+        let order = Resting {
+            oid,
+            coin,
+            side: request.side,
+            px: request.px,
+            // Reduce-only orders never flip the position (children are sized when they fire)
+            sz: match request.reduce_only && parent.is_none() && !position_tpsl {
+                true => request.sz.min(position.abs()),
+                false => request.sz,
+            },
+            orig_sz: request.sz,
+            time,
+            cloid: request.cloid,
+            reduce_only: request.reduce_only,
+            tif: request.tif,
+            trigger: request.trigger,
+            ours: true,
+            parent,
+            position_tpsl,
+        };
+
+        if order.trigger.is_some() {
+            self.update(&order, "open");
+            let status = match parent {
+                Some(_) => json!("waitingForFill"),
+                None => json!({"resting": {"oid": oid}}),
+            };
+            self.triggers.push(order);
+            return status;
+        }
+        self.submit(order, request.asset)
+    }
+
+    /// `request` as checked: a child waiting for its entry, or a position
+    /// TP/SL sent with one, may have no position to reduce yet, so it is
+    /// checked like an opening order
+    fn attached(request: &OrderRequest, parent: Option<u64>, position_tpsl: bool) -> OrderRequest {
+        OrderRequest { reduce_only: request.reduce_only && parent.is_none() && !position_tpsl, ..request.clone() }
+    }
+
+    /// Cancel the `normalTpsl` children still waiting for `parent`
+    fn cancel_children(&mut self, parent: u64) {
+        let (children, rest): (Vec<Resting>, Vec<Resting>) =
+            std::mem::take(&mut self.triggers).into_iter().partition(|o| o.parent == Some(parent));
+        self.triggers = rest;
+        for child in children {
+            self.update(&child, "canceled");
+        }
+    }
+
+    /// Match a live order, then rest what is left of it unless it is an IOC
+    fn submit(&mut self, mut order: Resting, asset: u32) -> Value {
+        let oid = order.oid;
+        let coin = order.coin.clone();
+        let (filled, notional) = self.sweep(&mut order);
+        if filled.is_zero() && order.tif == Tif::Ioc {
+            self.update(&order, "canceled");
+            self.cancel_children(oid);
+            return json!({
+                "error": format!(
+                    "Order could not immediately match against any resting orders. asset={}",
+                    asset
+                )
+            });
+        }
+
+        let status = if order.sz.is_zero() || order.tif == Tif::Ioc {
+            self.update(&order, "filled");
+            json!({"filled": {
+                "totalSz": filled.normalize().to_string(),
+                "avgPx": (notional / filled).round_dp(8).normalize().to_string(),
+                "oid": oid,
+            }})
+        } else {
+            self.update(&order, "open");
+            self.rest(order);
+            json!({"resting": {"oid": oid}})
+        };
+        self.events.push(Event::Book(coin));
+        status
+    }
+
+    /// Match `taker` against the opposite side; returns filled size and notional
+    fn sweep(&mut self, taker: &mut Resting) -> (Decimal, Decimal) {
+        let mut filled = Decimal::ZERO;
+        let mut notional = Decimal::ZERO;
+        let Some(book) = self.books.get_mut(&taker.coin) else {
+            return (filled, notional);
+        };
+        let mut matches = Vec::new();
+        let makers = book.side_mut(opposite(taker.side));
+        while taker.sz > Decimal::ZERO {
+            let Some(maker) = makers.first_mut() else { break };
+            if !crosses(taker.side, taker.px, maker.px) {
+                break;
+            }
+            let sz = taker.sz.min(maker.sz);
+            maker.sz -= sz;
+            taker.sz -= sz;
+            filled += sz;
+            notional += sz * maker.px;
+            matches.push((maker.clone(), sz));
+            if maker.sz.is_zero() {
+                makers.remove(0);
+            }
+        }
+
+        for (maker, sz) in matches {
+            let px = maker.px;
+            let (tid, hash) = self.next_trade();
+            let (buyer, seller) = match taker.side {
+                Side::Buy => (self.address.clone(), owner(&maker, &self.address)),
+                Side::Sell => (owner(&maker, &self.address), self.address.clone()),
+            };
+            let trade = Trade {
+                coin: taker.coin.clone(),
+                side: taker.side,
+                px,
+                sz,
+                time: taker.time,
+                hash: hash.clone(),
+                tid,
+                users: Some((buyer, seller)),
+            };
+            self.tape.push(trade.clone());
+            self.events.push(Event::Trade(trade));
+            self.fill(taker, px, sz, true, tid, &hash);
+            if maker.ours {
+                self.fill(&maker, px, sz, false, tid, &hash);
+                if maker.sz.is_zero() {
+                    self.update(&maker, "filled");
+                }
+            }
+        }
+        (filled, notional)
+    }
+
+    /// Book a fill for the scripted account
+    fn fill(&mut self, order: &Resting, px: Decimal, sz: Decimal, crossed: bool, tid: u64, hash: &str) {
+        let state = self.positions.entry(order.coin.clone()).or_default();
+        let start = state.szi;
+        let delta = if order.side.is_buy() { sz } else { -sz };
+        let end = start + delta;
+
+        let reducing = !start.is_zero() && start.is_sign_positive() != delta.is_sign_positive();
+        let closed = if reducing { sz.min(start.abs()) } else { Decimal::ZERO };
+        let closed_pnl = if start.is_sign_positive() {
+            closed * (px - state.entry_px)
+        } else {
+            closed * (state.entry_px - px)
+        };
+        let dir = match order.side {
+            Side::Buy if start >= Decimal::ZERO => "Open Long",
+            Side::Buy if end <= Decimal::ZERO => "Close Short",
+            Side::Buy => "Short > Long",
+            Side::Sell if start <= Decimal::ZERO => "Open Short",
+            Side::Sell if end >= Decimal::ZERO => "Close Long",
+            Side::Sell => "Long > Short",
+        };
+
+        state.entry_px = if !reducing {
+            (state.entry_px * start.abs() + px * sz) / end.abs()
+        } else if end.is_zero() || end.is_sign_positive() == start.is_sign_positive() {
+            state.entry_px
+        } else {
+            px
+        };
+        state.szi = end;
+        if end.is_zero() {
+            self.positions.remove(&order.coin);
+        }
+
+        // The entry's first fill activates its children; a closed position
+        // takes its position TP/SL with it
+        for child in self.triggers.iter_mut().filter(|o| o.parent == Some(order.oid)) {
+            child.parent = None;
+        }
+        if end.is_zero() {
+            let (closed, rest): (Vec<Resting>, Vec<Resting>) = std::mem::take(&mut self.triggers)
+                .into_iter()
+                .partition(|o| o.position_tpsl && o.coin == order.coin);
+            self.triggers = rest;
+            for trigger in closed {
+                self.update(&trigger, "canceled");
+            }
+        }
+
+        let fee = (px * sz * if crossed { TAKER_FEE } else { MAKER_FEE }).round_dp(6);
+        self.usdc += closed_pnl - fee;
+        let fill = Fill {
+            coin: order.coin.clone(),
+            px,
+            sz,
+            side: order.side,
+            time: self.clock,
+            start_position: start,
+            dir: dir.to_string(),
+            closed_pnl: closed_pnl.round_dp(6),
+            hash: hash.to_string(),
+            oid: order.oid,
+            crossed,
+            fee,
+            tid,
+            fee_token: "USDC".to_string(),
+            cloid: order.cloid.clone(),
+            builder_fee: None,
+            user: None,
+        };
+        self.fills.push(fill.clone());
+        self.events.push(Event::Fill(fill));
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Live market
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Replace the outside liquidity of a coin with a live book snapshot.
+    ///
+    /// Our resting orders keep their place behind outside orders at the same
+    /// price. Outside orders that now cross one of ours mean the market went
+    /// through it: it fills against them at its own price, as maker.
+    pub fn set_book(&mut self, snapshot: &L2Book) {
+        self.clock = self.clock.max(snapshot.time);
+        let coin = &snapshot.coin;
+        let book = self.books.entry(coin.clone()).or_default();
+        let ours: Vec<Resting> = book.bids.drain(..).chain(book.asks.drain(..)).filter(|o| o.ours).collect();
+        for (side, levels) in [(Side::Buy, snapshot.bids()), (Side::Sell, snapshot.asks())] {
+            for level in levels {
+                book.insert(Resting {
+                    oid: 0,
+                    coin: coin.clone(),
+                    side,
+                    px: level.px,
+                    sz: level.sz,
+                    orig_sz: level.sz,
+                    time: snapshot.time,
+                    cloid: None,
+                    reduce_only: false,
+                    tif: Tif::Gtc,
+                    trigger: None,
+                    ours: false,
+                    parent: None,
+                    position_tpsl: false,
+                });
+            }
+        }
+        let resting: Vec<(u64, Side, Decimal, Decimal)> = ours.iter().map(|o| (o.oid, o.side, o.px, o.sz)).collect();
+        for order in ours {
+            book.insert(order);
+        }
+
+        for (oid, side, px, sz) in resting {
+            let Some(book) = self.books.get_mut(coin) else { break };
+            let taken = book.take_outside(opposite(side), px, sz);
+            self.fill_resting(oid, taken);
+        }
+        self.events.push(Event::Book(coin.clone()));
+        if let Some(mid) = self.books.get(coin).and_then(Book::mid) {
+            self.fire_triggers(coin, mid);
+        }
+    }
+
+    /// Replay a public trade.
+    ///
+    /// Our resting orders priced strictly better than the trade fill, up to
+    /// its size (at the same price the queue ahead of us may have taken it).
+    pub fn on_trade(&mut self, trade: &Trade) {
+        self.clock = self.clock.max(trade.time);
+        let side = opposite(trade.side);
+        let through: Vec<u64> = self
+            .books
+            .get(&trade.coin)
+            .map(|b| b.side(side).iter())
+            .into_iter()
+            .flatten()
+            .filter(|o| o.ours && o.px != trade.px && crosses(trade.side, trade.px, o.px))
+            .map(|o| o.oid)
+            .collect();
+        let mut left = trade.sz;
+        for oid in through {
+            if left.is_zero() {
+                break;
+            }
+            left -= self.fill_resting(oid, left);
+        }
+        self.fire_triggers(&trade.coin, trade.px);
+    }
+
+    /// Fill up to `available` of one of our resting orders at its own price;
+    /// returns the size filled
+    fn fill_resting(&mut self, oid: u64, available: Decimal) -> Decimal {
+        let Some(order) = self
+            .books
+            .values_mut()
+            .flat_map(|b| b.bids.iter_mut().chain(b.asks.iter_mut()))
+            .find(|o| o.ours && o.oid == oid)
+        else {
+            return Decimal::ZERO;
+        };
+        let sz = available.min(order.sz);
+        if sz.is_zero() {
+            return sz;
+        }
+        order.sz -= sz;
+        let order = order.clone();
+
+        let (tid, hash) = self.next_trade();
+        let (buyer, seller) = match order.side {
+            Side::Buy => (self.address.clone(), MAKER_ADDRESS.to_string()),
+            Side::Sell => (MAKER_ADDRESS.to_string(), self.address.clone()),
+        };
+        let trade = Trade {
+            coin: order.coin.clone(),
+            side: opposite(order.side),
+            px: order.px,
+            sz,
+            time: self.clock,
+            hash: hash.clone(),
+            tid,
+            users: Some((buyer, seller)),
+        };
+        self.tape.push(trade.clone());
+        self.events.push(Event::Trade(trade));
+        self.fill(&order, order.px, sz, false, tid, &hash);
+        if order.sz.is_zero() {
+            self.remove(oid);
+            self.update(&order, "filled");
+        }
+        self.events.push(Event::Book(order.coin));
+        sz
+    }
+
+    /// Turn trigger orders on `coin` whose price was reached into live orders:
+    /// market triggers into IOCs at their limit price, the rest into GTCs
+    fn fire_triggers(&mut self, coin: &str, px: Decimal) {
+        let (fired, waiting): (Vec<Resting>, Vec<Resting>) = std::mem::take(&mut self.triggers)
+            .into_iter()
+            .partition(|o| {
+                o.coin == coin && o.parent.is_none() && o.trigger.as_ref().is_some_and(|t| t.fires(o.side, px))
+            });
+        self.triggers = waiting;
+
+        for mut order in fired {
+            self.update(&order, "triggered");
+            let Some(trigger) = order.trigger.take() else { continue };
+            order.tif = if trigger.is_market { Tif::Ioc } else { Tif::Gtc };
+            if order.position_tpsl {
+                order.sz = self.position(coin).abs();
+            } else if order.reduce_only {
+                order.sz = order.sz.min(self.position(coin).abs());
+            }
+            let asset = self.registry.get(coin).map(|m| m.asset_id);
+            match asset {
+                Some(asset) if !order.sz.is_zero() => {
+                    self.submit(order, asset);
+                }
+                _ => self.update(&order, "canceled"),
+            }
+        }
+    }
+
+    fn rest(&mut self, order: Resting) {
+        if order.trigger.is_some() {
+            self.triggers.push(order);
+        } else {
+            self.books.entry(order.coin.clone()).or_default().insert(order);
+        }
+    }
+
+    /// Oid of one of our open orders on `asset` matching `pred`
+    fn find(&self, asset: u32, pred: impl Fn(&Resting) -> bool) -> Option<u64> {
+        let coin = &self.registry.by_id(asset)?.symbol;
+        self.our_orders().find(|o| &o.coin == coin && pred(o)).map(|o| o.oid)
+    }
+
+    fn remove(&mut self, oid: u64) -> Option<Resting> {
+        if let Some(i) = self.triggers.iter().position(|o| o.oid == oid) {
+            return Some(self.triggers.remove(i));
+        }
+        for book in self.books.values_mut() {
+            for side in [Side::Buy, Side::Sell] {
+                let orders = book.side_mut(side);
+                if let Some(i) = orders.iter().position(|o| o.oid == oid && o.ours) {
+                    return Some(orders.remove(i));
+                }
+            }
+        }
+        None
+    }
+
+    fn update(&mut self, order: &Resting, status: &str) {
+        let update = OrderUpdate {
+            order: order.to_open_order(),
+            status: status.to_string(),
+            status_timestamp: Some(self.clock),
+            user: Some(self.address.clone()),
+        };
+        self.history.insert(order.oid, update.clone());
+        self.events.push(Event::OrderUpdate(update));
+    }
+
+    fn next_trade(&mut self) -> (u64, String) {
+        let tid = self.next_tid;
+        self.next_tid += 1;
+        (tid, format!("0x{:064x}", tid))
+    }
+
+    fn our_orders(&self) -> impl Iterator<Item = &Resting> {
+        self.books
+            .values()
+            .flat_map(|b| b.bids.iter().chain(&b.asks))
+            .chain(&self.triggers)
+            .filter(|o| o.ours)
+    }
+
+    fn position(&self, coin: &str) -> Decimal {
+        self.positions.get(coin).map(|p| p.szi).unwrap_or_default()
+    }
+
+    fn withdrawable(&self) -> Decimal {
+        let state = self.clearinghouse_state();
+        state.withdrawable
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Queries
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Answer an `/info` request about the account (open orders, order
+    /// status, fills, margin); `None` for every other request type
+    pub fn account_info(&self, request: &Value) -> Option<Value> {
+        let value = match request.get("type")?.as_str()? {
+            "openOrders" | "frontendOpenOrders" => json!(self.open_orders()),
+            "orderStatus" => {
+                let oid = request.get("oid").and_then(|o| o.as_u64()).unwrap_or_default();
+                self.order_status(oid)
+            }
+            "userFills" | "userFillsByTime" => json!(self.user_fills()),
+            "clearinghouseState" => json!(self.clearinghouse_state()),
+            "userTwapHistory" => json!(self.twaps),
+            "userTwapSliceFills" => json!(Vec::<TwapSliceFill>::new()),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    /// Mid from the book, else the scripted mid
+    pub fn mid(&self, coin: &str) -> Option<Decimal> {
+        self.books
+            .get(coin)
+            .and_then(Book::mid)
+            .map(|mid| mid.normalize())
+            .or_else(|| self.mids.get(coin).copied())
+    }
+
+    /// Mids of the main dex (perps and spot), or of one HIP-3 dex
+    pub fn all_mids(&self, dex: Option<&str>) -> BTreeMap<String, Decimal> {
+        self.books
+            .keys()
+            .chain(self.mids.keys())
+            .filter(|coin| {
+                let market_dex = self.registry.get(coin).and_then(Market::dex);
+                market_dex == dex.filter(|d| !d.is_empty())
+            })
+            .filter_map(|coin| Some((coin.clone(), self.mid(coin)?)))
+            .collect()
+    }
+
+    /// Aggregated book for a coin
+    pub fn l2_book(&self, coin: &str) -> Option<L2Book> {
+        let book = self.books.get(coin)?;
+        Some(L2Book {
+            coin: coin.to_string(),
+            time: self.clock,
+            levels: (book.levels(Side::Buy), book.levels(Side::Sell)),
+        })
+    }
+
+    /// Open orders of the scripted account, newest first
+    pub fn open_orders(&self) -> Vec<OpenOrder> {
+        let mut orders: Vec<OpenOrder> = self.our_orders().map(Resting::to_open_order).collect();
+        orders.sort_by_key(|o| std::cmp::Reverse(o.oid));
+        orders
+    }
+
+    /// `orderStatus` response for an oid
+    pub fn order_status(&self, oid: u64) -> Value {
+        match self.history.get(&oid) {
+            Some(update) => json!({"status": "order", "order": update}),
+            None => json!({"status": "unknownOid"}),
+        }
+    }
+
+    /// Max fee rate of the last `approveBuilderFee`, if any
+    pub fn builder_fee(&self) -> Option<&str> {
+        self.builder_fee.as_deref()
+    }
+
+    /// Fills of the scripted account, newest first
+    pub fn user_fills(&self) -> Vec<Fill> {
+        self.fills.iter().rev().cloned().collect()
+    }
+
+    /// Trades on the tape for a coin, newest first
+    pub fn recent_trades(&self, coin: &str) -> Vec<Trade> {
+        self.tape.iter().rev().filter(|t| t.coin == coin).cloned().collect()
+    }
+
+    /// Margin and positions of the scripted account, marked to the mids
+    pub fn clearinghouse_state(&self) -> ClearinghouseState {
+        let mut asset_positions = Vec::new();
+        let mut upnl_total = Decimal::ZERO;
+        let mut ntl_total = Decimal::ZERO;
+        let mut signed_ntl = Decimal::ZERO;
+        let mut margin_total = Decimal::ZERO;
+
+        for (coin, state) in &self.positions {
+            let market = self.registry.get(coin);
+            let max_leverage = market.and_then(|m| m.max_leverage).unwrap_or(DEFAULT_LEVERAGE);
+            let (leverage, is_cross) = self
+                .leverage
+                .get(coin)
+                .copied()
+                .unwrap_or((DEFAULT_LEVERAGE.min(max_leverage), !market.is_some_and(|m| m.only_isolated)));
+            let mark = self.mid(coin).unwrap_or(state.entry_px);
+            let value = state.szi.abs() * mark;
+            let upnl = state.szi * (mark - state.entry_px);
+            let margin = value / Decimal::from(leverage);
+            let cost = state.szi.abs() * state.entry_px / Decimal::from(leverage);
+            upnl_total += upnl;
+            ntl_total += value;
+            signed_ntl += state.szi * mark;
+            margin_total += margin;
+
+            asset_positions.push(AssetPosition {
+                kind: "oneWay".to_string(),
+                position: Position {
+                    coin: coin.clone(),
+                    szi: state.szi,
+                    leverage: Leverage {
+                        kind: if is_cross { "cross" } else { "isolated" }.to_string(),
+                        value: leverage,
+                        raw_usd: (!is_cross).then(|| (margin - state.szi * mark).round_dp(6)),
+                    },
+                    entry_px: Some(state.entry_px.round_dp(8).normalize()),
+                    position_value: value.round_dp(6),
+                    unrealized_pnl: upnl.round_dp(6),
+                    return_on_equity: if cost.is_zero() { Decimal::ZERO } else { (upnl / cost).round_dp(10) },
+                    liquidation_px: None,
+                    margin_used: margin.round_dp(6),
+                    max_leverage,
+                    cum_funding: Some(CumFunding {
+                        all_time: Decimal::ZERO,
+                        since_open: Decimal::ZERO,
+                        since_change: Decimal::ZERO,
+                    }),
+                },
+            });
+        }
+
+        let account_value = self.usdc + upnl_total;
+        let summary = MarginSummary {
+            account_value: account_value.round_dp(6),
+            total_ntl_pos: ntl_total.round_dp(6),
+            total_raw_usd: (account_value - signed_ntl).round_dp(6),
+            total_margin_used: margin_total.round_dp(6),
+        };
+        ClearinghouseState {
+            margin_summary: summary.clone(),
+            cross_margin_summary: summary,
+            cross_maintenance_margin_used: (margin_total / Decimal::TWO).round_dp(6),
+            withdrawable: (account_value - margin_total).max(Decimal::ZERO).round_dp(6),
+            asset_positions,
+            time: self.clock,
+        }
+    }
+}
+
+fn opposite(side: Side) -> Side {
+    match side {
+        Side::Buy => Side::Sell,
+        Side::Sell => Side::Buy,
+    }
+}
+
+/// Would a `side` order at `px` trade with a resting order at `other`?
+fn crosses(side: Side, px: Decimal, other: Decimal) -> bool {
+    match side {
+        Side::Buy => other <= px,
+        Side::Sell => other >= px,
+    }
+}
+
+fn owner(order: &Resting, address: &str) -> String {
+    if order.ours { address } else { MAKER_ADDRESS }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine() -> MatchingEngine {
+        MatchingEngine::new(&Script::default_market()).unwrap()
+    }
+
+    fn order(side: Side, px: &str, sz: &str, tif: &str) -> Value {
+        json!({
+            "type": "order",
+            "orders": [{"a": 0, "b": side.is_buy(), "p": px, "s": sz, "r": false, "t": {"limit": {"tif": tif}}}],
+            "grouping": "na",
+        })
+    }
+
+    fn status(response: &Value) -> &Value {
+        &response["response"]["data"]["statuses"][0]
+    }
+
+    #[test]
+    fn sweeps_levels_in_price_order() {
+        let mut engine = engine();
+        let response = engine.execute(&order(Side::Buy, "96255", "1.5", "Ioc"));
+
+        // 0.8 @ 96251 then 0.7 @ 96252; the remainder of the IOC is dropped
+        let filled = &status(&response)["filled"];
+        assert_eq!(filled["totalSz"], "1.5");
+        assert_eq!(filled["avgPx"], "96251.46666667");
+        let fills = engine.user_fills();
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[1].px, Decimal::from(96251));
+        assert_eq!(fills[0].dir, "Open Long");
+
+        let book = engine.l2_book("BTC").unwrap();
+        assert_eq!(book.levels.1[0].px, Decimal::from(96252));
+        assert_eq!(book.levels.1[0].sz, Decimal::new(13, 1));
+    }
+
+    #[test]
+    fn rests_the_unfilled_part_of_a_gtc() {
+        let mut engine = engine();
+        let response = engine.execute(&order(Side::Buy, "96251", "1", "Gtc"));
+        let oid = status(&response)["resting"]["oid"].as_u64().unwrap();
+
+        let open = engine.open_orders();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].sz, Decimal::new(2, 1));
+        assert_eq!(engine.l2_book("BTC").unwrap().levels.0[0].px, Decimal::from(96251));
+        assert_eq!(engine.order_status(oid)["order"]["status"], "open");
+    }
+
+    #[test]
+    fn rejects_like_the_exchange() {
+        let mut engine = engine();
+        let error = |engine: &mut MatchingEngine, action: Value| {
+            status(&engine.execute(&action))["error"].as_str().unwrap().to_string()
+        };
+
+        assert_eq!(error(&mut engine, order(Side::Buy, "96250.5", "0.001", "Gtc")), "Order has invalid price.");
+        assert_eq!(error(&mut engine, order(Side::Buy, "96250", "0.000001", "Gtc")), "Order has invalid size.");
+        assert_eq!(
+            error(&mut engine, order(Side::Buy, "90000", "0.0001", "Gtc")),
+            "Order must have minimum value of $10."
+        );
+        assert!(error(&mut engine, order(Side::Buy, "96251", "0.001", "Alo")).starts_with("Post only order"));
+        assert!(error(&mut engine, order(Side::Buy, "90000", "0.001", "Ioc")).starts_with("Order could not"));
+
+        let mut reduce = order(Side::Sell, "96250", "0.001", "Gtc");
+        reduce["orders"][0]["r"] = json!(true);
+        assert_eq!(error(&mut engine, reduce), "Reduce only order would increase position.");
+        assert!(engine.open_orders().is_empty());
+    }
+
+    #[test]
+    fn closing_realizes_pnl_and_fees() {
+        let mut engine = engine();
+        engine.execute(&order(Side::Buy, "96251", "0.5", "Ioc"));
+        engine.execute(&order(Side::Sell, "96250", "0.5", "Ioc"));
+
+        let fills = engine.user_fills();
+        assert_eq!(fills[0].dir, "Close Long");
+        assert_eq!(fills[0].closed_pnl, Decimal::new(-5, 1));
+        let state = engine.clearinghouse_state();
+        assert!(state.asset_positions.is_empty());
+        // 10000 - 0.5 pnl - taker fees on both legs
+        let fees = fills.iter().map(|f| f.fee).sum::<Decimal>();
+        assert_eq!(state.margin_summary.account_value, Decimal::from(10000) - Decimal::new(5, 1) - fees);
+    }
+
+    #[test]
+    fn cancels_by_oid_and_cloid() {
+        let mut engine = engine();
+        let mut resting = order(Side::Buy, "90000", "0.001", "Gtc");
+        resting["orders"][0]["c"] = json!("0x0000000000000000000000000000abcd");
+        let oid = status(&engine.execute(&resting))["resting"]["oid"].as_u64().unwrap();
+        engine.execute(&order(Side::Buy, "90001", "0.001", "Gtc"));
+
+        let by_cloid = json!({"type": "cancelByCloid", "cancels": [{"asset": 0, "cloid": "0x0000000000000000000000000000abcd"}]});
+        assert_eq!(*status(&engine.execute(&by_cloid)), json!("success"));
+        assert_eq!(engine.order_status(oid)["order"]["status"], "canceled");
+
+        let miss = json!({"type": "cancel", "cancels": [{"a": 0, "o": oid}]});
+        assert!(status(&engine.execute(&miss))["error"].as_str().unwrap().starts_with("Order was never placed"));
+        assert_eq!(engine.open_orders().len(), 1);
+    }
+
+    fn live_book(bid: &str, ask: &str) -> L2Book {
+        let level = |px: &str| Level {
+            px: px.parse().unwrap(),
+            sz: Decimal::ONE,
+            n: 1,
+        };
+        L2Book {
+            coin: "BTC".to_string(),
+            time: DEFAULT_TIME + 1000,
+            levels: (vec![level(bid)], vec![level(ask)]),
+        }
+    }
+
+    #[test]
+    fn live_books_replace_outside_liquidity() {
+        let mut engine = engine();
+        engine.execute(&order(Side::Buy, "96000", "0.002", "Gtc"));
+        engine.set_book(&live_book("96100", "96101"));
+
+        let book = engine.l2_book("BTC").unwrap();
+        assert_eq!(book.levels.0.len(), 2);
+        assert_eq!(book.levels.0[0].px, Decimal::from(96100));
+        assert_eq!(book.levels.1[0].sz, Decimal::ONE);
+        assert_eq!(engine.open_orders().len(), 1);
+
+        // The market drops through our bid: it fills at our price as maker
+        engine.set_book(&live_book("95900", "95950"));
+        let fills = engine.user_fills();
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].px, Decimal::from(96000));
+        assert!(!fills[0].crossed);
+        assert!(engine.open_orders().is_empty());
+        assert_eq!(engine.l2_book("BTC").unwrap().levels.1[0].sz, Decimal::new(998, 3));
+    }
+
+    #[test]
+    fn trades_through_our_price_fill_resting_orders() {
+        let mut engine = engine();
+        engine.execute(&order(Side::Sell, "96300", "0.003", "Gtc"));
+        let trade = |px: &str, sz: &str| Trade {
+            coin: "BTC".to_string(),
+            side: Side::Buy,
+            px: px.parse().unwrap(),
+            sz: sz.parse().unwrap(),
+            time: DEFAULT_TIME + 1000,
+            hash: String::new(),
+            tid: 1,
+            users: None,
+        };
+
+        // At our price the queue ahead may have taken it
+        engine.on_trade(&trade("96300", "1"));
+        assert!(engine.user_fills().is_empty());
+
+        engine.on_trade(&trade("96301", "0.001"));
+        assert_eq!(engine.user_fills()[0].sz, Decimal::new(1, 3));
+        assert_eq!(engine.open_orders()[0].sz, Decimal::new(2, 3));
+    }
+
+    #[test]
+    fn triggers_fire_when_the_market_reaches_them() {
+        let mut engine = engine();
+        engine.execute(&order(Side::Buy, "96251", "0.01", "Ioc"));
+        let stop = json!({
+            "type": "order",
+            "orders": [{
+                "a": 0, "b": false, "p": "90000", "s": "0.01", "r": true,
+                "t": {"trigger": {"triggerPx": "95000", "isMarket": true, "tpsl": "sl"}},
+            }],
+            "grouping": "na",
+        });
+        let oid = status(&engine.execute(&stop))["resting"]["oid"].as_u64().unwrap();
+
+        engine.set_book(&live_book("95500", "95501"));
+        assert_eq!(engine.order_status(oid)["order"]["status"], "open");
+
+        engine.set_book(&live_book("94990", "94991"));
+        assert_eq!(engine.order_status(oid)["order"]["status"], "filled");
+        assert_eq!(engine.user_fills()[0].dir, "Close Long");
+        assert!(engine.clearinghouse_state().asset_positions.is_empty());
+    }
+
+    fn bracket(grouping: &str, entry: Value) -> Value {
+        let stop = json!({
+            "a": 0, "b": false, "p": "90000", "s": "0.01", "r": true,
+            "t": {"trigger": {"triggerPx": "95000", "isMarket": true, "tpsl": "sl"}},
+        });
+        json!({"type": "order", "orders": [entry, stop], "grouping": grouping})
+    }
+
+    #[test]
+    fn normal_tpsl_children_wait_for_the_entry() {
+        let mut engine = engine();
+        let entry = json!({"a": 0, "b": true, "p": "96000", "s": "0.01", "r": false, "t": {"limit": {"tif": "Gtc"}}});
+        let response = engine.execute(&bracket("normalTpsl", entry.clone()));
+        assert_eq!(response["response"]["data"]["statuses"][1], "waitingForFill");
+
+        // Not fired while waiting, gone with the entry
+        engine.set_book(&live_book("96010", "96011"));
+        assert_eq!(engine.open_orders().len(), 2);
+        let oid = status(&response)["resting"]["oid"].as_u64().unwrap();
+        engine.execute(&json!({"type": "cancel", "cancels": [{"a": 0, "o": oid}]}));
+        assert!(engine.open_orders().is_empty());
+
+        // Active once the entry fills
+        let response = engine.execute(&bracket("normalTpsl", entry));
+        engine.set_book(&live_book("95990", "95995"));
+        assert_eq!(engine.user_fills().len(), 1);
+        let oid = response["response"]["data"]["statuses"][0]["resting"]["oid"].as_u64().unwrap() + 1;
+        assert_eq!(engine.order_status(oid)["order"]["status"], "open");
+        engine.set_book(&live_book("94990", "94991"));
+        assert_eq!(engine.order_status(oid)["order"]["status"], "filled");
+        assert!(engine.clearinghouse_state().asset_positions.is_empty());
+    }
+
+    #[test]
+    fn position_tpsl_closes_the_whole_position() {
+        let mut engine = engine();
+        engine.execute(&order(Side::Buy, "96251", "0.03", "Ioc"));
+        let entry = json!({"a": 0, "b": true, "p": "96251", "s": "0.02", "r": false, "t": {"limit": {"tif": "Ioc"}}});
+        let response = engine.execute(&bracket("positionTpsl", entry));
+        let oid = response["response"]["data"]["statuses"][1]["resting"]["oid"].as_u64().unwrap();
+        assert_eq!(engine.open_orders()[0].is_position_tpsl, Some(true));
+
+        // Sized 0.01, it closes all 0.05
+        engine.set_book(&live_book("94990", "94991"));
+        assert_eq!(engine.order_status(oid)["order"]["status"], "filled");
+        assert_eq!(engine.user_fills()[0].sz, Decimal::new(5, 2));
+        assert!(engine.clearinghouse_state().asset_positions.is_empty());
+    }
+
+    #[test]
+    fn mids_come_from_books_and_script() {
+        let engine = engine();
+        let mids = engine.all_mids(None);
+        assert_eq!(mids["BTC"], Decimal::new(962505, 1));
+        assert_eq!(mids["PURR/USDC"], Decimal::new(1875, 4));
+        assert!(!mids.contains_key("xyz:SILVER"));
+        assert_eq!(engine.all_mids(Some("xyz"))["xyz:SILVER"], Decimal::new(3142, 2));
+    }
+}
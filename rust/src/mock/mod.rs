@@ -0,0 +1,587 @@
+//! In-process mock of the Hyperliquid APIs.
+//!
+//! [`MockServer`] answers everything the examples talk to: `/info`,
+//! `/exchange` (build and send), HyperCore and EVM JSON-RPC, the worker's
+//! `/approval` and `/preflight`, and the `/ws` feed. Orders go through a
+//! deterministic [`MatchingEngine`]; everything else comes from a [`Script`].
+//!
+//! The SDK sends HTTP requests to fixed public hosts whatever the endpoint
+//! is, so the server is also an HTTPS proxy: it accepts `CONNECT` and
+//! terminates TLS with a throwaway CA. Point a binary at it with
+//! [`MockServer::env`]:
+//!
+//! ```no_run
+//! use hyperliquid_examples::mock::{MockServer, Script};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let server = MockServer::start(Script::default_market()).await?;
+//! let status = std::process::Command::new("target/debug/place_order")
+//!     .envs(server.env())
+//!     .env("PRIVATE_KEY", "0x...")
+//!     .status()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The SDK drops the port from the endpoint when building the WebSocket URL,
+//! so streams only reach a server bound to port 80 (see [`MockServer::bind`]).
+
+pub mod engine;
+pub(crate) mod proxy;
+pub mod script;
+#[cfg(test)]
+pub(crate) mod testing;
+pub(crate) mod tls;
+
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use axum::body::Bytes;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{any, post};
+use axum::{Json, Router};
+use hyperliquid_sdk::Chain;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::dry_run;
+use crate::error::Result;
+use crate::models::{Meta, Side};
+use crate::price::{Px, Rounding};
+
+pub use engine::{Event, MatchingEngine, OrderRequest, Tif, Trigger};
+pub use script::{Script, SeedBook, SeedLevel};
+
+/// Buffered WebSocket pushes per subscriber before it starts missing them
+const FEED_CAPACITY: usize = 1024;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Server
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// A running mock server; stops when dropped
+pub struct MockServer {
+    addr: SocketAddr,
+    ca_path: PathBuf,
+    shared: Arc<Shared>,
+    task: JoinHandle<()>,
+}
+
+impl MockServer {
+    /// Serve `script` on a free port of 127.0.0.1 (HTTP and proxy only)
+    pub async fn start(script: Script) -> Result<Self> {
+        Self::bind(SocketAddr::from(([127, 0, 0, 1], 0)), script).await
+    }
+
+    /// Serve `script` on `addr`.
+    ///
+    /// Bind port 80 of a loopback address (`127.0.0.2:80`, ...) for the
+    /// WebSocket feed to be reachable through [`MockServer::endpoint`].
+    pub async fn bind(addr: SocketAddr, script: Script) -> Result<Self> {
+        let authority = Arc::new(tls::Authority::generate()?);
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+
+        let name = addr.to_string().replace(['.', ':'], "-");
+        let ca_path = std::env::temp_dir().join(format!("hl-mock-ca-{}-{}.pem", std::process::id(), name));
+        std::fs::write(&ca_path, authority.ca_pem())?;
+
+        let (feed, _) = broadcast::channel(FEED_CAPACITY);
+        let shared = Arc::new(Shared {
+            engine: Mutex::new(MatchingEngine::new(&script)?),
+            script,
+            feed,
+            actions: Mutex::new(Vec::new()),
+        });
+        let task = proxy::spawn(listener, router(shared.clone()), authority);
+
+        Ok(MockServer {
+            addr,
+            ca_path,
+            shared,
+            task,
+        })
+    }
+
+    /// Address the server listens on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// `ENDPOINT` for the examples (no port when bound to 80, as the SDK drops it)
+    pub fn endpoint(&self) -> String {
+        if self.addr.port() == 80 {
+            format!("http://{}", self.addr.ip())
+        } else {
+            format!("http://{}", self.addr)
+        }
+    }
+
+    /// `HTTPS_PROXY` for the examples
+    pub fn proxy_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// PEM file of the CA the proxy signs with (`SSL_CERT_FILE`)
+    pub fn ca_path(&self) -> &Path {
+        &self.ca_path
+    }
+
+    /// Environment that points a binary at this server
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![("ENDPOINT", self.endpoint())];
+        env.extend(proxy::env(self.addr, &self.ca_path.display().to_string()));
+        env
+    }
+
+    /// The matching engine, for inspecting or seeding state
+    pub fn engine(&self) -> MutexGuard<'_, MatchingEngine> {
+        self.shared.engine()
+    }
+
+    /// Signed actions received so far, oldest first
+    pub fn actions(&self) -> Vec<Value> {
+        self.shared.actions.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Send a raw message to every WebSocket subscribed to its channel
+    pub fn push(&self, message: Value) {
+        let _ = self.shared.feed.send(message);
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.ca_path);
+    }
+}
+
+/// State shared by every connection
+struct Shared {
+    script: Script,
+    engine: Mutex<MatchingEngine>,
+    feed: broadcast::Sender<Value>,
+    actions: Mutex<Vec<Value>>,
+}
+
+impl Shared {
+    fn engine(&self) -> MutexGuard<'_, MatchingEngine> {
+        self.engine.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Push engine events to the WebSocket feed
+    fn publish(&self, events: Vec<Event>) {
+        let mut books = BTreeSet::new();
+        for event in events {
+            let message = match event {
+                Event::Trade(trade) => json!({"channel": "trades", "data": [trade]}),
+                Event::Fill(fill) => json!({
+                    "channel": "userFills",
+                    "data": {"user": self.engine().address(), "fills": [fill]},
+                }),
+                Event::OrderUpdate(update) => json!({"channel": "orderUpdates", "data": [update]}),
+                Event::Book(coin) => {
+                    books.insert(coin);
+                    continue;
+                }
+            };
+            let _ = self.feed.send(message);
+        }
+        if books.is_empty() {
+            return;
+        }
+        let engine = self.engine();
+        for coin in &books {
+            if let Some(book) = engine.l2_book(coin) {
+                let _ = self.feed.send(json!({"channel": "l2Book", "data": book}));
+            }
+        }
+        let mids = engine.all_mids(None);
+        let _ = self.feed.send(json!({"channel": "allMids", "data": {"mids": mids}}));
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Info
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Answer an `/info` request; `None` for unknown request types
+    fn info(&self, request: &Value) -> Option<Value> {
+        let kind = request.get("type")?.as_str()?;
+        let coin = request.get("coin").and_then(|c| c.as_str()).unwrap_or_default();
+        let dex = request.get("dex").and_then(|d| d.as_str()).filter(|d| !d.is_empty());
+        let engine = self.engine();
+        if let Some(value) = engine.account_info(request) {
+            return Some(value);
+        }
+        let value = match kind {
+            "allMids" => json!(engine.all_mids(dex)),
+            "l2Book" => json!(engine.l2_book(coin)),
+            "recentTrades" => json!(engine.recent_trades(coin)),
+            "meta" if dex.is_some() => self.dex_meta(dex?)?,
+            "metaAndAssetCtxs" if !self.script.info.contains_key(kind) => {
+                let meta = self.script.info.get("meta").cloned().unwrap_or_default();
+                let ctxs = self.asset_ctxs(&engine, &meta);
+                json!([meta, ctxs])
+            }
+            _ => return self.script.info.get(kind).cloned(),
+        };
+        Some(value)
+    }
+
+    /// Meta of a HIP-3 dex, from `perpDexs` and `allPerpMetas`
+    fn dex_meta(&self, dex: &str) -> Option<Value> {
+        let index = self
+            .script
+            .info
+            .get("perpDexs")?
+            .as_array()?
+            .iter()
+            .position(|d| d.get("name").and_then(|n| n.as_str()) == Some(dex))?;
+        self.script.info.get("allPerpMetas")?.get(index).cloned()
+    }
+
+    /// Asset contexts marked at the engine's mids
+    fn asset_ctxs(&self, engine: &MatchingEngine, meta: &Value) -> Vec<Value> {
+        let universe = serde_json::from_value::<Meta>(meta.clone())
+            .map(|m| m.universe)
+            .unwrap_or_default();
+        universe
+            .iter()
+            .map(|asset| {
+                let mid = engine.mid(&asset.name);
+                let book = engine.l2_book(&asset.name);
+                let impact = book.map(|b| {
+                    let bid = b.levels.0.first().map(|l| l.px);
+                    let ask = b.levels.1.first().map(|l| l.px);
+                    json!([bid, ask])
+                });
+                json!({
+                    "funding": "0.0000125",
+                    "openInterest": "0.0",
+                    "prevDayPx": mid,
+                    "dayNtlVlm": "0.0",
+                    "premium": "0.0",
+                    "oraclePx": mid,
+                    "markPx": mid,
+                    "midPx": mid,
+                    "impactPxs": impact,
+                    "dayBaseVlm": "0.0",
+                })
+            })
+            .collect()
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Worker
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// `/preflight`: would the exchange accept this order?
+    fn preflight(&self, request: &Value) -> Value {
+        let engine = self.engine();
+        let asset = request.get("asset").and_then(|a| a.as_str()).unwrap_or_default();
+        let number = |key: &str| {
+            request
+                .get(key)
+                .and_then(|v| v.as_f64())
+                .and_then(|v| Decimal::try_from(v).ok())
+                .unwrap_or_default()
+        };
+        let is_buy = request
+            .get("side")
+            .and_then(|s| s.as_str())
+            .is_some_and(|s| matches!(s.to_lowercase().as_str(), "buy" | "b" | "long"));
+
+        let result = engine.registry().resolve(asset).map_err(|e| e.to_string()).and_then(|market| {
+            // Prices arrive as floats; round them the way the SDK does for orders
+            let raw = number("price");
+            let px = market.precision().px(raw, Rounding::Nearest).map(Px::value).unwrap_or(raw);
+            let order = OrderRequest {
+                asset: market.asset_id,
+                side: if is_buy { Side::Buy } else { Side::Sell },
+                px,
+                sz: number("size"),
+                reduce_only: false,
+                tif: Tif::Gtc,
+                trigger: None,
+                cloid: None,
+            };
+            engine.check(&order).map(|_| ())
+        });
+        match result {
+            Ok(()) => json!({"valid": true, "errors": []}),
+            Err(error) => json!({"valid": false, "errors": [error]}),
+        }
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // WebSocket
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Replies to one client message, updating its subscriptions
+    fn on_ws_request(&self, request: &Value, subscriptions: &mut Vec<Subscription>) -> Vec<Value> {
+        let method = request.get("method").and_then(|m| m.as_str()).unwrap_or_default();
+        if method == "ping" {
+            return vec![json!({"channel": "pong"})];
+        }
+        let Some(subscription) = request.get("subscription").and_then(Subscription::parse) else {
+            return vec![json!({"channel": "error", "data": format!("Invalid request: {}", request)})];
+        };
+        match method {
+            "subscribe" => {
+                let mut replies = vec![json!({"channel": "subscriptionResponse", "data": request})];
+                let scripted: Vec<Value> = self
+                    .script
+                    .ws
+                    .get(&subscription.channel)
+                    .into_iter()
+                    .flatten()
+                    .filter(|m| subscription.matches(m))
+                    .cloned()
+                    .collect();
+                if scripted.is_empty() {
+                    replies.extend(self.snapshot(&subscription));
+                } else {
+                    replies.extend(scripted);
+                }
+                subscriptions.push(subscription);
+                replies
+            }
+            "unsubscribe" => {
+                subscriptions.retain(|s| *s != subscription);
+                vec![json!({"channel": "subscriptionResponse", "data": request})]
+            }
+            _ => vec![json!({"channel": "error", "data": format!("Unknown method: {}", method)})],
+        }
+    }
+
+    /// Current state sent to a new subscriber of a channel without scripted messages
+    fn snapshot(&self, subscription: &Subscription) -> Option<Value> {
+        let engine = self.engine();
+        match subscription.channel.as_str() {
+            "l2Book" => {
+                let book = engine.l2_book(subscription.coins.first()?)?;
+                Some(json!({"channel": "l2Book", "data": book}))
+            }
+            "allMids" => Some(json!({"channel": "allMids", "data": {"mids": engine.all_mids(None)}})),
+            _ => None,
+        }
+    }
+}
+
+/// One channel a WebSocket client subscribed to
+#[derive(Debug, Clone, PartialEq)]
+struct Subscription {
+    channel: String,
+    /// Empty for every coin
+    coins: Vec<String>,
+}
+
+impl Subscription {
+    /// Parse `{"type", "params": {...}}` or the flat `{"type", "coin"}` form
+    fn parse(subscription: &Value) -> Option<Self> {
+        let channel = subscription.get("type")?.as_str()?.to_string();
+        let params = subscription.get("params").unwrap_or(subscription);
+        let coins = match (params.get("coins"), params.get("coin")) {
+            (Some(Value::Array(coins)), _) => coins.iter().filter_map(|c| c.as_str()).map(str::to_string).collect(),
+            (_, Some(Value::String(coin))) => vec![coin.clone()],
+            _ => Vec::new(),
+        };
+        Some(Subscription { channel, coins })
+    }
+
+    fn matches(&self, message: &Value) -> bool {
+        if message.get("channel").and_then(|c| c.as_str()) != Some(self.channel.as_str()) {
+            return false;
+        }
+        let data = message.get("data");
+        let coin = data
+            .and_then(|d| d.get("coin").or_else(|| d.get(0).and_then(|first| first.get("coin"))))
+            .and_then(|c| c.as_str());
+        match coin {
+            Some(coin) if !self.coins.is_empty() => self.coins.iter().any(|c| c == coin),
+            _ => true,
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// HTTP
+// ══════════════════════════════════════════════════════════════════════════════
+
+fn router(shared: Arc<Shared>) -> Router {
+    Router::new()
+        .route("/info", post(info))
+        .route("/exchange", post(exchange))
+        .route("/hypercore", post(hypercore))
+        .route("/evm", post(evm))
+        .route("/nanoreth", post(evm))
+        .route("/approval", post(approval))
+        .route("/preflight", post(preflight))
+        .route("/ws", any(ws))
+        .with_state(shared)
+}
+
+pub(crate) fn parse_body(body: &Bytes) -> std::result::Result<Value, (StatusCode, String)> {
+    serde_json::from_slice(body).map_err(|e| {
+        let message = format!("Failed to deserialize the JSON body: {}", e);
+        (StatusCode::BAD_REQUEST, message)
+    })
+}
+
+async fn info(State(shared): State<Arc<Shared>>, body: Bytes) -> Response {
+    let request = match parse_body(&body) {
+        Ok(request) => request,
+        Err(rejection) => return rejection.into_response(),
+    };
+    // The SDK sends HyperCore JSON-RPC to the public /info URL
+    if request.get("jsonrpc").is_some() {
+        return Json(json_rpc(&shared.script.hypercore, &request)).into_response();
+    }
+    match shared.info(&request) {
+        Some(value) => Json(value).into_response(),
+        None => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Failed to deserialize the JSON body into the target type",
+        )
+            .into_response(),
+    }
+}
+
+/// Both halves of the SDK's build/send exchange flow
+async fn exchange(State(shared): State<Arc<Shared>>, body: Bytes) -> Response {
+    let request = match parse_body(&body) {
+        Ok(request) => request,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let Some(action) = request.get("action") else {
+        return Json(json!({"error": "Missing action"})).into_response();
+    };
+
+    // Build: hand back the mainnet hash to sign (signatures are not checked)
+    if request.get("signature").is_none() {
+        let nonce = shared.engine().tick();
+        let hash = dry_run::action_hash(action, nonce, Chain::Mainnet);
+        return Json(json!({
+            "hash": hash.map(|h| h.to_string()).unwrap_or_else(|| format!("0x{:064x}", nonce)),
+            "nonce": nonce,
+            "action": action,
+        }))
+        .into_response();
+    }
+
+    shared
+        .actions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(action.clone());
+    let (response, events) = {
+        let mut engine = shared.engine();
+        let response = engine.execute(action);
+        (response, engine.drain_events())
+    };
+    shared.publish(events);
+    Json(response).into_response()
+}
+
+async fn hypercore(State(shared): State<Arc<Shared>>, body: Bytes) -> Response {
+    match parse_body(&body) {
+        Ok(request) => Json(json_rpc(&shared.script.hypercore, &request)).into_response(),
+        Err(rejection) => rejection.into_response(),
+    }
+}
+
+async fn evm(State(shared): State<Arc<Shared>>, body: Bytes) -> Response {
+    let request = match parse_body(&body) {
+        Ok(request) => request,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let methods = &shared.script.evm;
+    match request.as_array() {
+        Some(batch) => Json(batch.iter().map(|r| json_rpc(methods, r)).collect::<Vec<_>>()).into_response(),
+        None => Json(json_rpc(methods, &request)).into_response(),
+    }
+}
+
+async fn approval(State(shared): State<Arc<Shared>>, body: Bytes) -> Response {
+    let request = match parse_body(&body) {
+        Ok(request) => request,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let engine = shared.engine();
+    Json(json!({
+        "user": request.get("user"),
+        "approved": engine.builder_fee().is_some(),
+        "maxFeeRate": engine.builder_fee(),
+    }))
+    .into_response()
+}
+
+async fn preflight(State(shared): State<Arc<Shared>>, body: Bytes) -> Response {
+    match parse_body(&body) {
+        Ok(request) => Json(shared.preflight(&request)).into_response(),
+        Err(rejection) => rejection.into_response(),
+    }
+}
+
+/// Answer a JSON-RPC call from a method → result table
+fn json_rpc(methods: &std::collections::BTreeMap<String, Value>, request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request.get("method").and_then(|m| m.as_str()).unwrap_or_default();
+    match methods.get(method) {
+        Some(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        None => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {"code": -32601, "message": format!("Method not found: {}", method)},
+        }),
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// WebSocket
+// ══════════════════════════════════════════════════════════════════════════════
+
+async fn ws(State(shared): State<Arc<Shared>>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| feed(shared, socket))
+}
+
+async fn feed(shared: Arc<Shared>, mut socket: WebSocket) {
+    let mut pushes = shared.feed.subscribe();
+    let mut subscriptions = Vec::new();
+    loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                let Some(Ok(message)) = incoming else { break };
+                let Message::Text(text) = message else { continue };
+                let Ok(request) = serde_json::from_str::<Value>(&text) else { continue };
+                for reply in shared.on_ws_request(&request, &mut subscriptions) {
+                    if socket.send(Message::Text(reply.to_string().into())).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            pushed = pushes.recv() => {
+                let message = match pushed {
+                    Ok(message) => message,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if !subscriptions.iter().any(|s: &Subscription| s.matches(&message)) {
+                    continue;
+                }
+                if socket.send(Message::Text(message.to_string().into())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Listener shared by the mock server, the paper exchange and the dry-run guard.
+//!
+//! One port serves plain HTTP (and WebSocket upgrades) and acts as an HTTPS
+//! proxy: a `CONNECT` is accepted, TLS is terminated with a certificate from
+//! the [`Authority`], and the same router answers the requests inside the
+//! tunnel. Those requests carry a [`Tunnel`] extension naming the host the
+//! client meant to reach, and [`forward`] sends them on there.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use axum::body::{Body, Bytes};
+use axum::http::{header, HeaderMap, Method, Request, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use hyper::body::Incoming;
+use hyper_util::rt::TokioIo;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tower::ServiceExt;
+
+use super::tls::Authority;
+use crate::error::{Error, Result};
+
+/// Where the system's trusted roots usually are, when `SSL_CERT_FILE` is unset
+const SYSTEM_ROOTS: &[&str] = &[
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/ssl/cert.pem",
+];
+
+static INSTALLED: Mutex<Option<Result<Intercept>>> = Mutex::new(None);
+
+/// `host[:port]` a tunnelled request was sent to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tunnel(pub String);
+
+impl Tunnel {
+    /// `https://` URL of the original request
+    pub fn url(&self, path_and_query: &str) -> String {
+        let authority = self.0.strip_suffix(":443").unwrap_or(&self.0);
+        format!("https://{}{}", authority, path_and_query)
+    }
+}
+
+/// Environment that routes a process's HTTPS through the listener at `addr`,
+/// trusting `ca_file`
+pub(crate) fn env(addr: SocketAddr, ca_file: &str) -> Vec<(&'static str, String)> {
+    let proxy = format!("http://{}", addr);
+    vec![
+        ("HTTPS_PROXY", proxy.clone()),
+        ("https_proxy", proxy),
+        ("NO_PROXY", String::new()),
+        ("no_proxy", String::new()),
+        ("SSL_CERT_FILE", ca_file.to_string()),
+    ]
+}
+
+/// A listener the process's HTTPS was routed through before the runtime started
+pub(crate) struct Intercept {
+    listener: std::net::TcpListener,
+    pub(crate) addr: SocketAddr,
+    pub(crate) authority: Arc<Authority>,
+    /// Built before the environment changed, so it still reaches the real hosts
+    pub(crate) client: reqwest::Client,
+}
+
+impl Intercept {
+    /// Bind a free port of 127.0.0.1 and point the environment at it: the
+    /// proxy variables, and `SSL_CERT_FILE` naming a bundle (`hl-<name>-ca-<pid>.pem`
+    /// in the temp directory) of the trusted roots plus the listener's CA, so
+    /// clients built later, the SDK's included, trust both.
+    ///
+    /// The environment is shared by the whole process and changing it while
+    /// another thread reads it is a data race, so this runs (through
+    /// [`install`]) before the tokio runtime starts any threads.
+    fn bind(name: &str) -> Result<Self> {
+        let client = reqwest::Client::new();
+        let authority = Arc::new(Authority::generate()?);
+        let listener = std::net::TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], 0)))?;
+        let addr = listener.local_addr()?;
+
+        // The file stays for the life of the process: clients read it when built
+        let ca_path = std::env::temp_dir().join(format!("hl-{}-ca-{}.pem", name, std::process::id()));
+        let mut bundle = trusted_roots();
+        bundle.push_str(authority.ca_pem());
+        std::fs::write(&ca_path, bundle)?;
+        for (key, value) in env(addr, &ca_path.display().to_string()) {
+            std::env::set_var(key, value);
+        }
+        Ok(Intercept { listener, addr, authority, client })
+    }
+
+    /// The listener [`install`] bound, or why it could not
+    pub(crate) fn take() -> Result<Self> {
+        INSTALLED.lock().unwrap().take().unwrap_or_else(|| {
+            Err(Error::ConfigError(
+                "call hyperliquid_examples::prepare() at the start of main, before the tokio runtime".to_string(),
+            ))
+        })
+    }
+
+    /// Serve `app` on the listener until the returned task is aborted
+    pub(crate) fn serve(self, app: Router) -> Result<JoinHandle<()>> {
+        self.listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(self.listener)?;
+        Ok(spawn(listener, app, self.authority))
+    }
+}
+
+/// Bind an [`Intercept`] for [`Intercept::take`]; a failure is kept for it to return
+pub(crate) fn install(name: &str) {
+    *INSTALLED.lock().unwrap() = Some(Intercept::bind(name));
+}
+
+/// PEM roots the process trusts now: `SSL_CERT_FILE`, else the system bundle
+fn trusted_roots() -> String {
+    let files = std::env::var_os("SSL_CERT_FILE")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(SYSTEM_ROOTS.iter().map(PathBuf::from));
+    for file in files {
+        if let Ok(mut pem) = std::fs::read_to_string(file) {
+            if !pem.ends_with('\n') {
+                pem.push('\n');
+            }
+            return pem;
+        }
+    }
+    String::new()
+}
+
+/// Send a request on to where it was going with `client` and relay the answer
+pub(crate) async fn forward(
+    client: &reqwest::Client,
+    tunnel: Option<&Tunnel>,
+    method: Method,
+    uri: &Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let url = match tunnel {
+        Some(tunnel) => tunnel.url(path),
+        None if uri.scheme().is_some() => uri.to_string(),
+        None => return (StatusCode::BAD_GATEWAY, "Not a proxied request").into_response(),
+    };
+    let mut request = client.request(method, &url).body(body);
+    for (name, value) in headers.iter() {
+        if !matches!(*name, header::HOST | header::CONTENT_LENGTH | header::CONNECTION | header::TRANSFER_ENCODING) {
+            request = request.header(name, value);
+        }
+    }
+    let upstream = match request.send().await {
+        Ok(upstream) => upstream,
+        Err(e) => return (StatusCode::BAD_GATEWAY, format!("{} unreachable: {}", url, e)).into_response(),
+    };
+
+    let mut response = Response::builder().status(upstream.status());
+    for (name, value) in upstream.headers() {
+        if !matches!(*name, header::CONTENT_LENGTH | header::CONNECTION | header::TRANSFER_ENCODING) {
+            response = response.header(name, value);
+        }
+    }
+    match upstream.bytes().await {
+        Ok(body) => response.body(Body::from(body)).unwrap_or_default(),
+        Err(e) => (StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
+    }
+}
+
+/// Accept connections on `listener` until the returned task is aborted
+pub(crate) fn spawn(listener: TcpListener, app: Router, authority: Arc<Authority>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            if let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, app.clone(), authority.clone(), None));
+            }
+        }
+    })
+}
+
+/// Serve one connection: plain requests, WebSocket upgrades and `CONNECT` tunnels
+async fn serve<S>(stream: S, app: Router, authority: Arc<Authority>, tunnel: Option<Tunnel>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+        let app = app.clone();
+        let authority = authority.clone();
+        let tunnel = tunnel.clone();
+        async move {
+            if request.method() == Method::CONNECT {
+                return Ok::<_, Infallible>(connect(app, authority, request));
+            }
+            if let Some(tunnel) = tunnel {
+                request.extensions_mut().insert(tunnel);
+            }
+            app.oneshot(request.map(Body::new)).await
+        }
+    });
+    let _ = hyper::server::conn::http1::Builder::new()
+        .serve_connection(TokioIo::new(stream), service)
+        .with_upgrades()
+        .await;
+}
+
+/// Accept a `CONNECT`, then serve the router over TLS inside the tunnel
+fn connect(app: Router, authority: Arc<Authority>, request: Request<Incoming>) -> Response {
+    let target = request
+        .uri()
+        .authority()
+        .map(|a| a.to_string())
+        .unwrap_or_default();
+    tokio::spawn(async move {
+        let host = target.split(':').next().unwrap_or_default().to_string();
+        let Ok(acceptor) = authority.acceptor(&host) else {
+            return;
+        };
+        let Ok(upgraded) = hyper::upgrade::on(request).await else {
+            return;
+        };
+        if let Ok(stream) = acceptor.accept(TokioIo::new(upgraded)).await {
+            serve(stream, app, authority, Some(Tunnel(target))).await;
+        }
+    });
+    Response::new(Body::empty())
+}
@@ -0,0 +1,146 @@
+//! Fixtures the mock server answers from.
+//!
+//! A script is one JSON file. Every section is optional and a file only
+//! needs the keys it changes; [`Script::load`] lays it over the built-in
+//! market (`fixtures/default.json`: BTC, ETH, SOL, a delisted MATIC, two
+//! spot pairs and one HIP-3 dex):
+//!
+//! ```json
+//! {
+//!   "account": { "usdc": "500.0" },
+//!   "books": { "BTC": { "bids": [["90000.0", "1.0"]], "asks": [["90010.0", "1.0"]] } },
+//!   "info": { "userFees": { "userCrossRate": "0.0003", "userAddRate": "0.0001" } },
+//!   "ws": { "allMids": [{ "channel": "allMids", "data": { "mids": { "BTC": "90005.0" } } }] },
+//!   "hypercore": { "hl_getLatestBlockNumber": 1 },
+//!   "evm": { "eth_chainId": "0x3e6" }
+//! }
+//! ```
+//!
+//! `books` seed the matching engine, `info` answers `/info` request types the
+//! engine does not own, `ws` messages are replayed to each new subscriber of
+//! their channel, and `hypercore`/`evm` map JSON-RPC methods to results.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::models::{de, decode, Meta, PerpDex, SpotMeta};
+use crate::registry::RegistrySnapshot;
+
+const DEFAULT_SCRIPT: &str = include_str!("fixtures/default.json");
+
+/// Fixtures for one mock server
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Script {
+    /// Start of the mock clock (ms); every action advances it by one
+    pub time: Option<u64>,
+    pub account: Option<Account>,
+    /// Resting liquidity per coin
+    pub books: BTreeMap<String, SeedBook>,
+    /// Mid prices for coins without a book (spot pairs)
+    #[serde(deserialize_with = "de::decimal_map")]
+    pub mids: BTreeMap<String, Decimal>,
+    /// `/info` responses by request type
+    pub info: BTreeMap<String, Value>,
+    /// WebSocket messages by channel
+    pub ws: BTreeMap<String, Vec<Value>>,
+    /// HyperCore JSON-RPC results by method
+    pub hypercore: BTreeMap<String, Value>,
+    /// EVM JSON-RPC results by method
+    pub evm: BTreeMap<String, Value>,
+}
+
+/// The one account the mock trades for
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Account {
+    pub address: Option<String>,
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub usdc: Option<Decimal>,
+}
+
+/// Liquidity resting on one coin before any request arrives
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SeedBook {
+    pub bids: Vec<SeedLevel>,
+    pub asks: Vec<SeedLevel>,
+}
+
+/// One `[px, sz]` level of a seed book
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SeedLevel(
+    #[serde(deserialize_with = "de::decimal")] pub Decimal,
+    #[serde(deserialize_with = "de::decimal")] pub Decimal,
+);
+
+impl Script {
+    /// The built-in market
+    pub fn default_market() -> Self {
+        serde_json::from_str(DEFAULT_SCRIPT).expect("built-in mock fixtures are valid")
+    }
+
+    /// A script file laid over the built-in market
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::ConfigError(format!("{}: {}", path.display(), e)))?;
+        let script: Script = serde_json::from_str(&text)
+            .map_err(|e| Error::ConfigError(format!("{}: {}", path.display(), e)))?;
+        Ok(Self::default_market().merge(script))
+    }
+
+    /// `other` laid over `self`, key by key within each section
+    pub fn merge(mut self, other: Script) -> Self {
+        self.time = other.time.or(self.time);
+        self.account = match (self.account, other.account) {
+            (Some(base), Some(over)) => Some(Account {
+                address: over.address.or(base.address),
+                usdc: over.usdc.or(base.usdc),
+            }),
+            (base, over) => over.or(base),
+        };
+        self.books.extend(other.books);
+        self.mids.extend(other.mids);
+        self.info.extend(other.info);
+        self.ws.extend(other.ws);
+        self.hypercore.extend(other.hypercore);
+        self.evm.extend(other.evm);
+        self
+    }
+
+    /// Metadata for an [`AssetRegistry`](crate::AssetRegistry) of the scripted markets
+    pub fn snapshot(&self) -> Result<RegistrySnapshot> {
+        let meta: Meta = decode(self.info.get("meta").cloned().unwrap_or_default())?;
+        let spot_meta: SpotMeta = match self.info.get("spotMeta") {
+            Some(value) => decode(value.clone())?,
+            None => SpotMeta {
+                universe: Vec::new(),
+                tokens: Vec::new(),
+            },
+        };
+        let dexes: Vec<Option<PerpDex>> = match self.info.get("perpDexs") {
+            Some(value) => decode(value.clone())?,
+            None => Vec::new(),
+        };
+        let metas: Vec<Meta> = match self.info.get("allPerpMetas") {
+            Some(value) => decode(value.clone())?,
+            None => Vec::new(),
+        };
+        let dexes = dexes
+            .into_iter()
+            .zip(metas)
+            .enumerate()
+            .filter_map(|(index, (dex, meta))| Some((index as u32, dex?, meta)))
+            .collect();
+        Ok(RegistrySnapshot {
+            fetched_at: self.time.unwrap_or_default(),
+            meta,
+            spot_meta,
+            dexes,
+        })
+    }
+}
@@ -0,0 +1,22 @@
+//! Fixtures shared by the unit tests of the order helpers.
+
+use rust_decimal::Decimal;
+
+use super::MatchingEngine;
+use crate::models::{L2Book, Level};
+use crate::registry::Market;
+
+pub(crate) fn dec(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+/// BTC as the engine's registry lists it
+pub(crate) fn btc(engine: &MatchingEngine) -> Market {
+    engine.registry().resolve("BTC").unwrap().clone()
+}
+
+/// A BTC book of one level of 5 a side, a second after the engine's clock
+pub(crate) fn book(engine: &MatchingEngine, bid: &str, ask: &str) -> L2Book {
+    let level = |px: &str| Level { px: dec(px), sz: dec("5"), n: 1 };
+    L2Book { coin: "BTC".to_string(), time: engine.now() + 1000, levels: (vec![level(bid)], vec![level(ask)]) }
+}
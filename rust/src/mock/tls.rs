@@ -0,0 +1,86 @@
+//! Throwaway certificate authority for the HTTPS proxy.
+//!
+//! The SDK sends exchange, info and EVM requests to fixed public hosts
+//! (`send.hyperliquidapi.com`, `api.hyperliquid.xyz`, `rpc.hyperliquid.xyz`).
+//! The proxy answers `CONNECT` for any host and terminates TLS itself with a
+//! certificate for that host, signed by a CA generated at start-up. Clients
+//! trust the CA through `SSL_CERT_FILE`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, IsCa, KeyPair, KeyUsagePurpose,
+};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use tokio_rustls::rustls::{crypto::ring, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+use crate::error::{Error, Result};
+
+/// A CA and the leaf certificates it has issued so far
+pub struct Authority {
+    ca_pem: String,
+    ca_cert: Certificate,
+    ca_key: KeyPair,
+    leaves: Mutex<HashMap<String, TlsAcceptor>>,
+}
+
+impl Authority {
+    /// Generate a fresh CA
+    pub fn generate() -> Result<Self> {
+        let mut params = CertificateParams::new(Vec::<String>::new()).map_err(tls_error)?;
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "hyperliquid-examples mock CA");
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+        let ca_key = KeyPair::generate().map_err(tls_error)?;
+        let ca_cert = params.self_signed(&ca_key).map_err(tls_error)?;
+
+        Ok(Authority {
+            ca_pem: ca_cert.pem(),
+            ca_cert,
+            ca_key,
+            leaves: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// The CA certificate, PEM encoded
+    pub fn ca_pem(&self) -> &str {
+        &self.ca_pem
+    }
+
+    /// TLS acceptor presenting a certificate for `host` (issued on first use)
+    pub fn acceptor(&self, host: &str) -> Result<TlsAcceptor> {
+        let mut leaves = self.leaves.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(acceptor) = leaves.get(host) {
+            return Ok(acceptor.clone());
+        }
+
+        let mut params = CertificateParams::new(vec![host.to_string()]).map_err(tls_error)?;
+        params.distinguished_name.push(DnType::CommonName, host);
+        let key = KeyPair::generate().map_err(tls_error)?;
+        let cert = params
+            .signed_by(&key, &self.ca_cert, &self.ca_key)
+            .map_err(tls_error)?;
+
+        let chain = vec![CertificateDer::from(cert.der().to_vec())];
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key.serialize_der()));
+        let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(tls_error)?
+            .with_no_client_auth()
+            .with_single_cert(chain, key)
+            .map_err(tls_error)?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+        leaves.insert(host.to_string(), acceptor.clone());
+        Ok(acceptor)
+    }
+}
+
+fn tls_error(e: impl std::fmt::Display) -> Error {
+    Error::ConfigError(format!("mock TLS setup failed: {}", e))
+}
@@ -0,0 +1,86 @@
+//! Deserializers for Hyperliquid's decimal-string fields.
+//!
+//! The API sends prices, sizes and balances as JSON strings (`"97123.5"`).
+//! These helpers parse them into [`Decimal`] and reject anything that is not
+//! a number, so a malformed field fails the whole response instead of turning
+//! into `0.0`. Bare JSON numbers are accepted too, since a few endpoints mix
+//! the two.
+
+use std::fmt;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde::de::{self, Deserializer, Visitor};
+
+struct DecimalVisitor;
+
+impl<'de> Visitor<'de> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a decimal string or number")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Decimal, E> {
+        Decimal::from_str(v)
+            .or_else(|_| Decimal::from_scientific(v))
+            .map_err(|_| E::custom(format!("invalid decimal string {:?}", v)))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Decimal, E> {
+        Ok(Decimal::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Decimal, E> {
+        Ok(Decimal::from(v))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Decimal, E> {
+        Decimal::try_from(v).map_err(|_| E::custom(format!("invalid decimal number {}", v)))
+    }
+}
+
+/// Deserialize a required decimal field
+pub fn decimal<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+    deserializer.deserialize_any(DecimalVisitor)
+}
+
+/// Deserialize an optional decimal field (`null` or missing becomes `None`)
+pub fn opt_decimal<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Decimal>, D::Error> {
+    struct OptVisitor;
+
+    impl<'de> Visitor<'de> for OptVisitor {
+        type Value = Option<Decimal>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a decimal string, number or null")
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            decimal(d).map(Some)
+        }
+    }
+
+    deserializer.deserialize_option(OptVisitor)
+}
+
+/// Deserialize a map of coin to decimal string (`allMids`)
+pub fn decimal_map<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<std::collections::BTreeMap<String, Decimal>, D::Error> {
+    #[derive(serde::Deserialize)]
+    struct Wrapped(#[serde(deserialize_with = "decimal")] Decimal);
+
+    let map: std::collections::BTreeMap<String, Wrapped> = serde::Deserialize::deserialize(deserializer)?;
+    Ok(map.into_iter().map(|(k, Wrapped(v))| (k, v)).collect())
+}
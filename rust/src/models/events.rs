@@ -0,0 +1,227 @@
+//! One event type for every stream message, whichever transport sent it.
+//!
+//! WebSocket and gRPC deliver the same data in different shapes: book updates
+//! arrive inside HyperCore blocks (`block.events[i][1]`) with `px`/`sz` levels
+//! over WebSocket and as bare objects with `price`/`size` levels over gRPC, L2
+//! snapshots carry `levels: [bids, asks]` or `bids`/`asks` of `[px, sz, n]`,
+//! and so on. [`MarketEvent::parse`] turns any of them into a list of
+//! [`MarketEvent`]s holding the same models as the Info API (`px`, `sz`,
+//! `levels` best first), so code downstream doesn't care where they came from.
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{block_events, de, decode, is_grpc_snapshot, BookUpdate, Fill, GrpcL2Book, L2Book, OrderUpdate, Trade};
+use crate::error::{Error, Result};
+
+/// Mid price of one coin (one per coin of an `allMids` message)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Mid {
+    pub coin: String,
+    pub px: Decimal,
+}
+
+/// A block from the gRPC `blocks` stream (gRPC adds `_block_number` and
+/// `_timestamp` to every payload)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Block {
+    pub number: u64,
+    /// Block time (ms), when the message has one
+    pub time: Option<u64>,
+    pub hash: Option<String>,
+    /// The block as sent, for the fields not named above
+    pub data: Value,
+}
+
+impl Block {
+    fn from_value(value: &Value) -> Result<Self> {
+        let field = |names: &[&str]| names.iter().find_map(|name| value.get(*name));
+        let number = field(&["block_number", "_block_number", "height", "number"])
+            .and_then(|n| n.as_u64())
+            .ok_or_else(|| Error::ValidationError(format!("block without a number: {}", value)))?;
+        Ok(Block {
+            number,
+            time: field(&["time", "timestamp", "_timestamp", "block_time"]).and_then(|t| t.as_u64()),
+            hash: field(&["hash", "block_hash"]).and_then(|h| h.as_str()).map(str::to_string),
+            data: value.clone(),
+        })
+    }
+}
+
+/// A stream message in transport-independent form
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum MarketEvent {
+    /// Public trade (`trades`)
+    Trade(Trade),
+    /// Full L2 book of one coin (WebSocket `l2Book`, gRPC `l2_book`)
+    BookSnapshot(L2Book),
+    /// Changed levels of one coin (`book_updates`); a size of zero removes a level
+    BookDelta(BookUpdate),
+    /// Mid price of one coin (`allMids`)
+    Mid(Mid),
+    /// Block (gRPC `blocks`)
+    Block(Block),
+    /// Status change of one of your orders (`orderUpdates`)
+    OrderUpdate(OrderUpdate),
+    /// One of your fills (`userFills`)
+    Fill(Fill),
+}
+
+/// Which event an item decodes to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    Trade,
+    Snapshot,
+    Delta,
+    Order,
+    Fill,
+    Block,
+}
+
+impl MarketEvent {
+    /// Events of one stream message from `sdk.stream()` or `sdk.grpc()`.
+    ///
+    /// Tagged messages (`{"channel": ..., "data": ...}`) are read by channel;
+    /// bare gRPC payloads and HyperCore blocks by the shape of each item.
+    /// Channels without an event type here (`candle`, `l4_book`, ...) and
+    /// items of unknown shape give no events; an item that looks like one but
+    /// fails to decode is an error.
+    pub fn parse(message: &Value) -> Result<Vec<MarketEvent>> {
+        Self::parse_each(message).into_iter().collect()
+    }
+
+    /// [`parse`](Self::parse) item by item: an item that fails to decode is
+    /// an error in its place, and the other items of the message still decode
+    pub fn parse_each(message: &Value) -> Vec<Result<MarketEvent>> {
+        let channel = message.get("channel").and_then(|c| c.as_str());
+        let payload = message.get("data").unwrap_or(message);
+        let items = block_events(payload);
+        let all = |kind: Kind| -> Vec<Result<MarketEvent>> {
+            items.iter().map(|&(user, item)| event(kind, item, user)).collect()
+        };
+        match channel {
+            Some("trades") => all(Kind::Trade),
+            Some("l2Book" | "l2_book") => all(Kind::Snapshot),
+            Some("bookUpdates" | "book_updates") => items
+                .iter()
+                .map(|&(user, item)| event(if is_grpc_snapshot(item) { Kind::Snapshot } else { Kind::Delta }, item, user))
+                .collect(),
+            Some("allMids" | "all_mids") => mids(payload),
+            Some("orderUpdates") => all(Kind::Order),
+            Some("userFills") => fills(payload),
+            Some("blocks") => vec![event(Kind::Block, payload, None)],
+            Some(_) => Vec::new(),
+            None if payload.get("mids").is_some() => mids(payload),
+            None if payload.get("fills").is_some() => fills(payload),
+            None => {
+                let in_block = payload.get("block").is_some();
+                items
+                    .iter()
+                    .filter_map(|&(user, item)| kind_of(item, in_block).map(|kind| event(kind, item, user)))
+                    .collect()
+            }
+        }
+    }
+
+    /// WebSocket channel the event arrives on
+    pub fn channel(&self) -> &'static str {
+        match self {
+            MarketEvent::Trade(_) => "trades",
+            MarketEvent::BookSnapshot(_) => "l2Book",
+            MarketEvent::BookDelta(_) => "bookUpdates",
+            MarketEvent::Mid(_) => "allMids",
+            MarketEvent::Block(_) => "blocks",
+            MarketEvent::OrderUpdate(_) => "orderUpdates",
+            MarketEvent::Fill(_) => "userFills",
+        }
+    }
+
+    /// Coin the event is about (`None` for blocks)
+    pub fn coin(&self) -> Option<&str> {
+        match self {
+            MarketEvent::Trade(t) => Some(&t.coin),
+            MarketEvent::BookSnapshot(b) => Some(&b.coin),
+            MarketEvent::BookDelta(b) => Some(&b.coin),
+            MarketEvent::Mid(m) => Some(&m.coin),
+            MarketEvent::Block(_) => None,
+            MarketEvent::OrderUpdate(u) => Some(&u.order.coin),
+            MarketEvent::Fill(f) => Some(&f.coin),
+        }
+    }
+
+    /// Exchange time of the event (ms), when the message has one
+    pub fn time(&self) -> Option<u64> {
+        match self {
+            MarketEvent::Trade(t) => Some(t.time),
+            MarketEvent::BookSnapshot(b) => Some(b.time),
+            MarketEvent::BookDelta(b) => b.time,
+            MarketEvent::Mid(_) => None,
+            MarketEvent::Block(b) => b.time,
+            MarketEvent::OrderUpdate(u) => u.status_timestamp,
+            MarketEvent::Fill(f) => Some(f.time),
+        }
+    }
+}
+
+/// Kind of an untagged item by its fields; inside a HyperCore block, `levels`
+/// are changed levels, elsewhere they are a WebSocket `l2Book` snapshot
+fn kind_of(item: &Value, in_block: bool) -> Option<Kind> {
+    let has = |field: &str| item.get(field).is_some();
+    if has("order") && has("status") {
+        Some(Kind::Order)
+    } else if has("closedPnl") || has("crossed") {
+        Some(Kind::Fill)
+    } else if has("tid") && has("side") {
+        Some(Kind::Trade)
+    } else if has("levels") {
+        Some(if in_block { Kind::Delta } else { Kind::Snapshot })
+    } else if has("bids") || has("asks") {
+        Some(if is_grpc_snapshot(item) { Kind::Snapshot } else { Kind::Delta })
+    } else if has("block_number") || has("height") || (has("_block_number") && !has("coin")) {
+        Some(Kind::Block)
+    } else {
+        None
+    }
+}
+
+fn event(kind: Kind, item: &Value, user: Option<&str>) -> Result<MarketEvent> {
+    // gRPC flattens block events into the item, with the user as `_user`
+    let user = user.or_else(|| item.get("_user").and_then(|u| u.as_str()));
+    let owner = || user.map(str::to_string);
+    Ok(match kind {
+        Kind::Trade => MarketEvent::Trade(decode(item.clone())?),
+        Kind::Snapshot if item.get("levels").is_some() => MarketEvent::BookSnapshot(decode(item.clone())?),
+        Kind::Snapshot => MarketEvent::BookSnapshot(decode::<GrpcL2Book>(item.clone())?.into()),
+        Kind::Delta => MarketEvent::BookDelta(decode(item.clone())?),
+        Kind::Order => {
+            let mut update: OrderUpdate = decode(item.clone())?;
+            update.user = update.user.or_else(owner);
+            MarketEvent::OrderUpdate(update)
+        }
+        Kind::Fill => {
+            let mut fill: Fill = decode(item.clone())?;
+            fill.user = fill.user.or_else(owner);
+            MarketEvent::Fill(fill)
+        }
+        Kind::Block => MarketEvent::Block(Block::from_value(item)?),
+    })
+}
+
+/// `{"mids": {coin: px}}`
+fn mids(payload: &Value) -> Vec<Result<MarketEvent>> {
+    match de::decimal_map(payload.get("mids").cloned().unwrap_or(Value::Null)) {
+        Ok(mids) => mids.into_iter().map(|(coin, px)| Ok(MarketEvent::Mid(Mid { coin, px }))).collect(),
+        Err(e) => vec![Err(e.into())],
+    }
+}
+
+/// `{"user": ..., "fills": [...]}`; each fill gets the message's user
+fn fills(payload: &Value) -> Vec<Result<MarketEvent>> {
+    let user = payload.get("user").and_then(|u| u.as_str());
+    match payload.get("fills") {
+        Some(fills) => block_events(fills).into_iter().map(|(_, item)| event(Kind::Fill, item, user)).collect(),
+        None => block_events(payload).into_iter().map(|(owner, item)| event(Kind::Fill, item, owner.or(user))).collect(),
+    }
+}
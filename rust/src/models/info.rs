@@ -0,0 +1,185 @@
+//! Info API client that returns typed models.
+
+use std::collections::BTreeMap;
+
+use hyperliquid_sdk::{HyperliquidSDK, Info};
+use rust_decimal::Decimal;
+
+use super::{
+    de, decode, Candle, ClearinghouseState, Delegation, Fill, L2Book, Meta, OpenOrder, PerpDex,
+    PredictedFunding, SpotMeta, Trade, TwapHistory, TwapSliceFill, UserFees, VaultDetails, VaultEquity,
+    VaultSummary,
+};
+use crate::error::{Error, Result};
+
+/// Wraps the SDK's [`Info`] client and decodes each response into its model
+pub struct TypedInfo {
+    info: Info,
+}
+
+impl TypedInfo {
+    /// Wrap an existing Info client
+    pub fn new(info: Info) -> Self {
+        Self { info }
+    }
+
+    /// Info client for an SDK instance
+    pub fn from_sdk(sdk: &HyperliquidSDK) -> Self {
+        Self::new(sdk.info())
+    }
+
+    /// Underlying untyped client, for queries without a model
+    pub fn raw(&self) -> &Info {
+        &self.info
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Market Data
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Mid prices by coin
+    pub async fn all_mids(&self, dex: Option<&str>) -> Result<BTreeMap<String, Decimal>> {
+        let value = self.info.all_mids(dex).await?;
+        Ok(de::decimal_map(value)?)
+    }
+
+    /// Mid price of one coin
+    pub async fn mid(&self, coin: &str) -> Result<Decimal> {
+        self.all_mids(None)
+            .await?
+            .remove(coin)
+            .ok_or_else(|| Error::ValidationError(format!("No mid price for {}", coin)))
+    }
+
+    /// L2 order book snapshot
+    pub async fn l2_book(&self, coin: &str) -> Result<L2Book> {
+        decode(self.info.l2_book(coin, None, None).await?)
+    }
+
+    /// Most recent public trades
+    pub async fn recent_trades(&self, coin: &str) -> Result<Vec<Trade>> {
+        decode(self.info.recent_trades(coin).await?)
+    }
+
+    /// Candles between `start_time` and `end_time` (ms)
+    pub async fn candles(
+        &self,
+        coin: &str,
+        interval: &str,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Candle>> {
+        decode(self.info.candles(coin, interval, start_time, end_time).await?)
+    }
+
+    /// Predicted funding rates for every coin
+    pub async fn predicted_fundings(&self) -> Result<Vec<PredictedFunding>> {
+        decode(self.info.predicted_fundings().await?)
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Metadata
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Perp universe
+    pub async fn meta(&self) -> Result<Meta> {
+        decode(self.info.meta().await?)
+    }
+
+    /// Spot universe
+    pub async fn spot_meta(&self) -> Result<SpotMeta> {
+        decode(self.info.spot_meta().await?)
+    }
+
+    /// Perp universe of every dex, in `perpDexs` order (the main dex first)
+    pub async fn all_perp_metas(&self) -> Result<Vec<Meta>> {
+        decode(self.info.all_perp_metas().await?)
+    }
+
+    /// HIP-3 perp dexes (the main dex, returned as `null`, is skipped)
+    pub async fn perp_dexes(&self) -> Result<Vec<PerpDex>> {
+        let dexes: Vec<Option<PerpDex>> = decode(self.info.perp_dexes().await?)?;
+        Ok(dexes.into_iter().flatten().collect())
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // User Data
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Perp account state
+    pub async fn clearinghouse_state(
+        &self,
+        user: &str,
+        dex: Option<&str>,
+    ) -> Result<ClearinghouseState> {
+        decode(self.info.clearinghouse_state(user, dex).await?)
+    }
+
+    /// Resting orders
+    pub async fn open_orders(&self, user: &str, dex: Option<&str>) -> Result<Vec<OpenOrder>> {
+        decode(self.info.open_orders(user, dex).await?)
+    }
+
+    /// Resting orders with order type, tif and trigger details
+    pub async fn frontend_open_orders(
+        &self,
+        user: &str,
+        dex: Option<&str>,
+    ) -> Result<Vec<OpenOrder>> {
+        decode(self.info.frontend_open_orders(user, dex).await?)
+    }
+
+    /// Most recent fills
+    pub async fn user_fills(&self, user: &str) -> Result<Vec<Fill>> {
+        decode(self.info.user_fills(user, false).await?)
+    }
+
+    /// Fills between `start_time` and `end_time` (ms)
+    pub async fn user_fills_by_time(
+        &self,
+        user: &str,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<Vec<Fill>> {
+        decode(self.info.user_fills_by_time(user, start_time, end_time).await?)
+    }
+
+    /// Fee rates
+    pub async fn user_fees(&self, user: &str) -> Result<UserFees> {
+        decode(self.info.user_fees(user).await?)
+    }
+
+    /// Native TWAPs, running and past
+    pub async fn user_twap_history(&self, user: &str) -> Result<Vec<TwapHistory>> {
+        decode(self.info.user_twap_history(user).await?)
+    }
+
+    /// Fills of native TWAP slices, newest first
+    pub async fn user_twap_slice_fills(&self, user: &str) -> Result<Vec<TwapSliceFill>> {
+        decode(self.info.user_twap_slice_fills(user, None).await?)
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Vaults & Staking
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// All vaults
+    pub async fn vault_summaries(&self) -> Result<Vec<VaultSummary>> {
+        decode(self.info.vault_summaries().await?)
+    }
+
+    /// Details for one vault
+    pub async fn vault_details(&self, vault: &str, user: Option<&str>) -> Result<VaultDetails> {
+        decode(self.info.vault_details(vault, user).await?)
+    }
+
+    /// A user's vault deposits
+    pub async fn user_vault_equities(&self, user: &str) -> Result<Vec<VaultEquity>> {
+        decode(self.info.user_vault_equities(user).await?)
+    }
+
+    /// A user's staking delegations
+    pub async fn delegations(&self, user: &str) -> Result<Vec<Delegation>> {
+        decode(self.info.delegations(user).await?)
+    }
+}
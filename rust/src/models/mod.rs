@@ -0,0 +1,842 @@
+//! Typed Info API responses.
+//!
+//! The Info API returns prices, sizes and balances as decimal strings. The
+//! types here deserialize them into [`Decimal`] through [`de`], so a missing
+//! or malformed field is reported as an error rather than read as zero.
+//! Use [`TypedInfo`] to query and decode in one step, or [`decode`] on a
+//! `Value` you already have (for example a WebSocket payload).
+
+pub mod de;
+mod events;
+mod info;
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Result;
+
+pub use events::{Block, MarketEvent, Mid};
+pub use info::TypedInfo;
+
+/// Decode a JSON value into a typed model
+pub fn decode<T: DeserializeOwned>(value: Value) -> Result<T> {
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Items of a stream message for `channel`.
+///
+/// Every stream callback sees every message, so a message tagged with another
+/// channel yields `None`. The payload may be one object, a list
+/// (`{"channel":"trades","data":[...]}`) or a HyperCore block
+/// (`{"block":{"events":[[user, event], ...]}}`); each is flattened into a list.
+pub fn stream_items<T: DeserializeOwned>(message: &Value, channel: &str) -> Option<Result<Vec<T>>> {
+    if let Some(tag) = message.get("channel").and_then(|c| c.as_str()) {
+        if tag != channel {
+            return None;
+        }
+    }
+    let payload = message.get("data").unwrap_or(message);
+    Some(block_events(payload).into_iter().map(|(_, item)| decode(item.clone())).collect())
+}
+
+/// Objects of a stream payload with the user they belong to: HyperCore block
+/// events (`[user, event]` pairs), a list, or one object
+pub(crate) fn block_events(payload: &Value) -> Vec<(Option<&str>, &Value)> {
+    if let Some(events) = payload
+        .get("block")
+        .and_then(|b| b.get("events"))
+        .and_then(|e| e.as_array())
+    {
+        return events
+            .iter()
+            .map(|event| match event.as_array() {
+                Some(pair) if pair.len() == 2 => (pair[0].as_str(), &pair[1]),
+                _ => (None, event),
+            })
+            .collect();
+    }
+    match payload.as_array() {
+        Some(list) => list.iter().map(|item| (None, item)).collect(),
+        None => vec![(None, payload)],
+    }
+}
+
+/// gRPC `l2_book` snapshots carry a block number and `[px, sz, n]` levels;
+/// `book_updates` carry `{price, size}` objects
+pub(crate) fn is_grpc_snapshot(item: &Value) -> bool {
+    let first_level = ["bids", "asks"]
+        .iter()
+        .find_map(|side| item.get(side).and_then(|l| l.as_array()).and_then(|l| l.first()));
+    item.get("block_number").is_some() || first_level.is_some_and(|level| level.is_array())
+}
+
+/// Order or trade side (`"B"` / `"A"` on the wire)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Side {
+    #[serde(rename = "B")]
+    Buy,
+    #[serde(rename = "A")]
+    Sell,
+}
+
+impl Side {
+    /// Whether this is the buy side
+    pub fn is_buy(self) -> bool {
+        self == Side::Buy
+    }
+
+    /// Side as `BUY` / `SELL`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Account State
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Account-level margin figures
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginSummary {
+    #[serde(deserialize_with = "de::decimal")]
+    pub account_value: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub total_ntl_pos: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub total_raw_usd: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub total_margin_used: Decimal,
+}
+
+/// Perp account state (`clearinghouseState`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearinghouseState {
+    pub margin_summary: MarginSummary,
+    pub cross_margin_summary: MarginSummary,
+    #[serde(deserialize_with = "de::decimal")]
+    pub cross_maintenance_margin_used: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub withdrawable: Decimal,
+    pub asset_positions: Vec<AssetPosition>,
+    pub time: u64,
+}
+
+impl ClearinghouseState {
+    /// Position for a coin, if one is open
+    pub fn position(&self, coin: &str) -> Option<&Position> {
+        self.asset_positions
+            .iter()
+            .map(|p| &p.position)
+            .find(|p| p.coin == coin)
+    }
+}
+
+/// Entry in `assetPositions`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetPosition {
+    /// Position mode, `oneWay` for perps
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub position: Position,
+}
+
+/// One open perp position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub coin: String,
+    /// Signed size: positive long, negative short
+    #[serde(deserialize_with = "de::decimal")]
+    pub szi: Decimal,
+    pub leverage: Leverage,
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub entry_px: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal")]
+    pub position_value: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub unrealized_pnl: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub return_on_equity: Decimal,
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub liquidation_px: Option<Decimal>,
+    #[serde(deserialize_with = "de::decimal")]
+    pub margin_used: Decimal,
+    pub max_leverage: u32,
+    #[serde(default)]
+    pub cum_funding: Option<CumFunding>,
+}
+
+impl Position {
+    /// Whether the position is long
+    pub fn is_long(&self) -> bool {
+        self.szi.is_sign_positive() && !self.szi.is_zero()
+    }
+}
+
+/// Leverage setting of a position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Leverage {
+    /// `cross` or `isolated`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: u32,
+    /// Isolated margin allocated (isolated positions only)
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub raw_usd: Option<Decimal>,
+}
+
+/// Funding paid on a position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CumFunding {
+    #[serde(deserialize_with = "de::decimal")]
+    pub all_time: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub since_open: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub since_change: Decimal,
+}
+
+/// Resting order (`openOrders`; the extra fields come from `frontendOpenOrders`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenOrder {
+    pub coin: String,
+    pub side: Side,
+    #[serde(deserialize_with = "de::decimal")]
+    pub limit_px: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub sz: Decimal,
+    pub oid: u64,
+    pub timestamp: u64,
+    #[serde(deserialize_with = "de::decimal")]
+    pub orig_sz: Decimal,
+    #[serde(default)]
+    pub cloid: Option<String>,
+    #[serde(default)]
+    pub order_type: Option<String>,
+    #[serde(default)]
+    pub tif: Option<String>,
+    #[serde(default)]
+    pub reduce_only: Option<bool>,
+    #[serde(default)]
+    pub is_trigger: Option<bool>,
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub trigger_px: Option<Decimal>,
+    #[serde(default)]
+    pub is_position_tpsl: Option<bool>,
+}
+
+/// Order status change (`orderUpdates` stream, HyperCore order events)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderUpdate {
+    pub order: OpenOrder,
+    /// `open`, `filled`, `canceled`, `triggered`, `rejected`, ...
+    pub status: String,
+    #[serde(default)]
+    pub status_timestamp: Option<u64>,
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+/// User fill (`userFills`, `userFillsByTime`, the `userFills` stream)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fill {
+    pub coin: String,
+    #[serde(deserialize_with = "de::decimal")]
+    pub px: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub sz: Decimal,
+    pub side: Side,
+    pub time: u64,
+    #[serde(deserialize_with = "de::decimal")]
+    pub start_position: Decimal,
+    /// Direction, e.g. `Open Long`, `Close Short`
+    pub dir: String,
+    #[serde(deserialize_with = "de::decimal")]
+    pub closed_pnl: Decimal,
+    pub hash: String,
+    pub oid: u64,
+    /// Whether the fill took liquidity
+    pub crossed: bool,
+    #[serde(deserialize_with = "de::decimal")]
+    pub fee: Decimal,
+    pub tid: u64,
+    pub fee_token: String,
+    #[serde(default)]
+    pub cloid: Option<String>,
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub builder_fee: Option<Decimal>,
+    /// Filling user (HyperCore trade events)
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+/// User fee schedule (`userFees`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserFees {
+    /// Taker rate for perps
+    #[serde(deserialize_with = "de::decimal")]
+    pub user_cross_rate: Decimal,
+    /// Maker rate for perps
+    #[serde(deserialize_with = "de::decimal")]
+    pub user_add_rate: Decimal,
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub user_spot_cross_rate: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub user_spot_add_rate: Option<Decimal>,
+}
+
+/// Parameters and progress of a native TWAP (`state` of `userTwapHistory`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapState {
+    pub coin: String,
+    pub user: String,
+    pub side: Side,
+    #[serde(deserialize_with = "de::decimal")]
+    pub sz: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub executed_sz: Decimal,
+    /// Notional executed so far (px × sz)
+    #[serde(deserialize_with = "de::decimal")]
+    pub executed_ntl: Decimal,
+    pub minutes: u64,
+    pub reduce_only: bool,
+    pub randomize: bool,
+    /// Start time (ms)
+    pub timestamp: u64,
+}
+
+/// Where a native TWAP stands: `activated`, `finished`, `terminated` or `error`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwapStatus {
+    pub status: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// One native TWAP of a user (`userTwapHistory`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapHistory {
+    /// Time of the last change (seconds)
+    pub time: u64,
+    pub state: TwapState,
+    pub status: TwapStatus,
+    /// Missing on TWAPs from before ids were reported
+    #[serde(default)]
+    pub twap_id: Option<u64>,
+}
+
+/// A fill of one slice of a native TWAP (`userTwapSliceFills`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapSliceFill {
+    pub fill: Fill,
+    pub twap_id: u64,
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Market Data
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// One price level of an L2 book
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Level {
+    #[serde(deserialize_with = "de::decimal")]
+    pub px: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub sz: Decimal,
+    /// Number of orders at this level
+    pub n: u32,
+}
+
+/// L2 order book snapshot (`l2Book` query and WebSocket channel)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct L2Book {
+    pub coin: String,
+    pub time: u64,
+    /// `[bids, asks]`, best first
+    pub levels: (Vec<Level>, Vec<Level>),
+}
+
+impl L2Book {
+    /// Bid levels, best first
+    pub fn bids(&self) -> &[Level] {
+        &self.levels.0
+    }
+
+    /// Ask levels, best first
+    pub fn asks(&self) -> &[Level] {
+        &self.levels.1
+    }
+
+    /// Best bid level
+    pub fn best_bid(&self) -> Option<&Level> {
+        self.levels.0.first()
+    }
+
+    /// Best ask level
+    pub fn best_ask(&self) -> Option<&Level> {
+        self.levels.1.first()
+    }
+
+    /// Best ask minus best bid
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.px - self.best_bid()?.px)
+    }
+
+    /// Midpoint of the best bid and ask
+    pub fn mid(&self) -> Option<Decimal> {
+        Some((self.best_ask()?.px + self.best_bid()?.px) / Decimal::TWO)
+    }
+}
+
+/// One `[px, sz, n]` level of a gRPC L2 book
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrpcLevel(
+    #[serde(deserialize_with = "de::decimal")] pub Decimal,
+    #[serde(deserialize_with = "de::decimal")] pub Decimal,
+    pub u32,
+);
+
+/// L2 order book snapshot from the gRPC `l2_book` stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrpcL2Book {
+    pub coin: String,
+    pub time: u64,
+    pub block_number: u64,
+    pub bids: Vec<GrpcLevel>,
+    pub asks: Vec<GrpcLevel>,
+}
+
+impl From<GrpcL2Book> for L2Book {
+    fn from(book: GrpcL2Book) -> Self {
+        let level = |GrpcLevel(px, sz, n)| Level { px, sz, n };
+        L2Book {
+            coin: book.coin,
+            time: book.time,
+            levels: (
+                book.bids.into_iter().map(level).collect(),
+                book.asks.into_iter().map(level).collect(),
+            ),
+        }
+    }
+}
+
+/// One changed level of a `book_updates` message; a size of zero removes it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelChange {
+    #[serde(alias = "price", deserialize_with = "de::decimal")]
+    pub px: Decimal,
+    #[serde(alias = "size", deserialize_with = "de::decimal")]
+    pub sz: Decimal,
+    /// Number of orders at this level, when the stream sends it
+    #[serde(default)]
+    pub n: Option<u32>,
+}
+
+/// Changed levels of one coin (`book_updates` stream).
+///
+/// The WebSocket stream sends `levels: [bids, asks]` of `{px, sz, n}` inside
+/// HyperCore blocks, gRPC sends `bids` and `asks` of `{price, size}`; both
+/// decode to the same struct.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawBookUpdate")]
+pub struct BookUpdate {
+    pub coin: String,
+    #[serde(default)]
+    pub time: Option<u64>,
+    pub bids: Vec<LevelChange>,
+    pub asks: Vec<LevelChange>,
+}
+
+#[derive(Deserialize)]
+struct RawBookUpdate {
+    coin: String,
+    #[serde(default)]
+    time: Option<u64>,
+    #[serde(default)]
+    levels: Option<(Vec<LevelChange>, Vec<LevelChange>)>,
+    #[serde(default)]
+    bids: Vec<LevelChange>,
+    #[serde(default)]
+    asks: Vec<LevelChange>,
+}
+
+impl From<RawBookUpdate> for BookUpdate {
+    fn from(raw: RawBookUpdate) -> Self {
+        let (bids, asks) = raw.levels.unwrap_or((raw.bids, raw.asks));
+        BookUpdate { coin: raw.coin, time: raw.time, bids, asks }
+    }
+}
+
+/// One resting order of a gRPC `l4_book` snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct L4Order {
+    pub user: String,
+    pub coin: String,
+    pub side: Side,
+    #[serde(deserialize_with = "de::decimal")]
+    pub limit_px: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub sz: Decimal,
+    pub oid: u64,
+    pub timestamp: u64,
+    #[serde(default)]
+    pub is_trigger: bool,
+    #[serde(default)]
+    pub reduce_only: bool,
+    #[serde(default)]
+    pub tif: Option<String>,
+    #[serde(default)]
+    pub cloid: Option<String>,
+}
+
+/// Every resting order of one coin (`{"type": "snapshot"}` on the gRPC `l4_book` stream)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct L4Snapshot {
+    pub coin: String,
+    pub time: u64,
+    pub height: u64,
+    /// Best first, in queue order within a level
+    pub bids: Vec<L4Order>,
+    pub asks: Vec<L4Order>,
+}
+
+/// Order status changes and book changes of one block (the `data` of a
+/// `{"type": "diff"}` message on the gRPC `l4_book` stream)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct L4Diff {
+    #[serde(default)]
+    pub order_statuses: Vec<OrderUpdate>,
+    #[serde(default)]
+    pub book_diffs: Vec<BookDiff>,
+}
+
+/// One order entering, changing size in or leaving the book
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookDiff {
+    pub user: String,
+    pub oid: u64,
+    pub coin: String,
+    /// Not always sent; the order status of the same block has it
+    #[serde(default)]
+    pub side: Option<Side>,
+    #[serde(deserialize_with = "de::decimal")]
+    pub px: Decimal,
+    pub raw_book_diff: RawBookDiff,
+}
+
+/// `{"new": {"sz"}}`, `{"update": {"origSz", "newSz"}}` or `"remove"`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RawBookDiff {
+    New {
+        #[serde(deserialize_with = "de::decimal")]
+        sz: Decimal,
+    },
+    #[serde(rename_all = "camelCase")]
+    Update {
+        #[serde(deserialize_with = "de::decimal")]
+        orig_sz: Decimal,
+        #[serde(deserialize_with = "de::decimal")]
+        new_sz: Decimal,
+    },
+    Remove,
+}
+
+/// Public trade (`recentTrades` and the `trades` stream)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    pub coin: String,
+    pub side: Side,
+    #[serde(deserialize_with = "de::decimal")]
+    pub px: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub sz: Decimal,
+    pub time: u64,
+    pub hash: String,
+    pub tid: u64,
+    /// `[buyer, seller]`
+    #[serde(default)]
+    pub users: Option<(String, String)>,
+}
+
+/// OHLCV candle (`candleSnapshot` and the `candle` stream)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    #[serde(rename = "t")]
+    pub open_time: u64,
+    #[serde(rename = "T")]
+    pub close_time: u64,
+    #[serde(rename = "s")]
+    pub coin: String,
+    #[serde(rename = "i")]
+    pub interval: String,
+    #[serde(rename = "o", deserialize_with = "de::decimal")]
+    pub open: Decimal,
+    #[serde(rename = "h", deserialize_with = "de::decimal")]
+    pub high: Decimal,
+    #[serde(rename = "l", deserialize_with = "de::decimal")]
+    pub low: Decimal,
+    #[serde(rename = "c", deserialize_with = "de::decimal")]
+    pub close: Decimal,
+    #[serde(rename = "v", deserialize_with = "de::decimal")]
+    pub volume: Decimal,
+    /// Number of trades
+    #[serde(rename = "n")]
+    pub trades: u64,
+}
+
+/// Venue funding entry inside [`PredictedFunding`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    #[serde(deserialize_with = "de::decimal")]
+    pub funding_rate: Decimal,
+    pub next_funding_time: u64,
+    /// Funding interval in hours, when the venue reports it
+    #[serde(default)]
+    pub funding_interval_hours: Option<u32>,
+}
+
+/// Predicted funding for one coin: `[coin, [[venue, rate | null], ...]]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PredictedFunding(pub String, pub Vec<(String, Option<FundingRate>)>);
+
+impl PredictedFunding {
+    /// Coin name
+    pub fn coin(&self) -> &str {
+        &self.0
+    }
+
+    /// Rate on a venue (`HlPerp`, `BinPerp`, `BybitPerp`)
+    pub fn venue(&self, venue: &str) -> Option<&FundingRate> {
+        self.1
+            .iter()
+            .find(|(name, _)| name == venue)
+            .and_then(|(_, rate)| rate.as_ref())
+    }
+
+    /// Hyperliquid's own predicted rate
+    pub fn hyperliquid(&self) -> Option<&FundingRate> {
+        self.venue("HlPerp")
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Metadata
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Perp universe (`meta`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    pub universe: Vec<AssetInfo>,
+    /// `[[id, table], ...]`
+    #[serde(default)]
+    pub margin_tables: Vec<(u32, MarginTable)>,
+}
+
+impl Meta {
+    /// Asset info by coin name
+    pub fn asset(&self, name: &str) -> Option<&AssetInfo> {
+        self.universe.iter().find(|a| a.name == name)
+    }
+
+    /// Margin table by id
+    pub fn margin_table(&self, id: u32) -> Option<&MarginTable> {
+        self.margin_tables
+            .iter()
+            .find(|(table_id, _)| *table_id == id)
+            .map(|(_, table)| table)
+    }
+}
+
+/// One perp asset in [`Meta`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetInfo {
+    pub name: String,
+    pub sz_decimals: u32,
+    pub max_leverage: u32,
+    #[serde(default)]
+    pub only_isolated: bool,
+    #[serde(default)]
+    pub is_delisted: bool,
+    #[serde(default)]
+    pub margin_table_id: Option<u32>,
+    /// `strictIsolated` / `noCross` on some markets
+    #[serde(default)]
+    pub margin_mode: Option<String>,
+}
+
+/// Leverage tiers by position notional
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginTable {
+    pub description: String,
+    pub margin_tiers: Vec<MarginTier>,
+}
+
+/// One tier of a [`MarginTable`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginTier {
+    #[serde(deserialize_with = "de::decimal")]
+    pub lower_bound: Decimal,
+    pub max_leverage: u32,
+}
+
+/// Spot universe (`spotMeta`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpotMeta {
+    pub universe: Vec<SpotPair>,
+    pub tokens: Vec<SpotToken>,
+}
+
+impl SpotMeta {
+    /// Token by index
+    pub fn token(&self, index: u32) -> Option<&SpotToken> {
+        self.tokens.iter().find(|t| t.index == index)
+    }
+
+    /// Human-readable `BASE/QUOTE` name for a pair
+    pub fn pair_name(&self, pair: &SpotPair) -> Option<String> {
+        let base = self.token(pair.tokens.0)?;
+        let quote = self.token(pair.tokens.1)?;
+        Some(format!("{}/{}", base.name, quote.name))
+    }
+}
+
+/// Spot trading pair; non-canonical pairs are named `@index`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotPair {
+    pub name: String,
+    /// `(base, quote)` token indices
+    pub tokens: (u32, u32),
+    pub index: u32,
+    pub is_canonical: bool,
+}
+
+/// Spot token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotToken {
+    pub name: String,
+    pub sz_decimals: u32,
+    pub wei_decimals: u32,
+    pub index: u32,
+    pub token_id: String,
+    pub is_canonical: bool,
+    #[serde(default)]
+    pub evm_contract: Option<EvmContract>,
+    #[serde(default)]
+    pub full_name: Option<String>,
+}
+
+/// HyperEVM contract linked to a spot token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvmContract {
+    pub address: String,
+    pub evm_extra_wei_decimals: i32,
+}
+
+/// HIP-3 builder-deployed perp dex (`perpDexs`; the first entry is `null` for the main dex)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpDex {
+    pub name: String,
+    #[serde(default)]
+    pub full_name: Option<String>,
+    #[serde(default)]
+    pub deployer: Option<String>,
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Vaults & Staking
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Vault listing entry (`vaultSummaries`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultSummary {
+    pub name: String,
+    pub vault_address: String,
+    pub leader: String,
+    #[serde(deserialize_with = "de::decimal")]
+    pub tvl: Decimal,
+    pub is_closed: bool,
+    #[serde(default)]
+    pub create_time_millis: Option<u64>,
+}
+
+/// Vault details (`vaultDetails`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDetails {
+    pub name: String,
+    pub vault_address: String,
+    pub leader: String,
+    #[serde(default)]
+    pub description: String,
+    /// Annualized return as a fraction
+    #[serde(deserialize_with = "de::decimal")]
+    pub apr: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub leader_fraction: Decimal,
+    #[serde(deserialize_with = "de::decimal")]
+    pub leader_commission: Decimal,
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub max_distributable: Option<Decimal>,
+    #[serde(default, deserialize_with = "de::opt_decimal")]
+    pub max_withdrawable: Option<Decimal>,
+    pub is_closed: bool,
+    #[serde(default)]
+    pub allow_deposits: bool,
+}
+
+/// A user's equity in one vault (`userVaultEquities`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultEquity {
+    pub vault_address: String,
+    #[serde(deserialize_with = "de::decimal")]
+    pub equity: Decimal,
+    #[serde(default)]
+    pub locked_until_timestamp: Option<u64>,
+}
+
+/// Stake delegated to one validator (`delegations`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delegation {
+    pub validator: String,
+    #[serde(deserialize_with = "de::decimal")]
+    pub amount: Decimal,
+    pub locked_until_timestamp: u64,
+}
@@ -0,0 +1,385 @@
+//! Decoding tests against captured Info API and stream responses.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use super::*;
+
+fn fixture(name: &str) -> Value {
+    let text = match name {
+        "candles" => include_str!("fixtures/candles.json"),
+        "clearinghouse_state" => include_str!("fixtures/clearinghouse_state.json"),
+        "delegations" => include_str!("fixtures/delegations.json"),
+        "frontend_open_orders" => include_str!("fixtures/frontend_open_orders.json"),
+        "grpc_l2_book" => include_str!("fixtures/grpc_l2_book.json"),
+        "l2_book" => include_str!("fixtures/l2_book.json"),
+        "meta" => include_str!("fixtures/meta.json"),
+        "open_orders" => include_str!("fixtures/open_orders.json"),
+        "perp_dexes" => include_str!("fixtures/perp_dexes.json"),
+        "predicted_fundings" => include_str!("fixtures/predicted_fundings.json"),
+        "spot_meta" => include_str!("fixtures/spot_meta.json"),
+        "user_fills" => include_str!("fixtures/user_fills.json"),
+        "user_twap_history" => include_str!("fixtures/user_twap_history.json"),
+        "user_twap_slice_fills" => include_str!("fixtures/user_twap_slice_fills.json"),
+        "vault_summaries" => include_str!("fixtures/vault_summaries.json"),
+        "ws_trades" => include_str!("fixtures/ws_trades.json"),
+        _ => panic!("unknown fixture {}", name),
+    };
+    serde_json::from_str(text).unwrap()
+}
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Account
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn clearinghouse_state() {
+    let state: ClearinghouseState = decode(fixture("clearinghouse_state")).unwrap();
+    assert_eq!(state.margin_summary.account_value, dec("13109.482328"));
+    assert_eq!(state.withdrawable, dec("12391.712303"));
+    assert_eq!(state.asset_positions.len(), 2);
+
+    let btc = state.position("BTC").unwrap();
+    assert!(btc.is_long());
+    assert_eq!(btc.entry_px, Some(dec("96244.8")));
+    assert_eq!(btc.liquidation_px, None);
+    assert_eq!(btc.leverage.kind, "cross");
+    assert_eq!(btc.leverage.raw_usd, None);
+
+    let eth = state.position("ETH").unwrap();
+    assert!(!eth.is_long());
+    assert_eq!(eth.szi, dec("-1.2"));
+    assert_eq!(eth.leverage.raw_usd, Some(dec("4335.1")));
+    assert_eq!(eth.cum_funding.as_ref().unwrap().since_open, dec("1.25"));
+
+    assert!(state.position("SOL").is_none());
+}
+
+#[test]
+fn open_orders() {
+    let orders: Vec<OpenOrder> = decode(fixture("open_orders")).unwrap();
+    assert_eq!(orders[0].side, Side::Buy);
+    assert_eq!(orders[0].limit_px, dec("93000.0"));
+    assert_eq!(orders[0].orig_sz, dec("0.002"));
+    assert_eq!(orders[0].order_type, None);
+
+    let orders: Vec<OpenOrder> = decode(fixture("frontend_open_orders")).unwrap();
+    assert_eq!(orders[0].side, Side::Sell);
+    assert_eq!(orders[0].order_type.as_deref(), Some("Stop Market"));
+    assert_eq!(orders[0].trigger_px, Some(dec("3850.0")));
+    assert_eq!(orders[0].is_position_tpsl, Some(true));
+    assert_eq!(orders[0].cloid, None);
+}
+
+#[test]
+fn user_fills() {
+    let fills: Vec<Fill> = decode(fixture("user_fills")).unwrap();
+    assert_eq!(fills.len(), 2);
+    assert_eq!(fills[0].px, dec("96244.8"));
+    assert!(fills[0].crossed);
+    assert_eq!(fills[0].builder_fee, None);
+
+    // Maker rebates come through as negative fees
+    assert_eq!(fills[1].fee, dec("-0.0867"));
+    assert_eq!(fills[1].side, Side::Sell);
+    assert_eq!(fills[1].builder_fee, Some(dec("0.4335")));
+    assert_eq!(fills[1].cloid.as_deref(), Some("0x00000000000000000000000000000001"));
+}
+
+#[test]
+fn user_twap_history_and_slice_fills() {
+    let history: Vec<TwapHistory> = decode(fixture("user_twap_history")).unwrap();
+    assert_eq!(history[0].twap_id, Some(4821));
+    assert_eq!(history[0].state.side, Side::Buy);
+    assert_eq!(history[0].state.executed_ntl, dec("12031.25"));
+    assert_eq!(history[0].status.status, "activated");
+    // Older entries have no id; failed ones say why
+    assert_eq!(history[1].twap_id, None);
+    assert_eq!(history[1].status.description.as_deref(), Some("Insufficient margin"));
+
+    let fills: Vec<TwapSliceFill> = decode(fixture("user_twap_slice_fills")).unwrap();
+    assert_eq!((fills[0].twap_id, fills[0].fill.sz), (4821, dec("0.00694")));
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Market Data
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn l2_book() {
+    let book: L2Book = decode(fixture("l2_book")).unwrap();
+    assert_eq!(book.bids().len(), 3);
+    assert_eq!(book.asks().len(), 2);
+    assert_eq!(book.best_bid().unwrap().px, dec("96250"));
+    assert_eq!(book.best_ask().unwrap().n, 3);
+    assert_eq!(book.spread(), Some(dec("1")));
+    assert_eq!(book.mid(), Some(dec("96250.5")));
+}
+
+#[test]
+fn grpc_l2_book_converts() {
+    let book: GrpcL2Book = decode(fixture("grpc_l2_book")).unwrap();
+    assert_eq!(book.block_number, 512345678);
+
+    let book = L2Book::from(book);
+    assert_eq!(book.bids().len(), 2);
+    assert_eq!(book.best_bid().unwrap().sz, dec("1.2345"));
+    assert_eq!(book.spread(), Some(dec("1")));
+}
+
+#[test]
+fn candles() {
+    let candles: Vec<Candle> = decode(fixture("candles")).unwrap();
+    assert_eq!(candles.len(), 2);
+    assert_eq!(candles[0].open_time, 1737406800000);
+    assert_eq!(candles[0].interval, "1h");
+    assert_eq!(candles[0].high, dec("96410"));
+    assert_eq!(candles[0].volume, dec("1532.48291"));
+    assert_eq!(candles[1].trades, 5120);
+}
+
+#[test]
+fn predicted_fundings() {
+    let fundings: Vec<PredictedFunding> = decode(fixture("predicted_fundings")).unwrap();
+    let btc = &fundings[0];
+    assert_eq!(btc.coin(), "BTC");
+    assert_eq!(btc.hyperliquid().unwrap().funding_rate, dec("0.0000125"));
+    assert_eq!(btc.hyperliquid().unwrap().funding_interval_hours, Some(1));
+    assert_eq!(btc.venue("BinPerp").unwrap().funding_interval_hours, None);
+    assert!(btc.venue("BybitPerp").is_none());
+}
+
+#[test]
+fn all_mids() {
+    let mids: BTreeMap<String, Decimal> =
+        de::decimal_map(json!({"BTC": "96250.5", "@107": "24.118"})).unwrap();
+    assert_eq!(mids["BTC"], dec("96250.5"));
+    assert_eq!(mids["@107"], dec("24.118"));
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Metadata
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn meta() {
+    let meta: Meta = decode(fixture("meta")).unwrap();
+    let btc = meta.asset("BTC").unwrap();
+    assert_eq!(btc.sz_decimals, 5);
+    assert_eq!(btc.max_leverage, 40);
+    assert!(!btc.only_isolated);
+
+    assert!(meta.asset("MATIC").unwrap().is_delisted);
+
+    let jelly = meta.asset("JELLY").unwrap();
+    assert!(jelly.only_isolated);
+    assert_eq!(jelly.margin_mode.as_deref(), Some("strictIsolated"));
+
+    let table = meta.margin_table(56).unwrap();
+    assert_eq!(table.margin_tiers[1].lower_bound, dec("150000000"));
+    assert_eq!(table.margin_tiers[1].max_leverage, 20);
+    assert!(meta.margin_table(55).is_none());
+}
+
+#[test]
+fn spot_meta() {
+    let spot: SpotMeta = decode(fixture("spot_meta")).unwrap();
+    assert_eq!(spot.universe.len(), 2);
+
+    let hype = &spot.universe[1];
+    assert_eq!(hype.name, "@107");
+    assert_eq!(spot.pair_name(hype).as_deref(), Some("HYPE/USDC"));
+
+    let purr = spot.token(1).unwrap();
+    assert_eq!(purr.evm_contract.as_ref().unwrap().evm_extra_wei_decimals, 13);
+    assert_eq!(spot.token(150).unwrap().full_name.as_deref(), Some("Hyperliquid"));
+}
+
+#[test]
+fn perp_dexes_skip_main_dex() {
+    let dexes: Vec<Option<PerpDex>> = decode(fixture("perp_dexes")).unwrap();
+    assert!(dexes[0].is_none());
+    assert_eq!(dexes[1].as_ref().unwrap().name, "xyz");
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Vaults & Staking
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn vault_summaries() {
+    let vaults: Vec<VaultSummary> = decode(fixture("vault_summaries")).unwrap();
+    assert_eq!(vaults[0].tvl, dec("412345678.123456"));
+    assert!(!vaults[0].is_closed);
+}
+
+#[test]
+fn delegations() {
+    let delegations: Vec<Delegation> = decode(fixture("delegations")).unwrap();
+    assert_eq!(delegations[0].amount, dec("1250.5"));
+    assert_eq!(delegations[0].locked_until_timestamp, 1737498745678);
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Streams
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn stream_trades() {
+    let message = fixture("ws_trades");
+    let trades = stream_items::<Trade>(&message, "trades").unwrap().unwrap();
+    assert_eq!(trades.len(), 2);
+    assert!(trades[0].side.is_buy());
+    assert_eq!(trades[1].sz, dec("0.2"));
+    assert_eq!(
+        trades[0].users.as_ref().unwrap().1,
+        "0x2222222222222222222222222222222222222222"
+    );
+}
+
+#[test]
+fn stream_items_skip_other_channels() {
+    let message = fixture("ws_trades");
+    assert!(stream_items::<L2Book>(&message, "l2Book").is_none());
+}
+
+#[test]
+fn stream_items_flatten_block_events() {
+    let trade = fixture("ws_trades")["data"][0].clone();
+    let message = json!({
+        "block": {
+            "events": [["0x1111111111111111111111111111111111111111", trade]]
+        }
+    });
+    let trades = stream_items::<Trade>(&message, "trades").unwrap().unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].px, dec("96251.0"));
+}
+
+#[test]
+fn stream_items_single_object() {
+    let message = json!({"channel": "l2Book", "data": fixture("l2_book")});
+    let books = stream_items::<L2Book>(&message, "l2Book").unwrap().unwrap();
+    assert_eq!(books[0].coin, "BTC");
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Stream Events
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn market_events_match_across_transports() {
+    // WebSocket: levels of {px, sz, n} inside a HyperCore block
+    let ws = json!({"block": {"events": [["0x1111111111111111111111111111111111111111", {
+        "coin": "BTC", "time": 1737412345123u64,
+        "levels": [[{"px": "96250.0", "sz": "1.5", "n": 3}], [{"px": "96251.0", "sz": "0", "n": 0}]]
+    }]]}});
+    // gRPC: a bare object with bids and asks of {price, size}
+    let grpc = json!({
+        "coin": "BTC", "time": 1737412345123u64,
+        "bids": [{"price": "96250.0", "size": "1.5"}], "asks": [{"price": "96251.0", "size": "0"}]
+    });
+    let [MarketEvent::BookDelta(ws)] = &MarketEvent::parse(&ws).unwrap()[..] else { panic!("not one delta") };
+    let [MarketEvent::BookDelta(grpc)] = &MarketEvent::parse(&grpc).unwrap()[..] else { panic!("not one delta") };
+    assert_eq!((&ws.coin, ws.time, ws.bids[0].px, ws.bids[0].sz), (&grpc.coin, grpc.time, grpc.bids[0].px, grpc.bids[0].sz));
+    assert_eq!(grpc.asks[0].sz, Decimal::ZERO);
+
+    // Snapshots: WebSocket l2Book and gRPC l2_book
+    let ws = MarketEvent::parse(&json!({"channel": "l2Book", "data": fixture("l2_book")})).unwrap();
+    let grpc = MarketEvent::parse(&fixture("grpc_l2_book")).unwrap();
+    for events in [&ws, &grpc] {
+        let [MarketEvent::BookSnapshot(book)] = &events[..] else { panic!("not one snapshot: {:?}", events) };
+        assert_eq!(book.best_bid().unwrap().px, dec("96250.0"));
+        assert_eq!(book.spread(), Some(dec("1")));
+    }
+
+    let trades = MarketEvent::parse(&fixture("ws_trades")).unwrap();
+    assert_eq!(trades.len(), 2);
+    assert!(matches!(&trades[1], MarketEvent::Trade(t) if t.sz == dec("0.2")));
+    assert_eq!(trades[0].coin(), Some("BTC"));
+    assert_eq!(trades[0].time(), Some(1737412345999));
+}
+
+#[test]
+fn market_events_of_account_and_block_channels() {
+    let mids = MarketEvent::parse(&json!({"channel": "allMids", "data": {"mids": {"BTC": "96250.5", "ETH": "3612.45"}}})).unwrap();
+    assert_eq!(mids[1], MarketEvent::Mid(Mid { coin: "ETH".to_string(), px: dec("3612.45") }));
+
+    let user = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+    let fills = json!({"channel": "userFills", "data": {"user": user, "fills": [fixture("user_fills")[0].clone()]}});
+    let [MarketEvent::Fill(fill)] = &MarketEvent::parse(&fills).unwrap()[..] else { panic!("not one fill") };
+    assert_eq!((fill.user.as_deref(), fill.px), (Some(user), dec("96244.8")));
+
+    let order = json!({"order": fixture("open_orders")[0].clone(), "status": "canceled", "statusTimestamp": 1737412400000u64});
+    let updates = MarketEvent::parse(&json!({"channel": "orderUpdates", "data": [order.clone()]})).unwrap();
+    assert!(matches!(&updates[0], MarketEvent::OrderUpdate(u) if u.status == "canceled" && u.order.oid == 58211299999));
+    assert_eq!(updates[0].time(), Some(1737412400000));
+    // The same order event inside a HyperCore block keeps its user
+    let block = MarketEvent::parse(&json!({"block": {"events": [[user, order]]}})).unwrap();
+    assert!(matches!(&block[0], MarketEvent::OrderUpdate(u) if u.user.as_deref() == Some(user)));
+
+    let [MarketEvent::Block(block)] = &MarketEvent::parse(&json!({"block_number": 512345679, "time": 1737412345200u64})).unwrap()[..] else {
+        panic!("not one block")
+    };
+    assert_eq!((block.number, block.time), (512345679, Some(1737412345200)));
+
+    assert!(MarketEvent::parse(&json!({"channel": "candle", "data": {"s": "BTC"}})).unwrap().is_empty());
+    assert!(MarketEvent::parse(&json!({"channel": "trades", "data": [{"coin": "BTC"}]})).is_err());
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Malformed Data
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn malformed_decimal_is_an_error() {
+    let mut book = fixture("l2_book");
+    book["levels"][0][0]["px"] = json!("abc");
+    let err = decode::<L2Book>(book).unwrap_err();
+    assert!(err.to_string().contains("invalid decimal string"), "{}", err);
+}
+
+#[test]
+fn missing_decimal_is_an_error() {
+    let mut fills = fixture("user_fills");
+    fills[0].as_object_mut().unwrap().remove("px");
+    assert!(decode::<Vec<Fill>>(fills).is_err());
+}
+
+#[test]
+fn null_required_decimal_is_an_error() {
+    let mut state = fixture("clearinghouse_state");
+    state["withdrawable"] = Value::Null;
+    assert!(decode::<ClearinghouseState>(state).is_err());
+}
+
+#[test]
+fn malformed_mid_is_an_error() {
+    assert!(de::decimal_map(json!({"BTC": ""})).is_err());
+    assert!(de::decimal_map(json!({"BTC": true})).is_err());
+}
+
+#[test]
+fn malformed_stream_item_is_reported() {
+    let mut message = fixture("ws_trades");
+    message["data"][1]["sz"] = json!("0.2.1");
+    assert!(stream_items::<Trade>(&message, "trades").unwrap().is_err());
+}
+
+#[test]
+fn decimal_accepts_numbers_and_scientific() {
+    let fundings: Vec<FundingRate> = decode(json!([
+        {"fundingRate": 0.0001, "nextFundingTime": 1},
+        {"fundingRate": "1.25e-5", "nextFundingTime": 2}
+    ]))
+    .unwrap();
+    assert_eq!(fundings[0].funding_rate, dec("0.0001"));
+    assert_eq!(fundings[1].funding_rate, dec("0.0000125"));
+}
@@ -0,0 +1,562 @@
+//! One-cancels-other groups of resting orders, managed client-side.
+//!
+//! The exchange only links an entry with its TP/SL (see
+//! [`bracket`](crate::bracket)). An [`OcoManager`] groups any two or more
+//! resting orders of a coin (limits, stop-losses, take-profits) and cancels
+//! the rest of a group as soon as one of them fills:
+//!
+//! - [`OcoManager::watch`] subscribes a stream to the account's
+//!   `orderUpdates` and `userFills` and hands out the cancels to send with
+//!   [`OcoManager::cancel`];
+//! - groups are saved to disk on every change, and [`OcoManager::resume`]
+//!   catches up after a restart: an order that is no longer open counts as
+//!   filled if it has fills, else as cancelled.
+//!
+//! ```no_run
+//! use hyperliquid_examples::models::{decode, OpenOrder};
+//! use hyperliquid_examples::oco::{OcoManager, OrderRef};
+//!
+//! # async fn run(sdk: &hyperliquid_sdk::HyperliquidSDK) -> hyperliquid_examples::Result<()> {
+//! let oco = OcoManager::open("oco.json")?;
+//! for cancel in oco.resume(sdk).await? {
+//!     oco.cancel(sdk, &cancel).await?;
+//! }
+//! let open: Vec<OpenOrder> = decode(sdk.open_orders().await?)?;
+//! oco.add("BTC", &[OrderRef::Oid(1234), OrderRef::Oid(1235)], &open)?;
+//!
+//! let mut stream = sdk.stream();
+//! let user = format!("{:?}", sdk.address().unwrap());
+//! let mut cancels = oco.watch(&mut stream, &user);
+//! stream.start()?;
+//! while let Some(cancel) = cancels.recv().await {
+//!     oco.cancel(sdk, &cancel?).await?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Two orders of a group can still both fill when the second fills before
+//! its cancel arrives; [`OcoGroup::filled`] then lists both.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use hyperliquid_sdk::stream::Stream;
+use hyperliquid_sdk::HyperliquidSDK;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use crate::config::Network;
+use crate::error::{Error, Result};
+use crate::exchange::rejection;
+use crate::feed;
+use crate::models::{decode, Fill, MarketEvent, OpenOrder, TypedInfo};
+use crate::registry::cache_dir;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Groups
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// An order given by its oid or its cloid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderRef {
+    Oid(u64),
+    Cloid(String),
+}
+
+impl FromStr for OrderRef {
+    type Err = Error;
+
+    /// `1234` or `0x` and 32 hex digits
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Ok(oid) = s.parse() {
+            return Ok(OrderRef::Oid(oid));
+        }
+        let hex = s.strip_prefix("0x").unwrap_or_default();
+        if hex.len() == 32 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Ok(OrderRef::Cloid(s.to_lowercase()));
+        }
+        Err(Error::ValidationError(format!("Not an oid or cloid: {}", s)))
+    }
+}
+
+impl fmt::Display for OrderRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderRef::Oid(oid) => write!(f, "{}", oid),
+            OrderRef::Cloid(cloid) => f.write_str(cloid),
+        }
+    }
+}
+
+/// Where one order of a group stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberState {
+    Open,
+    /// Cancel sent for it, not confirmed yet
+    Cancelling,
+    /// Filled at least in part
+    Filled,
+    /// Cancelled, rejected or otherwise gone without a fill
+    Cancelled,
+}
+
+impl MemberState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MemberState::Open => "open",
+            MemberState::Cancelling => "cancelling",
+            MemberState::Filled => "filled",
+            MemberState::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// One order of a group
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    /// Known once the order was seen open, when added by cloid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oid: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cloid: Option<String>,
+    pub state: MemberState,
+}
+
+impl Member {
+    fn is(&self, oid: u64, cloid: Option<&str>) -> bool {
+        self.oid == Some(oid) || (cloid.is_some() && self.cloid.as_deref() == cloid)
+    }
+
+    fn refers_to(&self, order: &OrderRef) -> bool {
+        match order {
+            OrderRef::Oid(oid) => self.oid == Some(*oid),
+            OrderRef::Cloid(cloid) => self.cloid.as_ref() == Some(cloid),
+        }
+    }
+
+    fn is_live(&self) -> bool {
+        matches!(self.state, MemberState::Open | MemberState::Cancelling)
+    }
+}
+
+impl fmt::Display for Member {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.oid, &self.cloid) {
+            (Some(oid), _) => write!(f, "{}", oid),
+            (None, Some(cloid)) => f.write_str(cloid),
+            (None, None) => f.write_str("?"),
+        }
+    }
+}
+
+/// Orders of one coin of which at most one should fill
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcoGroup {
+    pub id: String,
+    pub coin: String,
+    pub members: Vec<Member>,
+}
+
+impl OcoGroup {
+    /// Members that filled (more than one if the cancel came too late)
+    pub fn filled(&self) -> Vec<&Member> {
+        self.members.iter().filter(|m| m.state == MemberState::Filled).collect()
+    }
+
+    /// No order of the group is open any more
+    pub fn is_done(&self) -> bool {
+        !self.members.iter().any(Member::is_live)
+    }
+
+    /// Cancels due because a member filled; marks them as sent
+    fn settle(&mut self) -> Vec<Cancel> {
+        if self.filled().is_empty() {
+            return Vec::new();
+        }
+        let (id, coin) = (self.id.clone(), self.coin.clone());
+        self.members
+            .iter_mut()
+            .filter(|m| m.state == MemberState::Open)
+            .map(|member| {
+                member.state = MemberState::Cancelling;
+                Cancel { group: id.clone(), coin: coin.clone(), oid: member.oid, cloid: member.cloid.clone() }
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for OcoGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}:", self.id, self.coin)?;
+        for (i, member) in self.members.iter().enumerate() {
+            write!(f, "{} {} ({})", if i == 0 { "" } else { "," }, member, member.state.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// An order to cancel because another order of its group filled
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cancel {
+    pub group: String,
+    pub coin: String,
+    pub oid: Option<u64>,
+    pub cloid: Option<String>,
+}
+
+impl fmt::Display for Cancel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let order = match (&self.oid, &self.cloid) {
+            (Some(oid), _) => oid.to_string(),
+            (None, cloid) => cloid.clone().unwrap_or_default(),
+        };
+        write!(f, "Cancel {} {} ({})", self.coin, order, self.group)
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// OcoManager
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Contents of the state file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Saved {
+    next_id: u64,
+    groups: Vec<OcoGroup>,
+}
+
+/// OCO groups, their state file, and the cancels they call for; see the
+/// [module docs](self). Clones share the groups.
+#[derive(Debug, Clone)]
+pub struct OcoManager {
+    path: Arc<PathBuf>,
+    saved: Arc<Mutex<Saved>>,
+}
+
+impl OcoManager {
+    /// Groups saved at `path`, if the file exists (groups that were done
+    /// are dropped)
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut saved: Saved = match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Saved::default(),
+            Err(e) => return Err(e.into()),
+        };
+        saved.groups.retain(|g| !g.is_done());
+        Ok(Self { path: Arc::new(path), saved: Arc::new(Mutex::new(saved)) })
+    }
+
+    /// Default state file of a network, `oco-<network>.json` in the cache
+    /// directory of the [`RegistryCache`](crate::registry::RegistryCache)
+    pub fn for_network(network: Network) -> Result<Self> {
+        Self::open(cache_dir().join(format!("oco-{}.json", network)))
+    }
+
+    /// Path of the state file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Current groups, done ones included
+    pub fn groups(&self) -> Vec<OcoGroup> {
+        self.lock().groups.clone()
+    }
+
+    /// Group resting orders of `coin`, each of which must be among the
+    /// account's `open` orders (`sdk.open_orders()`); returns the new group
+    pub fn add(&self, coin: &str, orders: &[OrderRef], open: &[OpenOrder]) -> Result<OcoGroup> {
+        if orders.len() < 2 {
+            return Err(Error::ValidationError("An OCO group needs at least two orders".to_string()));
+        }
+        for order in orders {
+            let resting = open.iter().filter(|o| o.coin == coin).any(|o| match order {
+                OrderRef::Oid(oid) => o.oid == *oid,
+                OrderRef::Cloid(cloid) => o.cloid.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(cloid)),
+            });
+            if !resting {
+                return Err(Error::ValidationError(format!("Order {} is not open on {}", order, coin)));
+            }
+        }
+        let mut saved = self.lock();
+        for order in orders {
+            let taken = saved.groups.iter().filter(|g| !g.is_done()).find(|g| g.members.iter().any(|m| m.refers_to(order)));
+            if let Some(group) = taken {
+                return Err(Error::ValidationError(format!("Order {} is already in {}", order, group.id)));
+            }
+        }
+
+        saved.next_id += 1;
+        let members = orders
+            .iter()
+            .map(|order| match order {
+                OrderRef::Oid(oid) => Member { oid: Some(*oid), cloid: None, state: MemberState::Open },
+                OrderRef::Cloid(cloid) => Member { oid: None, cloid: Some(cloid.clone()), state: MemberState::Open },
+            })
+            .collect();
+        let group = OcoGroup { id: format!("oco-{}", saved.next_id), coin: coin.to_string(), members };
+        saved.groups.push(group.clone());
+        self.save(&saved)?;
+        Ok(group)
+    }
+
+    /// Apply an `orderUpdates` or `userFills` event; returns the cancels it
+    /// calls for (other events change nothing)
+    pub fn on_event(&self, event: &MarketEvent) -> Result<Vec<Cancel>> {
+        let mut saved = self.lock();
+        let mut changed = false;
+        for member in saved.groups.iter_mut().flat_map(|g| g.members.iter_mut()) {
+            let state = match event {
+                MarketEvent::Fill(fill) if member.is(fill.oid, fill.cloid.as_deref()) => MemberState::Filled,
+                MarketEvent::OrderUpdate(update) if member.is(update.order.oid, update.order.cloid.as_deref()) => {
+                    changed |= member.oid.replace(update.order.oid).is_none();
+                    match update.status.as_str() {
+                        "filled" => MemberState::Filled,
+                        "open" | "triggered" => continue,
+                        _ if member.state == MemberState::Filled => continue,
+                        _ => MemberState::Cancelled,
+                    }
+                }
+                _ => continue,
+            };
+            changed |= member.state != state;
+            member.state = state;
+        }
+        self.settle(&mut saved, changed)
+    }
+
+    /// Catch up with the account's `open` orders and `fills`: members no
+    /// longer open are filled if they have fills, else cancelled. Cancels
+    /// sent before but not confirmed are due again if still open.
+    pub fn reconcile(&self, open: &[OpenOrder], fills: &[Fill]) -> Result<Vec<Cancel>> {
+        let mut saved = self.lock();
+        for member in saved.groups.iter_mut().flat_map(|g| g.members.iter_mut()).filter(|m| m.is_live()) {
+            if let Some(order) = open.iter().find(|o| member.is(o.oid, o.cloid.as_deref())) {
+                member.oid = Some(order.oid);
+                member.state = MemberState::Open;
+            } else if fills.iter().any(|f| member.is(f.oid, f.cloid.as_deref())) {
+                member.state = MemberState::Filled;
+            } else {
+                member.state = MemberState::Cancelled;
+            }
+        }
+        self.settle(&mut saved, true)
+    }
+
+    /// [`reconcile`](Self::reconcile) with the signer's `sdk.open_orders()`
+    /// and recent fills
+    pub async fn resume(&self, sdk: &HyperliquidSDK) -> Result<Vec<Cancel>> {
+        let user = sdk
+            .address()
+            .map(|a| format!("{:?}", a))
+            .ok_or_else(|| Error::ConfigError("OCO groups need PRIVATE_KEY".to_string()))?;
+        let open: Vec<OpenOrder> = decode(sdk.open_orders().await?)?;
+        let fills = TypedInfo::from_sdk(sdk).user_fills(&user).await?;
+        self.reconcile(&open, &fills)
+    }
+
+    /// Subscribe `stream` to `user`'s `orderUpdates` and `userFills`; the
+    /// cancels they call for (or errors decoding the events or saving the
+    /// groups) arrive on the returned channel once the stream is started
+    pub fn watch(&self, stream: &mut Stream, user: &str) -> UnboundedReceiver<Result<Cancel>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handler = |channel: &'static str| {
+            let (oco, events, errors) = (self.clone(), tx.clone(), tx.clone());
+            feed::handler(
+                channel,
+                move |event| match oco.on_event(&event) {
+                    Ok(cancels) => cancels.into_iter().for_each(|c| {
+                        let _ = events.send(Ok(c));
+                    }),
+                    Err(e) => {
+                        let _ = events.send(Err(e));
+                    }
+                },
+                move |e| {
+                    let _ = errors.send(Err(e));
+                },
+            )
+        };
+        stream.order_updates(user, handler("orderUpdates"));
+        stream.user_fills(user, handler("userFills"));
+        rx
+    }
+
+    /// Send a cancel with `sdk.cancel` (or `sdk.cancel_by_cloid` before the
+    /// oid is known) and record it
+    pub async fn cancel(&self, sdk: &HyperliquidSDK, cancel: &Cancel) -> Result<()> {
+        let response = match (cancel.oid, &cancel.cloid) {
+            (Some(oid), _) => sdk.cancel(oid, &cancel.coin).await?,
+            (None, Some(cloid)) => sdk.cancel_by_cloid(cloid, &cancel.coin).await?,
+            (None, None) => return Err(Error::ValidationError(format!("{}: no oid or cloid", cancel))),
+        };
+        if let Some(reason) = rejection(&response) {
+            // Likely filled or cancelled meanwhile; its update will tell
+            return Err(Error::ValidationError(format!("{} rejected: {}", cancel, reason)));
+        }
+        let mut saved = self.lock();
+        let member = saved
+            .groups
+            .iter_mut()
+            .filter(|g| g.id == cancel.group)
+            .flat_map(|g| g.members.iter_mut())
+            .find(|m| m.oid == cancel.oid && m.cloid == cancel.cloid);
+        if let Some(member) = member.filter(|m| m.state == MemberState::Cancelling) {
+            member.state = MemberState::Cancelled;
+        }
+        self.save(&saved)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Saved> {
+        self.saved.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Collect the cancels of every group and save if anything changed
+    fn settle(&self, saved: &mut Saved, mut changed: bool) -> Result<Vec<Cancel>> {
+        let cancels: Vec<Cancel> = saved.groups.iter_mut().flat_map(OcoGroup::settle).collect();
+        changed |= !cancels.is_empty();
+        if changed {
+            self.save(saved)?;
+        }
+        Ok(cancels)
+    }
+
+    /// Write the groups (to a temp file, then renamed into place)
+    fn save(&self, saved: &Saved) -> Result<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(saved)?)?;
+        std::fs::rename(&tmp, self.path.as_ref())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::mock::testing::book;
+    use crate::mock::{Event, MatchingEngine, Script};
+
+    fn state_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("hl-oco-test-{}-{}.json", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    /// Rest a GTC order on BTC and return its oid
+    fn rest(engine: &mut MatchingEngine, is_buy: bool, px: &str) -> u64 {
+        let response = engine.execute(&json!({
+            "type": "order",
+            "orders": [{"a": 0, "b": is_buy, "p": px, "s": "0.01", "r": false, "t": {"limit": {"tif": "Gtc"}}}],
+            "grouping": "na",
+        }));
+        response["response"]["data"]["statuses"][0]["resting"]["oid"].as_u64().unwrap()
+    }
+
+    /// Feed the engine's order updates and fills to `oco`
+    fn feed(oco: &OcoManager, engine: &mut MatchingEngine) -> Vec<Cancel> {
+        let mut cancels = Vec::new();
+        for event in engine.drain_events() {
+            let event = match event {
+                Event::Fill(fill) => MarketEvent::Fill(fill),
+                Event::OrderUpdate(update) => MarketEvent::OrderUpdate(update),
+                _ => continue,
+            };
+            cancels.extend(oco.on_event(&event).unwrap());
+        }
+        cancels
+    }
+
+    #[test]
+    fn a_fill_cancels_the_rest_of_the_group() {
+        let path = state_file("fill");
+        let mut engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let (buy, sell) = (rest(&mut engine, true, "96000"), rest(&mut engine, false, "96500"));
+        let oco = OcoManager::open(&path).unwrap();
+        let group = oco.add("BTC", &[OrderRef::Oid(buy), OrderRef::Oid(sell)], &engine.open_orders()).unwrap();
+        assert_eq!(group.id, "oco-1");
+        engine.drain_events();
+
+        // The fill and the order update both report it; one cancel is due
+        engine.set_book(&book(&engine, "95990", "95995"));
+        let cancels = feed(&oco, &mut engine);
+        assert_eq!(cancels.len(), 1);
+        assert_eq!(cancels[0].to_string(), format!("Cancel BTC {} (oco-1)", sell));
+
+        // Saved as sent, until the exchange confirms
+        let reopened = OcoManager::open(&path).unwrap();
+        assert_eq!(reopened.groups()[0].to_string(), format!("oco-1 BTC: {} (filled), {} (cancelling)", buy, sell));
+
+        engine.execute(&json!({"type": "cancel", "cancels": [{"a": 0, "o": sell}]}));
+        assert!(feed(&oco, &mut engine).is_empty());
+        assert!(oco.groups()[0].is_done());
+        assert!(OcoManager::open(&path).unwrap().groups().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn reconciles_what_happened_while_away() {
+        let path = state_file("reconcile");
+        let mut engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let (buy, sell) = (rest(&mut engine, true, "96000"), rest(&mut engine, false, "96500"));
+        let (other, gone) = (rest(&mut engine, true, "95000"), rest(&mut engine, true, "94000"));
+        let oco = OcoManager::open(&path).unwrap();
+        oco.add("BTC", &[OrderRef::Oid(buy), OrderRef::Oid(sell)], &engine.open_orders()).unwrap();
+        oco.add("BTC", &[OrderRef::Oid(other), OrderRef::Oid(gone)], &engine.open_orders()).unwrap();
+        drop(oco);
+
+        // Meanwhile the buy filled and one order of the other group was cancelled
+        engine.set_book(&book(&engine, "95990", "95995"));
+        engine.execute(&json!({"type": "cancel", "cancels": [{"a": 0, "o": gone}]}));
+
+        let oco = OcoManager::open(&path).unwrap();
+        let cancels = oco.reconcile(&engine.open_orders(), &engine.user_fills()).unwrap();
+        assert_eq!(cancels.iter().map(|c| c.oid).collect::<Vec<_>>(), [Some(sell)]);
+        let groups = oco.groups();
+        assert_eq!(groups[0].filled().len(), 1);
+        assert_eq!(groups[1].members[1].state, MemberState::Cancelled);
+        assert!(!groups[1].is_done());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn groups_need_two_free_open_orders() {
+        let path = state_file("add");
+        let mut engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let oco = OcoManager::open(&path).unwrap();
+        let cloid: OrderRef = "0x00000000000000000000000000000ABC".parse().unwrap();
+        assert_eq!("17".parse::<OrderRef>().unwrap(), OrderRef::Oid(17));
+        assert!("0xabc".parse::<OrderRef>().is_err());
+
+        let (buy, sell) = (rest(&mut engine, true, "96000"), rest(&mut engine, false, "96500"));
+        engine.execute(&json!({
+            "type": "order",
+            "orders": [{"a": 0, "b": true, "p": "95000", "s": "0.01", "r": false, "t": {"limit": {"tif": "Gtc"}},
+                "c": "0x00000000000000000000000000000abc"}],
+            "grouping": "na",
+        }));
+        let open = engine.open_orders();
+
+        let error = oco.add("BTC", &[OrderRef::Oid(buy)], &open).unwrap_err();
+        assert!(error.to_string().contains("at least two orders"), "{}", error);
+        let error = oco.add("BTC", &[OrderRef::Oid(buy), OrderRef::Oid(99)], &open).unwrap_err();
+        assert!(error.to_string().contains("Order 99 is not open on BTC"), "{}", error);
+        let error = oco.add("ETH", &[OrderRef::Oid(buy), OrderRef::Oid(sell)], &open).unwrap_err();
+        assert!(error.to_string().contains("is not open on ETH"), "{}", error);
+        assert!(oco.groups().is_empty());
+
+        oco.add("BTC", &[OrderRef::Oid(buy), cloid.clone()], &open).unwrap();
+        let error = oco.add("BTC", &[cloid, OrderRef::Oid(sell)], &open).unwrap_err();
+        assert!(error.to_string().contains("is already in oco-1"), "{}", error);
+        let _ = std::fs::remove_file(&path);
+    }
+}
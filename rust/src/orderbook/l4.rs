@@ -0,0 +1,346 @@
+//! Order-by-order (L4) book rebuilt from the gRPC `l4_book` stream.
+//!
+//! The stream opens with a snapshot of every resting order of one coin, then
+//! sends one diff per block listing orders that were added, resized or
+//! removed. [`L4Book`] applies them per `oid` and keeps each price level in
+//! queue order, so it can tell where an order stands ([`L4Book::queue_position`])
+//! and whose orders rest where ([`L4Book::orders_of`]). [`L4Book::to_l2`]
+//! aggregates it into an L2 view and [`L4Book::compare`] lists the levels
+//! that disagree with an `info.l2_book` snapshot.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::models::{decode, BookDiff, L2Book, L4Diff, L4Order, L4Snapshot, Level, RawBookDiff, Side};
+
+/// Order resting in an [`L4Book`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestingOrder {
+    pub oid: u64,
+    pub user: String,
+    pub side: Side,
+    pub px: Decimal,
+    pub sz: Decimal,
+}
+
+/// Where an order stands in its price level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct QueuePosition {
+    /// Orders ahead of it (0 = first to fill)
+    pub orders_ahead: usize,
+    /// Size that fills before it does
+    pub size_ahead: Decimal,
+    /// Total size of the level, including the order
+    pub level_size: Decimal,
+}
+
+/// A price level where the L4 and L2 books disagree
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Discrepancy {
+    pub side: Side,
+    pub px: Decimal,
+    /// Level aggregated from the orders, if any rest there
+    pub l4: Option<Level>,
+    /// Level in the L2 snapshot, if it has one
+    pub l2: Option<Level>,
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = |level: &Option<Level>| match level {
+            Some(l) => format!("{} ({})", l.sz, l.n),
+            None => "-".to_string(),
+        };
+        write!(f, "{} {}: L4 {} vs L2 {}", self.side, self.px, level(&self.l4), level(&self.l2))
+    }
+}
+
+/// Resting orders of one coin, by price level and queue order
+#[derive(Debug, Clone, Default)]
+pub struct L4Book {
+    coin: String,
+    orders: HashMap<u64, RestingOrder>,
+    /// Price → oids, oldest first
+    bids: BTreeMap<Decimal, VecDeque<u64>>,
+    asks: BTreeMap<Decimal, VecDeque<u64>>,
+    time: u64,
+    height: u64,
+    synced: bool,
+    /// Diffs for an unknown oid, or a new order of unknown side
+    unmatched: u64,
+}
+
+impl L4Book {
+    pub fn new(coin: &str) -> Self {
+        L4Book { coin: coin.to_string(), ..Default::default() }
+    }
+
+    pub fn coin(&self) -> &str {
+        &self.coin
+    }
+
+    /// Time of the last snapshot or diff applied (ms)
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Block height of the last snapshot or diff applied
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// Whether a snapshot has been applied; diffs are ignored until then
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Number of diffs that did not match the book
+    pub fn unmatched(&self) -> u64 {
+        self.unmatched
+    }
+
+    /// Number of resting orders
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Apply a gRPC `l4_book` message; returns whether the book changed.
+    ///
+    /// Diffs that arrive before the first snapshot, or at a height the book
+    /// already has, are ignored.
+    pub fn ingest(&mut self, message: &Value) -> Result<bool> {
+        match message.get("type").and_then(|t| t.as_str()) {
+            Some("snapshot") => {
+                let snapshot: L4Snapshot = decode(message.clone())?;
+                if snapshot.coin != self.coin {
+                    return Ok(false);
+                }
+                self.apply_snapshot(&snapshot);
+                Ok(true)
+            }
+            Some("diff") => {
+                let height = message.get("height").and_then(|h| h.as_u64()).unwrap_or_default();
+                if !self.synced || height <= self.height {
+                    return Ok(false);
+                }
+                let diff: L4Diff = decode(message.get("data").cloned().unwrap_or(Value::Null))?;
+                let time = message.get("time").and_then(|t| t.as_u64()).unwrap_or(self.time);
+                self.apply_diff(&diff, time, height);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Replace every order with those of `snapshot`
+    pub fn apply_snapshot(&mut self, snapshot: &L4Snapshot) {
+        self.orders.clear();
+        self.bids.clear();
+        self.asks.clear();
+        // Oldest first, so each level's queue is in time priority
+        let mut orders: Vec<&L4Order> = snapshot.bids.iter().chain(&snapshot.asks).filter(|o| !o.is_trigger).collect();
+        orders.sort_by_key(|o| o.timestamp);
+        for order in orders {
+            self.add(resting(order));
+        }
+        self.time = snapshot.time;
+        self.height = snapshot.height;
+        self.synced = true;
+    }
+
+    /// Apply the book diffs of this coin in one block
+    pub fn apply_diff(&mut self, diff: &L4Diff, time: u64, height: u64) {
+        // New orders may leave the side out; their status update has it
+        let sides: HashMap<u64, Side> = diff.order_statuses.iter().map(|s| (s.order.oid, s.order.side)).collect();
+        for change in &diff.book_diffs {
+            if change.coin == self.coin {
+                self.apply(change, sides.get(&change.oid).copied());
+            }
+        }
+        self.time = time;
+        self.height = height;
+    }
+
+    fn apply(&mut self, diff: &BookDiff, status_side: Option<Side>) {
+        let matched = match &diff.raw_book_diff {
+            RawBookDiff::New { sz } => match diff.side.or(status_side) {
+                Some(side) => {
+                    self.add(RestingOrder { oid: diff.oid, user: diff.user.clone(), side, px: diff.px, sz: *sz });
+                    true
+                }
+                None => false,
+            },
+            RawBookDiff::Update { new_sz, .. } => self.modify(diff.oid, *new_sz),
+            RawBookDiff::Remove => self.remove(diff.oid).is_some(),
+        };
+        if !matched {
+            self.unmatched += 1;
+        }
+    }
+
+    /// Add an order at the back of its price level (replacing one with the same oid)
+    pub fn add(&mut self, order: RestingOrder) {
+        self.remove(order.oid);
+        self.ladder_mut(order.side).entry(order.px).or_default().push_back(order.oid);
+        self.orders.insert(order.oid, order);
+    }
+
+    /// Change an order's size; it keeps its place in the queue. Returns
+    /// `false` if the order is not in the book
+    pub fn modify(&mut self, oid: u64, sz: Decimal) -> bool {
+        if sz.is_zero() {
+            return self.remove(oid).is_some();
+        }
+        match self.orders.get_mut(&oid) {
+            Some(order) => {
+                order.sz = sz;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Take an order out of the book
+    pub fn remove(&mut self, oid: u64) -> Option<RestingOrder> {
+        let order = self.orders.remove(&oid)?;
+        let ladder = self.ladder_mut(order.side);
+        if let Some(queue) = ladder.get_mut(&order.px) {
+            queue.retain(|o| *o != oid);
+            if queue.is_empty() {
+                ladder.remove(&order.px);
+            }
+        }
+        Some(order)
+    }
+
+    pub fn order(&self, oid: u64) -> Option<&RestingOrder> {
+        self.orders.get(&oid)
+    }
+
+    /// Orders at one price, in queue order
+    pub fn level(&self, side: Side, px: Decimal) -> impl Iterator<Item = &RestingOrder> {
+        self.ladder(side)
+            .get(&px)
+            .into_iter()
+            .flatten()
+            .filter_map(|oid| self.orders.get(oid))
+    }
+
+    /// Where `oid` stands in its price level
+    pub fn queue_position(&self, oid: u64) -> Option<QueuePosition> {
+        let order = self.orders.get(&oid)?;
+        let mut position = QueuePosition { orders_ahead: 0, size_ahead: Decimal::ZERO, level_size: Decimal::ZERO };
+        let mut ahead = true;
+        for other in self.level(order.side, order.px) {
+            if other.oid == oid {
+                ahead = false;
+            } else if ahead {
+                position.orders_ahead += 1;
+                position.size_ahead += other.sz;
+            }
+            position.level_size += other.sz;
+        }
+        Some(position)
+    }
+
+    /// Resting orders of `user`, best price first
+    pub fn orders_of<'a>(&'a self, user: &'a str) -> impl Iterator<Item = &'a RestingOrder> + 'a {
+        let bids = self.bids.values().rev().flatten();
+        let asks = self.asks.values().flatten();
+        bids.chain(asks)
+            .filter_map(|oid| self.orders.get(oid))
+            .filter(move |o| o.user.eq_ignore_ascii_case(user))
+    }
+
+    /// Users with resting orders and their order count, most orders first
+    pub fn users(&self) -> Vec<(&str, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for order in self.orders.values() {
+            *counts.entry(&order.user).or_default() += 1;
+        }
+        let mut users: Vec<_> = counts.into_iter().collect();
+        users.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        users
+    }
+
+    /// Aggregate the top `depth` levels of each side into an L2 book
+    pub fn to_l2(&self, depth: usize) -> L2Book {
+        let level = |(px, queue): (&Decimal, &VecDeque<u64>)| Level {
+            px: *px,
+            sz: queue.iter().filter_map(|oid| self.orders.get(oid)).map(|o| o.sz).sum(),
+            n: queue.len() as u32,
+        };
+        L2Book {
+            coin: self.coin.clone(),
+            time: self.time,
+            levels: (
+                self.bids.iter().rev().take(depth).map(level).collect(),
+                self.asks.iter().take(depth).map(level).collect(),
+            ),
+        }
+    }
+
+    /// Levels where this book and an L2 snapshot disagree on size or order
+    /// count. Only prices the snapshot covers are compared, since it is
+    /// truncated to its top levels.
+    pub fn compare(&self, snapshot: &L2Book) -> Vec<Discrepancy> {
+        let ours = self.to_l2(usize::MAX);
+        let mut found = Vec::new();
+        for (side, l4, l2) in [(Side::Buy, ours.bids(), snapshot.bids()), (Side::Sell, ours.asks(), snapshot.asks())] {
+            let Some(worst) = l2.last().map(|l| l.px) else {
+                found.extend(l4.iter().take(1).map(|l| Discrepancy { side, px: l.px, l4: Some(l.clone()), l2: None }));
+                continue;
+            };
+            let covered = |px: Decimal| if side.is_buy() { px >= worst } else { px <= worst };
+            let mut prices: BTreeMap<Decimal, (Option<&Level>, Option<&Level>)> = BTreeMap::new();
+            for level in l4.iter().filter(|l| covered(l.px)) {
+                prices.entry(level.px).or_default().0 = Some(level);
+            }
+            for level in l2 {
+                prices.entry(level.px).or_default().1 = Some(level);
+            }
+            for (px, (l4, l2)) in prices {
+                let same = matches!((l4, l2), (Some(a), Some(b)) if a.sz == b.sz && a.n == b.n);
+                if !same {
+                    found.push(Discrepancy { side, px, l4: l4.cloned(), l2: l2.cloned() });
+                }
+            }
+        }
+        found
+    }
+
+    fn ladder(&self, side: Side) -> &BTreeMap<Decimal, VecDeque<u64>> {
+        if side.is_buy() {
+            &self.bids
+        } else {
+            &self.asks
+        }
+    }
+
+    fn ladder_mut(&mut self, side: Side) -> &mut BTreeMap<Decimal, VecDeque<u64>> {
+        if side.is_buy() {
+            &mut self.bids
+        } else {
+            &mut self.asks
+        }
+    }
+}
+
+fn resting(order: &L4Order) -> RestingOrder {
+    RestingOrder {
+        oid: order.oid,
+        user: order.user.clone(),
+        side: order.side,
+        px: order.limit_px,
+        sz: order.sz,
+    }
+}
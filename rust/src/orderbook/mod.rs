@@ -0,0 +1,376 @@
+//! Local L2 order book maintained from stream messages.
+//!
+//! [`OrderBook`] keeps sorted bid and ask ladders per coin. Hand it every
+//! message from `stream.l2_book`, `grpc.l2_book` or `book_updates` with
+//! [`OrderBook::ingest`]: snapshots replace a coin's ladders (older ones are
+//! skipped), updates change single price levels and a size of zero removes a
+//! level. Each coin's [`Book`] answers best bid/ask, depth within N bps of the
+//! mid, the VWAP to fill a size and the bid/ask imbalance;
+//! [`OrderBook::issues`] reports books that are empty, crossed or stale.
+//!
+//! [`L4Book`] does the same order by order from the gRPC `l4_book` stream.
+//!
+//! ```no_run
+//! use std::sync::{Arc, Mutex};
+//! use hyperliquid_examples::orderbook::OrderBook;
+//!
+//! # fn run(sdk: &hyperliquid_sdk::HyperliquidSDK) {
+//! let books = Arc::new(Mutex::new(OrderBook::new()));
+//! let feed = books.clone();
+//! let mut stream = sdk.stream();
+//! stream.l2_book("BTC", move |data| {
+//!     let _ = feed.lock().unwrap().ingest(&data);
+//! });
+//! # }
+//! ```
+
+mod l4;
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::models::{block_events, decode, is_grpc_snapshot, BookUpdate, GrpcL2Book, L2Book, Level, LevelChange, Side};
+
+pub use l4::{Discrepancy, L4Book, QueuePosition, RestingOrder};
+
+/// How long a book may go without a message before [`OrderBook::issues`] calls it stale
+pub const DEFAULT_STALE_AFTER: Duration = Duration::from_secs(5);
+
+const BPS: Decimal = Decimal::from_parts(10_000, 0, 0, false, 0);
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Book
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Bid and ask ladders of one coin
+#[derive(Debug, Clone, Default)]
+pub struct Book {
+    coin: String,
+    bids: BTreeMap<Decimal, Level>,
+    asks: BTreeMap<Decimal, Level>,
+    /// Exchange time of the newest message (ms)
+    time: u64,
+    /// Local time the newest message was received (ms)
+    received_ms: u64,
+    /// Snapshots and updates applied
+    messages: u64,
+}
+
+/// Size resting within some distance of the mid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Depth {
+    pub bid: Decimal,
+    pub ask: Decimal,
+}
+
+impl Book {
+    fn new(coin: &str) -> Self {
+        Book { coin: coin.to_string(), ..Default::default() }
+    }
+
+    pub fn coin(&self) -> &str {
+        &self.coin
+    }
+
+    /// Exchange time of the newest message (ms)
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Local time the newest message was received (ms)
+    pub fn received_ms(&self) -> u64 {
+        self.received_ms
+    }
+
+    /// Number of snapshots and updates applied
+    pub fn messages(&self) -> u64 {
+        self.messages
+    }
+
+    /// Bid levels, best (highest) first
+    pub fn bids(&self) -> impl Iterator<Item = &Level> {
+        self.bids.values().rev()
+    }
+
+    /// Ask levels, best (lowest) first
+    pub fn asks(&self) -> impl Iterator<Item = &Level> {
+        self.asks.values()
+    }
+
+    /// Levels a taker on `side` trades against: asks for a buy, bids for a sell
+    fn opposite(&self, side: Side) -> Box<dyn Iterator<Item = &Level> + '_> {
+        match side {
+            Side::Buy => Box::new(self.asks()),
+            Side::Sell => Box::new(self.bids()),
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<&Level> {
+        self.bids.values().next_back()
+    }
+
+    pub fn best_ask(&self) -> Option<&Level> {
+        self.asks.values().next()
+    }
+
+    /// Midpoint of the best bid and ask
+    pub fn mid(&self) -> Option<Decimal> {
+        Some((self.best_bid()?.px + self.best_ask()?.px) / Decimal::TWO)
+    }
+
+    /// Best ask minus best bid (negative when crossed)
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.px - self.best_bid()?.px)
+    }
+
+    /// Spread in basis points of the mid
+    pub fn spread_bps(&self) -> Option<Decimal> {
+        let mid = self.mid().filter(|mid| !mid.is_zero())?;
+        Some(self.spread()? / mid * BPS)
+    }
+
+    /// Whether the best bid is at or above the best ask
+    pub fn is_crossed(&self) -> bool {
+        matches!((self.best_bid(), self.best_ask()), (Some(bid), Some(ask)) if bid.px >= ask.px)
+    }
+
+    /// Size on each side priced within `bps` basis points of the mid
+    pub fn depth_bps(&self, bps: Decimal) -> Option<Depth> {
+        let mid = self.mid()?;
+        let band = mid * bps / BPS;
+        Some(Depth {
+            bid: self.bids().take_while(|l| l.px >= mid - band).map(|l| l.sz).sum(),
+            ask: self.asks().take_while(|l| l.px <= mid + band).map(|l| l.sz).sum(),
+        })
+    }
+
+    /// Average price a taker on `side` would pay to fill `size` by walking the
+    /// book, or `None` if the book is too thin (or `size` is not positive)
+    pub fn vwap(&self, side: Side, size: Decimal) -> Option<Decimal> {
+        if size <= Decimal::ZERO {
+            return None;
+        }
+        let mut left = size;
+        let mut notional = Decimal::ZERO;
+        for level in self.opposite(side) {
+            let take = left.min(level.sz);
+            notional += take * level.px;
+            left -= take;
+            if left.is_zero() {
+                return Some(notional / size);
+            }
+        }
+        None
+    }
+
+    /// `(bid - ask) / (bid + ask)` of the size in the top `levels` of each
+    /// side: +1 is all bids, -1 all asks
+    pub fn imbalance(&self, levels: usize) -> Option<Decimal> {
+        let bid: Decimal = self.bids().take(levels).map(|l| l.sz).sum();
+        let ask: Decimal = self.asks().take(levels).map(|l| l.sz).sum();
+        let total = bid + ask;
+        (!total.is_zero()).then(|| (bid - ask) / total)
+    }
+
+    /// Milliseconds since the newest message was received
+    pub fn age_ms(&self, now_ms: u64) -> u64 {
+        now_ms.saturating_sub(self.received_ms)
+    }
+
+    /// What is wrong with this book at `now_ms`, if anything
+    pub fn issue(&self, now_ms: u64, stale_after: Duration) -> Option<BookIssue> {
+        if let (Some(bid), Some(ask)) = (self.best_bid(), self.best_ask()) {
+            if bid.px >= ask.px {
+                return Some(BookIssue::Crossed { bid: bid.px, ask: ask.px });
+            }
+        } else {
+            return Some(BookIssue::Empty);
+        }
+        let age_ms = self.age_ms(now_ms);
+        (age_ms > stale_after.as_millis() as u64).then_some(BookIssue::Stale { age_ms })
+    }
+
+    /// Top `depth` levels of each side as an [`L2Book`]
+    pub fn to_l2(&self, depth: usize) -> L2Book {
+        L2Book {
+            coin: self.coin.clone(),
+            time: self.time,
+            levels: (self.bids().take(depth).cloned().collect(), self.asks().take(depth).cloned().collect()),
+        }
+    }
+
+    fn replace(&mut self, book: &L2Book) {
+        let ladder = |levels: &[Level]| {
+            levels
+                .iter()
+                .filter(|l| !l.sz.is_zero())
+                .map(|l| (l.px, l.clone()))
+                .collect()
+        };
+        self.bids = ladder(book.bids());
+        self.asks = ladder(book.asks());
+    }
+
+    fn change(&mut self, is_bid: bool, change: &LevelChange) {
+        let ladder = if is_bid { &mut self.bids } else { &mut self.asks };
+        if change.sz.is_zero() {
+            ladder.remove(&change.px);
+            return;
+        }
+        let n = change.n.or_else(|| ladder.get(&change.px).map(|l| l.n)).unwrap_or_default();
+        ladder.insert(change.px, Level { px: change.px, sz: change.sz, n });
+    }
+
+    fn touch(&mut self, time: Option<u64>, received_ms: u64) {
+        self.time = time.unwrap_or(self.time).max(self.time);
+        self.received_ms = received_ms;
+        self.messages += 1;
+    }
+}
+
+/// Reason a book should not be trusted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BookIssue {
+    /// No bids or no asks
+    Empty,
+    /// Best bid at or above the best ask
+    Crossed { bid: Decimal, ask: Decimal },
+    /// No message for longer than allowed
+    Stale { age_ms: u64 },
+}
+
+impl fmt::Display for BookIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookIssue::Empty => f.write_str("one side is empty"),
+            BookIssue::Crossed { bid, ask } => write!(f, "crossed: bid {} >= ask {}", bid, ask),
+            BookIssue::Stale { age_ms } => write!(f, "stale: no update for {}ms", age_ms),
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// OrderBook
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Books of every coin seen on the stream
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    books: BTreeMap<String, Book>,
+    /// Snapshots older than the book they would replace
+    skipped: u64,
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn book(&self, coin: &str) -> Option<&Book> {
+        self.books.get(coin)
+    }
+
+    /// Coins with a book, sorted
+    pub fn coins(&self) -> impl Iterator<Item = &str> {
+        self.books.keys().map(String::as_str)
+    }
+
+    /// Number of out-of-order snapshots skipped
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    /// Apply a stream message received now; see [`OrderBook::ingest_at`]
+    pub fn ingest(&mut self, message: &Value) -> Result<Vec<String>> {
+        self.ingest_at(message, now_ms())
+    }
+
+    /// Apply a stream message received at `received_ms` and return the coins
+    /// whose book changed.
+    ///
+    /// Accepts WebSocket `l2Book` messages, gRPC `l2_book` snapshots and
+    /// `book_updates` from either transport. Messages tagged with another
+    /// channel are ignored.
+    pub fn ingest_at(&mut self, message: &Value, received_ms: u64) -> Result<Vec<String>> {
+        let channel = message.get("channel").and_then(|c| c.as_str());
+        let payload = message.get("data").unwrap_or(message);
+        let mut changed = Vec::new();
+
+        match channel {
+            Some("l2Book") | None if payload.get("levels").is_some() => {
+                let book: L2Book = decode(payload.clone())?;
+                if self.apply_snapshot(&book, received_ms) {
+                    changed.push(book.coin);
+                }
+            }
+            Some("l2Book") | Some("book_updates") | Some("bookUpdates") | None => {
+                for (_, item) in block_events(payload) {
+                    if is_grpc_snapshot(item) {
+                        let book: GrpcL2Book = decode(item.clone())?;
+                        let book = L2Book::from(book);
+                        if self.apply_snapshot(&book, received_ms) {
+                            changed.push(book.coin);
+                        }
+                    } else {
+                        let update: BookUpdate = decode(item.clone())?;
+                        self.apply_update(&update, received_ms);
+                        changed.push(update.coin);
+                    }
+                }
+            }
+            Some(_) => {}
+        }
+        changed.sort();
+        changed.dedup();
+        Ok(changed)
+    }
+
+    /// Replace a coin's ladders; returns `false` (and skips it) when the
+    /// snapshot is older than the book
+    pub fn apply_snapshot(&mut self, snapshot: &L2Book, received_ms: u64) -> bool {
+        let book = self.books.entry(snapshot.coin.clone()).or_insert_with(|| Book::new(&snapshot.coin));
+        if snapshot.time < book.time {
+            self.skipped += 1;
+            return false;
+        }
+        book.replace(snapshot);
+        book.touch(Some(snapshot.time), received_ms);
+        true
+    }
+
+    /// Change the levels listed in `update`
+    pub fn apply_update(&mut self, update: &BookUpdate, received_ms: u64) {
+        let book = self.books.entry(update.coin.clone()).or_insert_with(|| Book::new(&update.coin));
+        for change in &update.bids {
+            book.change(true, change);
+        }
+        for change in &update.asks {
+            book.change(false, change);
+        }
+        book.touch(update.time, received_ms);
+    }
+
+    /// Books that are empty, crossed or stale at `now_ms`
+    pub fn issues(&self, now_ms: u64, stale_after: Duration) -> Vec<(&str, BookIssue)> {
+        self.books
+            .values()
+            .filter_map(|book| Some((book.coin(), book.issue(now_ms, stale_after)?)))
+            .collect()
+    }
+}
+
+/// Current time in ms, the clock [`OrderBook::ingest`] stamps messages with
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
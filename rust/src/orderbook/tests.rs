@@ -0,0 +1,335 @@
+//! Replays recorded stream messages through the local book.
+
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use super::*;
+
+/// Recorded messages, one per line
+fn recording(name: &str) -> Vec<Value> {
+    let text = match name {
+        "book_updates" => include_str!("fixtures/book_updates.ndjson"),
+        "grpc_l2_book" => include_str!("fixtures/grpc_l2_book.ndjson"),
+        "l4_book" => include_str!("fixtures/l4_book.ndjson"),
+        "ws_l2_book" => include_str!("fixtures/ws_l2_book.ndjson"),
+        _ => panic!("unknown recording {}", name),
+    };
+    text.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+}
+
+/// Ingest every message of a recording, 100ms apart, returning the coins each one changed
+fn replay(books: &mut OrderBook, name: &str) -> Vec<Vec<String>> {
+    recording(name)
+        .iter()
+        .enumerate()
+        .map(|(i, message)| books.ingest_at(message, i as u64 * 100).unwrap())
+        .collect()
+}
+
+fn dec(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+/// `(px, sz)` of each level, best first
+fn ladder<'a>(levels: impl Iterator<Item = &'a Level>) -> Vec<(Decimal, Decimal)> {
+    levels.map(|l| (l.px, l.sz)).collect()
+}
+
+fn levels(pairs: &[(&str, &str)]) -> Vec<(Decimal, Decimal)> {
+    pairs.iter().map(|(px, sz)| (dec(px), dec(sz))).collect()
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Snapshots
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn ws_snapshots_replace_the_book_and_skip_older_ones() {
+    let mut books = OrderBook::new();
+    let changed = replay(&mut books, "ws_l2_book");
+
+    assert!(changed[0].is_empty(), "subscription response");
+    assert_eq!(changed[1], ["BTC"]);
+    assert!(changed[4].is_empty(), "older snapshot");
+    assert!(changed[5].is_empty(), "trades message");
+    assert_eq!(books.skipped(), 1);
+    assert_eq!(books.coins().collect::<Vec<_>>(), ["BTC", "ETH"]);
+
+    let btc = books.book("BTC").unwrap();
+    assert_eq!(btc.time(), 1737412345500);
+    assert_eq!(btc.messages(), 2);
+    assert_eq!(ladder(btc.bids()), levels(&[("96251", "1"), ("96250", "2")]));
+    assert_eq!(ladder(btc.asks()), levels(&[("96252", "0.4"), ("96253", "1.1"), ("96300", "5")]));
+    assert_eq!(books.book("ETH").unwrap().mid(), Some(dec("3612.4")));
+}
+
+#[test]
+fn queries_walk_the_ladders() {
+    let mut books = OrderBook::new();
+    replay(&mut books, "ws_l2_book");
+    let btc = books.book("BTC").unwrap();
+
+    assert_eq!(btc.best_bid().unwrap().n, 2);
+    assert_eq!(btc.best_ask().unwrap().px, dec("96252"));
+    assert_eq!(btc.mid(), Some(dec("96251.5")));
+    assert_eq!(btc.spread(), Some(dec("1")));
+    assert_eq!(btc.spread_bps(), Some(dec("1") / dec("96251.5") * dec("10000")));
+
+    // 1 bps of 96251.5 is ~9.6: both bids, the first two asks
+    assert_eq!(btc.depth_bps(dec("1")), Some(Depth { bid: dec("3"), ask: dec("1.5") }));
+    assert_eq!(btc.depth_bps(dec("10")), Some(Depth { bid: dec("3"), ask: dec("6.5") }));
+
+    // 0.4 @ 96252 + 0.6 @ 96253
+    assert_eq!(btc.vwap(Side::Buy, dec("1")), Some(dec("96252.6")));
+    // 1 @ 96251 + 1.5 @ 96250
+    assert_eq!(btc.vwap(Side::Sell, dec("2.5")), Some(dec("96250.4")));
+    assert!(btc.vwap(Side::Buy, dec("6.5")).is_some());
+    assert_eq!(btc.vwap(Side::Buy, dec("6.6")), None);
+    assert_eq!(btc.vwap(Side::Buy, Decimal::ZERO), None);
+
+    assert_eq!(btc.imbalance(1), Some(dec("0.6") / dec("1.4")));
+    assert_eq!(btc.imbalance(10), Some(dec("-3.5") / dec("9.5")));
+    assert_eq!(OrderBook::new().book("BTC").and_then(|b| b.imbalance(1)), None);
+}
+
+#[test]
+fn grpc_snapshots_flag_a_crossed_book() {
+    let mut books = OrderBook::new();
+    let messages = recording("grpc_l2_book");
+
+    assert_eq!(books.ingest_at(&messages[0], 0).unwrap(), ["SOL"]);
+    let sol = books.book("SOL").unwrap();
+    assert_eq!(ladder(sol.bids()), levels(&[("187.23", "40"), ("187.20", "100")]));
+    assert!(books.issues(0, DEFAULT_STALE_AFTER).is_empty());
+
+    books.ingest_at(&messages[1], 100).unwrap();
+    assert!(books.book("SOL").unwrap().is_crossed());
+    assert_eq!(
+        books.issues(100, DEFAULT_STALE_AFTER),
+        [("SOL", BookIssue::Crossed { bid: dec("187.25"), ask: dec("187.24") })]
+    );
+
+    books.ingest_at(&messages[2], 200).unwrap();
+    assert!(!books.book("SOL").unwrap().is_crossed());
+    assert!(books.issues(200, DEFAULT_STALE_AFTER).is_empty());
+}
+
+#[test]
+fn snapshot_round_trips_through_to_l2() {
+    let snapshot: L2Book = serde_json::from_str(include_str!("../models/fixtures/l2_book.json")).unwrap();
+    let mut books = OrderBook::new();
+    assert!(books.apply_snapshot(&snapshot, 0));
+
+    let book = books.book("BTC").unwrap();
+    assert_eq!(book.to_l2(20), snapshot);
+    assert_eq!(book.to_l2(1).bids(), &snapshot.bids()[..1]);
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Updates
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn book_updates_change_single_levels() {
+    let mut books = OrderBook::new();
+    let messages = recording("book_updates");
+    let hype = |books: &OrderBook| {
+        let book = books.book("HYPE").unwrap();
+        (ladder(book.bids()), ladder(book.asks()))
+    };
+
+    books.ingest_at(&messages[0], 0).unwrap();
+
+    // HyperCore block: 25.00 removed, 24.97 added, 25.01 resized
+    assert_eq!(books.ingest_at(&messages[1], 100).unwrap(), ["HYPE"]);
+    assert_eq!(
+        hype(&books),
+        (
+            levels(&[("24.99", "20"), ("24.98", "30"), ("24.97", "12")]),
+            levels(&[("25.01", "8"), ("25.02", "15"), ("25.03", "40")]),
+        )
+    );
+    assert_eq!(books.book("HYPE").unwrap().time(), 1737412347100);
+
+    // gRPC: {price, size} levels without an order count or time
+    books.ingest_at(&messages[2], 200).unwrap();
+    assert_eq!(
+        hype(&books),
+        (
+            levels(&[("25.00", "6"), ("24.99", "20"), ("24.98", "30"), ("24.97", "12")]),
+            levels(&[("25.01", "8"), ("25.03", "40")]),
+        )
+    );
+    let book = books.book("HYPE").unwrap();
+    assert_eq!(book.time(), 1737412347100);
+    assert_eq!(book.messages(), 3);
+    assert_eq!(book.best_bid().unwrap().n, 0);
+    assert_eq!(book.bids().nth(1).unwrap().n, 4, "unchanged level keeps its count");
+
+    books.ingest_at(&messages[3], 300).unwrap();
+    assert_eq!(
+        books.issues(300, DEFAULT_STALE_AFTER),
+        [("HYPE", BookIssue::Crossed { bid: dec("25.05"), ask: dec("25.01") })]
+    );
+    books.ingest_at(&messages[4], 400).unwrap();
+    assert_eq!(books.book("HYPE").unwrap().best_bid().unwrap().px, dec("25.00"));
+    assert!(books.issues(400, DEFAULT_STALE_AFTER).is_empty());
+}
+
+#[test]
+fn updated_level_keeps_its_order_count() {
+    let mut books = OrderBook::new();
+    let messages = recording("book_updates");
+    books.ingest_at(&messages[0], 0).unwrap();
+    books.ingest_at(&json!({"coin": "HYPE", "bids": [{"price": "24.99", "size": "21"}], "asks": []}), 100).unwrap();
+
+    let level = books.book("HYPE").unwrap().bids().nth(1).unwrap().clone();
+    assert_eq!((level.sz, level.n), (dec("21"), 4));
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Health
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn books_go_stale_without_messages() {
+    let mut books = OrderBook::new();
+    replay(&mut books, "ws_l2_book");
+
+    // BTC last changed at 300ms, ETH at 200ms
+    assert!(books.issues(5_200, DEFAULT_STALE_AFTER).is_empty());
+    assert_eq!(books.issues(5_250, DEFAULT_STALE_AFTER), [("ETH", BookIssue::Stale { age_ms: 5_050 })]);
+    assert_eq!(books.issues(6_000, Duration::from_secs(10)), []);
+    assert_eq!(books.book("BTC").unwrap().age_ms(6_000), 5_700);
+}
+
+#[test]
+fn one_sided_book_is_empty() {
+    let mut books = OrderBook::new();
+    books.ingest_at(&json!({"coin": "PURR/USDC", "bids": [{"price": "0.21", "size": "100"}], "asks": []}), 0).unwrap();
+
+    let book = books.book("PURR/USDC").unwrap();
+    assert_eq!(book.mid(), None);
+    assert_eq!(book.depth_bps(dec("10")), None);
+    assert_eq!(book.vwap(Side::Buy, dec("1")), None);
+    assert_eq!(book.vwap(Side::Sell, dec("50")), Some(dec("0.21")));
+    assert_eq!(book.issue(0, DEFAULT_STALE_AFTER), Some(BookIssue::Empty));
+    assert_eq!(BookIssue::Empty.to_string(), "one side is empty");
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// L4
+// ══════════════════════════════════════════════════════════════════════════════
+
+const ALICE: &str = "0x31ca8395cf837de08b24da3f660e77761dfb974b";
+const BOB: &str = "0x5b5d51203a0f9079f8aeb098a6523a13f298c060";
+const DAVE: &str = "0xf9109ada2f73c62e9889b45453065f0d99260a2d";
+
+/// `(oid, sz)` of the orders at one price, in queue order
+fn queue(book: &L4Book, side: Side, px: &str) -> Vec<(u64, Decimal)> {
+    book.level(side, dec(px)).map(|o| (o.oid, o.sz)).collect()
+}
+
+/// ETH L4 book after the first `n` recorded messages
+fn l4_after(n: usize) -> L4Book {
+    let mut book = L4Book::new("ETH");
+    for message in recording("l4_book").iter().take(n) {
+        book.ingest(message).unwrap();
+    }
+    book
+}
+
+#[test]
+fn l4_snapshot_queues_orders_by_time() {
+    let mut book = L4Book::new("ETH");
+    let messages = recording("l4_book");
+    assert!(!book.ingest(&messages[0]).unwrap(), "diff before the snapshot");
+    assert!(book.ingest(&messages[1]).unwrap());
+
+    assert!(book.is_synced());
+    assert_eq!(book.height(), 100);
+    assert_eq!(book.len(), 6, "trigger order is not resting");
+    assert_eq!(queue(&book, Side::Buy, "3612.3"), [(11, dec("1")), (12, dec("2"))]);
+    assert_eq!(queue(&book, Side::Sell, "3612.5"), [(22, dec("1.5")), (21, dec("0.5"))]);
+    assert_eq!(
+        book.queue_position(21),
+        Some(QueuePosition { orders_ahead: 1, size_ahead: dec("1.5"), level_size: dec("2") })
+    );
+}
+
+#[test]
+fn l4_diffs_add_modify_and_remove_by_oid() {
+    let book = l4_after(4);
+
+    assert_eq!(book.height(), 101, "repeated height is skipped");
+    assert_eq!(book.time(), 1737412349100);
+    assert_eq!(book.order(13).map(|o| o.sz), Some(dec("5")));
+    assert_eq!(book.order(99), None, "other coin");
+
+    // The new order takes its side from the order status and joins the back;
+    // the partially filled one keeps its place
+    assert_eq!(queue(&book, Side::Buy, "3612.3"), [(11, dec("0.4")), (12, dec("2")), (31, dec("0.7"))]);
+    assert_eq!(book.order(31).unwrap().user, DAVE);
+    assert_eq!(
+        book.queue_position(31),
+        Some(QueuePosition { orders_ahead: 2, size_ahead: dec("2.4"), level_size: dec("3.1") })
+    );
+    assert_eq!(queue(&book, Side::Sell, "3612.5"), [(21, dec("0.5"))]);
+    assert_eq!(book.queue_position(21).unwrap().orders_ahead, 0);
+
+    let book = l4_after(5);
+    assert_eq!(book.unmatched(), 1);
+    assert_eq!(queue(&book, Side::Buy, "3612.3"), [(11, dec("0.4")), (31, dec("0.7"))]);
+    assert_eq!(book.queue_position(31).unwrap().size_ahead, dec("0.4"));
+    assert_eq!(book.queue_position(12), None);
+}
+
+#[test]
+fn l4_attributes_orders_to_users() {
+    let book = l4_after(5);
+
+    // Best bid first; addresses match in any case
+    let alice: Vec<u64> = book.orders_of(&ALICE.to_uppercase()).map(|o| o.oid).collect();
+    assert_eq!(alice, [11, 13, 23]);
+    assert_eq!(book.orders_of(BOB).map(|o| o.oid).collect::<Vec<_>>(), [41, 21]);
+    assert_eq!(book.users()[0], (ALICE, 3));
+    assert_eq!(book.users().len(), 3);
+}
+
+#[test]
+fn l4_aggregates_to_l2_and_compares_with_a_snapshot() {
+    let book = l4_after(5);
+    let l2 = book.to_l2(2);
+    assert_eq!(ladder(l2.bids().iter()), levels(&[("3612.4", "2"), ("3612.3", "1.1")]));
+    assert_eq!(l2.bids()[1].n, 2);
+    assert_eq!(ladder(l2.asks().iter()), levels(&[("3612.5", "0.5"), ("3613", "4")]));
+
+    // Truncated snapshot that agrees: deeper levels are not compared
+    let snapshot: L2Book = serde_json::from_value(json!({
+        "coin": "ETH",
+        "time": 1737412349200u64,
+        "levels": [
+            [{"px": "3612.4", "sz": "2.0", "n": 1}, {"px": "3612.3", "sz": "1.1", "n": 2}],
+            [{"px": "3612.5", "sz": "0.5", "n": 1}]
+        ]
+    }))
+    .unwrap();
+    assert_eq!(book.compare(&snapshot), []);
+
+    let snapshot: L2Book = serde_json::from_value(json!({
+        "coin": "ETH",
+        "time": 1737412349200u64,
+        "levels": [
+            [{"px": "3612.4", "sz": "2.0", "n": 1}, {"px": "3612.3", "sz": "1.2", "n": 2}, {"px": "3612.0", "sz": "5", "n": 1}],
+            [{"px": "3612.5", "sz": "0.5", "n": 1}, {"px": "3612.9", "sz": "1", "n": 1}]
+        ]
+    }))
+    .unwrap();
+    let found = book.compare(&snapshot);
+    assert_eq!(found.len(), 2, "{:?}", found);
+    assert_eq!((found[0].side, found[0].px), (Side::Buy, dec("3612.3")));
+    assert_eq!(found[0].to_string(), "BUY 3612.3: L4 1.1 (2) vs L2 1.2 (2)");
+    assert_eq!((found[1].side, found[1].px, found[1].l4.is_none()), (Side::Sell, dec("3612.9"), true));
+}
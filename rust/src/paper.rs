@@ -0,0 +1,367 @@
+//! Paper trading against the live market.
+//!
+//! With `--paper` (or `PAPER=1`), [`prepare`](crate::prepare) routes the
+//! process's HTTPS traffic through a local port before the runtime starts,
+//! the same way tests reach the [`mock`](crate::mock) server, and
+//! [`Config::connect`](crate::Config::connect) starts a [`PaperExchange`] on
+//! it before building the SDK. Signed `/exchange` actions (orders, cancels,
+//! modifies, leverage, transfers) go to a local [`MatchingEngine`] and never
+//! reach Hyperliquid. Queries about the paper account (open orders, order
+//! status, fills, margin) are answered by the engine. Every other request is
+//! forwarded unchanged, so prices and metadata stay live.
+//!
+//! The first order on a coin loads its book and subscribes to its `l2Book`
+//! and `trades` streams. Market orders then sweep real liquidity, and resting
+//! orders fill when the market trades through them. Fees use the base tier.
+//! Call [`report`] before exiting to print fills, fees and P&L:
+//!
+//! ```bash
+//! cargo run --bin market_order -- --paper
+//! PAPER=1 PAPER_USDC=50000 cargo run --bin roundtrip
+//! ```
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::Bytes;
+use axum::extract::{Request, State};
+use axum::http::{Method, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use hyperliquid_sdk::{Chain, HyperliquidSDK, Stream};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::task::JoinHandle;
+
+use crate::config::Config;
+use crate::dry_run;
+use crate::error::{Error, Result};
+use crate::mock::proxy::{self, Intercept, Tunnel};
+use crate::mock::{parse_body, MatchingEngine};
+use crate::models::{stream_items, L2Book, Trade, TypedInfo};
+use crate::registry::AssetRegistry;
+
+/// Key used when none is configured (Anvil's first dev key); paper actions
+/// are signed but never sent anywhere
+pub const PAPER_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Starting collateral when `PAPER_USDC` is not set
+pub const DEFAULT_USDC: Decimal = Decimal::from_parts(10_000, 0, 0, false, 0);
+
+static EXCHANGE: OnceLock<PaperExchange> = OnceLock::new();
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Entry points
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Build an SDK whose trading goes to the paper exchange (started on first use)
+pub async fn connect(config: &Config) -> Result<HyperliquidSDK> {
+    let mut config = config.clone();
+    if config.private_key.is_none() {
+        config.private_key = Some(PAPER_KEY.to_string());
+    }
+
+    if EXCHANGE.get().is_none() {
+        let usdc = match std::env::var("PAPER_USDC") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .map_err(|_| Error::ConfigError(format!("invalid PAPER_USDC '{}'", value)))?,
+            Err(_) => DEFAULT_USDC,
+        };
+        let exchange = PaperExchange::start(Intercept::take()?, &config, usdc).await?;
+        println!(
+            "Paper trading: orders fill on a local simulated exchange fed by {} ({} USDC)",
+            config.network,
+            usdc.normalize()
+        );
+        EXCHANGE.get_or_init(|| exchange);
+    }
+    Ok(config.builder().build().await?)
+}
+
+/// Route the process's HTTPS through the paper exchange's port; see [`prepare`](crate::prepare)
+pub(crate) fn install() {
+    proxy::install("paper");
+}
+
+/// The paper exchange of this process, if paper trading is on
+pub fn exchange() -> Option<&'static PaperExchange> {
+    EXCHANGE.get()
+}
+
+/// Print the paper account's fills, fees and P&L (nothing outside paper mode)
+pub fn report() {
+    if let Some(exchange) = exchange() {
+        exchange.report();
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Exchange
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// A local exchange that simulates trading against live market data
+pub struct PaperExchange {
+    addr: SocketAddr,
+    shared: Arc<Paper>,
+    start_usdc: Decimal,
+    _task: JoinHandle<()>,
+}
+
+impl PaperExchange {
+    /// Start on the port the process's HTTPS goes through, trading as the
+    /// configured key's address.
+    ///
+    /// Until the exchange is up the port only forwards, so the SDK built
+    /// here loads the live metadata through it; the forwarding client was
+    /// built before the environment changed and still reaches the real API.
+    async fn start(intercept: Intercept, config: &Config, usdc: Decimal) -> Result<Self> {
+        let gate = Arc::new(Gate { client: intercept.client.clone(), paper: OnceLock::new() });
+        let addr = intercept.addr;
+        let task = intercept.serve(router(gate.clone()))?;
+
+        let upstream = config.builder().build().await?;
+        let address = upstream
+            .address()
+            .map(|a| format!("{:?}", a))
+            .ok_or_else(|| Error::ConfigError("paper trading needs a private key".to_string()))?;
+        let registry = AssetRegistry::load(&upstream).await?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+
+        let shared = Arc::new(Paper {
+            engine: Arc::new(Mutex::new(MatchingEngine::with_registry(registry, &address, usdc, now))),
+            info: TypedInfo::from_sdk(&upstream),
+            endpoint: config.endpoint.clone(),
+            feeds: tokio::sync::Mutex::new(HashMap::new()),
+            chain: upstream.chain(),
+        });
+        let _ = gate.paper.set(shared.clone());
+
+        Ok(PaperExchange {
+            addr,
+            shared,
+            start_usdc: usdc,
+            _task: task,
+        })
+    }
+
+    /// Address the exchange listens on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The simulated account and books
+    pub fn engine(&self) -> MutexGuard<'_, MatchingEngine> {
+        self.shared.engine()
+    }
+
+    /// Print fills, fees and P&L since the exchange started
+    pub fn report(&self) {
+        let engine = self.engine();
+        let fills = engine.user_fills();
+        let state = engine.clearinghouse_state();
+        let volume: Decimal = fills.iter().map(|f| f.px * f.sz).sum();
+        let fees: Decimal = fills.iter().map(|f| f.fee).sum();
+        let realized: Decimal = fills.iter().map(|f| f.closed_pnl).sum();
+        let unrealized: Decimal = state.asset_positions.iter().map(|p| p.position.unrealized_pnl).sum();
+        let value = state.margin_summary.account_value;
+
+        println!("\nPaper trading summary:");
+        println!("   Fills: {} (${} volume)", fills.len(), volume.round_dp(2));
+        println!("   Fees: ${}", fees.round_dp(4).normalize());
+        println!("   Realized P&L: ${}", realized.round_dp(4).normalize());
+        println!("   Unrealized P&L: ${}", unrealized.round_dp(4).normalize());
+        for position in &state.asset_positions {
+            let p = &position.position;
+            println!(
+                "   Position: {} {} @ {}",
+                p.coin,
+                p.szi.normalize(),
+                p.entry_px.unwrap_or_default().normalize()
+            );
+        }
+        println!(
+            "   Account value: ${} -> ${} ({:+})",
+            self.start_usdc.normalize(),
+            value.round_dp(4).normalize(),
+            (value - self.start_usdc).round_dp(4).normalize()
+        );
+    }
+}
+
+/// What the port serves: forwarding alone until the exchange is up
+struct Gate {
+    /// Client for forwarded requests
+    client: reqwest::Client,
+    paper: OnceLock<Arc<Paper>>,
+}
+
+/// State shared by every connection
+struct Paper {
+    engine: Arc<Mutex<MatchingEngine>>,
+    /// Info client for book snapshots
+    info: TypedInfo,
+    /// Endpoint for the market data streams
+    endpoint: Option<String>,
+    /// Live streams by coin
+    feeds: tokio::sync::Mutex<HashMap<String, Stream>>,
+    /// Chain the upstream SDK signs for
+    chain: Chain,
+}
+
+impl Paper {
+    fn engine(&self) -> MutexGuard<'_, MatchingEngine> {
+        lock(&self.engine)
+    }
+
+    /// Start following the books of every coin `action` trades
+    async fn follow(&self, action: &Value) -> Result<()> {
+        let orders = action
+            .get("orders")
+            .and_then(|o| o.as_array())
+            .into_iter()
+            .flatten()
+            .chain(
+                action
+                    .get("modifies")
+                    .and_then(|m| m.as_array())
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|m| m.get("order")),
+            )
+            .chain(action.get("order"));
+        let coins: Vec<String> = {
+            let engine = self.engine();
+            orders
+                .filter_map(|o| o.get("a").and_then(|a| a.as_u64()))
+                .filter_map(|a| engine.registry().by_id(a as u32))
+                .map(|m| m.symbol.clone())
+                .collect()
+        };
+
+        let mut feeds = self.feeds.lock().await;
+        for coin in coins {
+            if feeds.contains_key(&coin) {
+                continue;
+            }
+            let book = self.info.l2_book(&coin).await?;
+            {
+                let mut engine = self.engine();
+                engine.set_book(&book);
+                engine.drain_events();
+            }
+            feeds.insert(coin.clone(), self.stream(&coin)?);
+        }
+        Ok(())
+    }
+
+    /// `l2Book` and `trades` stream of one coin, feeding the engine
+    fn stream(&self, coin: &str) -> Result<Stream> {
+        let mut stream = Stream::new(self.endpoint.clone());
+        let (engine, name) = (self.engine.clone(), coin.to_string());
+        stream.l2_book(coin, move |message| {
+            let Some(Ok(books)) = stream_items::<L2Book>(&message, "l2Book") else { return };
+            let mut engine = lock(&engine);
+            for book in books.iter().filter(|b| b.coin == name) {
+                engine.set_book(book);
+            }
+            engine.drain_events();
+        });
+        let (engine, name) = (self.engine.clone(), coin.to_string());
+        stream.trades(&[coin], move |message| {
+            let Some(Ok(trades)) = stream_items::<Trade>(&message, "trades") else { return };
+            let mut engine = lock(&engine);
+            for trade in trades.iter().filter(|t| t.coin == name) {
+                engine.on_trade(trade);
+            }
+            engine.drain_events();
+        });
+        stream.start()?;
+        Ok(stream)
+    }
+
+    /// Answer an info query about the paper account; `None` for anything else
+    fn account_info(&self, request: &Value) -> Option<Value> {
+        let engine = self.engine();
+        let user = request.get("user").and_then(|u| u.as_str())?;
+        if !user.eq_ignore_ascii_case(engine.address()) {
+            return None;
+        }
+        engine.account_info(request)
+    }
+}
+
+fn lock(engine: &Mutex<MatchingEngine>) -> MutexGuard<'_, MatchingEngine> {
+    engine.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// HTTP
+// ══════════════════════════════════════════════════════════════════════════════
+
+fn router(gate: Arc<Gate>) -> Router {
+    Router::new()
+        .route("/exchange", post(exchange_action))
+        .fallback(passthrough)
+        .with_state(gate)
+}
+
+/// Both halves of the SDK's build/send exchange flow, simulated locally
+async fn exchange_action(State(gate): State<Arc<Gate>>, body: Bytes) -> Response {
+    let Some(shared) = gate.paper.get() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Paper exchange starting").into_response();
+    };
+    let request = match parse_body(&body) {
+        Ok(request) => request,
+        Err(rejection) => return rejection.into_response(),
+    };
+    let Some(action) = request.get("action") else {
+        return Json(json!({"error": "Missing action"})).into_response();
+    };
+
+    // Build: hand back the hash to sign
+    if request.get("signature").is_none() {
+        let nonce = shared.engine().tick();
+        let hash = dry_run::action_hash(action, nonce, shared.chain);
+        return Json(json!({
+            "hash": hash.map(|h| h.to_string()).unwrap_or_else(|| format!("0x{:064x}", nonce)),
+            "nonce": nonce,
+            "action": action,
+        }))
+        .into_response();
+    }
+
+    if let Err(e) = shared.follow(action).await {
+        let message = format!("Paper trading could not load the market: {}", e);
+        return Json(json!({"status": "err", "response": message})).into_response();
+    }
+    let mut engine = shared.engine();
+    let response = engine.execute(action);
+    engine.drain_events();
+    Json(response).into_response()
+}
+
+/// Account queries from the engine; everything else forwarded upstream
+async fn passthrough(State(gate): State<Arc<Gate>>, request: Request) -> Response {
+    let (parts, body) = request.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    if let Some(shared) = gate.paper.get().filter(|_| parts.method == Method::POST && parts.uri.path().ends_with("/info")) {
+        let answer = serde_json::from_slice::<Value>(&body)
+            .ok()
+            .and_then(|request| shared.account_info(&request));
+        if let Some(answer) = answer {
+            return Json(answer).into_response();
+        }
+    }
+    let tunnel = parts.extensions.get::<Tunnel>();
+    proxy::forward(&gate.client, tunnel, parts.method.clone(), &parts.uri, parts.headers.clone(), body).await
+}
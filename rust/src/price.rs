@@ -0,0 +1,409 @@
+//! Exact prices and sizes that the exchange will accept.
+//!
+//! Hyperliquid rejects an order whose price or size has too many digits:
+//!
+//! - Sizes are multiples of the asset's lot, `10^-szDecimals`.
+//! - Prices have at most 5 significant figures and at most
+//!   `MAX_DECIMALS - szDecimals` decimal places (`MAX_DECIMALS` is 6 for perps,
+//!   8 for spot). Integer prices are always allowed.
+//!
+//! Computing `mid * 0.97` in `f64` breaks both rules routinely. [`Precision`]
+//! holds an asset's limits and rounds a [`Decimal`] into a [`Px`] or [`Sz`]
+//! that satisfies them, in the direction you choose:
+//!
+//! ```
+//! use hyperliquid_examples::{Precision, Rounding};
+//! use rust_decimal::Decimal;
+//!
+//! let btc = Precision::perp(5);
+//! let mid = Decimal::new(9625050, 2); // 96250.50
+//!
+//! // A resting buy 3% under the mid, rounded down so it never crosses
+//! let px = btc.px_away_from(mid * Decimal::new(97, 2), mid).unwrap();
+//! assert_eq!(px.to_string(), "93362");
+//!
+//! let sz = btc.sz(Decimal::new(123456, 8), Rounding::Down).unwrap();
+//! assert_eq!(sz.to_string(), "0.00123");
+//! ```
+
+use std::fmt;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Serialize, Serializer};
+
+use crate::error::{Error, Result};
+use crate::models::{AssetInfo, Meta, SpotMeta};
+
+/// Significant figures allowed in a price
+pub const MAX_SIG_FIGS: u32 = 5;
+
+/// Decimal places available to perp prices before subtracting `szDecimals`
+pub const PERP_MAX_DECIMALS: u32 = 6;
+
+/// Decimal places available to spot prices before subtracting `szDecimals`
+pub const SPOT_MAX_DECIMALS: u32 = 8;
+
+/// Worst price of a market order as a fraction of the reference price (the
+/// SDK's 3%): the IOC is priced at mid ± slippage
+pub const DEFAULT_SLIPPAGE: Decimal = Decimal::from_parts(3, 0, 0, false, 2);
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Rounding
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Direction to round a price or size onto the tick/lot grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Nearest valid value, halves away from zero
+    Nearest,
+    /// Largest valid value not above the input
+    Down,
+    /// Smallest valid value not below the input
+    Up,
+}
+
+impl Rounding {
+    /// Round `value` toward `mid` (a more aggressive limit price)
+    pub fn toward(value: Decimal, mid: Decimal) -> Self {
+        if value < mid {
+            Rounding::Up
+        } else if value > mid {
+            Rounding::Down
+        } else {
+            Rounding::Nearest
+        }
+    }
+
+    /// Round `value` away from `mid` (a more passive limit price)
+    pub fn away_from(value: Decimal, mid: Decimal) -> Self {
+        if value < mid {
+            Rounding::Down
+        } else if value > mid {
+            Rounding::Up
+        } else {
+            Rounding::Nearest
+        }
+    }
+
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            Rounding::Nearest => RoundingStrategy::MidpointAwayFromZero,
+            Rounding::Down => RoundingStrategy::ToNegativeInfinity,
+            Rounding::Up => RoundingStrategy::ToPositiveInfinity,
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Px / Sz
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// A price that is valid on the tick grid of the asset it was rounded for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Px(Decimal);
+
+/// A size that is a whole number of lots of the asset it was rounded for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sz(Decimal);
+
+macro_rules! wire_decimal {
+    ($name:ident) => {
+        impl $name {
+            /// Exact value
+            pub fn value(self) -> Decimal {
+                self.0
+            }
+
+            /// Value as `f64`, for SDK methods that take floats.
+            ///
+            /// The result is the double nearest to the exact value, but the
+            /// SDK's order methods (`sdk.order`, `sdk.buy`, `sdk.trigger_order`,
+            /// ...) round prices to whole numbers before sending them. Where
+            /// the tick is below 1, put the [`Display`](fmt::Display) string
+            /// in the action instead and send it with
+            /// [`exchange::send`](crate::exchange::send).
+            pub fn to_f64(self) -> f64 {
+                self.0.to_f64().unwrap_or_default()
+            }
+        }
+
+        impl From<$name> for Decimal {
+            fn from(value: $name) -> Decimal {
+                value.0
+            }
+        }
+
+        /// Wire format: no trailing zeros (`"96250"`, `"0.001"`)
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0.normalize(), f)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+    };
+}
+
+wire_decimal!(Px);
+wire_decimal!(Sz);
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Precision
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Price and size limits of one asset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Precision {
+    /// Size decimals (`szDecimals` in `meta` / `spotMeta`)
+    pub sz_decimals: u32,
+    /// Decimal places available before subtracting `sz_decimals`
+    pub max_decimals: u32,
+}
+
+impl Precision {
+    /// Limits for a perp with the given `szDecimals`
+    pub fn perp(sz_decimals: u32) -> Self {
+        Self {
+            sz_decimals,
+            max_decimals: PERP_MAX_DECIMALS,
+        }
+    }
+
+    /// Limits for a spot pair whose base token has the given `szDecimals`
+    pub fn spot(sz_decimals: u32) -> Self {
+        Self {
+            sz_decimals,
+            max_decimals: SPOT_MAX_DECIMALS,
+        }
+    }
+
+    /// Limits for a perp listed in `meta`
+    pub fn from_meta(meta: &Meta, coin: &str) -> Result<Self> {
+        meta.asset(coin)
+            .map(Self::from)
+            .ok_or_else(|| Error::ValidationError(format!("Unknown perp: {}", coin)))
+    }
+
+    /// Limits for a spot pair, by pair name (`PURR/USDC`, `@107`) or `BASE/QUOTE`
+    pub fn from_spot_meta(spot: &SpotMeta, pair: &str) -> Result<Self> {
+        spot.universe
+            .iter()
+            .find(|p| p.name == pair || spot.pair_name(p).as_deref() == Some(pair))
+            .and_then(|p| spot.token(p.tokens.0))
+            .map(|base| Self::spot(base.sz_decimals))
+            .ok_or_else(|| Error::ValidationError(format!("Unknown spot pair: {}", pair)))
+    }
+
+    /// Most decimal places a price may have
+    pub fn px_decimals(&self) -> u32 {
+        self.max_decimals.saturating_sub(self.sz_decimals)
+    }
+
+    /// Smallest size increment
+    pub fn lot(&self) -> Decimal {
+        Decimal::new(1, self.sz_decimals)
+    }
+
+    /// Price increment at the magnitude of `px`
+    pub fn tick(&self, px: Decimal) -> Decimal {
+        match self.px_dp(px) {
+            0 => Decimal::ONE,
+            dp => Decimal::new(1, dp),
+        }
+    }
+
+    /// Round a price onto the tick grid
+    pub fn px(&self, raw: Decimal, rounding: Rounding) -> Result<Px> {
+        if raw <= Decimal::ZERO {
+            return Err(Error::ValidationError(format!("Price must be positive: {}", raw)));
+        }
+        let px = raw.round_dp_with_strategy(self.px_dp(raw), rounding.strategy());
+        if px.is_zero() {
+            return Err(Error::ValidationError(format!(
+                "Price {} is below the smallest tick",
+                raw
+            )));
+        }
+        Ok(Px(px.normalize()))
+    }
+
+    /// Round a price toward `mid`
+    pub fn px_toward(&self, raw: Decimal, mid: Decimal) -> Result<Px> {
+        self.px(raw, Rounding::toward(raw, mid))
+    }
+
+    /// Round a price away from `mid`
+    pub fn px_away_from(&self, raw: Decimal, mid: Decimal) -> Result<Px> {
+        self.px(raw, Rounding::away_from(raw, mid))
+    }
+
+    /// Round a size onto the lot grid
+    pub fn sz(&self, raw: Decimal, rounding: Rounding) -> Result<Sz> {
+        if raw <= Decimal::ZERO {
+            return Err(Error::ValidationError(format!("Size must be positive: {}", raw)));
+        }
+        let sz = raw.round_dp_with_strategy(self.sz_decimals, rounding.strategy());
+        if sz.is_zero() {
+            return Err(Error::ValidationError(format!(
+                "Size {} is below the lot size {}",
+                raw,
+                self.lot()
+            )));
+        }
+        Ok(Sz(sz.normalize()))
+    }
+
+    /// Whether the exchange would accept `px` as is
+    pub fn is_valid_px(&self, px: Decimal) -> bool {
+        px > Decimal::ZERO && px.round_dp(self.px_dp(px)) == px
+    }
+
+    /// Whether the exchange would accept `sz` as is
+    pub fn is_valid_sz(&self, sz: Decimal) -> bool {
+        sz > Decimal::ZERO && sz.round_dp(self.sz_decimals) == sz
+    }
+
+    /// Decimal places allowed at the magnitude of `px`
+    fn px_dp(&self, px: Decimal) -> u32 {
+        // Integers are always valid, so only sub-10^4 prices need decimals
+        let exponent = magnitude(px);
+        let sig_dp = (MAX_SIG_FIGS as i64 - 1 - exponent).max(0) as u32;
+        sig_dp.min(self.px_decimals())
+    }
+}
+
+impl From<&AssetInfo> for Precision {
+    fn from(asset: &AssetInfo) -> Self {
+        Self::perp(asset.sz_decimals)
+    }
+}
+
+/// Fails unless `slippage` is a fraction strictly between 0 and 1
+pub fn check_slippage(slippage: Decimal) -> Result<()> {
+    if slippage <= Decimal::ZERO || slippage >= Decimal::ONE {
+        return Err(Error::ValidationError(format!("Slippage must be between 0 and 1: {}", slippage)));
+    }
+    Ok(())
+}
+
+/// Base-10 exponent of the leading digit (`96250.5` → 4, `0.0123` → -2)
+fn magnitude(value: Decimal) -> i64 {
+    let digits = value.mantissa().unsigned_abs().to_string().len() as i64;
+    digits - 1 - value.scale() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn magnitude_of_leading_digit() {
+        assert_eq!(magnitude(dec("96250.5")), 4);
+        assert_eq!(magnitude(dec("96250.500")), 4);
+        assert_eq!(magnitude(dec("1")), 0);
+        assert_eq!(magnitude(dec("0.0123")), -2);
+    }
+
+    #[test]
+    fn five_significant_figures() {
+        let eth = Precision::perp(4);
+        assert_eq!(eth.px(dec("3612.345"), Rounding::Nearest).unwrap().to_string(), "3612.3");
+        assert_eq!(eth.px(dec("3612.35"), Rounding::Nearest).unwrap().to_string(), "3612.4");
+        assert_eq!(eth.px(dec("3612.39"), Rounding::Down).unwrap().to_string(), "3612.3");
+        assert_eq!(eth.px(dec("3612.31"), Rounding::Up).unwrap().to_string(), "3612.4");
+    }
+
+    #[test]
+    fn integer_prices_are_always_valid() {
+        let btc = Precision::perp(5);
+        assert_eq!(btc.px(dec("96250.5"), Rounding::Down).unwrap().to_string(), "96250");
+        assert_eq!(btc.px(dec("123456.7"), Rounding::Up).unwrap().to_string(), "123457");
+        assert!(btc.is_valid_px(dec("123456")));
+        assert_eq!(btc.tick(dec("123456")), Decimal::ONE);
+    }
+
+    #[test]
+    fn decimals_capped_by_sz_decimals() {
+        // 6 - 2 = 4 decimals, tighter than 5 significant figures at 0.0012345
+        let perp = Precision::perp(2);
+        assert_eq!(perp.px(dec("0.0012345"), Rounding::Nearest).unwrap().to_string(), "0.0012");
+        assert_eq!(perp.tick(dec("0.0012345")), dec("0.0001"));
+
+        // Spot allows 8 - 2 = 6 decimals
+        let spot = Precision::spot(2);
+        assert_eq!(spot.px(dec("0.0012345"), Rounding::Nearest).unwrap().to_string(), "0.001235");
+    }
+
+    #[test]
+    fn rounding_relative_to_mid() {
+        let eth = Precision::perp(4);
+        let mid = dec("3600");
+        // Below the mid: away rounds down, toward rounds up
+        assert_eq!(eth.px_away_from(dec("3491.97"), mid).unwrap().to_string(), "3491.9");
+        assert_eq!(eth.px_toward(dec("3491.97"), mid).unwrap().to_string(), "3492");
+        // Above the mid: the reverse
+        assert_eq!(eth.px_away_from(dec("3708.01"), mid).unwrap().to_string(), "3708.1");
+        assert_eq!(eth.px_toward(dec("3708.01"), mid).unwrap().to_string(), "3708");
+    }
+
+    #[test]
+    fn sizes_round_to_lots() {
+        let btc = Precision::perp(5);
+        assert_eq!(btc.sz(dec("0.0012399"), Rounding::Down).unwrap().to_string(), "0.00123");
+        assert_eq!(btc.sz(dec("0.0012301"), Rounding::Up).unwrap().to_string(), "0.00124");
+        assert_eq!(btc.lot(), dec("0.00001"));
+        assert!(btc.is_valid_sz(dec("0.001")));
+        assert!(!btc.is_valid_sz(dec("0.000001")));
+
+        let whole = Precision::perp(0);
+        assert_eq!(whole.sz(dec("12.7"), Rounding::Down).unwrap().to_string(), "12");
+    }
+
+    #[test]
+    fn values_below_the_grid_are_errors() {
+        let btc = Precision::perp(5);
+        assert!(btc.sz(dec("0.000001"), Rounding::Down).is_err());
+        assert!(btc.sz(Decimal::ZERO, Rounding::Up).is_err());
+        assert!(btc.px(dec("-1"), Rounding::Nearest).is_err());
+        assert!(Precision::perp(6).px(dec("0.4"), Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn rounded_values_are_valid() {
+        for (sz_decimals, raw) in [(5, "96251.49"), (4, "3612.349"), (0, "0.123456789"), (2, "12.34567")] {
+            let precision = Precision::perp(sz_decimals);
+            for rounding in [Rounding::Nearest, Rounding::Down, Rounding::Up] {
+                let px = precision.px(dec(raw), rounding).unwrap();
+                assert!(precision.is_valid_px(px.value()), "{} {:?} -> {}", raw, rounding, px);
+            }
+        }
+    }
+
+    #[test]
+    fn f64_round_trip_keeps_digits() {
+        let px = Precision::perp(4).px(dec("3612.3"), Rounding::Nearest).unwrap();
+        assert_eq!(px.to_f64().to_string(), "3612.3");
+        assert_eq!(serde_json::to_string(&px).unwrap(), "\"3612.3\"");
+    }
+
+    #[test]
+    fn precision_from_meta() {
+        let meta: Meta = serde_json::from_value(serde_json::json!({
+            "universe": [{"name": "ETH", "szDecimals": 4, "maxLeverage": 25}]
+        }))
+        .unwrap();
+        assert_eq!(Precision::from_meta(&meta, "ETH").unwrap(), Precision::perp(4));
+        assert!(Precision::from_meta(&meta, "NOPE").is_err());
+    }
+}
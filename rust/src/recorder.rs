@@ -0,0 +1,590 @@
+//! Record stream messages to disk for research and replay.
+//!
+//! [`Recorder`] writes every message it is handed as one [`Record`] line of
+//! gzip-compressed NDJSON, stamped with the local receive time and two
+//! sequence numbers (across all channels and within its channel), so gaps and
+//! ordering survive the trip to disk:
+//!
+//! ```text
+//! {"seq":42,"channel":"trades","channel_seq":17,"source":"ws","coin":"BTC","recv_ms":1737412345123,"mono_us":5012345,"data":{...}}
+//! ```
+//!
+//! Files roll over every hour (on the wall-clock hour, UTC) and are named after
+//! the hour they start in, `2025-01-20T22.ndjson.gz`. `manifest.json` in the
+//! same directory lists every file with its time and sequence range and the
+//! message count per channel; it is rewritten whenever a file is opened or
+//! closed, and a recorder started on an existing directory appends to it.
+//! Files are flushed every second, so a file cut short by a crash still
+//! decompresses up to the last flush.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// Name of the manifest in a recording directory
+pub const MANIFEST: &str = "manifest.json";
+
+/// How often files roll over by default
+pub const DEFAULT_ROLLOVER: Duration = Duration::from_secs(3600);
+
+/// Longest a record waits in the compressor before it is flushed to disk
+const FLUSH_EVERY: Duration = Duration::from_secs(1);
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Records
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Stream a recording can contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum Channel {
+    Trades,
+    L2Book,
+    AllMids,
+    BookUpdates,
+    Blocks,
+    L4Book,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 6] = [
+        Channel::Trades,
+        Channel::L2Book,
+        Channel::AllMids,
+        Channel::BookUpdates,
+        Channel::Blocks,
+        Channel::L4Book,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Channel::Trades => "trades",
+            Channel::L2Book => "l2_book",
+            Channel::AllMids => "all_mids",
+            Channel::BookUpdates => "book_updates",
+            Channel::Blocks => "blocks",
+            Channel::L4Book => "l4_book",
+        }
+    }
+
+    /// Transports that carry this channel: `all_mids` is WebSocket only,
+    /// `blocks` and `l4_book` gRPC only
+    pub fn sources(self) -> &'static [Source] {
+        match self {
+            Channel::AllMids => &[Source::Ws],
+            Channel::Blocks | Channel::L4Book => &[Source::Grpc],
+            _ => &[Source::Ws, Source::Grpc],
+        }
+    }
+
+    /// Whether the subscription takes coins (`all_mids` and `blocks` do not)
+    pub fn per_coin(self) -> bool {
+        !matches!(self, Channel::AllMids | Channel::Blocks)
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Channel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Channel::ALL
+            .into_iter()
+            .find(|c| c.as_str() == s)
+            .ok_or_else(|| Error::ValidationError(format!("unknown channel '{}'", s)))
+    }
+}
+
+/// Transport a message arrived on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Ws,
+    Grpc,
+}
+
+impl Source {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Source::Ws => "ws",
+            Source::Grpc => "grpc",
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One recorded message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    /// Position across all channels of the recording, from 1
+    pub seq: u64,
+    pub channel: Channel,
+    /// Position within `channel`, from 1
+    pub channel_seq: u64,
+    pub source: Source,
+    /// Coin of the subscription, for per-coin channels
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coin: Option<String>,
+    /// Wall-clock receive time (ms since the epoch)
+    pub recv_ms: u64,
+    /// Monotonic receive time (µs since the recorder started); unlike
+    /// `recv_ms` it never jumps, so gaps between messages are exact
+    pub mono_us: u64,
+    /// The message as the SDK delivered it
+    pub data: Value,
+}
+
+/// Read every record of a recorded file (`.ndjson` or `.ndjson.gz`).
+///
+/// A file still being written (or cut short) is read up to its last flush.
+pub fn read_file(path: &Path) -> Result<Vec<Record>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn BufRead> = if path.extension().is_some_and(|e| e == "gz") {
+        Box::new(BufReader::new(MultiGzDecoder::new(file)))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+    let mut records = Vec::new();
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        };
+        if !line.trim().is_empty() {
+            records.push(serde_json::from_str(&line)?);
+        }
+    }
+    Ok(records)
+}
+
+/// Files of a recording directory (through its manifest), or `path` itself
+/// when it is a single `.ndjson` / `.ndjson.gz` file
+pub fn recording_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_dir() {
+        let manifest = Manifest::load(path)
+            .map_err(|e| Error::ConfigError(format!("{} is not a recording ({}): {}", path.display(), MANIFEST, e)))?;
+        Ok(manifest.paths(path))
+    } else if path.is_file() {
+        Ok(vec![path.to_path_buf()])
+    } else {
+        Err(Error::ConfigError(format!("no recording at {}", path.display())))
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Manifest
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Index of a recording directory (`manifest.json`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    /// When the first recorder started on this directory (ms)
+    pub started_ms: u64,
+    pub updated_ms: u64,
+    /// Channels and coins recorded, across every run
+    pub channels: Vec<Channel>,
+    pub coins: Vec<String>,
+    /// Recorded files, oldest first
+    pub files: Vec<FileEntry>,
+}
+
+/// One file of a recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileEntry {
+    /// File name, relative to the manifest
+    pub file: String,
+    /// Receive time of the first and last record (ms)
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub first_seq: u64,
+    pub last_seq: u64,
+    pub messages: u64,
+    /// Messages per channel
+    pub channels: BTreeMap<Channel, u64>,
+    /// `false` while the file is being written (or if the recorder died)
+    pub complete: bool,
+}
+
+impl Manifest {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let text = fs::read_to_string(dir.join(MANIFEST))?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Write the manifest through a temporary file, so readers never see half of it
+    fn save(&self, dir: &Path) -> Result<()> {
+        let tmp = dir.join(format!("{}.tmp", MANIFEST));
+        fs::write(&tmp, serde_json::to_string_pretty(self)?)?;
+        fs::rename(tmp, dir.join(MANIFEST))?;
+        Ok(())
+    }
+
+    /// Absolute paths of the recorded files, oldest first
+    pub fn paths(&self, dir: &Path) -> Vec<PathBuf> {
+        self.files.iter().map(|f| dir.join(&f.file)).collect()
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Recorder
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// File currently being written
+struct Segment {
+    /// Rollover period the file covers (`recv_ms / rollover`)
+    period: u64,
+    encoder: GzEncoder<BufWriter<File>>,
+    flushed: Instant,
+    /// Index of its entry in the manifest
+    entry: usize,
+}
+
+/// Writes records to rotating gzip NDJSON files and keeps the manifest
+pub struct Recorder {
+    dir: PathBuf,
+    rollover_ms: u64,
+    manifest: Manifest,
+    segment: Option<Segment>,
+    seq: u64,
+    channel_seqs: BTreeMap<Channel, u64>,
+    started: Instant,
+}
+
+impl Recorder {
+    /// Record into `dir` (created if needed), continuing its manifest if it has one
+    pub fn create(dir: &Path, channels: &[Channel], coins: &[String]) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let now = now_ms();
+        let mut manifest = match Manifest::load(dir) {
+            Ok(manifest) => manifest,
+            Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::NotFound => Manifest {
+                version: 1,
+                started_ms: now,
+                ..Default::default()
+            },
+            Err(e) => return Err(e),
+        };
+        for channel in channels {
+            if !manifest.channels.contains(channel) {
+                manifest.channels.push(*channel);
+            }
+        }
+        for coin in coins {
+            if !manifest.coins.contains(coin) {
+                manifest.coins.push(coin.clone());
+            }
+        }
+        manifest.updated_ms = now;
+        manifest.save(dir)?;
+
+        // Sequence numbers continue from the previous run
+        let seq = manifest.files.iter().map(|f| f.last_seq).max().unwrap_or_default();
+        Ok(Recorder {
+            dir: dir.to_path_buf(),
+            rollover_ms: DEFAULT_ROLLOVER.as_millis() as u64,
+            manifest,
+            segment: None,
+            seq,
+            channel_seqs: BTreeMap::new(),
+            started: Instant::now(),
+        })
+    }
+
+    /// Roll over every `period` instead of every hour
+    pub fn rollover(mut self, period: Duration) -> Self {
+        self.rollover_ms = (period.as_millis() as u64).max(1);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Messages recorded so far (all runs)
+    pub fn messages(&self) -> u64 {
+        self.seq
+    }
+
+    /// Record a message received now; returns its sequence number
+    pub fn record(&mut self, channel: Channel, source: Source, coin: Option<&str>, data: Value) -> Result<u64> {
+        self.record_at(channel, source, coin, data, now_ms())
+    }
+
+    /// Record a message received at `recv_ms`
+    pub fn record_at(
+        &mut self,
+        channel: Channel,
+        source: Source,
+        coin: Option<&str>,
+        data: Value,
+        recv_ms: u64,
+    ) -> Result<u64> {
+        let period = recv_ms / self.rollover_ms;
+        if self.segment.as_ref().is_none_or(|s| s.period != period) {
+            self.close_segment()?;
+            self.open_segment(period)?;
+        }
+
+        self.seq += 1;
+        let channel_seq = self.channel_seqs.entry(channel).or_default();
+        *channel_seq += 1;
+        let record = Record {
+            seq: self.seq,
+            channel,
+            channel_seq: *channel_seq,
+            source,
+            coin: coin.map(str::to_string),
+            recv_ms,
+            mono_us: self.started.elapsed().as_micros() as u64,
+            data,
+        };
+
+        let segment = self.segment.as_mut().expect("segment opened above");
+        serde_json::to_writer(&mut segment.encoder, &record)?;
+        segment.encoder.write_all(b"\n")?;
+
+        let entry = &mut self.manifest.files[segment.entry];
+        if entry.messages == 0 {
+            entry.start_ms = recv_ms;
+            entry.first_seq = record.seq;
+        }
+        entry.end_ms = recv_ms;
+        entry.last_seq = record.seq;
+        entry.messages += 1;
+        *entry.channels.entry(channel).or_default() += 1;
+
+        if segment.flushed.elapsed() >= FLUSH_EVERY {
+            segment.encoder.flush()?;
+            segment.flushed = Instant::now();
+        }
+        Ok(record.seq)
+    }
+
+    /// Flush the current file so everything recorded so far is readable
+    pub fn flush(&mut self) -> Result<()> {
+        if let Some(segment) = &mut self.segment {
+            segment.encoder.flush()?;
+            segment.flushed = Instant::now();
+        }
+        self.manifest.updated_ms = now_ms();
+        self.manifest.save(&self.dir)
+    }
+
+    /// Finish the current file and write the manifest; a later record starts a new file
+    pub fn close(&mut self) -> Result<Manifest> {
+        self.close_segment()?;
+        Ok(self.manifest.clone())
+    }
+
+    fn open_segment(&mut self, period: u64) -> Result<()> {
+        let start = period * self.rollover_ms;
+        let stem = if self.rollover_ms.is_multiple_of(3_600_000) {
+            utc_hour(start)
+        } else {
+            format!("{}{:02}{:02}", utc_hour(start), start / 60_000 % 60, start / 1000 % 60)
+        };
+        // A second run within the same period gets its own file
+        let mut file = format!("{}.ndjson.gz", stem);
+        let mut n = 1;
+        while self.dir.join(&file).exists() {
+            file = format!("{}.{}.ndjson.gz", stem, n);
+            n += 1;
+        }
+
+        let writer = BufWriter::new(File::create(self.dir.join(&file))?);
+        self.manifest.files.push(FileEntry {
+            file,
+            start_ms: start,
+            end_ms: start,
+            first_seq: self.seq + 1,
+            last_seq: self.seq,
+            messages: 0,
+            channels: BTreeMap::new(),
+            complete: false,
+        });
+        self.segment = Some(Segment {
+            period,
+            encoder: GzEncoder::new(writer, Compression::default()),
+            flushed: Instant::now(),
+            entry: self.manifest.files.len() - 1,
+        });
+        self.manifest.updated_ms = now_ms();
+        self.manifest.save(&self.dir)
+    }
+
+    fn close_segment(&mut self) -> Result<()> {
+        if let Some(segment) = self.segment.take() {
+            segment.encoder.finish()?.flush()?;
+            self.manifest.files[segment.entry].complete = true;
+            self.manifest.updated_ms = now_ms();
+            self.manifest.save(&self.dir)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.close_segment();
+    }
+}
+
+/// Current time in ms
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// `YYYY-MM-DDTHH` (UTC) of a time in ms
+fn utc_hour(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (days, hour) = (secs / 86_400, secs / 3600 % 24);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}T{:02}", year, month, day, hour)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 2025-01-20T22:59:59.000Z
+    const BEFORE_HOUR: u64 = 1_737_413_999_000;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hl-recorder-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn formats_utc_hours() {
+        assert_eq!(utc_hour(0), "1970-01-01T00");
+        assert_eq!(utc_hour(BEFORE_HOUR), "2025-01-20T22");
+        assert_eq!(utc_hour(BEFORE_HOUR + 1000), "2025-01-20T23");
+        assert_eq!(utc_hour(951_782_400_000), "2000-02-29T00");
+    }
+
+    #[test]
+    fn channels_parse_by_name() {
+        assert_eq!("l4_book".parse::<Channel>().unwrap(), Channel::L4Book);
+        assert!("l3_book".parse::<Channel>().is_err());
+        assert_eq!(serde_json::to_value(Channel::AllMids).unwrap(), json!("all_mids"));
+        assert_eq!(Channel::Blocks.sources(), [Source::Grpc]);
+        assert!(!Channel::AllMids.per_coin());
+    }
+
+    #[test]
+    fn rolls_over_on_the_hour_and_keeps_a_manifest() {
+        let dir = scratch("rollover");
+        let mut recorder = Recorder::create(&dir, &[Channel::Trades, Channel::AllMids], &["BTC".to_string()]).unwrap();
+
+        let trade = json!({"channel": "trades", "data": [{"coin": "BTC", "px": "96251.0"}]});
+        let mids = json!({"channel": "allMids", "data": {"mids": {"BTC": "96250.5"}}});
+        recorder.record_at(Channel::Trades, Source::Ws, Some("BTC"), trade.clone(), BEFORE_HOUR).unwrap();
+        recorder.record_at(Channel::AllMids, Source::Ws, None, mids.clone(), BEFORE_HOUR + 500).unwrap();
+        assert_eq!(recorder.manifest().files.len(), 1);
+        assert!(!recorder.manifest().files[0].complete);
+
+        recorder.record_at(Channel::Trades, Source::Ws, Some("BTC"), trade.clone(), BEFORE_HOUR + 1000).unwrap();
+        let manifest = recorder.close().unwrap();
+        assert_eq!(manifest, Manifest::load(&dir).unwrap());
+
+        let files: Vec<_> = manifest.files.iter().map(|f| f.file.as_str()).collect();
+        assert_eq!(files, ["2025-01-20T22.ndjson.gz", "2025-01-20T23.ndjson.gz"]);
+        let first = &manifest.files[0];
+        assert!(first.complete);
+        assert_eq!((first.first_seq, first.last_seq, first.messages), (1, 2, 2));
+        assert_eq!((first.start_ms, first.end_ms), (BEFORE_HOUR, BEFORE_HOUR + 500));
+        assert_eq!(first.channels, BTreeMap::from([(Channel::Trades, 1), (Channel::AllMids, 1)]));
+        assert_eq!((manifest.files[1].first_seq, manifest.files[1].last_seq), (3, 3));
+
+        let records = read_file(&dir.join("2025-01-20T22.ndjson.gz")).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].seq, records[0].channel_seq, records[0].coin.as_deref()), (1, 1, Some("BTC")));
+        assert_eq!(records[0].data, trade);
+        assert_eq!((records[1].channel, records[1].channel_seq, records[1].coin.as_ref()), (Channel::AllMids, 1, None));
+        assert!(records[1].mono_us >= records[0].mono_us);
+
+        let records = read_file(&dir.join("2025-01-20T23.ndjson.gz")).unwrap();
+        assert_eq!((records[0].seq, records[0].channel_seq), (3, 2));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn a_second_run_appends_to_the_recording() {
+        let dir = scratch("append");
+        let mut recorder = Recorder::create(&dir, &[Channel::Trades], &["BTC".to_string()]).unwrap();
+        recorder.record_at(Channel::Trades, Source::Ws, Some("BTC"), json!({}), BEFORE_HOUR).unwrap();
+        drop(recorder);
+
+        let mut recorder = Recorder::create(&dir, &[Channel::L4Book], &["ETH".to_string()]).unwrap();
+        let seq = recorder.record_at(Channel::L4Book, Source::Grpc, Some("ETH"), json!({}), BEFORE_HOUR).unwrap();
+        assert_eq!(seq, 2);
+        let manifest = recorder.close().unwrap();
+
+        assert_eq!(manifest.channels, [Channel::Trades, Channel::L4Book]);
+        assert_eq!(manifest.coins, ["BTC", "ETH"]);
+        let files: Vec<_> = manifest.files.iter().map(|f| f.file.as_str()).collect();
+        assert_eq!(files, ["2025-01-20T22.ndjson.gz", "2025-01-20T22.1.ndjson.gz"]);
+        assert!(manifest.files.iter().all(|f| f.complete));
+        assert_eq!(manifest.paths(&dir).len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn flushed_file_is_readable_before_it_is_closed() {
+        let dir = scratch("flush");
+        let mut recorder = Recorder::create(&dir, &[Channel::Blocks], &[]).unwrap();
+        recorder.record_at(Channel::Blocks, Source::Grpc, None, json!({"height": 1}), BEFORE_HOUR).unwrap();
+        recorder.flush().unwrap();
+
+        let records = read_file(&dir.join("2025-01-20T22.ndjson.gz")).unwrap();
+        assert_eq!(records[0].data, json!({"height": 1}));
+        drop(recorder);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,560 @@
+//! One lookup for every tradable market: perps, spot pairs and HIP-3 dexes.
+//!
+//! The exchange identifies an order's market by a numeric asset id:
+//!
+//! | Market | Symbol | Asset id |
+//! |--------|--------|----------|
+//! | Perp | `BTC` | index in `meta.universe` |
+//! | Spot | `PURR/USDC`, `@107` (also `HYPE/USDC`) | `10000 + pair index` |
+//! | HIP-3 perp | `xyz:XYZ100` | `100000 + dex index * 10000 + index in the dex's universe` |
+//!
+//! [`AssetRegistry`] builds that table from `meta`, `spotMeta`, `perpDexs`
+//! and `allPerpMetas` and caches the raw responses on disk, so a binary can
+//! reject a misspelled symbol before it signs anything:
+//!
+//! ```no_run
+//! use hyperliquid_examples::{connect, AssetRegistry, Requires};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let sdk = connect("place_order", Requires::Signer).await?;
+//! let registry = AssetRegistry::load(&sdk).await?;
+//! let btc = registry.resolve("BTC")?;
+//! println!("{} is asset {} with {} size decimals", btc.symbol, btc.asset_id, btc.sz_decimals);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hyperliquid_sdk::HyperliquidSDK;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Network;
+use crate::error::{Error, Result};
+use crate::models::{MarginTable, Meta, PerpDex, SpotMeta, TypedInfo};
+use crate::price::Precision;
+
+/// How long a cached registry is used before it is fetched again
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Asset id offset of spot pairs
+pub const SPOT_ASSET_OFFSET: u32 = 10_000;
+
+/// Asset id offset of HIP-3 dexes
+pub const HIP3_ASSET_OFFSET: u32 = 100_000;
+
+/// Asset ids reserved per HIP-3 dex
+pub const HIP3_DEX_STRIDE: u32 = 10_000;
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Market
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Kind of market an asset id refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarketKind {
+    /// Perp on the main dex
+    Perp,
+    /// Spot pair
+    Spot,
+    /// Perp on a builder-deployed (HIP-3) dex
+    Hip3 { dex: String },
+}
+
+/// One tradable market
+#[derive(Debug, Clone, PartialEq)]
+pub struct Market {
+    /// Name used in orders and subscriptions
+    pub symbol: String,
+    /// Asset id used on the wire
+    pub asset_id: u32,
+    pub kind: MarketKind,
+    /// Size decimals (the base token's for spot)
+    pub sz_decimals: u32,
+    /// Maximum leverage (perps only)
+    pub max_leverage: Option<u32>,
+    /// Cross margin is not available
+    pub only_isolated: bool,
+    pub is_delisted: bool,
+    /// Leverage tiers by notional (perps with a margin table only)
+    pub margin_table: Option<MarginTable>,
+    /// `BASE/QUOTE` for spot pairs
+    pub pair: Option<String>,
+}
+
+impl Market {
+    /// Whether this is a perp (main dex or HIP-3)
+    pub fn is_perp(&self) -> bool {
+        !matches!(self.kind, MarketKind::Spot)
+    }
+
+    /// HIP-3 dex name, if any
+    pub fn dex(&self) -> Option<&str> {
+        match &self.kind {
+            MarketKind::Hip3 { dex } => Some(dex),
+            _ => None,
+        }
+    }
+
+    /// Tick and lot limits for prices and sizes
+    pub fn precision(&self) -> Precision {
+        if self.is_perp() {
+            Precision::perp(self.sz_decimals)
+        } else {
+            Precision::spot(self.sz_decimals)
+        }
+    }
+
+    /// Maximum leverage for a position of `notional` USD
+    pub fn max_leverage_at(&self, notional: Decimal) -> Option<u32> {
+        let Some(table) = &self.margin_table else {
+            return self.max_leverage;
+        };
+        table
+            .margin_tiers
+            .iter()
+            .rfind(|tier| tier.lower_bound <= notional)
+            .map(|tier| tier.max_leverage)
+            .or(self.max_leverage)
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Snapshot
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Raw metadata a registry is built from, as cached on disk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrySnapshot {
+    /// When the metadata was fetched (ms since the epoch)
+    pub fetched_at: u64,
+    pub meta: Meta,
+    pub spot_meta: SpotMeta,
+    /// HIP-3 dexes with their universe, with the dex index from `perpDexs`
+    pub dexes: Vec<(u32, PerpDex, Meta)>,
+}
+
+impl RegistrySnapshot {
+    /// Fetch everything the registry needs
+    pub async fn fetch(info: &TypedInfo) -> Result<Self> {
+        let meta = info.meta().await?;
+        let spot_meta = info.spot_meta().await?;
+        let perp_dexes: Vec<Option<PerpDex>> =
+            crate::models::decode(info.raw().perp_dexes().await?)?;
+
+        let dexes = if perp_dexes.iter().any(Option::is_some) {
+            let metas = info.all_perp_metas().await?;
+            perp_dexes
+                .into_iter()
+                .zip(metas)
+                .enumerate()
+                .filter_map(|(index, (dex, meta))| Some((index as u32, dex?, meta)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            fetched_at: now_millis(),
+            meta,
+            spot_meta,
+            dexes,
+        })
+    }
+
+    /// Age of the snapshot
+    pub fn age(&self) -> Duration {
+        Duration::from_millis(now_millis().saturating_sub(self.fetched_at))
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Cache
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Location and lifetime of the on-disk registry cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegistryCache {
+    pub path: PathBuf,
+    pub ttl: Duration,
+}
+
+/// `$HL_CACHE_DIR`, else `$XDG_CACHE_HOME/hyperliquid`, else `~/.cache/hyperliquid`
+pub(crate) fn cache_dir() -> PathBuf {
+    std::env::var_os("HL_CACHE_DIR")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("XDG_CACHE_HOME").map(|d| PathBuf::from(d).join("hyperliquid")))
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache/hyperliquid")))
+        .unwrap_or_else(|| PathBuf::from(".hyperliquid-cache"))
+}
+
+impl RegistryCache {
+    /// Cache at `path`, fresh for `ttl`
+    pub fn new(path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            path: path.into(),
+            ttl,
+        }
+    }
+
+    /// Default cache for a network.
+    ///
+    /// Lives in `$HL_CACHE_DIR`, else `$XDG_CACHE_HOME/hyperliquid`, else
+    /// `~/.cache/hyperliquid`, as `registry-<network>.json`. `HL_REGISTRY_TTL`
+    /// overrides the lifetime in seconds (`0` always refetches).
+    pub fn for_network(network: Network) -> Self {
+        let ttl = std::env::var("HL_REGISTRY_TTL")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_CACHE_TTL);
+        Self::new(cache_dir().join(format!("registry-{}.json", network)), ttl)
+    }
+
+    /// Cached snapshot, if present and younger than the TTL
+    pub fn read(&self) -> Option<RegistrySnapshot> {
+        let text = std::fs::read_to_string(&self.path).ok()?;
+        let snapshot: RegistrySnapshot = serde_json::from_str(&text).ok()?;
+        (snapshot.age() < self.ttl).then_some(snapshot)
+    }
+
+    /// Store a snapshot (written to a temp file, then renamed into place)
+    pub fn write(&self, snapshot: &RegistrySnapshot) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(snapshot)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Path of the cache file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Registry
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Every market, keyed by symbol and by asset id
+#[derive(Debug, Clone)]
+pub struct AssetRegistry {
+    markets: Vec<Market>,
+    by_symbol: HashMap<String, usize>,
+    by_id: HashMap<u32, usize>,
+    fetched_at: u64,
+}
+
+impl AssetRegistry {
+    /// Registry for an SDK's network, from the default cache when fresh
+    pub async fn load(sdk: &HyperliquidSDK) -> Result<Self> {
+        let cache = RegistryCache::for_network(sdk.chain().into());
+        Self::load_cached(&TypedInfo::from_sdk(sdk), &cache).await
+    }
+
+    /// Registry from `cache` when fresh, else fetched and written back
+    pub async fn load_cached(info: &TypedInfo, cache: &RegistryCache) -> Result<Self> {
+        if let Some(snapshot) = cache.read() {
+            return Ok(Self::from_snapshot(&snapshot));
+        }
+        let snapshot = RegistrySnapshot::fetch(info).await?;
+        // A read-only cache directory should not stop a trade
+        if let Err(e) = cache.write(&snapshot) {
+            eprintln!("Warning: could not cache asset registry: {}", e);
+        }
+        Ok(Self::from_snapshot(&snapshot))
+    }
+
+    /// Registry fetched from the API, bypassing any cache
+    pub async fn fetch(info: &TypedInfo) -> Result<Self> {
+        Ok(Self::from_snapshot(&RegistrySnapshot::fetch(info).await?))
+    }
+
+    /// Build the lookup tables from raw metadata
+    pub fn from_snapshot(snapshot: &RegistrySnapshot) -> Self {
+        let mut markets = Vec::new();
+
+        markets.extend(perps(&snapshot.meta, None, 0));
+
+        for pair in &snapshot.spot_meta.universe {
+            let Some(base) = snapshot.spot_meta.token(pair.tokens.0) else {
+                continue;
+            };
+            markets.push(Market {
+                symbol: pair.name.clone(),
+                asset_id: SPOT_ASSET_OFFSET + pair.index,
+                kind: MarketKind::Spot,
+                sz_decimals: base.sz_decimals,
+                max_leverage: None,
+                only_isolated: false,
+                is_delisted: false,
+                margin_table: None,
+                pair: snapshot.spot_meta.pair_name(pair),
+            });
+        }
+
+        for (index, dex, meta) in &snapshot.dexes {
+            let offset = HIP3_ASSET_OFFSET + index * HIP3_DEX_STRIDE;
+            markets.extend(perps(meta, Some(&dex.name), offset));
+        }
+
+        let mut by_symbol = HashMap::new();
+        let mut by_id = HashMap::new();
+        for (i, market) in markets.iter().enumerate() {
+            by_id.insert(market.asset_id, i);
+            by_symbol.insert(market.symbol.clone(), i);
+            if let Some(pair) = &market.pair {
+                // `@107` is also reachable as `HYPE/USDC`; never shadow a real name
+                by_symbol.entry(pair.clone()).or_insert(i);
+            }
+        }
+
+        Self {
+            markets,
+            by_symbol,
+            by_id,
+            fetched_at: snapshot.fetched_at,
+        }
+    }
+
+    /// Market by symbol (`BTC`, `PURR/USDC`, `@107`, `HYPE/USDC`, `xyz:XYZ100`)
+    pub fn get(&self, symbol: &str) -> Option<&Market> {
+        self.by_symbol.get(symbol).map(|&i| &self.markets[i])
+    }
+
+    /// Market by asset id
+    pub fn by_id(&self, asset_id: u32) -> Option<&Market> {
+        self.by_id.get(&asset_id).map(|&i| &self.markets[i])
+    }
+
+    /// Market for a symbol that is safe to trade.
+    ///
+    /// Fails for unknown symbols (suggesting a different-case match if one
+    /// exists) and for delisted markets.
+    pub fn resolve(&self, symbol: &str) -> Result<&Market> {
+        let Some(market) = self.get(symbol) else {
+            let hint = self
+                .by_symbol
+                .keys()
+                .find(|known| known.eq_ignore_ascii_case(symbol))
+                .map(|known| format!(" (did you mean {}?)", known))
+                .unwrap_or_default();
+            return Err(Error::ValidationError(format!("Unknown symbol: {}{}", symbol, hint)));
+        };
+        if market.is_delisted {
+            return Err(Error::ValidationError(format!("{} is delisted", symbol)));
+        }
+        Ok(market)
+    }
+
+    /// Tick and lot limits for a symbol
+    pub fn precision(&self, symbol: &str) -> Result<Precision> {
+        self.resolve(symbol).map(Market::precision)
+    }
+
+    /// All markets, perps first, then spot, then HIP-3
+    pub fn markets(&self) -> impl Iterator<Item = &Market> {
+        self.markets.iter()
+    }
+
+    /// Main-dex perps
+    pub fn perps(&self) -> impl Iterator<Item = &Market> {
+        self.markets.iter().filter(|m| m.kind == MarketKind::Perp)
+    }
+
+    /// Spot pairs
+    pub fn spot(&self) -> impl Iterator<Item = &Market> {
+        self.markets.iter().filter(|m| m.kind == MarketKind::Spot)
+    }
+
+    /// HIP-3 perps, optionally for one dex
+    pub fn hip3<'a>(&'a self, dex: Option<&'a str>) -> impl Iterator<Item = &'a Market> + 'a {
+        self.markets
+            .iter()
+            .filter(move |m| m.dex().is_some_and(|d| dex.is_none_or(|want| want == d)))
+    }
+
+    /// HIP-3 dex names
+    pub fn dexes(&self) -> Vec<&str> {
+        let mut dexes: Vec<&str> = self.markets.iter().filter_map(Market::dex).collect();
+        dexes.dedup();
+        dexes
+    }
+
+    /// Number of markets
+    pub fn len(&self) -> usize {
+        self.markets.len()
+    }
+
+    /// Whether the registry has no markets
+    pub fn is_empty(&self) -> bool {
+        self.markets.is_empty()
+    }
+
+    /// Age of the metadata
+    pub fn age(&self) -> Duration {
+        Duration::from_millis(now_millis().saturating_sub(self.fetched_at))
+    }
+}
+
+/// Markets of one perp universe, prefixing HIP-3 names with `dex:`
+fn perps<'a>(meta: &'a Meta, dex: Option<&'a str>, offset: u32) -> impl Iterator<Item = Market> + 'a {
+    meta.universe.iter().enumerate().map(move |(i, asset)| {
+        let (symbol, kind) = match dex {
+            Some(dex) if asset.name.contains(':') => (asset.name.clone(), MarketKind::Hip3 { dex: dex.to_string() }),
+            Some(dex) => (format!("{}:{}", dex, asset.name), MarketKind::Hip3 { dex: dex.to_string() }),
+            None => (asset.name.clone(), MarketKind::Perp),
+        };
+        Market {
+            symbol,
+            asset_id: offset + i as u32,
+            kind,
+            sz_decimals: asset.sz_decimals,
+            max_leverage: Some(asset.max_leverage),
+            only_isolated: asset.only_isolated
+                || matches!(asset.margin_mode.as_deref(), Some("strictIsolated" | "noCross")),
+            is_delisted: asset.is_delisted,
+            margin_table: asset
+                .margin_table_id
+                .and_then(|id| meta.margin_table(id))
+                .cloned(),
+            pair: None,
+        }
+    })
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn snapshot() -> RegistrySnapshot {
+        let meta: Meta = serde_json::from_str(include_str!("models/fixtures/meta.json")).unwrap();
+        let spot_meta: SpotMeta =
+            serde_json::from_str(include_str!("models/fixtures/spot_meta.json")).unwrap();
+        let dex: PerpDex = serde_json::from_value(json!({"name": "xyz"})).unwrap();
+        let dex_meta: Meta = serde_json::from_value(json!({
+            "universe": [
+                {"name": "xyz:XYZ100", "szDecimals": 4, "maxLeverage": 20, "onlyIsolated": true},
+                {"name": "xyz:SILVER", "szDecimals": 2, "maxLeverage": 10, "marginMode": "noCross"}
+            ]
+        }))
+        .unwrap();
+        RegistrySnapshot {
+            fetched_at: now_millis(),
+            meta,
+            spot_meta,
+            dexes: vec![(1, dex, dex_meta)],
+        }
+    }
+
+    #[test]
+    fn perps_keep_universe_index() {
+        let registry = AssetRegistry::from_snapshot(&snapshot());
+        let eth = registry.resolve("ETH").unwrap();
+        assert_eq!(eth.asset_id, 1);
+        assert_eq!(eth.kind, MarketKind::Perp);
+        assert_eq!(eth.sz_decimals, 4);
+        assert_eq!(registry.by_id(0).unwrap().symbol, "BTC");
+    }
+
+    #[test]
+    fn spot_by_name_and_pair() {
+        let registry = AssetRegistry::from_snapshot(&snapshot());
+        let purr = registry.resolve("PURR/USDC").unwrap();
+        assert_eq!(purr.asset_id, 10_000);
+        assert_eq!(purr.precision(), Precision::spot(0));
+
+        let hype = registry.resolve("@107").unwrap();
+        assert_eq!(hype.asset_id, 10_107);
+        assert_eq!(hype.pair.as_deref(), Some("HYPE/USDC"));
+        assert_eq!(registry.resolve("HYPE/USDC").unwrap().asset_id, 10_107);
+    }
+
+    #[test]
+    fn hip3_ids_and_flags() {
+        let registry = AssetRegistry::from_snapshot(&snapshot());
+        let xyz = registry.resolve("xyz:XYZ100").unwrap();
+        assert_eq!(xyz.asset_id, 110_000);
+        assert_eq!(xyz.dex(), Some("xyz"));
+        assert!(xyz.only_isolated);
+
+        let silver = registry.by_id(110_001).unwrap();
+        assert_eq!(silver.symbol, "xyz:SILVER");
+        assert!(silver.only_isolated);
+        assert_eq!(registry.hip3(Some("xyz")).count(), 2);
+        assert_eq!(registry.hip3(Some("abc")).count(), 0);
+        assert_eq!(registry.dexes(), vec!["xyz"]);
+    }
+
+    #[test]
+    fn unprefixed_hip3_names_get_the_dex() {
+        let mut snapshot = snapshot();
+        snapshot.dexes[0].2.universe[0].name = "XYZ100".to_string();
+        let registry = AssetRegistry::from_snapshot(&snapshot);
+        assert!(registry.get("xyz:XYZ100").is_some());
+        assert!(registry.get("XYZ100").is_none());
+    }
+
+    #[test]
+    fn resolve_rejects_unknown_and_delisted() {
+        let registry = AssetRegistry::from_snapshot(&snapshot());
+        let err = registry.resolve("btc").unwrap_err().to_string();
+        assert!(err.contains("Unknown symbol: btc (did you mean BTC?)"), "{}", err);
+        assert!(registry.resolve("DOGE").is_err());
+        assert!(registry.get("MATIC").is_some());
+        assert!(registry.resolve("MATIC").unwrap_err().to_string().contains("delisted"));
+    }
+
+    #[test]
+    fn margin_tiers_by_notional() {
+        let registry = AssetRegistry::from_snapshot(&snapshot());
+        let btc = registry.resolve("BTC").unwrap();
+        assert_eq!(btc.max_leverage, Some(40));
+        assert_eq!(btc.max_leverage_at(Decimal::from(1_000)), Some(40));
+        assert_eq!(btc.max_leverage_at(Decimal::from(200_000_000)), Some(20));
+
+        // ETH's table is not in the fixture; fall back to maxLeverage
+        let eth = registry.resolve("ETH").unwrap();
+        assert!(eth.margin_table.is_none());
+        assert_eq!(eth.max_leverage_at(Decimal::from(1_000)), Some(25));
+
+        let jelly = registry.resolve("JELLY").unwrap();
+        assert!(jelly.only_isolated);
+    }
+
+    #[test]
+    fn cache_round_trip_and_ttl() {
+        let dir = std::env::temp_dir().join(format!("hl-registry-test-{}", std::process::id()));
+        let cache = RegistryCache::new(dir.join("registry-testnet.json"), Duration::from_secs(60));
+        let snapshot = snapshot();
+
+        assert!(cache.read().is_none());
+        cache.write(&snapshot).unwrap();
+        assert_eq!(cache.read().unwrap(), snapshot);
+
+        let stale = RegistrySnapshot {
+            fetched_at: snapshot.fetched_at - 120_000,
+            ..snapshot
+        };
+        cache.write(&stale).unwrap();
+        assert!(cache.read().is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
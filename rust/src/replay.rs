@@ -0,0 +1,682 @@
+//! Replay recorded streams through the same callbacks as a live stream.
+//!
+//! [`Replayer`] has the subscription surface of `sdk.stream()` and
+//! `sdk.grpc()` (`trades`, `l2_book`, `book_updates`, `on_open`, `on_error`,
+//! `start`, `stop`...), but its messages come from a [`recorder`](crate::recorder)
+//! directory instead of the network. Each callback receives the message exactly
+//! as the SDK delivered it while recording, so strategy code written against
+//! the stream examples runs on a recording unmodified:
+//!
+//! ```no_run
+//! use hyperliquid_examples::replay::{Replayer, Speed};
+//!
+//! let mut replay = Replayer::open("recordings".as_ref())?
+//!     .speed(Speed::Max)
+//!     .on_error(|e| eprintln!("[Error] {}", e));
+//! replay.trades(&["BTC"], |data| println!("{}", data));
+//! replay.start()?;
+//! replay.wait();
+//! # Ok::<(), hyperliquid_examples::Error>(())
+//! ```
+//!
+//! Records are delivered one at a time, in recording order, from one thread,
+//! so a replay is deterministic: the same recording and subscriptions always
+//! produce the same callbacks in the same order. At [`Speed::Original`] the
+//! gaps between messages are those of the recording (from the monotonic
+//! receive time), [`Speed::Accelerated`] divides them and [`Speed::Max`] drops
+//! them. [`ReplayClock`] gives the receive time of the message being delivered,
+//! for code that would otherwise read the wall clock.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use hyperliquid_sdk::stream::{ConnectionState, Subscription};
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::recorder::{read_file, recording_files, Channel, Record, Source};
+
+/// Longest the replay thread sleeps before checking whether it was stopped
+const STOP_CHECK: Duration = Duration::from_millis(50);
+
+type Callback = Arc<dyn Fn(Value) + Send + Sync>;
+type ErrorCallback = Arc<dyn Fn(String) + Send + Sync>;
+type EventCallback = Arc<dyn Fn() + Send + Sync>;
+type StateCallback = Arc<dyn Fn(ConnectionState) + Send + Sync>;
+
+/// How fast a recording is played back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Speed {
+    /// Keep the recorded gaps between messages
+    Original,
+    /// Recorded gaps divided by a factor (`Accelerated(10.0)` = 10x)
+    Accelerated(f64),
+    /// No gaps: every message as soon as the previous callback returns
+    Max,
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Speed::Original => f.write_str("1x"),
+            Speed::Accelerated(factor) => write!(f, "{}x", factor),
+            Speed::Max => f.write_str("max"),
+        }
+    }
+}
+
+/// `max`, `original`, or a factor such as `10x` / `0.5`
+impl FromStr for Speed {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "max" => Ok(Speed::Max),
+            "original" | "1x" | "1" => Ok(Speed::Original),
+            other => match other.trim_end_matches('x').parse::<f64>() {
+                Ok(factor) if factor.is_finite() && factor > 0.0 => Ok(Speed::Accelerated(factor)),
+                _ => Err(Error::ValidationError(format!("bad replay speed '{}' (max, original or e.g. 10x)", s))),
+            },
+        }
+    }
+}
+
+/// Receive time of the message being replayed, shared with the callbacks
+#[derive(Debug, Clone, Default)]
+pub struct ReplayClock(Arc<AtomicU64>);
+
+impl ReplayClock {
+    /// Recorded receive time (ms) of the current message; 0 before the first
+    pub fn now_ms(&self) -> u64 {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Where the records come from
+enum Input {
+    Files(Vec<PathBuf>),
+    Records(Vec<Record>),
+}
+
+/// Subscriber to one channel
+struct Target {
+    channel: Channel,
+    /// Coins to deliver; empty for channels that are not per coin
+    coins: Vec<String>,
+    callback: Callback,
+}
+
+impl Target {
+    fn matches(&self, record: &Record) -> bool {
+        record.channel == self.channel
+            && (self.coins.is_empty() || record.coin.as_ref().is_some_and(|c| self.coins.contains(c)))
+    }
+}
+
+/// Callbacks and counters shared with the replay thread
+#[derive(Default)]
+struct Shared {
+    targets: Mutex<BTreeMap<u32, Target>>,
+    running: AtomicBool,
+    finished: AtomicBool,
+    delivered: AtomicU64,
+    state: Mutex<Option<ConnectionState>>,
+    on_error: Option<ErrorCallback>,
+    on_open: Option<EventCallback>,
+    on_close: Option<EventCallback>,
+    on_state_change: Option<StateCallback>,
+}
+
+impl Shared {
+    fn set_state(&self, state: ConnectionState) {
+        *self.state.lock().unwrap() = Some(state);
+        if let Some(cb) = &self.on_state_change {
+            cb(state);
+        }
+    }
+
+    fn error(&self, message: String) {
+        if let Some(cb) = &self.on_error {
+            cb(message);
+        }
+    }
+}
+
+/// Plays a recording back through stream-style callbacks
+pub struct Replayer {
+    input: Option<Input>,
+    speed: Speed,
+    max_gap: Option<Duration>,
+    source: Option<Source>,
+    shared: Shared,
+    /// Set once started; the callbacks can no longer change
+    live: Option<Arc<Shared>>,
+    clock: ReplayClock,
+    next_id: u32,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Replayer {
+    /// Replay a recording directory (through its manifest) or a single
+    /// `.ndjson` / `.ndjson.gz` file
+    pub fn open(path: &Path) -> Result<Self> {
+        Ok(Self::with_input(Input::Files(recording_files(path)?)))
+    }
+
+    /// Replay records already in memory, in `seq` order
+    pub fn from_records(mut records: Vec<Record>) -> Self {
+        records.sort_by_key(|r| r.seq);
+        Self::with_input(Input::Records(records))
+    }
+
+    fn with_input(input: Input) -> Self {
+        Replayer {
+            input: Some(input),
+            speed: Speed::Original,
+            max_gap: None,
+            source: None,
+            shared: Shared::default(),
+            live: None,
+            clock: ReplayClock::default(),
+            next_id: 0,
+            thread: None,
+        }
+    }
+
+    /// Playback speed (default [`Speed::Original`])
+    pub fn speed(mut self, speed: Speed) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Cap every wait at `gap`, e.g. to skip the time between two recorder runs
+    pub fn max_gap(mut self, gap: Duration) -> Self {
+        self.max_gap = Some(gap);
+        self
+    }
+
+    /// Only replay messages that arrived on `source`, for recordings that
+    /// hold the same channel from both WebSocket and gRPC
+    pub fn source(mut self, source: Source) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Set error callback: files that cannot be read
+    pub fn on_error<F>(mut self, f: F) -> Self
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        self.shared.on_error = Some(Arc::new(f));
+        self
+    }
+
+    /// Set open callback: called by `start`
+    pub fn on_open<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.shared.on_open = Some(Arc::new(f));
+        self
+    }
+
+    /// Same as [`on_open`](Self::on_open), named as on `sdk.grpc()`
+    pub fn on_connect<F>(self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.on_open(f)
+    }
+
+    /// Set close callback: called once the recording ends or the replay is stopped
+    pub fn on_close<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.shared.on_close = Some(Arc::new(f));
+        self
+    }
+
+    /// Accepted for compatibility with the live streams; a replay never reconnects
+    pub fn on_reconnect<F>(self, _f: F) -> Self
+    where
+        F: Fn(u32) + Send + Sync + 'static,
+    {
+        self
+    }
+
+    /// Set state change callback: Connecting and Connected on start,
+    /// Disconnected at the end
+    pub fn on_state_change<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ConnectionState) + Send + Sync + 'static,
+    {
+        self.shared.on_state_change = Some(Arc::new(f));
+        self
+    }
+
+    pub fn state(&self) -> ConnectionState {
+        let state = self.live.as_ref().map_or(&self.shared.state, |s| &s.state);
+        state.lock().unwrap().unwrap_or(ConnectionState::Disconnected)
+    }
+
+    /// Clock that follows the recorded receive times
+    pub fn clock(&self) -> ReplayClock {
+        self.clock.clone()
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Subscriptions (same signatures as `Stream` / `GRPCStream`)
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Subscribe to trades
+    pub fn trades<F>(&mut self, coins: &[&str], callback: F) -> Subscription
+    where
+        F: Fn(Value) + Send + Sync + 'static,
+    {
+        self.subscribe(Channel::Trades, coins, callback)
+    }
+
+    /// Subscribe to L2 book snapshots
+    pub fn l2_book<F>(&mut self, coin: &str, callback: F) -> Subscription
+    where
+        F: Fn(Value) + Send + Sync + 'static,
+    {
+        self.subscribe(Channel::L2Book, &[coin], callback)
+    }
+
+    /// Subscribe to all mids
+    pub fn all_mids<F>(&mut self, callback: F) -> Subscription
+    where
+        F: Fn(Value) + Send + Sync + 'static,
+    {
+        self.subscribe(Channel::AllMids, &[], callback)
+    }
+
+    /// Subscribe to book level changes
+    pub fn book_updates<F>(&mut self, coins: &[&str], callback: F) -> Subscription
+    where
+        F: Fn(Value) + Send + Sync + 'static,
+    {
+        self.subscribe(Channel::BookUpdates, coins, callback)
+    }
+
+    /// Subscribe to blocks
+    pub fn blocks<F>(&mut self, callback: F) -> Subscription
+    where
+        F: Fn(Value) + Send + Sync + 'static,
+    {
+        self.subscribe(Channel::Blocks, &[], callback)
+    }
+
+    /// Subscribe to the L4 book
+    pub fn l4_book<F>(&mut self, coin: &str, callback: F) -> Subscription
+    where
+        F: Fn(Value) + Send + Sync + 'static,
+    {
+        self.subscribe(Channel::L4Book, &[coin], callback)
+    }
+
+    fn subscribe<F>(&mut self, channel: Channel, coins: &[&str], callback: F) -> Subscription
+    where
+        F: Fn(Value) + Send + Sync + 'static,
+    {
+        self.next_id += 1;
+        let target = Target {
+            channel,
+            coins: coins.iter().map(|c| c.to_string()).collect(),
+            callback: Arc::new(callback),
+        };
+        self.shared().targets.lock().unwrap().insert(self.next_id, target);
+        Subscription { id: self.next_id, channel: channel.to_string() }
+    }
+
+    /// Stop delivering to a subscription (also while the replay runs)
+    pub fn unsubscribe(&mut self, subscription: &Subscription) {
+        self.shared().targets.lock().unwrap().remove(&subscription.id);
+    }
+
+    fn shared(&self) -> &Shared {
+        self.live.as_deref().unwrap_or(&self.shared)
+    }
+
+    // ──────────────────────────────────────────────────────────────────────────
+    // Lifecycle
+    // ──────────────────────────────────────────────────────────────────────────
+
+    /// Start the replay in the background (non-blocking). A recording is
+    /// replayed once; starting again does nothing.
+    pub fn start(&mut self) -> Result<()> {
+        let Some(input) = self.input.take() else {
+            return Ok(());
+        };
+        let shared = Arc::new(std::mem::take(&mut self.shared));
+        shared.running.store(true, Ordering::SeqCst);
+        shared.set_state(ConnectionState::Connecting);
+        shared.set_state(ConnectionState::Connected);
+        if let Some(cb) = &shared.on_open {
+            cb();
+        }
+
+        let player = Player {
+            shared: shared.clone(),
+            clock: self.clock.clone(),
+            speed: self.speed,
+            max_gap: self.max_gap,
+            source: self.source,
+        };
+        self.live = Some(shared);
+        self.thread = Some(
+            std::thread::Builder::new()
+                .name("replay".to_string())
+                .spawn(move || player.run(input))?,
+        );
+        Ok(())
+    }
+
+    /// Stop the replay; the close callback has run when this returns
+    pub fn stop(&mut self) {
+        if let Some(shared) = &self.live {
+            shared.running.store(false, Ordering::SeqCst);
+        }
+        self.wait();
+    }
+
+    /// Block until the whole recording has been replayed (or `stop` is called)
+    pub fn wait(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+
+    /// Whether the recording was played to its end
+    pub fn is_finished(&self) -> bool {
+        self.live.as_ref().is_some_and(|s| s.finished.load(Ordering::SeqCst))
+    }
+
+    /// Messages delivered to at least one callback so far
+    pub fn delivered(&self) -> u64 {
+        self.shared().delivered.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Replayer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// State of the replay thread
+struct Player {
+    shared: Arc<Shared>,
+    clock: ReplayClock,
+    speed: Speed,
+    max_gap: Option<Duration>,
+    source: Option<Source>,
+}
+
+impl Player {
+    fn run(self, input: Input) {
+        let mut pace = Pace::new(self.speed, self.max_gap);
+        let completed = match input {
+            Input::Records(records) => self.play(records, &mut pace),
+            Input::Files(paths) => paths.iter().all(|path| match read_file(path) {
+                Ok(records) => self.play(records, &mut pace),
+                Err(e) => {
+                    self.shared.error(format!("{}: {}", path.display(), e));
+                    self.shared.running.load(Ordering::SeqCst)
+                }
+            }),
+        };
+
+        self.shared.finished.store(completed, Ordering::SeqCst);
+        self.shared.running.store(false, Ordering::SeqCst);
+        self.shared.set_state(ConnectionState::Disconnected);
+        if let Some(cb) = &self.shared.on_close {
+            cb();
+        }
+    }
+
+    /// Deliver `records`; returns `false` if the replay was stopped
+    fn play(&self, records: Vec<Record>, pace: &mut Pace) -> bool {
+        for record in records {
+            if self.source.is_some_and(|s| s != record.source) {
+                continue;
+            }
+            if !pace.wait_for(&record, &self.shared.running) {
+                return false;
+            }
+            self.clock.0.store(record.recv_ms, Ordering::SeqCst);
+
+            // Hold the lock only to pick the callbacks, so they may take a while
+            let callbacks: Vec<Callback> = self
+                .shared
+                .targets
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|t| t.matches(&record))
+                .map(|t| t.callback.clone())
+                .collect();
+            if callbacks.is_empty() {
+                continue;
+            }
+            for callback in callbacks {
+                callback(record.data.clone());
+            }
+            self.shared.delivered.fetch_add(1, Ordering::SeqCst);
+        }
+        self.shared.running.load(Ordering::SeqCst)
+    }
+}
+
+/// Maps recorded receive times onto the replay's own clock
+struct Pace {
+    speed: Speed,
+    max_gap: Option<Duration>,
+    started: Instant,
+    /// Recorded time elapsed since the first message, after speed and max_gap
+    elapsed: Duration,
+    last: Option<(u64, u64)>,
+}
+
+impl Pace {
+    fn new(speed: Speed, max_gap: Option<Duration>) -> Self {
+        Pace { speed, max_gap, started: Instant::now(), elapsed: Duration::ZERO, last: None }
+    }
+
+    /// Sleep until `record` is due; returns `false` if `running` was cleared
+    fn wait_for(&mut self, record: &Record, running: &AtomicBool) -> bool {
+        if !running.load(Ordering::SeqCst) {
+            return false;
+        }
+        let gap = match self.last.replace((record.mono_us, record.recv_ms)) {
+            // The monotonic time restarts with each recorder run; fall back to the wall clock
+            Some((mono_us, _)) if record.mono_us >= mono_us => Duration::from_micros(record.mono_us - mono_us),
+            Some((_, recv_ms)) => Duration::from_millis(record.recv_ms.saturating_sub(recv_ms)),
+            None => Duration::ZERO,
+        };
+        let gap = match self.speed {
+            Speed::Max => return true,
+            Speed::Original => gap,
+            Speed::Accelerated(factor) => gap.div_f64(factor),
+        };
+        self.elapsed += self.max_gap.map_or(gap, |max| gap.min(max));
+
+        let due = self.started + self.elapsed;
+        loop {
+            let now = Instant::now();
+            if now >= due {
+                return true;
+            }
+            std::thread::sleep((due - now).min(STOP_CHECK));
+            if !running.load(Ordering::SeqCst) {
+                return false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recorder::Recorder;
+    use serde_json::json;
+
+    fn record(seq: u64, channel: Channel, coin: Option<&str>, mono_ms: u64, data: Value) -> Record {
+        Record {
+            seq,
+            channel,
+            channel_seq: seq,
+            source: Source::Ws,
+            coin: coin.map(str::to_string),
+            recv_ms: 1_737_412_345_000 + mono_ms,
+            mono_us: mono_ms * 1000,
+            data,
+        }
+    }
+
+    fn recording() -> Vec<Record> {
+        vec![
+            record(1, Channel::Trades, Some("BTC"), 0, json!({"px": "96251.0"})),
+            record(2, Channel::L2Book, Some("BTC"), 100, json!({"coin": "BTC"})),
+            record(3, Channel::Trades, Some("ETH"), 200, json!({"px": "3612.3"})),
+            record(4, Channel::AllMids, None, 300, json!({"mids": {}})),
+            record(5, Channel::Trades, Some("BTC"), 400, json!({"px": "96252.0"})),
+        ]
+    }
+
+    /// Replay `records` and return what each callback saw, in order
+    fn replay(records: Vec<Record>, speed: Speed) -> (Vec<String>, Replayer) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut replayer = Replayer::from_records(records).speed(speed);
+        let log = |name: &'static str| {
+            let seen = seen.clone();
+            move |data: Value| seen.lock().unwrap().push(format!("{} {}", name, data))
+        };
+        replayer.trades(&["BTC"], log("trades"));
+        replayer.l2_book("BTC", log("l2_book"));
+        replayer.all_mids(log("all_mids"));
+        replayer.start().unwrap();
+        replayer.wait();
+        let seen = seen.lock().unwrap().clone();
+        (seen, replayer)
+    }
+
+    #[test]
+    fn delivers_matching_records_in_order() {
+        let mut records = recording();
+        records.reverse();
+        let (seen, replayer) = replay(records, Speed::Max);
+        assert_eq!(
+            seen,
+            [
+                r#"trades {"px":"96251.0"}"#,
+                r#"l2_book {"coin":"BTC"}"#,
+                r#"all_mids {"mids":{}}"#,
+                r#"trades {"px":"96252.0"}"#,
+            ]
+        );
+        assert_eq!(replayer.delivered(), 4);
+        assert!(replayer.is_finished());
+        assert_eq!(replayer.clock().now_ms(), 1_737_412_345_400);
+        assert_eq!(replayer.state(), ConnectionState::Disconnected);
+    }
+
+    #[test]
+    fn keeps_recorded_gaps_scaled_by_speed() {
+        let started = Instant::now();
+        let (seen, _) = replay(recording(), Speed::Accelerated(10.0));
+        let elapsed = started.elapsed();
+        assert_eq!(seen.len(), 4);
+        // 400 ms of recording at 10x
+        assert!(elapsed >= Duration::from_millis(40), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(400), "{:?}", elapsed);
+
+        let mut pace = Pace::new(Speed::Original, Some(Duration::from_millis(10)));
+        let running = AtomicBool::new(true);
+        let started = Instant::now();
+        for record in recording() {
+            assert!(pace.wait_for(&record, &running));
+        }
+        assert!(started.elapsed() < Duration::from_millis(200), "max_gap ignored: {:?}", started.elapsed());
+    }
+
+    #[test]
+    fn stop_interrupts_a_replay_and_closes_once() {
+        let closed = Arc::new(AtomicU64::new(0));
+        let closed_cb = closed.clone();
+        let slow = vec![
+            record(1, Channel::Trades, Some("BTC"), 0, json!(1)),
+            record(2, Channel::Trades, Some("BTC"), 60_000, json!(2)),
+        ];
+        let mut replayer = Replayer::from_records(slow).on_close(move || {
+            closed_cb.fetch_add(1, Ordering::SeqCst);
+        });
+        let sub = replayer.trades(&["BTC"], |_| {});
+        replayer.start().unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        replayer.unsubscribe(&sub);
+
+        let started = Instant::now();
+        replayer.stop();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(!replayer.is_finished());
+        assert_eq!(replayer.delivered(), 1);
+        drop(replayer);
+        assert_eq!(closed.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn replays_a_recorded_directory() {
+        let dir = std::env::temp_dir().join(format!("hl-replay-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut recorder = Recorder::create(&dir, &[Channel::Trades], &["BTC".to_string()]).unwrap();
+        for (i, px) in ["96251.0", "96252.0"].iter().enumerate() {
+            let data = json!({"channel": "trades", "data": [{"coin": "BTC", "px": px}]});
+            recorder.record_at(Channel::Trades, Source::Grpc, Some("BTC"), data, 1_737_412_345_000 + i as u64).unwrap();
+        }
+        recorder.close().unwrap();
+
+        let errors = Arc::new(AtomicU64::new(0));
+        let errors_cb = errors.clone();
+        let prices = Arc::new(Mutex::new(Vec::new()));
+        let prices_cb = prices.clone();
+        let mut replayer = Replayer::open(&dir).unwrap().speed(Speed::Max).on_error(move |_| {
+            errors_cb.fetch_add(1, Ordering::SeqCst);
+        });
+        replayer.trades(&["BTC"], move |data| {
+            prices_cb.lock().unwrap().push(data["data"][0]["px"].as_str().unwrap().to_string());
+        });
+        replayer.start().unwrap();
+        replayer.wait();
+        assert_eq!(*prices.lock().unwrap(), ["96251.0", "96252.0"]);
+        assert_eq!(errors.load(Ordering::SeqCst), 0);
+
+        // Filtered out by source
+        let mut ws_only = Replayer::open(&dir).unwrap().speed(Speed::Max).source(Source::Ws);
+        ws_only.trades(&["BTC"], |_| panic!("gRPC trade replayed"));
+        ws_only.start().unwrap();
+        ws_only.wait();
+        assert!(ws_only.is_finished());
+
+        assert!(Replayer::open(&dir.join("missing")).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn parses_speeds() {
+        assert_eq!("max".parse::<Speed>().unwrap(), Speed::Max);
+        assert_eq!("1x".parse::<Speed>().unwrap(), Speed::Original);
+        assert_eq!("10x".parse::<Speed>().unwrap(), Speed::Accelerated(10.0));
+        assert_eq!("0.5".parse::<Speed>().unwrap(), Speed::Accelerated(0.5));
+        assert!("0x".parse::<Speed>().is_err());
+        assert!("fast".parse::<Speed>().is_err());
+    }
+}
@@ -0,0 +1,621 @@
+//! Trailing stops: a stop-loss that follows the market.
+//!
+//! [`TrailingStop`] places a stop-loss trigger order a trail distance (a
+//! percentage or a price) behind the mid, then moves it up as the mid makes
+//! new highs (or down as it makes new lows, for a buy stop covering a short).
+//! Mids come from `allMids` or `l2Book` messages as [`MarketEvent`]s:
+//! [`TrailingStopOrder::on_event`] works out the [`Move`]s, only once the stop
+//! would move by at least the step and at most once per `min_interval`, and
+//! [`TrailingStopOrder::move_stop`] makes them.
+//!
+//! `sdk.modify` only sends limit orders, so a move is a `batchModify` of the
+//! trigger order signed here; when the exchange rejects it the stop is
+//! cancelled and placed again at the new price, as a new order.
+//!
+//! ```no_run
+//! use std::time::Instant;
+//!
+//! use hyperliquid_examples::models::MarketEvent;
+//! use hyperliquid_examples::trailing::{MidSource, Trail, TrailingStop};
+//! use hyperliquid_examples::{Config, Requires};
+//! use rust_decimal::Decimal;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let config = Config::load()?.require("trailing_stop", Requires::Signer);
+//! let sdk = config.connect().await?;
+//! let mut order = TrailingStop::sell("BTC", Decimal::new(1, 3), "2%".parse::<Trail>()?)
+//!     .place(&config, &sdk)
+//!     .await?;
+//! let user = format!("{:?}", sdk.address().unwrap());
+//! let mut stream = sdk.stream();
+//! let mut events = order.watch(&mut stream, &user, MidSource::Mids);
+//! stream.start()?;
+//! while let Some(event) = events.recv().await {
+//!     if let Some(step) = order.on_event(&event?, Instant::now()) {
+//!         order.move_stop(&config, &step).await?;
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use hyperliquid_sdk::{HyperliquidSDK, Stream};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+
+use crate::config::Config;
+use crate::dry_run::actions;
+use crate::error::{Error, Result};
+use crate::exchange::{self, rejection, OrderStatus};
+use crate::feed;
+use crate::models::{MarketEvent, TypedInfo};
+use crate::price::{check_slippage, Precision, Rounding, DEFAULT_SLIPPAGE};
+use crate::registry::{AssetRegistry, Market};
+
+/// Shortest time between two moves of the stop, by default
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(2);
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Trail
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// A distance in price: a percentage of the price (`2%`) or a fixed amount (`150`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trail {
+    Percent(Decimal),
+    Price(Decimal),
+}
+
+impl Trail {
+    /// The distance at `px`
+    pub fn distance(self, px: Decimal) -> Decimal {
+        match self {
+            Trail::Percent(pct) => px * pct / Decimal::ONE_HUNDRED,
+            Trail::Price(amount) => amount,
+        }
+    }
+
+    fn is_zero(self) -> bool {
+        match self {
+            Trail::Percent(v) | Trail::Price(v) => v.is_zero(),
+        }
+    }
+}
+
+impl FromStr for Trail {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (number, percent) = match s.strip_suffix('%') {
+            Some(number) => (number.trim(), true),
+            None => (s, false),
+        };
+        let value: Decimal = number
+            .parse()
+            .map_err(|_| Error::ValidationError(format!("Invalid distance '{}': a price or a percentage like 2%", s)))?;
+        if value.is_sign_negative() || (percent && value >= Decimal::ONE_HUNDRED) {
+            return Err(Error::ValidationError(format!("Distance out of range: {}", s)));
+        }
+        Ok(if percent { Trail::Percent(value) } else { Trail::Price(value) })
+    }
+}
+
+impl fmt::Display for Trail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trail::Percent(pct) => write!(f, "{}%", pct.normalize()),
+            Trail::Price(amount) => write!(f, "{}", amount.normalize()),
+        }
+    }
+}
+
+/// Where the mid comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MidSource {
+    /// `allMids`
+    Mids,
+    /// Best bid and ask of the coin's `l2Book`
+    Book,
+}
+
+impl FromStr for MidSource {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "mids" | "allMids" => Ok(MidSource::Mids),
+            "book" | "l2Book" => Ok(MidSource::Book),
+            other => Err(Error::ValidationError(format!("Unknown mid source '{}': mids or book", other))),
+        }
+    }
+}
+
+impl fmt::Display for MidSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MidSource::Mids => "allMids",
+            MidSource::Book => "l2Book",
+        })
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// TrailingStop
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Builder of a trailing stop
+#[derive(Debug, Clone)]
+pub struct TrailingStop {
+    pub coin: String,
+    /// Buy stop (covering a short) rather than a sell stop (closing a long)
+    pub is_buy: bool,
+    pub size: Decimal,
+    pub trail: Trail,
+    step: Trail,
+    min_interval: Duration,
+    slippage: Decimal,
+}
+
+impl TrailingStop {
+    /// Stop closing a long: trails below the highest mid
+    pub fn sell(coin: &str, size: Decimal, trail: Trail) -> Self {
+        Self::new(coin, false, size, trail)
+    }
+
+    /// Stop covering a short: trails above the lowest mid
+    pub fn buy(coin: &str, size: Decimal, trail: Trail) -> Self {
+        Self::new(coin, true, size, trail)
+    }
+
+    fn new(coin: &str, is_buy: bool, size: Decimal, trail: Trail) -> Self {
+        Self {
+            coin: coin.to_string(),
+            is_buy,
+            size,
+            trail,
+            step: Trail::Price(Decimal::ZERO),
+            min_interval: DEFAULT_MIN_INTERVAL,
+            slippage: DEFAULT_SLIPPAGE,
+        }
+    }
+
+    /// Move the stop only by at least this much (default: any tick)
+    pub fn step(mut self, step: Trail) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Move the stop at most once per `interval` (default 2s)
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Worst price of the market order once the stop fires, as a fraction of
+    /// the trigger price
+    pub fn slippage(mut self, fraction: Decimal) -> Self {
+        self.slippage = fraction;
+        self
+    }
+
+    /// The stop a trail behind `mid`, rounded onto `market`'s grids
+    pub fn build(&self, market: &Market, mid: Decimal) -> Result<TrailingStopOrder> {
+        if !market.is_perp() {
+            return Err(Error::ValidationError(format!(
+                "{} is a spot market; trailing stops need a perp",
+                market.symbol
+            )));
+        }
+        if self.trail.is_zero() {
+            return Err(Error::ValidationError("The trail distance must be above zero".to_string()));
+        }
+        if self.trail.distance(mid) >= mid {
+            return Err(Error::ValidationError(format!("Trail {} is wider than the mid {}", self.trail, mid)));
+        }
+        check_slippage(self.slippage)?;
+
+        let precision = market.precision();
+        let mut order = TrailingStopOrder {
+            coin: market.symbol.clone(),
+            asset_id: market.asset_id,
+            precision,
+            is_buy: self.is_buy,
+            size: precision.sz(self.size, Rounding::Down)?.value(),
+            trail: self.trail,
+            step: self.step,
+            min_interval: self.min_interval,
+            slippage: self.slippage,
+            cloid: exchange::cloid(),
+            oid: None,
+            stop_px: Decimal::ZERO,
+            extreme: mid,
+            status: StopStatus::Open,
+            moves: 0,
+            moved_at: None,
+        };
+        order.stop_px = order.target(mid)?;
+        Ok(order)
+    }
+
+    /// Build the stop at the current mid and place it
+    pub async fn place(&self, config: &Config, sdk: &HyperliquidSDK) -> Result<TrailingStopOrder> {
+        let registry = AssetRegistry::load(sdk).await?;
+        let market = registry.resolve(&self.coin)?;
+        let mid = TypedInfo::from_sdk(sdk).mid(&market.symbol).await?;
+        let mut order = self.build(market, mid)?;
+        let response = exchange::send(config, &order.action(), None).await?;
+        order.placed(&response)?;
+        Ok(order)
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// TrailingStopOrder
+// ══════════════════════════════════════════════════════════════════════════════
+
+/// Where a trailing stop stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopStatus {
+    /// Waiting for its trigger, following the mid
+    Open,
+    /// Fired: now a market order closing the position
+    Triggered,
+    Filled,
+    /// Cancelled or rejected by the exchange
+    Cancelled,
+}
+
+impl fmt::Display for StopStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            StopStatus::Open => "open",
+            StopStatus::Triggered => "triggered",
+            StopStatus::Filled => "filled",
+            StopStatus::Cancelled => "cancelled",
+        })
+    }
+}
+
+/// A move of the stop called for by a new high (or low) of the mid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Move {
+    pub from: Decimal,
+    pub to: Decimal,
+    pub mid: Decimal,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Stop {} -> {} (mid {})", self.from, self.to, self.mid)
+    }
+}
+
+/// A stop built by [`TrailingStop::build`]
+#[derive(Debug, Clone)]
+pub struct TrailingStopOrder {
+    pub coin: String,
+    pub asset_id: u32,
+    precision: Precision,
+    pub is_buy: bool,
+    pub size: Decimal,
+    pub trail: Trail,
+    pub step: Trail,
+    pub min_interval: Duration,
+    slippage: Decimal,
+    cloid: String,
+    /// Set once placed; a move may change it
+    pub oid: Option<u64>,
+    /// Current trigger price
+    pub stop_px: Decimal,
+    /// Best mid so far: the highest for a sell stop, the lowest for a buy stop
+    pub extreme: Decimal,
+    pub status: StopStatus,
+    /// Moves made so far
+    pub moves: u32,
+    moved_at: Option<Instant>,
+}
+
+impl TrailingStopOrder {
+    /// Wire form of the stop at `trigger_px`, a price on the tick grid:
+    /// reduce-only, and a market order at most `slippage` through it once it
+    /// fires (`sdk.trigger_order` would send prices rounded to whole numbers)
+    fn wire(&self, trigger_px: Decimal) -> Value {
+        let worst = match self.is_buy {
+            true => trigger_px * (Decimal::ONE + self.slippage),
+            false => trigger_px * (Decimal::ONE - self.slippage),
+        };
+        let limit_px = self.precision.px_toward(worst, trigger_px).map(|px| px.value()).unwrap_or(trigger_px);
+        json!({
+            "a": self.asset_id,
+            "b": self.is_buy,
+            "p": limit_px.to_string(),
+            "s": self.size.normalize().to_string(),
+            "r": true,
+            "t": {"trigger": {"isMarket": true, "triggerPx": trigger_px.normalize().to_string(), "tpsl": "sl"}},
+            "c": self.cloid,
+        })
+    }
+
+    /// The `order` action placing the stop at `stop_px`
+    pub fn action(&self) -> Value {
+        self.action_at(self.stop_px)
+    }
+
+    fn action_at(&self, trigger_px: Decimal) -> Value {
+        json!({
+            "type": "order",
+            "orders": [self.wire(trigger_px)],
+            "grouping": "na",
+        })
+    }
+
+    /// The `batchModify` action moving the stop to `trigger_px`
+    pub fn modify_action(&self, trigger_px: Decimal) -> Value {
+        json!({
+            "type": "batchModify",
+            "modifies": [{"oid": self.oid, "order": self.wire(trigger_px)}],
+        })
+    }
+
+    /// The `order` action placing the stop again at `trigger_px` once the
+    /// old one is cancelled: a new order, with a cloid of its own
+    fn replacement(&mut self, trigger_px: Decimal) -> Value {
+        self.oid = None;
+        self.cloid = exchange::cloid();
+        self.action_at(trigger_px)
+    }
+
+    /// Record the oid the exchange answered [`action`](Self::action) with
+    pub fn placed(&mut self, response: &Value) -> Result<()> {
+        self.oid = Some(resting_oid(response, "Trailing stop")?);
+        Ok(())
+    }
+
+    /// Record a move the exchange answered `response` to, either to
+    /// [`modify_action`](Self::modify_action) or to the replacing order
+    pub fn modified(&mut self, step: &Move, response: &Value) -> Result<()> {
+        self.oid = Some(resting_oid(response, &step.to_string())?);
+        self.stop_px = step.to;
+        self.moves += 1;
+        Ok(())
+    }
+
+    /// Record the answer to [`replacement`](Self::replacement). The old stop
+    /// is gone by then, so a failure leaves the position without one.
+    fn replaced(&mut self, step: &Move, response: Result<Value>) -> Result<()> {
+        match response.and_then(|response| self.modified(step, &response)) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                self.status = StopStatus::Cancelled;
+                Err(Error::ValidationError(format!(
+                    "{}: the stop was cancelled but not placed again, the position is unprotected: {}",
+                    step, e
+                )))
+            }
+        }
+    }
+
+    /// Follow one stream event received at `now`: a mid of the coin (from
+    /// `allMids` or its `l2Book`) may call for a [`Move`]; an update of the
+    /// stop's order records whether it fired, filled or was cancelled.
+    pub fn on_event(&mut self, event: &MarketEvent, now: Instant) -> Option<Move> {
+        let mid = match event {
+            MarketEvent::Mid(mid) if mid.coin == self.coin => mid.px,
+            MarketEvent::BookSnapshot(book) if book.coin == self.coin => {
+                let (bids, asks) = &book.levels;
+                (bids.first()?.px + asks.first()?.px) / Decimal::TWO
+            }
+            MarketEvent::OrderUpdate(update) if Some(update.order.oid) == self.oid => {
+                self.status = match update.status.as_str() {
+                    "open" => return None,
+                    "triggered" => StopStatus::Triggered,
+                    "filled" => StopStatus::Filled,
+                    _ => StopStatus::Cancelled,
+                };
+                return None;
+            }
+            _ => return None,
+        };
+        if self.status != StopStatus::Open {
+            return None;
+        }
+
+        self.extreme = if self.is_buy { self.extreme.min(mid) } else { self.extreme.max(mid) };
+        let to = self.target(self.extreme).ok()?;
+        // Only tighter, by at least the step, and still short of the mid
+        let gain = if self.is_buy { self.stop_px - to } else { to - self.stop_px };
+        let short_of_mid = if self.is_buy { to > mid } else { to < mid };
+        if gain <= Decimal::ZERO || gain < self.step.distance(self.stop_px) || !short_of_mid {
+            return None;
+        }
+        if self.moved_at.is_some_and(|at| now.saturating_duration_since(at) < self.min_interval) {
+            return None;
+        }
+        self.moved_at = Some(now);
+        Some(Move { from: self.stop_px, to, mid })
+    }
+
+    /// Stop price a trail behind `px`, rounded away from it
+    fn target(&self, px: Decimal) -> Result<Decimal> {
+        let distance = self.trail.distance(px);
+        let raw = if self.is_buy { px + distance } else { px - distance };
+        Ok(self.precision.px_away_from(raw, px)?.value())
+    }
+
+    /// Send `step` as a `batchModify`, or cancel and place the stop again
+    /// when the modify is rejected.
+    ///
+    /// Fails with the stop [`Cancelled`](StopStatus::Cancelled) when it was
+    /// cancelled and could not be placed again.
+    pub async fn move_stop(&mut self, config: &Config, step: &Move) -> Result<()> {
+        let response = exchange::send(config, &self.modify_action(step.to), None).await?;
+        if rejection(&response).is_none() {
+            return self.modified(step, &response);
+        }
+        let Some(oid) = self.oid else {
+            return self.modified(step, &response);
+        };
+        let cancelled = exchange::send(config, &actions::cancel(&[(self.asset_id, oid)]), None).await?;
+        if let Some(reason) = rejection(&cancelled) {
+            return Err(Error::ValidationError(format!("{}: cancel rejected: {}", step, reason)));
+        }
+        let action = self.replacement(step.to);
+        let response = exchange::send(config, &action, None).await;
+        self.replaced(step, response)
+    }
+
+    /// Subscribe `stream` to the mids of `source` and `user`'s
+    /// `orderUpdates`; their events (or the items that failed to decode)
+    /// arrive on the returned channel once the stream is started
+    pub fn watch(&self, stream: &mut Stream, user: &str, source: MidSource) -> UnboundedReceiver<Result<MarketEvent>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        match source {
+            MidSource::Mids => stream.all_mids(feed::forward("allMids", &tx)),
+            MidSource::Book => stream.l2_book(&self.coin, feed::forward("l2Book", &tx)),
+        };
+        stream.order_updates(user, feed::forward("orderUpdates", &tx));
+        rx
+    }
+}
+
+/// Oid of the order `response` placed (or modified), or why it was rejected
+fn resting_oid(response: &Value, what: &str) -> Result<u64> {
+    match OrderStatus::first(response) {
+        OrderStatus::Resting { oid } => Ok(oid),
+        OrderStatus::Error(reason) => Err(Error::ValidationError(format!("{} rejected: {}", what, reason))),
+        other => Err(Error::ValidationError(format!("{}: unexpected status {:?}", what, other))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::testing::{book, dec};
+    use crate::mock::{Event, MatchingEngine, Script};
+    use crate::models::Mid;
+
+    fn mid(px: &str) -> MarketEvent {
+        MarketEvent::Mid(Mid { coin: "BTC".to_string(), px: dec(px) })
+    }
+
+    #[test]
+    fn trail_is_a_percentage_or_a_price() {
+        assert_eq!("2%".parse::<Trail>().unwrap(), Trail::Percent(dec("2")));
+        assert_eq!("150".parse::<Trail>().unwrap(), Trail::Price(dec("150")));
+        assert_eq!(Trail::Percent(dec("2")).distance(dec("96000")), dec("1920"));
+        assert!("-1".parse::<Trail>().is_err());
+        assert!("100%".parse::<Trail>().is_err());
+        assert!("abc".parse::<Trail>().is_err());
+        assert_eq!(Trail::Percent(dec("2.50")).to_string(), "2.5%");
+    }
+
+    #[test]
+    fn follows_new_highs_by_the_step_at_most_once_per_interval() {
+        let engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let btc = engine.registry().resolve("BTC").unwrap().clone();
+        let mut order = TrailingStop::sell("BTC", dec("0.01"), Trail::Price(dec("1000")))
+            .step(Trail::Price(dec("100")))
+            .min_interval(Duration::from_secs(10))
+            .build(&btc, dec("96250.5"))
+            .unwrap();
+        assert_eq!(order.stop_px, dec("95250"));
+
+        let t0 = Instant::now();
+        let at = |secs: u64| t0 + Duration::from_secs(secs);
+        // Less than the step, then a move, then lower mids never move it back
+        assert_eq!(order.on_event(&mid("96300"), at(0)), None);
+        let step = order.on_event(&mid("96500"), at(0)).unwrap();
+        assert_eq!((step.from, step.to), (dec("95250"), dec("95500")));
+        order.stop_px = step.to;
+        assert_eq!(order.on_event(&mid("96000"), at(1)), None);
+        // A new high within the interval waits; the next mid after it moves
+        assert_eq!(order.on_event(&mid("97000"), at(5)), None);
+        assert_eq!(order.on_event(&mid("96900"), at(11)).map(|s| s.to), Some(dec("96000")));
+        assert_eq!(order.extreme, dec("97000"));
+
+        // A buy stop trails above the lows
+        let mut short = TrailingStop::buy("BTC", dec("0.01"), "1%".parse().unwrap()).build(&btc, dec("96000")).unwrap();
+        assert_eq!(short.stop_px, dec("96960"));
+        assert_eq!(short.on_event(&mid("95000"), at(0)).map(|s| s.to), Some(dec("95950")));
+    }
+
+    #[test]
+    fn moves_the_trigger_order_until_it_fires() {
+        let mut engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let btc = engine.registry().resolve("BTC").unwrap().clone();
+        let precision = btc.precision();
+        let (px, sz) = (precision.px(dec("96300"), Rounding::Down).unwrap(), precision.sz(dec("0.01"), Rounding::Down).unwrap());
+        let long = engine.execute(&actions::order(btc.asset_id, true, px, sz, "Ioc", false));
+        assert_eq!(rejection(&long), None);
+
+        let mut order = TrailingStop::sell("BTC", dec("0.01"), Trail::Price(dec("1000")))
+            .min_interval(Duration::ZERO)
+            .build(&btc, dec("96250.5"))
+            .unwrap();
+        order.placed(&engine.execute(&order.action())).unwrap();
+        let step = order.on_event(&mid("97000"), Instant::now()).unwrap();
+        order.modified(&step, &engine.execute(&order.modify_action(step.to))).unwrap();
+
+        let stops = engine.open_orders();
+        assert_eq!(stops.len(), 1);
+        assert_eq!((stops[0].oid, stops[0].trigger_px), (order.oid.unwrap(), Some(dec("96000"))));
+        assert_eq!(stops[0].cloid, Some(order.cloid.clone()));
+        assert_eq!(order.moves, 1);
+
+        // The market drops through the stop, which fires
+        engine.drain_events();
+        engine.set_book(&book(&engine, "95900", "95910"));
+        for event in engine.drain_events() {
+            if let Event::OrderUpdate(update) = event {
+                order.on_event(&MarketEvent::OrderUpdate(update), Instant::now());
+            }
+        }
+        assert_ne!(order.status, StopStatus::Open);
+        assert_eq!(order.on_event(&mid("99000"), Instant::now()), None);
+        assert!(engine.open_orders().is_empty());
+    }
+
+    #[test]
+    fn a_stop_not_placed_again_leaves_the_position_unprotected() {
+        let mut engine = MatchingEngine::new(&Script::default_market()).unwrap();
+        let btc = engine.registry().resolve("BTC").unwrap().clone();
+        let precision = btc.precision();
+        let (px, sz) = (precision.px(dec("96300"), Rounding::Down).unwrap(), precision.sz(dec("0.01"), Rounding::Down).unwrap());
+        assert_eq!(rejection(&engine.execute(&actions::order(btc.asset_id, true, px, sz, "Ioc", false))), None);
+
+        let mut order = TrailingStop::sell("BTC", dec("0.01"), Trail::Price(dec("1000")))
+            .min_interval(Duration::ZERO)
+            .build(&btc, dec("96250.5"))
+            .unwrap();
+        order.placed(&engine.execute(&order.action())).unwrap();
+        let (oid, cloid) = (order.oid.unwrap(), order.cloid.clone());
+
+        // Cancelled, then placed again as a new order with a cloid of its own
+        let step = order.on_event(&mid("97000"), Instant::now()).unwrap();
+        assert_eq!(rejection(&engine.execute(&actions::cancel(&[(btc.asset_id, oid)]))), None);
+        let action = order.replacement(step.to);
+        assert_ne!(order.cloid, cloid);
+        order.replaced(&step, Ok(engine.execute(&action))).unwrap();
+        let stops = engine.open_orders();
+        assert_eq!((stops[0].oid, &stops[0].cloid), (order.oid.unwrap(), &Some(order.cloid.clone())));
+        assert_ne!(order.oid, Some(oid));
+
+        // A rejected replacement: no stop any more, and no more moves
+        let step = order.on_event(&mid("98000"), Instant::now()).unwrap();
+        order.replacement(step.to);
+        let rejected = json!({
+            "status": "ok",
+            "response": {"type": "order", "data": {"statuses": [{"error": "Insufficient margin to place order."}]}},
+        });
+        let error = order.replaced(&step, Ok(rejected)).unwrap_err();
+        assert!(error.to_string().contains("the position is unprotected"), "{}", error);
+        assert_eq!(order.status, StopStatus::Cancelled);
+        assert_eq!(order.on_event(&mid("99000"), Instant::now()), None);
+    }
+}
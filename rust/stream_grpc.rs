@@ -8,31 +8,23 @@
 //! cargo run --example stream_grpc
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::{decode, stream_items, GrpcL2Book, L2Book, Trade};
+use hyperliquid_examples::{connect, stream_duration, Requires};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint/TOKEN'");
-        eprintln!("  cargo run --example stream_grpc");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("stream_grpc", Requires::Endpoint).await?;
 
     println!("gRPC Streaming Example");
     println!("{}", "=".repeat(50));
 
-    // Create SDK
-    let sdk = HyperliquidSDK::new()
-        .endpoint(endpoint.as_ref().unwrap())
-        .build()
-        .await?;
-
     // Create gRPC stream via SDK
     println!("\n1. Creating gRPC stream...");
 
@@ -54,13 +46,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _trade_sub = grpc.trades(&["BTC", "ETH"], move |data| {
         let count = trade_count_cb.fetch_add(1, Ordering::SeqCst) + 1;
         if count <= 10 {
-            if let Some(trades) = data.get("data").and_then(|v| v.as_array()) {
-                for trade in trades {
-                    let coin = trade.get("coin").and_then(|v| v.as_str()).unwrap_or("?");
-                    let px = trade.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                    let sz = trade.get("sz").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [trades {}] {} {} @ {}", count, coin, sz, px);
+            match stream_items::<Trade>(&data, "trades") {
+                Some(Ok(trades)) => {
+                    for trade in trades {
+                        println!("   [trades {}] {} {} @ {}", count, trade.coin, trade.sz, trade.px);
+                    }
                 }
+                Some(Err(e)) => eprintln!("   [Error] bad trade payload: {}", e),
+                None => {}
             }
         }
     });
@@ -70,29 +63,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _book_sub = grpc.l2_book("BTC", move |data| {
         let count = book_count_cb.fetch_add(1, Ordering::SeqCst) + 1;
         if count <= 10 {
-            if let Some(levels) = data.get("levels").and_then(|v| v.as_array()) {
-                if levels.len() >= 2 {
-                    let bids = levels[0].as_array();
-                    let asks = levels[1].as_array();
-                    if let (Some(bids), Some(asks)) = (bids, asks) {
-                        if let (Some(bid), Some(ask)) = (bids.first(), asks.first()) {
-                            let bid_px = bid.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                            let ask_px = ask.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                            println!("   [l2Book {}] BTC: {} / {}", count, bid_px, ask_px);
-                        }
+            match decode::<GrpcL2Book>(data) {
+                Ok(book) => {
+                    let book = L2Book::from(book);
+                    if let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) {
+                        println!("   [l2Book {}] BTC: {} / {}", count, bid.px, ask.px);
                     }
                 }
+                Err(e) => eprintln!("   [Error] bad l2Book payload: {}", e),
             }
         }
     });
 
     // Start streaming
-    println!("\n4. Receiving messages (30 seconds):");
+    let duration = stream_duration(30);
+    println!("\n4. Receiving messages ({} seconds):", duration.as_secs());
 
     grpc.start()?;
 
-    // Run for 30 seconds
-    tokio::time::sleep(Duration::from_secs(30)).await;
+    // Run for 30 seconds (HL_STREAM_SECONDS overrides)
+    tokio::time::sleep(duration).await;
 
     grpc.stop();
 
@@ -0,0 +1,157 @@
+//! Stream Health Example
+//!
+//! Stream trades and book updates while measuring their health: latency from
+//! exchange time to receipt, gaps between messages per channel and coin,
+//! reconnects, and with `--grpc` gaps in the HyperCore block numbers. The
+//! metrics are served for Prometheus at `/metrics`, and an alert is printed
+//! when a feed goes stale (format: `hyperliquid_examples::health`).
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint/TOKEN"
+//! cargo run --bin stream_health -- BTC,ETH
+//! cargo run --bin stream_health -- BTC --grpc --stale 10 --listen 0.0.0.0:9184
+//! curl localhost:9184/metrics
+//! ```
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use clap::Parser;
+use hyperliquid_examples::health::{HealthMonitor, DEFAULT_STALE_AFTER};
+use hyperliquid_examples::recorder::{now_ms, Channel, Source};
+use hyperliquid_examples::{stream_duration, AssetRegistry, Config, Requires};
+
+/// How often feeds are checked for staleness
+const CHECK_EVERY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Parser)]
+#[command(name = "stream_health", about = "Stream latency, gap and staleness metrics for Prometheus")]
+struct Args {
+    /// Coins, comma-separated
+    #[arg(default_value = "BTC,ETH")]
+    coins: String,
+    /// Stream over gRPC (and watch the blocks stream) instead of WebSocket
+    #[arg(long)]
+    grpc: bool,
+    /// Address of the metrics endpoint
+    #[arg(long, default_value = "127.0.0.1:9184")]
+    listen: SocketAddr,
+    /// Seconds without a message before a feed is stale
+    #[arg(long, default_value_t = DEFAULT_STALE_AFTER.as_secs())]
+    stale: u64,
+}
+
+fn ms(d: Option<Duration>) -> String {
+    d.map_or("-".to_string(), |d| format!("{}ms", d.as_millis()))
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("stream_health", Requires::Endpoint);
+    let args = Args::parse_from(std::iter::once("stream_health".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+
+    println!("Stream Health Example");
+    println!("{}", "=".repeat(50));
+
+    let registry = AssetRegistry::load(&sdk).await?;
+    let coins: Vec<String> = args
+        .coins
+        .split(',')
+        .map(|coin| registry.resolve(coin.trim()).map(|m| m.symbol.clone()))
+        .collect::<Result<_, _>>()?;
+    let names: Vec<&str> = coins.iter().map(String::as_str).collect();
+
+    let monitor = HealthMonitor::new()
+        .stale_after(Duration::from_secs(args.stale))
+        .on_alert(|alert| {
+            println!("   [Alert] {}", alert);
+        });
+    let server = monitor.serve(args.listen).await?;
+    println!("\n1. Metrics at http://{}/metrics", server.addr());
+
+    let source = if args.grpc { Source::Grpc } else { Source::Ws };
+    let (m1, m2, m3) = (monitor.clone(), monitor.clone(), monitor.clone());
+    let mut stream = sdk.stream()
+        .on_error(move |e| {
+            m1.error(Source::Ws);
+            eprintln!("   [Error] {}", e);
+        })
+        .on_reconnect(move |attempt| {
+            m2.reconnected(Source::Ws);
+            println!("   [Reconnect] attempt {}", attempt);
+        })
+        .on_state_change(move |state| m3.state_changed(Source::Ws, state));
+    let (m1, m2, m3) = (monitor.clone(), monitor.clone(), monitor.clone());
+    let mut grpc = sdk.grpc()
+        .on_error(move |e| {
+            m1.error(Source::Grpc);
+            eprintln!("   [Error] {}", e);
+        })
+        .on_reconnect(move |attempt| {
+            m2.reconnected(Source::Grpc);
+            println!("   [Reconnect] attempt {}", attempt);
+        })
+        .on_state_change(move |state| m3.state_changed(Source::Grpc, state));
+
+    println!("\n2. Streaming trades and book updates of {} ({}):", coins.join(", "), source);
+    let trades = monitor.watch(source, Channel::Trades, &names, |_| {});
+    let books = monitor.watch(source, Channel::BookUpdates, &names, |_| {});
+    if args.grpc {
+        grpc.trades(&names, trades);
+        grpc.book_updates(&names, books);
+        grpc.blocks(monitor.watch(source, Channel::Blocks, &[], |_| {}));
+        grpc.start()?;
+    } else {
+        stream.trades(&names, trades);
+        stream.book_updates(&names, books);
+        stream.start()?;
+    }
+
+    let deadline = tokio::time::sleep(stream_duration(300));
+    tokio::pin!(deadline);
+    let mut check = tokio::time::interval(CHECK_EVERY);
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            _ = tokio::signal::ctrl_c() => break,
+            _ = check.tick() => {
+                monitor.check();
+            }
+        }
+    }
+    stream.stop();
+    grpc.stop();
+
+    println!("\n3. Summary:");
+    let now = now_ms();
+    for feed in monitor.feeds() {
+        let age = feed.last_recv_ms.map_or("never".to_string(), |t| format!("{:.1}s ago", now.saturating_sub(t) as f64 / 1000.0));
+        println!(
+            "   {}: {} messages, latency avg {} max {}, max gap {}, last {}{}",
+            feed.key,
+            feed.messages,
+            ms(feed.latency_mean),
+            ms(feed.latency_max),
+            ms(feed.gap_max),
+            age,
+            if feed.stale { " (stale)" } else { "" }
+        );
+    }
+    let link = monitor.link(source);
+    println!("   {}: {} reconnects, {} errors", source, link.reconnects, link.errors);
+    if let (Some(last), gaps, missed) = monitor.blocks() {
+        println!("   Blocks: up to #{}, {} gaps, {} missed", last, gaps, missed);
+    }
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
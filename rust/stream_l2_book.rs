@@ -8,31 +8,24 @@
 //! cargo run --example stream_l2_book
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::{stream_items, L2Book};
+use hyperliquid_examples::{connect, stream_duration, Requires};
+use serde_json::Value;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint/TOKEN'");
-        eprintln!("  cargo run --example stream_l2_book");
-        std::process::exit(1);
-    }
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("stream_l2_book", Requires::Endpoint).await?;
 
     println!("L2 Orderbook Stream Example");
     println!("{}", "=".repeat(50));
 
-    // Create SDK
-    let sdk = HyperliquidSDK::new()
-        .endpoint(endpoint.as_ref().unwrap())
-        .build()
-        .await?;
-
     // Create stream via SDK
     println!("\n1. Creating WebSocket stream...");
 
@@ -55,20 +48,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _btc_sub = stream.l2_book("BTC", move |data| {
         let count = btc_count.fetch_add(1, Ordering::SeqCst) + 1;
         if count <= 30 {
-            if let Some(levels) = data.get("levels").and_then(|v| v.as_array()) {
-                if levels.len() >= 2 {
-                    let bids = levels[0].as_array();
-                    let asks = levels[1].as_array();
-                    if let (Some(bids), Some(asks)) = (bids, asks) {
-                        if let (Some(bid), Some(ask)) = (bids.first(), asks.first()) {
-                            let bid_px = bid.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                            let bid_sz = bid.get("sz").and_then(|v| v.as_str()).unwrap_or("?");
-                            let ask_px = ask.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                            let ask_sz = ask.get("sz").and_then(|v| v.as_str()).unwrap_or("?");
-                            println!("   [{}] BTC: bid={} ({}) / ask={} ({})",
-                                count, bid_px, bid_sz, ask_px, ask_sz);
-                        }
-                    }
+            if let Some(book) = top_of_book(&data, "BTC") {
+                if let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) {
+                    println!("   [{}] BTC: bid={} ({}) / ask={} ({})",
+                        count, bid.px, bid.sz, ask.px, ask.sz);
                 }
             }
         }
@@ -77,17 +60,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _eth_sub = stream.l2_book("ETH", move |data| {
         let count = eth_count.fetch_add(1, Ordering::SeqCst) + 1;
         if count <= 30 {
-            if let Some(levels) = data.get("levels").and_then(|v| v.as_array()) {
-                if levels.len() >= 2 {
-                    let bids = levels[0].as_array();
-                    let asks = levels[1].as_array();
-                    if let (Some(bids), Some(asks)) = (bids, asks) {
-                        if let (Some(bid), Some(ask)) = (bids.first(), asks.first()) {
-                            let bid_px = bid.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                            let ask_px = ask.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                            println!("   [{}] ETH: bid={} / ask={}", count, bid_px, ask_px);
-                        }
-                    }
+            if let Some(book) = top_of_book(&data, "ETH") {
+                if let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) {
+                    println!("   [{}] ETH: bid={} / ask={}", count, bid.px, ask.px);
                 }
             }
         }
@@ -96,29 +71,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let _sol_sub = stream.l2_book("SOL", move |data| {
         let count = sol_count.fetch_add(1, Ordering::SeqCst) + 1;
         if count <= 30 {
-            if let Some(levels) = data.get("levels").and_then(|v| v.as_array()) {
-                if levels.len() >= 2 {
-                    let bids = levels[0].as_array();
-                    let asks = levels[1].as_array();
-                    if let (Some(bids), Some(asks)) = (bids, asks) {
-                        if let (Some(bid), Some(ask)) = (bids.first(), asks.first()) {
-                            let bid_px = bid.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                            let ask_px = ask.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                            println!("   [{}] SOL: bid={} / ask={}", count, bid_px, ask_px);
-                        }
-                    }
+            if let Some(book) = top_of_book(&data, "SOL") {
+                if let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) {
+                    println!("   [{}] SOL: bid={} / ask={}", count, bid.px, ask.px);
                 }
             }
         }
     });
 
     // Start streaming
-    println!("\n3. Receiving L2 updates (10 seconds):");
+    let duration = stream_duration(10);
+    println!("\n3. Receiving L2 updates ({} seconds):", duration.as_secs());
 
     stream.start()?;
 
-    // Run for 10 seconds
-    tokio::time::sleep(Duration::from_secs(10)).await;
+    // Run for 10 seconds (HL_STREAM_SECONDS overrides)
+    tokio::time::sleep(duration).await;
 
     stream.stop();
 
@@ -130,3 +98,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Decode an l2Book message, keeping it only if it is for `coin`
+fn top_of_book(message: &Value, coin: &str) -> Option<L2Book> {
+    match stream_items::<L2Book>(message, "l2Book")? {
+        Ok(books) => books.into_iter().find(|book| book.coin == coin),
+        Err(e) => {
+            eprintln!("   [Error] bad l2Book payload: {}", e);
+            None
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! L4 Orderbook Stream Example
 //!
-//! Stream full L4 orderbook with individual orders via gRPC.
+//! Stream full L4 orderbook with individual orders via gRPC, rebuild it order
+//! by order in an [`L4Book`], and cross-check its L2 view against `info.l2_book`.
 //!
 //! # Usage
 //! ```bash
@@ -8,21 +9,20 @@
 //! cargo run --example stream_l4_book
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::{Side, TypedInfo};
+use hyperliquid_examples::orderbook::L4Book;
+use hyperliquid_examples::{connect, stream_duration, Requires};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint/TOKEN'");
-        eprintln!("  cargo run --example stream_l4_book");
-        std::process::exit(1);
-    }
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("stream_l4_book", Requires::Endpoint).await?;
 
     println!("L4 Orderbook Stream Example");
     println!("{}", "=".repeat(50));
@@ -33,17 +33,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   - Higher bandwidth than L2");
     println!("   - Use for market making or detailed analysis");
 
-    // Create SDK
-    let sdk = HyperliquidSDK::new()
-        .endpoint(endpoint.as_ref().unwrap())
-        .build()
-        .await?;
-
     // Create gRPC stream via SDK for L4 (more efficient for full book)
     println!("\n2. Creating gRPC stream...");
 
     let update_count = Arc::new(AtomicUsize::new(0));
     let update_count_cb = update_count.clone();
+    let book = Arc::new(Mutex::new(L4Book::new("BTC")));
+    let book_cb = book.clone();
 
     let mut grpc = sdk.grpc()
         .on_connect(|| {
@@ -55,33 +51,72 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Subscribe to L4 book
     let _sub = grpc.l4_book("BTC", move |data| {
+        let mut book = book_cb.lock().unwrap();
+        match book.ingest(&data) {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                eprintln!("   [Error] bad l4_book payload: {}", e);
+                return;
+            }
+        }
         let count = update_count_cb.fetch_add(1, Ordering::SeqCst) + 1;
         if count <= 20 {
-            if let Some(orders) = data.get("orders").and_then(|v| v.as_array()) {
-                println!("   [{}] {} orders in update", count, orders.len());
-                for order in orders.iter().take(3) {
-                    let px = order.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-                    let sz = order.get("sz").and_then(|v| v.as_str()).unwrap_or("?");
-                    let oid = order.get("oid").and_then(|v| v.as_u64()).unwrap_or(0);
-                    println!("       {} @ {} (oid: {})", sz, px, oid);
-                }
+            let l2 = book.to_l2(1);
+            match (l2.best_bid(), l2.best_ask()) {
+                (Some(bid), Some(ask)) => println!(
+                    "   [{}] height {}: {} orders, bid {} x {} ({} orders) / ask {} x {} ({} orders)",
+                    count, book.height(), book.len(), bid.px, bid.sz, bid.n, ask.px, ask.sz, ask.n
+                ),
+                _ => println!("   [{}] height {}: {} orders", count, book.height(), book.len()),
             }
         }
     });
 
     // Start streaming
-    println!("3. Receiving order updates (10 seconds):");
+    let duration = stream_duration(10);
+    println!("3. Receiving order updates ({} seconds):", duration.as_secs());
 
     grpc.start()?;
 
-    // Run for 10 seconds
-    tokio::time::sleep(Duration::from_secs(10)).await;
+    // Run for 10 seconds (HL_STREAM_SECONDS overrides)
+    tokio::time::sleep(duration).await;
 
     grpc.stop();
 
     let total = update_count.load(Ordering::SeqCst);
     println!("\n   Total updates: {}", total);
 
+    let book = book.lock().unwrap().clone();
+    if book.is_synced() {
+        println!("\n4. Largest makers:");
+        for (user, orders) in book.users().into_iter().take(5) {
+            println!("   {} {} orders", user, orders);
+        }
+        if let Some(best) = book.to_l2(1).best_bid() {
+            if let Some(first) = book.level(Side::Buy, best.px).next() {
+                println!("   Front of the best bid: oid {} ({} {})", first.oid, first.sz, first.user);
+            }
+        }
+
+        // An L2 snapshot is only comparable at the same block; a few
+        // differences at the top are expected if the book moved in between
+        println!("\n5. Cross-check against info.l2_book:");
+        match TypedInfo::from_sdk(&sdk).l2_book("BTC").await {
+            Ok(snapshot) => {
+                let found = book.compare(&snapshot);
+                println!("   L4 height {} (t={}) vs L2 t={}", book.height(), book.time(), snapshot.time);
+                if found.is_empty() {
+                    println!("   All {} snapshot levels match", snapshot.bids().len() + snapshot.asks().len());
+                }
+                for discrepancy in found.iter().take(10) {
+                    println!("   {}", discrepancy);
+                }
+            }
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
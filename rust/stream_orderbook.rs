@@ -1,6 +1,8 @@
 //! Orderbook Stream Example
 //!
-//! Stream real-time orderbook updates.
+//! Stream real-time orderbook updates into a local [`OrderBook`] and query it:
+//! best bid/ask, depth within 10 bps of the mid, the VWAP to buy 1 BTC and
+//! the top-5 imbalance.
 //!
 //! # Usage
 //! ```bash
@@ -8,36 +10,32 @@
 //! cargo run --example stream_orderbook
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::Side;
+use hyperliquid_examples::orderbook::{now_ms, OrderBook, DEFAULT_STALE_AFTER};
+use hyperliquid_examples::{connect, stream_duration, Requires};
+use rust_decimal::Decimal;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint/TOKEN'");
-        eprintln!("  cargo run --example stream_orderbook");
-        std::process::exit(1);
-    }
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("stream_orderbook", Requires::Endpoint).await?;
 
     println!("Orderbook Stream Example");
     println!("{}", "=".repeat(50));
 
-    // Create SDK
-    let sdk = HyperliquidSDK::new()
-        .endpoint(endpoint.as_ref().unwrap())
-        .build()
-        .await?;
-
     // Create stream via SDK
     println!("\n1. Creating WebSocket stream...");
 
     let update_count = Arc::new(AtomicUsize::new(0));
     let update_count_cb = update_count.clone();
+    let books = Arc::new(Mutex::new(OrderBook::new()));
+    let books_cb = books.clone();
 
     let mut stream = sdk.stream()
         .on_open(|| {
@@ -50,50 +48,53 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Subscribe to BTC L2 book
     println!("2. Subscribing to BTC orderbook...");
     let _sub = stream.l2_book("BTC", move |data| {
+        let mut books = books_cb.lock().unwrap();
+        let changed = match books.ingest(&data) {
+            Ok(changed) => changed,
+            Err(e) => {
+                eprintln!("   [Error] bad l2Book payload: {}", e);
+                return;
+            }
+        };
+        let Some(book) = changed.iter().find(|coin| *coin == "BTC").and_then(|coin| books.book(coin)) else {
+            return;
+        };
         let count = update_count_cb.fetch_add(1, Ordering::SeqCst) + 1;
 
-        if let Some(levels) = data.get("levels").and_then(|v| v.as_array()) {
-            if levels.len() >= 2 {
-                let bids = levels[0].as_array();
-                let asks = levels[1].as_array();
-                if let (Some(bids), Some(asks)) = (bids, asks) {
-                    if let (Some(bid), Some(ask)) = (bids.first(), asks.first()) {
-                        let bid_px: f64 = bid.get("px")
-                            .and_then(|v| v.as_str())
-                            .and_then(|s| s.parse().ok())
-                            .unwrap_or(0.0);
-                        let ask_px: f64 = ask.get("px")
-                            .and_then(|v| v.as_str())
-                            .and_then(|s| s.parse().ok())
-                            .unwrap_or(0.0);
-                        let spread = ask_px - bid_px;
-                        let spread_bps = if bid_px > 0.0 { (spread / bid_px) * 10000.0 } else { 0.0 };
-
-                        // Only print first 20 updates
-                        if count <= 20 {
-                            println!("   [{}] BTC: {:.2} / {:.2} ({:.2} bps)",
-                                count, bid_px, ask_px, spread_bps);
-                        }
-                    }
-                }
+        if let (Some(bid), Some(ask), Some(spread_bps)) = (book.best_bid(), book.best_ask(), book.spread_bps()) {
+            // Only print first 20 updates
+            if count <= 20 {
+                let depth = book.depth_bps(Decimal::TEN).unwrap_or_default();
+                let vwap = book.vwap(Side::Buy, Decimal::ONE)
+                    .map(|px| format!("{:.2}", px))
+                    .unwrap_or_else(|| "-".to_string());
+                let imbalance = book.imbalance(5).unwrap_or_default();
+                println!("   [{}] BTC: {:.2} / {:.2} ({:.2} bps) depth {} / {} vwap(1) {} imb {:+.2}",
+                    count, bid.px, ask.px, spread_bps, depth.bid, depth.ask, vwap, imbalance);
             }
         }
     });
 
     // Start streaming
-    println!("\n3. Orderbook Updates (10 seconds):");
-    println!("   Format: best_bid / best_ask (spread)");
+    let duration = stream_duration(10);
+    println!("\n3. Orderbook Updates ({} seconds):", duration.as_secs());
+    println!("   Format: best_bid / best_ask (spread) depth within 10 bps, bid / ask, VWAP to buy 1 BTC, imbalance");
 
     stream.start()?;
 
-    // Run for 10 seconds
-    tokio::time::sleep(Duration::from_secs(10)).await;
+    // Run for 10 seconds (HL_STREAM_SECONDS overrides)
+    tokio::time::sleep(duration).await;
 
     stream.stop();
 
     let total = update_count.load(Ordering::SeqCst);
     println!("\n   Total updates: {}", total);
 
+    let books = books.lock().unwrap();
+    for (coin, issue) in books.issues(now_ms(), DEFAULT_STALE_AFTER) {
+        println!("   {} book: {}", coin, issue);
+    }
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
@@ -8,31 +8,23 @@
 //! cargo run --example stream_trades
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::{stream_items, Trade};
+use hyperliquid_examples::{connect, stream_duration, Requires};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint/TOKEN'");
-        eprintln!("  cargo run --example stream_trades");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("stream_trades", Requires::Endpoint).await?;
 
     println!("Trades Stream Example");
     println!("{}", "=".repeat(50));
 
-    // Create SDK
-    let sdk = HyperliquidSDK::new()
-        .endpoint(endpoint.as_ref().unwrap())
-        .build()
-        .await?;
-
     // Create stream via SDK
     println!("\n1. Creating WebSocket stream...");
 
@@ -52,31 +44,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("2. Subscribing to trades: {:?}", assets);
 
     let _sub = stream.trades(&assets, move |data| {
-        if let Some(trades) = data.get("data").and_then(|v| v.as_array()) {
-            for trade in trades {
-                let count = trade_count_cb.fetch_add(1, Ordering::SeqCst) + 1;
-                let coin = trade.get("coin").and_then(|v| v.as_str()).unwrap_or("?");
-                let side = trade.get("side").and_then(|v| v.as_str()).unwrap_or("?");
-                let side_str = if side == "B" { "BUY " } else { "SELL" };
-                let sz = trade.get("sz").and_then(|v| v.as_str()).unwrap_or("?");
-                let px = trade.get("px").and_then(|v| v.as_str()).unwrap_or("?");
-
-                // Only print first 50 trades
-                if count <= 50 {
-                    println!("   [{}] {} {} {} @ ${}", count, coin, side_str, sz, px);
+        match stream_items::<Trade>(&data, "trades") {
+            Some(Ok(trades)) => {
+                for trade in trades {
+                    let count = trade_count_cb.fetch_add(1, Ordering::SeqCst) + 1;
+                    let side_str = if trade.side.is_buy() { "BUY " } else { "SELL" };
+
+                    // Only print first 50 trades
+                    if count <= 50 {
+                        println!("   [{}] {} {} {} @ ${}", count, trade.coin, side_str, trade.sz, trade.px);
+                    }
                 }
             }
+            Some(Err(e)) => eprintln!("   [Error] bad trade payload: {}", e),
+            None => {}
         }
     });
 
     // Start streaming
-    println!("\n3. Trade Executions (60 seconds):");
+    let duration = stream_duration(60);
+    println!("\n3. Trade Executions ({} seconds):", duration.as_secs());
     println!("   Format: ASSET SIDE SIZE @ PRICE");
 
     stream.start()?;
 
-    // Run for 60 seconds
-    tokio::time::sleep(Duration::from_secs(60)).await;
+    // Run for 60 seconds (HL_STREAM_SECONDS overrides)
+    tokio::time::sleep(duration).await;
 
     stream.stop();
 
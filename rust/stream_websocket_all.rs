@@ -8,21 +8,18 @@
 //! cargo run --example stream_websocket_all
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::{connect, stream_duration, Requires};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-
-    if endpoint.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint/TOKEN'");
-        eprintln!("  cargo run --example stream_websocket_all");
-        std::process::exit(1);
-    }
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("stream_websocket_all", Requires::Endpoint).await?;
 
     println!("WebSocket All Channels Example");
     println!("{}", "=".repeat(50));
@@ -36,12 +33,6 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   - allMids: All mid prices");
     println!("   - candle: Candlestick data");
 
-    // Create SDK
-    let sdk = HyperliquidSDK::new()
-        .endpoint(endpoint.as_ref().unwrap())
-        .build()
-        .await?;
-
     // Create stream via SDK
     println!("\n2. Creating WebSocket stream...");
 
@@ -102,12 +93,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   Subscribed: allMids");
 
     // Start streaming
-    println!("\n4. Receiving messages (30 seconds):");
+    let duration = stream_duration(30);
+    println!("\n4. Receiving messages ({} seconds):", duration.as_secs());
 
     stream.start()?;
 
-    // Run for 30 seconds
-    tokio::time::sleep(Duration::from_secs(30)).await;
+    // Run for 30 seconds (HL_STREAM_SECONDS overrides)
+    tokio::time::sleep(duration).await;
 
     stream.stop();
 
@@ -0,0 +1,893 @@
+//! Runs the example binaries end to end against the mock server.
+//!
+//! Each test starts its own [`MockServer`], runs one binary with the
+//! environment pointing at it, then checks both the output and what the
+//! exchange saw. Streaming tests need port 80 on a loopback address (the SDK
+//! drops the port from WebSocket URLs) and are skipped when it cannot be bound.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::Output;
+use std::time::Duration;
+
+use hyperliquid_examples::candles::CandleStore;
+use hyperliquid_examples::export;
+use hyperliquid_examples::mock::{MatchingEngine, MockServer, Script, SeedBook};
+use hyperliquid_examples::models::{L2Book, Level, Side, Trade};
+use hyperliquid_examples::oco::{OcoManager, OrderRef};
+use hyperliquid_examples::recorder::{read_file, Channel, Manifest, Record, Recorder, Source};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+/// Anvil's first dev key (address 0xf39f...2266, the built-in mock account)
+const DEV_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Run a binary against `server` and return its output
+async fn run(server: &MockServer, bin: &str, exe: &str) -> Output {
+    run_with_args(server, bin, exe, &[]).await
+}
+
+/// [`run`] with command-line arguments
+async fn run_with_args(server: &MockServer, bin: &str, exe: &str, args: &[&str]) -> Output {
+    let output = execute(server, bin, exe, args).await;
+    assert!(
+        output.status.success(),
+        "{} failed:\n{}\n{}",
+        bin,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    output
+}
+
+/// Working directory (and `HOME`) of `bin` run against `server`
+fn work_dir(server: &MockServer, bin: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "hl-examples-{}-{}-{}",
+        std::process::id(),
+        bin,
+        server.addr().to_string().replace(['.', ':'], "-")
+    ))
+}
+
+/// Run a binary against `server`, whatever its exit status
+async fn execute(server: &MockServer, bin: &str, exe: &str, args: &[&str]) -> Output {
+    let dir = work_dir(server, bin);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let output = Command::new(exe)
+        .args(args)
+        .envs(server.env())
+        .env("PRIVATE_KEY", DEV_KEY)
+        .env("HOME", &dir)
+        .env("HL_CACHE_DIR", dir.join("cache"))
+        .env("HL_STREAM_SECONDS", "1")
+        .env_remove("HL_PROFILE")
+        .env_remove("HL_CONFIG")
+        .env_remove("TESTNET")
+        .env_remove("PAPER")
+        .env_remove("DRY_RUN")
+        .env_remove("HL_ACCOUNT")
+        .current_dir(&dir)
+        .output()
+        .await
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    output
+}
+
+/// Run `hl` with `args` and parse its `--json` output
+async fn hl_json(server: &MockServer, args: &[&str]) -> (Option<i32>, serde_json::Value) {
+    let args: Vec<&str> = args.iter().copied().chain(["--json"]).collect();
+    let output = execute(server, "hl", env!("CARGO_BIN_EXE_hl"), &args).await;
+    let json = serde_json::from_slice(&output.stdout).unwrap_or_else(|e| panic!("{}: {}", e, stdout(&output)));
+    (output.status.code(), json)
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn action_types(server: &MockServer) -> Vec<String> {
+    server
+        .actions()
+        .iter()
+        .filter_map(|a| a.get("type").and_then(|t| t.as_str()).map(str::to_string))
+        .collect()
+}
+
+/// Server on port 80 of `ip`, or `None` (test skipped) without permission to bind it
+async fn ws_server(ip: [u8; 4], script: Script) -> Option<MockServer> {
+    match MockServer::bind(SocketAddr::from((ip, 80)), script).await {
+        Ok(server) => Some(server),
+        Err(e) => {
+            eprintln!("skipping: cannot bind {:?}:80 ({})", ip, e);
+            None
+        }
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Trading
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn place_order_rests_then_cancels() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run(&server, "place_order", env!("CARGO_BIN_EXE_place_order")).await);
+
+    assert!(out.contains("BTC mid price: $96250.50"), "{}", out);
+    assert!(out.contains("Status: resting"), "{}", out);
+    assert!(out.contains("Done!"));
+
+    let actions = action_types(&server);
+    assert!(actions.contains(&"order".to_string()));
+    assert!(actions.contains(&"cancel".to_string()));
+    assert!(server.engine().open_orders().is_empty());
+}
+
+#[tokio::test]
+async fn market_order_fills_against_the_book() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run(&server, "market_order", env!("CARGO_BIN_EXE_market_order")).await);
+
+    assert!(out.contains("Status: filled"), "{}", out);
+    assert!(out.contains("@ $Some(\"96251\")"), "{}", out);
+
+    // $100 buy + 0.001 buy - 0.001 sell
+    let engine = server.engine();
+    let state = engine.clearinghouse_state();
+    assert_eq!(state.asset_positions.len(), 1);
+    assert_eq!(state.asset_positions[0].position.szi, Decimal::new(104, 5));
+    assert_eq!(engine.user_fills().len(), 3);
+}
+
+#[tokio::test]
+async fn market_order_reports_an_empty_book() {
+    let mut script = Script::default_market();
+    script.books.insert("BTC".to_string(), SeedBook::default());
+    script.mids.insert("BTC".to_string(), Decimal::from(96250));
+    let server = MockServer::start(script).await.unwrap();
+    let out = stdout(&run(&server, "market_order", env!("CARGO_BIN_EXE_market_order")).await);
+
+    assert!(out.contains("Status: error"), "{}", out);
+    assert!(server.engine().user_fills().is_empty());
+}
+
+#[tokio::test]
+async fn modify_order_sends_batch_modify() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run(&server, "modify_order", env!("CARGO_BIN_EXE_modify_order")).await);
+
+    assert!(out.contains("Done!"), "{}", out);
+    assert!(action_types(&server).contains(&"batchModify".to_string()));
+}
+
+#[tokio::test]
+async fn cancel_all_leaves_no_open_orders() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run(&server, "cancel_all", env!("CARGO_BIN_EXE_cancel_all")).await);
+
+    assert!(out.contains("Done!"), "{}", out);
+    assert!(server.engine().open_orders().is_empty());
+}
+
+#[tokio::test]
+async fn preflight_checks_tick_and_lot() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run(&server, "preflight", env!("CARGO_BIN_EXE_preflight")).await);
+
+    assert!(out.contains("Valid: true"), "{}", out);
+    assert!(out.contains("Order has invalid size."), "{}", out);
+    assert!(server.actions().is_empty());
+}
+
+#[tokio::test]
+async fn paper_market_order_never_reaches_the_exchange() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let exe = env!("CARGO_BIN_EXE_market_order");
+    let out = stdout(&run_with_args(&server, "market_order", exe, &["--paper"]).await);
+
+    // Filled against the upstream book, booked locally
+    assert!(out.contains("Paper trading: orders fill on a local simulated exchange"), "{}", out);
+    assert!(out.contains("Status: filled"), "{}", out);
+    assert!(out.contains("@ $Some(\"96251\")"), "{}", out);
+    assert!(out.contains("Paper trading summary:"), "{}", out);
+    assert!(out.contains("Fills: 3"), "{}", out);
+    assert!(out.contains("Position: BTC 0.00104 @ 96251"), "{}", out);
+
+    assert!(server.actions().is_empty());
+    assert!(server.engine().user_fills().is_empty());
+}
+
+#[tokio::test]
+async fn dry_run_market_order_never_reaches_the_exchange() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let exe = env!("CARGO_BIN_EXE_market_order");
+    let out = stdout(&run_with_args(&server, "market_order", exe, &["--dry-run"]).await);
+
+    // Built by the worker and signed, then printed and refused instead of sent
+    assert!(out.contains("Dry run (not sent): "), "{}", out);
+    assert!(out.contains("\"type\":\"order\""), "{}", out);
+    assert!(!out.contains("Status: filled"), "{}", out);
+
+    assert!(server.actions().is_empty());
+    assert!(server.engine().user_fills().is_empty());
+}
+
+#[tokio::test]
+async fn examples_refuse_an_account_they_cannot_act_for() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let exe = env!("CARGO_BIN_EXE_market_order");
+    let output = execute(&server, "market_order", exe, &["--account", "fund"]).await;
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not --account/HL_ACCOUNT 'fund'"), "{}", stderr);
+    assert!(server.actions().is_empty());
+}
+
+#[tokio::test]
+async fn dry_run_signs_withdrawals_without_sending() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run_with_args(&server, "withdraw", env!("CARGO_BIN_EXE_withdraw"), &["--dry-run"]).await);
+
+    assert!(out.contains("Dry run (not sent):"), "{}", out);
+    assert!(out.contains("\"type\":\"withdraw3\""), "{}", out);
+    assert!(out.contains("\"primaryType\": \"HyperliquidTransaction:Withdraw\""), "{}", out);
+    assert!(out.contains("Signer: 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"), "{}", out);
+    assert!(!out.contains("Warning"), "{}", out);
+    assert!(out.contains("Done!"));
+
+    assert!(server.actions().is_empty());
+}
+
+#[tokio::test]
+async fn dry_run_signs_l1_actions_as_agent() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run_with_args(&server, "leverage", env!("CARGO_BIN_EXE_leverage"), &["--dry-run"]).await);
+
+    assert!(out.contains("\"type\":\"updateLeverage\",\"asset\":0"), "{}", out);
+    assert!(out.contains("\"primaryType\": \"Agent\""), "{}", out);
+    assert!(out.contains("\"source\": \"a\""), "{}", out);
+    assert!(!out.contains("Warning"), "{}", out);
+
+    assert!(server.actions().is_empty());
+}
+
+#[tokio::test]
+async fn bracket_sizes_tpsl_to_a_partial_fill() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let exe = env!("CARGO_BIN_EXE_bracket");
+    let args = ["buy", "BTC", "1", "--px", "96251", "--tp", "100000", "--sl", "95000", "--interval", "1"];
+    let out = stdout(&run_with_args(&server, "bracket", exe, &args).await);
+
+    // 0.8 fills against the book and 0.2 rests; the TP/SL cover the 0.8
+    assert!(out.contains("Buy BTC (normalTpsl), status open"), "{}", out);
+    assert!(out.contains("filled 0.8 @ 96251"), "{}", out);
+    assert!(out.contains("take profit: 0.8 at trigger 100000, open"), "{}", out);
+    assert!(out.contains("stop loss: 0.8 at trigger 95000, open"), "{}", out);
+    assert!(out.contains("Done!"));
+
+    let placed = &server.actions()[0];
+    assert_eq!(placed["grouping"], "normalTpsl");
+    assert_eq!(placed["orders"].as_array().unwrap().len(), 3);
+    let triggers: Vec<Decimal> = server
+        .engine()
+        .open_orders()
+        .into_iter()
+        .filter(|o| o.is_trigger == Some(true))
+        .map(|o| o.sz)
+        .collect();
+    assert_eq!(triggers, [Decimal::new(8, 1), Decimal::new(8, 1)]);
+}
+
+#[tokio::test]
+async fn roundtrip_enters_with_an_attached_bracket() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run(&server, "roundtrip", env!("CARGO_BIN_EXE_roundtrip")).await);
+
+    assert!(out.contains("Filled: 0.00104 @ $Some(96251)"), "{}", out);
+    assert!(out.contains("stop loss at $Some(91438): size 0.00104"), "{}", out);
+    assert!(out.contains("TP/SL match the fill (open)"), "{}", out);
+
+    assert_eq!(server.actions()[0]["grouping"], "normalTpsl");
+    assert!(server.engine().open_orders().is_empty());
+}
+
+/// Rest a 0.01 GTC order on BTC and return its oid
+fn rest(engine: &mut MatchingEngine, is_buy: bool, px: &str) -> u64 {
+    let response = engine.execute(&json!({
+        "type": "order",
+        "orders": [{"a": 0, "b": is_buy, "p": px, "s": "0.01", "r": false, "t": {"limit": {"tif": "Gtc"}}}],
+        "grouping": "na",
+    }));
+    response["response"]["data"]["statuses"][0]["resting"]["oid"].as_u64().unwrap()
+}
+
+#[tokio::test]
+async fn oco_cancels_the_sibling_of_a_streamed_fill() {
+    // Oids are deterministic, so the fill of the buy can be scripted up front
+    let mut script = Script::default_market();
+    let mut engine = MatchingEngine::new(&script).unwrap();
+    let (buy, sell) = (rest(&mut engine, true, "96000"), rest(&mut engine, false, "96500"));
+    let fill = json!({
+        "coin": "BTC", "px": "96000.0", "sz": "0.01", "side": "B", "time": engine.now(), "startPosition": "0.0",
+        "dir": "Open Long", "closedPnl": "0.0", "hash": "0x0", "oid": buy, "crossed": false, "fee": "0.0",
+        "tid": 1, "feeToken": "USDC",
+    });
+    script.ws.insert("userFills".to_string(), vec![json!({"channel": "userFills", "data": {"user": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266", "fills": [fill]}})]);
+    let Some(server) = ws_server([127, 0, 0, 110], script).await else {
+        return;
+    };
+    {
+        let mut engine = server.engine();
+        assert_eq!((rest(&mut engine, true, "96000"), rest(&mut engine, false, "96500")), (buy, sell));
+    }
+
+    let group = format!("{},{}", buy, sell);
+    let args = ["--group", &group, "--state", "oco.json"];
+    let out = stdout(&run_with_args(&server, "oco", env!("CARGO_BIN_EXE_oco"), &args).await);
+    assert!(out.contains(&format!("oco-1 BTC: {} (open), {} (open)", buy, sell)), "{}", out);
+    assert!(out.contains(&format!("Cancel BTC {} (oco-1): done", sell)), "{}", out);
+    assert!(out.contains(&format!("oco-1 BTC: {} (filled), {} (cancelled) - done", buy, sell)), "{}", out);
+
+    assert_eq!(action_types(&server), ["cancel"]);
+    let open: Vec<u64> = server.engine().open_orders().iter().map(|o| o.oid).collect();
+    assert_eq!(open, [buy]);
+}
+
+#[tokio::test]
+async fn oco_catches_up_with_a_fill_after_a_restart() {
+    let Some(server) = ws_server([127, 0, 0, 111], Script::default_market()).await else {
+        return;
+    };
+    let (buy, sell) = {
+        let mut engine = server.engine();
+        (rest(&mut engine, true, "96000"), rest(&mut engine, false, "96500"))
+    };
+    let dir = work_dir(&server, "oco");
+    std::fs::create_dir_all(&dir).unwrap();
+    let oco = OcoManager::open(dir.join("oco.json")).unwrap();
+    oco.add("BTC", &[OrderRef::Oid(buy), OrderRef::Oid(sell)], &server.engine().open_orders()).unwrap();
+
+    // The buy fills while nothing is watching
+    {
+        let mut engine = server.engine();
+        let level = |px: &str| Level { px: px.parse().unwrap(), sz: "5".parse().unwrap(), n: 1 };
+        let time = engine.now() + 1000;
+        engine.set_book(&L2Book { coin: "BTC".to_string(), time, levels: (vec![level("95990")], vec![level("95995")]) });
+    }
+
+    let out = stdout(&run_with_args(&server, "oco", env!("CARGO_BIN_EXE_oco"), &["--state", "oco.json"]).await);
+    assert!(out.contains(&format!("oco-1 BTC: {} (open), {} (open)", buy, sell)), "{}", out);
+    assert!(out.contains(&format!("Cancel BTC {} (oco-1): done", sell)), "{}", out);
+    assert_eq!(action_types(&server), ["cancel"]);
+    assert!(server.engine().open_orders().is_empty());
+}
+
+#[tokio::test]
+async fn trailing_stop_follows_new_highs_of_the_mid() {
+    let mut script = Script::default_market();
+    let mids: Vec<_> = ["96500.0", "97000.0", "96800.0", "97400.0"]
+        .iter()
+        .map(|px| json!({"channel": "allMids", "data": {"mids": {"BTC": px}}}))
+        .collect();
+    script.ws.insert("allMids".to_string(), mids);
+    let Some(server) = ws_server([127, 0, 0, 112], script).await else {
+        return;
+    };
+    // The long the stop protects
+    let long = server.engine().execute(&json!({
+        "type": "order",
+        "orders": [{"a": 0, "b": true, "p": "96300", "s": "0.01", "r": false, "t": {"limit": {"tif": "Ioc"}}}],
+        "grouping": "na",
+    }));
+    assert!(long["response"]["data"]["statuses"][0].get("filled").is_some(), "{}", long);
+
+    let args = ["sell", "BTC", "0.01", "--trail", "1000", "--min-interval", "0"];
+    let out = stdout(&run_with_args(&server, "trailing_stop", env!("CARGO_BIN_EXE_trailing_stop"), &args).await);
+    assert!(out.contains("Sell stop 0.01 BTC at trigger 95250, trailing 1000 (open, oid"), "{}", out);
+    assert!(out.contains("Stop 95250 -> 95500 (mid 96500.0)"), "{}", out);
+    assert!(out.contains("Stop 95500 -> 96000 (mid 97000.0)"), "{}", out);
+    assert!(out.contains("Stop 96000 -> 96400 (mid 97400.0)"), "{}", out);
+    assert!(out.contains("Moved 3 time(s), best mid 97400"), "{}", out);
+
+    assert_eq!(action_types(&server), ["order", "batchModify", "batchModify", "batchModify"]);
+    let stops = server.engine().open_orders();
+    assert_eq!(stops.len(), 1);
+    assert_eq!(stops[0].trigger_px, Some(Decimal::new(96400, 0)));
+    assert_eq!(stops[0].reduce_only, Some(true));
+}
+
+#[tokio::test]
+async fn algo_works_a_twap_into_ioc_children() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+
+    let args = ["buy", "BTC", "0.4", "--duration", "2s", "--slices", "4", "--interval", "0.2"];
+    let out = stdout(&run_with_args(&server, "algo", env!("CARGO_BIN_EXE_algo"), &args).await);
+    assert!(out.contains("TWAP buy 0.4 over 2s, 4 slices, IOC children"), "{}", out);
+    assert!(out.contains("#1 Buy 0.1 @ "), "{}", out);
+    assert!(out.contains("Buy 0.2/0.4 BTC (50%), avg 96251, 2 child orders, running"), "{}", out);
+    assert!(out.contains("Schedule complete"), "{}", out);
+    assert!(out.contains("Buy 0.4/0.4 BTC (100%), avg 96251, 4 child orders, done"), "{}", out);
+
+    assert_eq!(action_types(&server), ["order"; 4]);
+    let filled: Decimal = server.engine().user_fills().iter().map(|f| f.sz).sum();
+    assert_eq!(filled, Decimal::new(4, 1));
+}
+
+#[tokio::test]
+async fn algo_native_follows_and_cancels_the_exchange_twap() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+
+    let args = ["sell", "BTC", "0.1", "--duration", "30m", "--native", "--interval", "0.2"];
+    let out = stdout(&run_with_args(&server, "algo", env!("CARGO_BIN_EXE_algo"), &args).await);
+    assert!(out.contains("Sell 0.1 BTC over 30 minutes"), "{}", out);
+    assert!(out.contains("TWAP ID: "), "{}", out);
+    assert!(out.contains("Sell 0/0.1 BTC (0%), 0 child orders, activated"), "{}", out);
+    assert!(out.contains("Sell 0/0.1 BTC (0%), 0 child orders, terminated"), "{}", out);
+
+    assert_eq!(action_types(&server), ["twapOrder", "twapCancel"]);
+}
+
+#[tokio::test]
+async fn iceberg_replenishes_each_clip_once_it_fills() {
+    let Some(server) = ws_server([127, 0, 0, 113], Script::default_market()).await else {
+        return;
+    };
+
+    let args = ["buy", "BTC", "0.003", "--px", "96000", "--clip", "0.001", "--poll", "0.1"];
+    let run = run_with_args(&server, "iceberg", env!("CARGO_BIN_EXE_iceberg"), &args);
+    tokio::pin!(run);
+    // The market sells through whichever clip rests
+    let output = loop {
+        tokio::select! {
+            output = &mut run => break output,
+            _ = tokio::time::sleep(Duration::from_millis(50)) => {
+                let mut engine = server.engine();
+                if let Some(clip) = engine.open_orders().first() {
+                    let trade = Trade {
+                        coin: "BTC".to_string(),
+                        side: Side::Sell,
+                        px: clip.limit_px - Decimal::ONE,
+                        sz: clip.sz,
+                        time: engine.now(),
+                        hash: String::new(),
+                        tid: 0,
+                        users: None,
+                    };
+                    engine.on_trade(&trade);
+                }
+            }
+        }
+    };
+    let out = stdout(&output);
+    assert!(out.contains("Buy 0.003 BTC at 96000, clips of 0.001"), "{}", out);
+    assert!(out.contains("Clip #1: 0.001 @ 96000 resting (oid"), "{}", out);
+    assert!(out.contains("Clip #3: 0.001 @ 96000 resting (oid"), "{}", out);
+    assert!(out.contains("Buy 0.002/0.003 BTC (66.7%), avg 96000, 2 child orders, working"), "{}", out);
+    assert!(out.contains("Buy 0.003/0.003 BTC (100%), avg 96000, 3 child orders, filled"), "{}", out);
+
+    assert_eq!(action_types(&server), ["order"; 3]);
+    let orders: Vec<Value> = server.actions().iter().map(|a| a["orders"][0].clone()).collect();
+    assert!(orders.iter().all(|o| o["t"] == json!({"limit": {"tif": "Alo"}}) && o["s"] == "0.001"), "{:?}", orders);
+    assert!(server.engine().open_orders().is_empty());
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// hl
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn hl_places_lists_and_cancels_by_cloid() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+
+    let (code, placed) = hl_json(&server, &["order", "buy", "BTC", "0.01", "--px", "60000.7", "--tif", "alo"]).await;
+    assert_eq!(code, Some(0), "{}", placed);
+    assert_eq!(placed["status"], "resting");
+    let oid = placed["oid"].as_u64().unwrap();
+
+    let (code, orders) = hl_json(&server, &["orders", "BTC"]).await;
+    assert_eq!(code, Some(0));
+    assert_eq!(orders[0]["oid"], oid);
+    // Rounded down onto the tick, away from the market
+    assert_eq!(orders[0]["limitPx"], "60000");
+    let cloid = orders[0]["cloid"].as_str().unwrap().to_string();
+
+    let (code, cancelled) = hl_json(&server, &["cancel", "--cloid", &cloid]).await;
+    assert_eq!(code, Some(0), "{}", cancelled);
+    assert!(server.engine().open_orders().is_empty());
+    assert_eq!(action_types(&server), ["order", "cancel"]);
+}
+
+#[tokio::test]
+async fn hl_sends_prices_on_fractional_ticks() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+
+    // ETH ticks by 0.1: rounded up, away from the market, and not to a whole number
+    let (code, placed) = hl_json(&server, &["order", "sell", "ETH", "0.5", "--px", "3700.25", "--tif", "alo"]).await;
+    assert_eq!(code, Some(0), "{}", placed);
+    assert_eq!((&placed["status"], &placed["price"]), (&json!("resting"), &json!("3700.3")));
+    assert_eq!(server.actions()[0]["orders"][0]["p"], "3700.3");
+
+    // A market order is an IOC 3% through the mid, on the tick
+    let (code, filled) = hl_json(&server, &["order", "buy", "ETH", "0.5"]).await;
+    assert_eq!(code, Some(0), "{}", filled);
+    assert_eq!(filled["status"], "filled");
+    let order = server.actions()[1]["orders"][0].clone();
+    assert_eq!((&order["p"], &order["t"]), (&json!("3720.8"), &json!({"limit": {"tif": "Ioc"}})));
+}
+
+#[tokio::test]
+async fn hl_exit_codes_tell_scripts_what_happened() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+
+    let (code, error) = hl_json(&server, &["order", "buy", "BTCX", "1"]).await;
+    assert_eq!(code, Some(2));
+    assert!(error["error"].as_str().unwrap().contains("Unknown symbol: BTCX"), "{}", error);
+
+    let (code, _) = hl_json(&server, &["cancel", "--cloid", "0x0123456789abcdef0123456789abcdef"]).await;
+    assert_eq!(code, Some(4));
+
+    let (code, book) = hl_json(&server, &["book", "BTC", "--depth", "1"]).await;
+    assert_eq!(code, Some(0));
+    assert_eq!(book["bids"].as_array().unwrap().len(), 1);
+    assert_eq!(book["asks"][0]["px"], "96251.0");
+
+    let (code, signed) = hl_json(&server, &["withdraw", "100", "--dry-run"]).await;
+    assert_eq!(code, Some(0));
+    assert_eq!(signed["action"]["type"], "withdraw3");
+    assert!(signed["mismatch"].is_null());
+    assert!(server.actions().is_empty());
+}
+
+#[tokio::test]
+async fn hl_acts_for_named_accounts_and_vaults() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let vault = "0xdfc24b077bc1425ad1dea75bcb6f8158e10df303";
+    let profile = format!(
+        "[accounts.main]\nprivate_key = \"{}\"\n\n[accounts.fund]\nvault = \"{}\"\nsigner = \"main\"\n",
+        DEV_KEY, vault
+    );
+
+    // Each call removes the working directory, so write the profile file every time
+    let hl = |args: &'static [&'static str]| {
+        let dir = work_dir(&server, "hl");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hyperliquid.toml"), &profile).unwrap();
+        hl_json(&server, args)
+    };
+
+    let (code, positions) = hl(&["positions", "--account", "all"]).await;
+    assert_eq!(code, Some(0), "{}", positions);
+    assert_eq!(positions["main"]["marginSummary"]["accountValue"], "10000.0");
+    assert!(positions["fund"].is_object(), "{}", positions);
+
+    // Signed by main's key for the vault, locally (the worker's hash has no vaultAddress)
+    let (code, signed) = hl(&["cancel", "BTC", "--oid", "1", "--account", "fund", "--dry-run"]).await;
+    assert_eq!(code, Some(0), "{}", signed);
+    assert_eq!(signed["vaultAddress"], vault);
+    assert_eq!(signed["signer"], "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+    assert!(server.actions().is_empty());
+
+    let (code, placed) = hl(&["order", "buy", "BTC", "0.01", "--px", "60000", "--account", "fund"]).await;
+    assert_eq!(code, Some(0), "{}", placed);
+    assert!(placed.pointer("/response/data/statuses/0/resting/oid").is_some(), "{}", placed);
+    assert_eq!(action_types(&server), ["order"]);
+
+    // ETH ticks by 0.1: the vault's limit and market prices stay on it
+    let (code, placed) = hl(&["order", "sell", "ETH", "0.5", "--px", "3700.25", "--tif", "alo", "--account", "fund"]).await;
+    assert_eq!(code, Some(0), "{}", placed);
+    let (code, filled) = hl(&["order", "sell", "ETH", "0.5", "--account", "fund"]).await;
+    assert_eq!(code, Some(0), "{}", filled);
+    let prices: Vec<Value> = server.actions()[1..].iter().map(|a| a["orders"][0]["p"].clone()).collect();
+    assert_eq!(prices, [json!("3700.3"), json!("3504.1")]);
+
+    let (code, _) = hl(&["withdraw", "1", "--account", "fund"]).await;
+    assert_eq!(code, Some(2));
+
+    // Orders and cancels run for each selected account in turn
+    let (code, placed) = hl(&["order", "buy", "BTC", "0.01", "--px", "60000", "--account", "main,fund"]).await;
+    assert_eq!(code, Some(0), "{}", placed);
+    assert_eq!(placed["main"]["status"], "resting", "{}", placed);
+    assert!(placed["fund"].pointer("/response/data/statuses/0/resting/oid").is_some(), "{}", placed);
+    assert_eq!(server.actions().len(), 5);
+
+    // Leverage and withdrawals act for one account, and paper trading only for its own
+    let (code, error) = hl(&["withdraw", "1", "--account", "main,fund"]).await;
+    assert_eq!(code, Some(2), "{}", error);
+    assert!(error["error"].as_str().unwrap().contains("--account selects 2"), "{}", error);
+    let (code, error) = hl(&["order", "buy", "BTC", "0.01", "--px", "60000", "--account", "fund", "--paper"]).await;
+    assert_eq!(code, Some(2), "{}", error);
+    assert!(error["error"].as_str().unwrap().contains("cannot act for vault 'fund'"), "{}", error);
+    assert_eq!(server.actions().len(), 5);
+    let (code, _) = hl(&["orders", "--account", "nobody"]).await;
+    assert_eq!(code, Some(3));
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Info, HyperCore, EVM
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn open_orders_queries_order_status() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run(&server, "open_orders", env!("CARGO_BIN_EXE_open_orders")).await);
+
+    assert!(out.contains("No open orders"), "{}", out);
+    assert!(out.contains("unknownOid"), "{}", out);
+}
+
+#[tokio::test]
+async fn markets_lists_the_scripted_registry() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run(&server, "markets", env!("CARGO_BIN_EXE_markets")).await);
+
+    assert!(out.contains("BTC (asset 0)"), "{}", out);
+    assert!(out.contains("xyz (1 markets)"), "{}", out);
+}
+
+#[tokio::test]
+async fn hypercore_blocks_reads_scripted_rpc() {
+    let mut script = Script::default_market();
+    script
+        .hypercore
+        .insert("hl_getLatestBlockNumber".to_string(), 42.into());
+    let server = MockServer::start(script).await.unwrap();
+    let out = stdout(&run(&server, "hypercore_blocks", env!("CARGO_BIN_EXE_hypercore_blocks")).await);
+
+    assert!(out.contains("Height: 42"), "{}", out);
+}
+
+#[tokio::test]
+async fn export_writes_candles_and_trades_to_parquet() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let dir = std::env::temp_dir().join(format!("hl-export-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let read = |name: &str| {
+        let file = std::fs::File::open(dir.join(name)).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap().build().unwrap();
+        reader.map(|batch| batch.unwrap()).collect::<Vec<_>>()
+    };
+
+    let file = dir.join("btc.parquet");
+    let args = ["candles", "BTC", "1h", "--since", "1737400000000", "--until", "1737420000000", "--out", file.to_str().unwrap()];
+    let out = stdout(&run_with_args(&server, "export", env!("CARGO_BIN_EXE_export"), &args).await);
+    assert!(out.contains("2 candles"), "{}", out);
+    assert!(out.contains("close            Decimal128(38, 10)"), "{}", out);
+    let batches = read("btc.parquet");
+    assert_eq!(batches[0].schema(), export::candle_schema());
+    assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+
+    let file = dir.join("trades.parquet");
+    let args = ["trades", "--coin", "BTC", "--blocks", "5", "--out", file.to_str().unwrap()];
+    let out = stdout(&run_with_args(&server, "export", env!("CARGO_BIN_EXE_export"), &args).await);
+    assert!(out.contains("1 trade sides"), "{}", out);
+    let batches = read("trades.parquet");
+    assert_eq!(batches[0].schema(), export::fill_schema());
+    assert_eq!(batches[0].num_rows(), 1);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn candle_backfill_stores_history_in_sqlite() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let db = std::env::temp_dir().join(format!("hl-candles-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&db);
+    let args = ["BTC", "1h", "--since", "1737400000000", "--until", "1737414000000", "--db", db.to_str().unwrap()];
+    let out = stdout(&run_with_args(&server, "candle_backfill", env!("CARGO_BIN_EXE_candle_backfill"), &args).await);
+    assert!(out.contains("BTC 1h: 2 candles in 1 requests, back to 1737406800000"), "{}", out);
+    assert!(out.contains("BTC 1h: 0 found, 0 refilled, 0 bars still missing"), "{}", out);
+
+    // A second run resumes from the stored candles
+    let out = stdout(&run_with_args(&server, "candle_backfill", env!("CARGO_BIN_EXE_candle_backfill"), &args).await);
+    assert!(out.contains("BTC 1h: 2 candles (1737406800000 - 1737410400000)"), "{}", out);
+
+    let store = CandleStore::open(&db).unwrap();
+    let candles = store.candles("BTC", "1h", 0, u64::MAX).unwrap();
+    assert_eq!(candles.len(), 2);
+    assert_eq!(candles[1].close.to_string(), "96250.0");
+    drop(store);
+    std::fs::remove_file(db).unwrap();
+}
+
+#[tokio::test]
+async fn account_exporter_polls_the_signer_account() {
+    let mut script = Script::default_market();
+    script.info.insert("userVaultEquities".to_string(), json!([{"vaultAddress": "0xdfc24b077bc1425ad1dea75bcb6f8158e10df303", "equity": "250.5"}]));
+    script.info.insert("delegations".to_string(), json!([]));
+    let server = MockServer::start(script).await.unwrap();
+    let out = stdout(&run_with_args(&server, "account_exporter", env!("CARGO_BIN_EXE_account_exporter"), &["--listen", "127.0.0.1:0"]).await);
+    assert!(out.contains("Metrics at http://127.0.0.1:"), "{}", out);
+    assert!(out.contains("Polling every 15s:"), "{}", out);
+    assert!(out.contains("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266: value $10000.0, leverage 0x, 0 positions, 0 open orders"), "{}", out);
+}
+
+#[tokio::test]
+async fn evm_basics_reads_chain_info() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let out = stdout(&run(&server, "evm_basics", env!("CARGO_BIN_EXE_evm_basics")).await);
+
+    assert!(out.contains("Chain ID: 999"), "{}", out);
+    assert!(out.contains("Nonce: 5"), "{}", out);
+}
+
+// ══════════════════════════════════════════════════════════════════════════════
+// Streams
+// ══════════════════════════════════════════════════════════════════════════════
+
+#[tokio::test]
+async fn stream_trades_replays_scripted_messages() {
+    let Some(server) = ws_server([127, 0, 0, 101], Script::default_market()).await else {
+        return;
+    };
+    let out = stdout(&run(&server, "stream_trades", env!("CARGO_BIN_EXE_stream_trades")).await);
+
+    assert!(out.contains("[1] BTC BUY  0.01 @ $96251.0"), "{}", out);
+    assert!(out.contains("[2] ETH SELL 0.5 @ $3612.4"), "{}", out);
+    assert!(out.contains("Total trades: 2"), "{}", out);
+}
+
+#[tokio::test]
+async fn stream_l2_book_sends_engine_snapshots() {
+    let Some(server) = ws_server([127, 0, 0, 102], Script::default_market()).await else {
+        return;
+    };
+    let out = stdout(&run(&server, "stream_l2_book", env!("CARGO_BIN_EXE_stream_l2_book")).await);
+
+    assert!(out.contains("BTC: bid=96250.0 (1.2345) / ask=96251.0 (0.8)"), "{}", out);
+    assert!(out.contains("SOL: bid=187.23 / ask=187.24"), "{}", out);
+}
+
+#[tokio::test]
+async fn stream_health_measures_feeds_and_flags_stale_ones() {
+    let Some(server) = ws_server([127, 0, 0, 109], Script::default_market()).await else {
+        return;
+    };
+    let args = ["BTC,ETH", "--listen", "127.0.0.1:0", "--stale", "0"];
+    let out = stdout(&run_with_args(&server, "stream_health", env!("CARGO_BIN_EXE_stream_health"), &args).await);
+    assert!(out.contains("Metrics at http://127.0.0.1:"), "{}", out);
+    assert!(out.contains("Streaming trades and book updates of BTC, ETH (ws)"), "{}", out);
+    assert!(out.contains("[Alert] ws book_updates BTC stale: no message for"), "{}", out);
+    assert!(out.contains("ws trades BTC: 1 messages, latency avg"), "{}", out);
+    assert!(out.contains("ws trades ETH: 1 messages"), "{}", out);
+    assert!(out.contains("ws book_updates ETH: 0 messages, latency avg - max -, max gap -, last never"), "{}", out);
+    assert!(out.contains("ws: 0 reconnects, 0 errors"), "{}", out);
+}
+
+#[tokio::test]
+async fn recorder_writes_compressed_records_and_a_manifest() {
+    let Some(server) = ws_server([127, 0, 0, 104], Script::default_market()).await else {
+        return;
+    };
+    let out = std::env::temp_dir().join(format!("hl-recorder-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&out);
+    let args = ["--channels", "trades,l2_book", "--coins", "BTC", "--out", out.to_str().unwrap()];
+    let stdout = stdout(&run_with_args(&server, "recorder", env!("CARGO_BIN_EXE_recorder"), &args).await);
+    assert!(stdout.contains("trades BTC (WebSocket)"), "{}", stdout);
+
+    let manifest = Manifest::load(&out).unwrap();
+    assert_eq!(manifest.channels, [Channel::Trades, Channel::L2Book]);
+    assert_eq!(manifest.coins, ["BTC"]);
+    assert!(manifest.files.iter().all(|f| f.complete && f.file.ends_with(".ndjson.gz")), "{:?}", manifest);
+
+    let records: Vec<Record> = manifest.paths(&out).iter().flat_map(|p| read_file(p).unwrap()).collect();
+    let seqs: Vec<u64> = records.iter().map(|r| r.seq).collect();
+    assert_eq!(seqs, (1..=records.len() as u64).collect::<Vec<_>>());
+    let trades: Vec<&Record> = records.iter().filter(|r| r.channel == Channel::Trades).collect();
+    assert!(trades.iter().any(|r| r.data["data"][0]["px"] == "96251.0"), "{:?}", trades);
+    assert!(records.iter().any(|r| r.channel == Channel::L2Book && r.coin.as_deref() == Some("BTC")));
+    assert_eq!(manifest.files.iter().map(|f| f.messages).sum::<u64>(), records.len() as u64);
+
+    std::fs::remove_dir_all(out).unwrap();
+}
+
+#[tokio::test]
+async fn replay_feeds_a_recording_through_the_stream_callbacks() {
+    let Some(server) = ws_server([127, 0, 0, 105], Script::default_market()).await else {
+        return;
+    };
+    let out = std::env::temp_dir().join(format!("hl-replay-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&out);
+    let args = ["--channels", "trades,l2_book", "--coins", "BTC", "--out", out.to_str().unwrap()];
+    run_with_args(&server, "recorder", env!("CARGO_BIN_EXE_recorder"), &args).await;
+
+    let args = [out.to_str().unwrap(), "--speed", "max"];
+    let stdout = stdout(&run_with_args(&server, "replay", env!("CARGO_BIN_EXE_replay"), &args).await);
+    assert!(stdout.contains("Channels: trades, l2_book"), "{}", stdout);
+    assert!(stdout.contains("Replaying at max"), "{}", stdout);
+    assert!(stdout.contains("[1] BTC BUY  0.01 @ $96251.0"), "{}", stdout);
+    assert!(stdout.contains("BTC book: 96250.0 / 96251.0"), "{}", stdout);
+
+    std::fs::remove_dir_all(out).unwrap();
+}
+
+#[tokio::test]
+async fn bars_builds_tick_bars_from_the_trades_stream() {
+    let Some(server) = ws_server([127, 0, 0, 107], Script::default_market()).await else {
+        return;
+    };
+    let args = ["BTC,ETH", "--bar", "tick:1", "--bar", "1m"];
+    let out = stdout(&run_with_args(&server, "bars", env!("CARGO_BIN_EXE_bars"), &args).await);
+    assert!(out.contains("Building tick:1, 1m bars of BTC, ETH (WebSocket)"), "{}", out);
+    assert!(out.contains("[tick:1] BTC t="), "{}", out);
+    assert!(out.contains("O=96251.0 H=96251.0 L=96251.0 C=96251.0 V=0.01 VWAP=96251.0 buy=0.01 sell=0 n=1"), "{}", out);
+    assert!(out.contains("buy=0 sell=0.5 n=1"), "{}", out);
+    assert!(out.contains("tick:1: 2 closed, 0 still open"), "{}", out);
+}
+
+#[tokio::test]
+async fn bars_resamples_a_recording_in_batch() {
+    let server = MockServer::start(Script::default_market()).await.unwrap();
+    let dir = std::env::temp_dir().join(format!("hl-bars-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut recorder = Recorder::create(&dir, &[Channel::Trades], &["BTC".to_string()]).unwrap();
+    for (i, (side, px, sz)) in [("B", "100.0", "0.4"), ("A", "101.0", "0.4"), ("B", "102.0", "0.4"), ("B", "99.0", "1.0")].iter().enumerate() {
+        let trade = json!({"coin": "BTC", "side": side, "px": px, "sz": sz, "time": 1737406800000u64 + i as u64 * 20_000, "hash": "0x0", "tid": i + 1});
+        recorder.record(Channel::Trades, Source::Ws, Some("BTC"), json!({"channel": "trades", "data": [trade]})).unwrap();
+    }
+    recorder.close().unwrap();
+
+    let args = ["--from", dir.to_str().unwrap(), "--bar", "volume:1", "--bar", "1m"];
+    let out = stdout(&run_with_args(&server, "bars", env!("CARGO_BIN_EXE_bars"), &args).await);
+    assert!(out.contains("4 trades of BTC"), "{}", out);
+    assert!(out.contains("volume:1: 2 closed bars"), "{}", out);
+    assert!(out.contains("[volume:1] BTC t=1737406800000 O=100.0 H=102.0 L=100.0 C=102.0 V=1.2 VWAP=101.0 buy=0.8 sell=0.4 n=3"), "{}", out);
+    assert!(out.contains("1m: 1 closed bars"), "{}", out);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn resilient_feed_fails_over_to_the_websocket() {
+    // The mock serves no gRPC, so the primary never comes up
+    let Some(server) = ws_server([127, 0, 0, 108], Script::default_market()).await else {
+        return;
+    };
+    let out = stdout(&run_with_args(&server, "resilient_feed", env!("CARGO_BIN_EXE_resilient_feed"), &["BTC,ETH"]).await);
+    assert!(out.contains("Streaming BTC, ETH (primary grpc, stall after 10s)"), "{}", out);
+    assert!(out.contains("[Switch] grpc -> ws"), "{}", out);
+    assert!(out.contains("[ws] BTC BUY 0.01 @ 96251.0"), "{}", out);
+    assert!(out.contains("[ws] ETH SELL 0.5 @ 3612.4"), "{}", out);
+    assert!(out.contains("Delivered: 0 over gRPC, 2 over WebSocket"), "{}", out);
+    assert!(out.contains("Active transport: ws"), "{}", out);
+}
+
+#[tokio::test]
+async fn candle_backfill_follows_the_open_candle() {
+    let mut script = Script::default_market();
+    let open = json!({"channel": "candle", "data": {
+        "t": 4102444800000u64, "T": 4102448399999u64, "s": "BTC", "i": "1h",
+        "o": "96250.0", "h": "96300.0", "l": "96200.0", "c": "96280.0", "v": "12.5", "n": 310
+    }});
+    script.ws.insert("candle".to_string(), vec![open]);
+    let Some(server) = ws_server([127, 0, 0, 106], script).await else {
+        return;
+    };
+    let db = std::env::temp_dir().join(format!("hl-candles-follow-{}.db", std::process::id()));
+    let _ = std::fs::remove_file(&db);
+    let args = ["BTC", "1h", "--since", "1737400000000", "--until", "1737414000000", "--db", db.to_str().unwrap(), "--follow"];
+    let out = stdout(&run_with_args(&server, "candle_backfill", env!("CARGO_BIN_EXE_candle_backfill"), &args).await);
+    assert!(out.contains("BTC 1h t=4102444800000 O=96250.0 H=96300.0 L=96200.0 C=96280.0"), "{}", out);
+    assert!(out.contains("BTC 1h open candle t=4102444800000 C=96280.0"), "{}", out);
+
+    let store = CandleStore::open(&db).unwrap();
+    assert_eq!(store.candles("BTC", "1h", 0, u64::MAX).unwrap().len(), 3);
+    drop(store);
+    std::fs::remove_file(db).unwrap();
+}
+
+#[tokio::test]
+async fn stream_orderbook_queries_the_local_book() {
+    let Some(server) = ws_server([127, 0, 0, 103], Script::default_market()).await else {
+        return;
+    };
+    let out = stdout(&run(&server, "stream_orderbook", env!("CARGO_BIN_EXE_stream_orderbook")).await);
+
+    assert!(out.contains("[1] BTC: 96250.00 / 96251.00 (0.10 bps)"), "{}", out);
+    assert!(out.contains("depth 4.7445 / 7.8 vwap(1) 96251.20"), "{}", out);
+}
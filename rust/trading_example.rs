@@ -7,49 +7,38 @@
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
 //! cargo run --example trading_example
+//! cargo run --example trading_example -- --paper   # simulated fills, nothing sent
 //! ```
 
-use hyperliquid_sdk::{HyperliquidSDK, Order};
+use hyperliquid_examples::{paper, AssetRegistry, Config, Requires};
+use hyperliquid_sdk::Order;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Get endpoint and private key from environment
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
-
-    if endpoint.is_none() {
-        eprintln!("Error: Set ENDPOINT environment variable");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        std::process::exit(1);
-    }
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if private_key.is_none() {
-        eprintln!("Error: Set PRIVATE_KEY environment variable");
-        eprintln!("  export PRIVATE_KEY='0xYourPrivateKey'");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("trading_example", Requires::Signer);
 
     println!("Hyperliquid Trading Example");
     println!("{}", "=".repeat(50));
 
     // Initialize SDK with QuickNode endpoint and private key
     // All requests route through QuickNode - never directly to Hyperliquid
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
+    let sdk = config.connect().await?;
 
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
-    if let Some(ep) = &endpoint {
-        let display_len = ep.len().min(50);
-        println!("Endpoint: {}...", &ep[..display_len]);
+
+    // Check the symbols exist before sending anything
+    let registry = AssetRegistry::load(&sdk).await?;
+    for symbol in ["BTC", "ETH"] {
+        registry.resolve(symbol)?;
     }
+    println!("Endpoint: {}", config.redacted_endpoint());
     println!();
 
     // ══════════════════════════════════════════════════════════════════════════
@@ -163,6 +152,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    paper::report();
+
     println!();
     println!("{}", "=".repeat(50));
     println!("Done!");
@@ -0,0 +1,169 @@
+//! Trailing Stop Example
+//!
+//! Protect a position with a stop-loss that follows the market
+//! (`hyperliquid_examples::trailing`): placed a trail behind the mid, then
+//! moved along (a `batchModify` of the trigger order) as the mid from
+//! `allMids` or the coin's `l2Book` makes new highs, or new lows for a buy
+//! stop covering a short.
+//!
+//! # Usage
+//! ```bash
+//! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
+//! export PRIVATE_KEY="0x..."
+//! cargo run --bin trailing_stop -- sell BTC 0.001 --trail 2%              # closes a long
+//! cargo run --bin trailing_stop -- buy ETH 0.1 --trail 50 --step 10      # covers a short
+//! cargo run --bin trailing_stop -- sell BTC 0.001 --trail 1.5% --source book --min-interval 5
+//! cargo run --bin trailing_stop -- sell BTC 0.001 --trail 2% --dry-run   # sign and print, nothing sent
+//! ```
+
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use hyperliquid_examples::cli::OrderSide;
+use hyperliquid_examples::dry_run;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::trailing::{MidSource, StopStatus, Trail, TrailingStop, TrailingStopOrder};
+use hyperliquid_examples::{paper, stream_duration, AssetRegistry, Config, Requires};
+use hyperliquid_sdk::HyperliquidSDK;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Parser)]
+#[command(name = "trailing_stop", about = "Keep a stop-loss a trail behind the mid")]
+struct Args {
+    /// `sell` closes a long, `buy` covers a short
+    side: OrderSide,
+    /// Perp symbol, e.g. BTC, xyz:XYZ100
+    coin: String,
+    /// Size in the base asset (rounded down to the lot)
+    size: Decimal,
+    /// Distance behind the mid: a price (150) or a percentage (2%)
+    #[arg(long)]
+    trail: Trail,
+    /// Move the stop only by at least this much: a price or a percentage
+    #[arg(long, default_value = "0")]
+    step: Trail,
+    /// Seconds between two moves at least
+    #[arg(long, default_value_t = 2.0)]
+    min_interval: f64,
+    /// Where the mid comes from
+    #[arg(long, value_parser = ["mids", "book"], default_value = "mids")]
+    source: String,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
+
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("trailing_stop", Requires::Signer);
+    let args = Args::parse_from(std::iter::once("trailing_stop".to_string()).chain(config.args.clone()));
+    let sdk = config.connect().await?;
+
+    println!("Trailing Stop Example");
+    println!("{}", "=".repeat(50));
+
+    let user = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
+    println!("Address: {}", user);
+
+    let stop = match args.side {
+        OrderSide::Buy => TrailingStop::buy(&args.coin, args.size, args.trail),
+        OrderSide::Sell => TrailingStop::sell(&args.coin, args.size, args.trail),
+    }
+    .step(args.step)
+    .min_interval(Duration::from_secs_f64(args.min_interval.max(0.0)));
+
+    println!("\n1. Placing:");
+    if config.dry_run {
+        let registry = AssetRegistry::load(&sdk).await?;
+        let market = registry.resolve(&args.coin)?;
+        let order = stop.build(market, TypedInfo::from_sdk(&sdk).mid(&market.symbol).await?)?;
+        print_stop(&order);
+        dry_run::show(&config, &order.action()).await;
+    } else {
+        match stop.place(&config, &sdk).await {
+            Ok(order) => follow(&config, &sdk, &user, args.source.parse()?, order).await?,
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
+
+    paper::report();
+
+    println!("\n{}", "=".repeat(50));
+    println!("Done!");
+
+    Ok(())
+}
+
+/// Move `order` along with the mid until it fires, or until Ctrl-C
+async fn follow(
+    config: &Config,
+    sdk: &HyperliquidSDK,
+    user: &str,
+    source: MidSource,
+    mut order: TrailingStopOrder,
+) -> Result<(), Box<dyn std::error::Error>> {
+    print_stop(&order);
+
+    println!(
+        "\n2. Following the {} mid (step {}, at most every {:?}):",
+        source, order.step, order.min_interval
+    );
+    let mut stream = sdk.stream().on_error(|e| eprintln!("   [Error] {}", e));
+    let mut events = order.watch(&mut stream, user, source);
+    stream.start()?;
+
+    let deadline = async {
+        // Until Ctrl-C, or for HL_STREAM_SECONDS
+        match std::env::var_os("HL_STREAM_SECONDS") {
+            Some(_) => tokio::time::sleep(stream_duration(0)).await,
+            None => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    };
+    tokio::pin!(deadline);
+    while order.status == StopStatus::Open {
+        tokio::select! {
+            _ = &mut deadline => break,
+            event = events.recv() => {
+                let event = match event {
+                    Some(Ok(event)) => event,
+                    Some(Err(e)) => {
+                        println!("   Error: {}", e);
+                        continue;
+                    }
+                    None => break,
+                };
+                if let Some(step) = order.on_event(&event, Instant::now()) {
+                    match order.move_stop(config, &step).await {
+                        Ok(()) => println!("   {}", step),
+                        Err(e) => println!("   Error: {}", e),
+                    }
+                }
+            }
+        }
+    }
+    stream.stop();
+
+    println!("\n3. Final state:");
+    print_stop(&order);
+    println!("   Moved {} time(s), best mid {}", order.moves, order.extreme);
+    Ok(())
+}
+
+fn print_stop(order: &TrailingStopOrder) {
+    println!(
+        "   {} stop {} {} at trigger {}, trailing {} ({})",
+        if order.is_buy { "Buy" } else { "Sell" },
+        order.size,
+        order.coin,
+        order.stop_px,
+        order.trail,
+        match order.oid {
+            Some(oid) => format!("{}, oid {}", order.status, oid),
+            None => "not placed".to_string(),
+        }
+    );
+}
@@ -7,54 +7,50 @@
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
 //! cargo run --example trigger_orders
+//! cargo run --example trigger_orders -- --paper   # simulated fills, nothing sent
 //! ```
 
-use hyperliquid_sdk::{HyperliquidSDK, TriggerOrder};
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{connect, paper, AssetRegistry, Requires, Rounding};
+use hyperliquid_sdk::TriggerOrder;
+use rust_decimal::Decimal;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
-
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example trigger_orders");
-        std::process::exit(1);
-    }
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let sdk = connect("trigger_orders", Requires::Signer).await?;
 
     println!("Trigger Orders Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
-    // Get current price
-    let mid = sdk.get_mid("BTC").await?;
+    // Validate BTC and get its tick/lot limits, then the current price
+    let btc = AssetRegistry::load(&sdk).await?.precision("BTC")?;
+    let mid = TypedInfo::from_sdk(&sdk).mid("BTC").await?;
+    let size = btc.sz(Decimal::new(1, 3), Rounding::Down)?;
     println!("\nBTC mid price: ${:.2}", mid);
 
+    // Percentage offsets from the mid, rounded onto BTC's tick grid
+    let pct = |p: i64| btc.px(mid * Decimal::new(p, 2), Rounding::Nearest);
+
     // Stop Loss - triggers sell when price drops below trigger
     println!("\n1. Stop Loss Order:");
     let stop_loss = TriggerOrder::stop_loss("BTC")
-        .size(0.001)
-        .trigger_price(mid * 0.95);  // 5% below current price
+        .size(size.to_f64())
+        .trigger_price(pct(95)?.to_f64());  // 5% below current price
 
     match sdk.trigger_order(stop_loss).await {
         Ok(order) => {
             println!("   Status: {}", order.status);
             println!("   OID: {:?}", order.oid);
-            println!("   Trigger: ${:.2}", mid * 0.95);
+            println!("   Trigger: ${}", pct(95)?);
         }
         Err(e) => println!("   Error: {}", e),
     }
@@ -62,14 +58,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Take Profit - triggers sell when price rises above trigger
     println!("\n2. Take Profit Order:");
     let take_profit = TriggerOrder::take_profit("BTC")
-        .size(0.001)
-        .trigger_price(mid * 1.10);  // 10% above current price
+        .size(size.to_f64())
+        .trigger_price(pct(110)?.to_f64());  // 10% above current price
 
     match sdk.trigger_order(take_profit).await {
         Ok(order) => {
             println!("   Status: {}", order.status);
             println!("   OID: {:?}", order.oid);
-            println!("   Trigger: ${:.2}", mid * 1.10);
+            println!("   Trigger: ${}", pct(110)?);
         }
         Err(e) => println!("   Error: {}", e),
     }
@@ -77,9 +73,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Stop Loss with limit price
     println!("\n3. Stop Loss with Limit:");
     let stop_loss_limit = TriggerOrder::stop_loss("BTC")
-        .size(0.001)
-        .trigger_price(mid * 0.95)
-        .limit(mid * 0.94);  // Limit price below trigger
+        .size(size.to_f64())
+        .trigger_price(pct(95)?.to_f64())
+        .limit(btc.px_away_from(mid * Decimal::new(94, 2), mid)?.to_f64());  // Limit price below trigger
 
     match sdk.trigger_order(stop_loss_limit).await {
         Ok(order) => {
@@ -91,7 +87,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Using sdk convenience methods
     println!("\n4. Stop Loss (convenience method):");
-    match sdk.stop_loss("BTC", 0.001, mid * 0.93).await {
+    match sdk.stop_loss("BTC", size.to_f64(), pct(93)?.to_f64()).await {
         Ok(order) => {
             println!("   Status: {}", order.status);
             println!("   OID: {:?}", order.oid);
@@ -100,7 +96,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     println!("\n5. Take Profit (convenience method):");
-    match sdk.take_profit("BTC", 0.001, mid * 1.15).await {
+    match sdk.take_profit("BTC", size.to_f64(), pct(115)?.to_f64()).await {
         Ok(order) => {
             println!("   Status: {}", order.status);
             println!("   OID: {:?}", order.oid);
@@ -115,6 +111,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Err(e) => println!("   Error: {}", e),
     }
 
+    paper::report();
+
     println!("\n{}", "=".repeat(50));
     println!("Done!");
 
@@ -1,6 +1,8 @@
 //! TWAP Order Example
 //!
-//! Time-Weighted Average Price orders for large executions.
+//! Time-Weighted Average Price orders for large executions, run by the
+//! exchange: placed, followed and cancelled (`hyperliquid_examples::execution`).
+//! `algo` works an order over time from the client instead.
 //!
 //! # Usage
 //! ```bash
@@ -9,40 +11,35 @@
 //! cargo run --example twap
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::execution::NativeTwap;
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{AssetRegistry, Config, Requires};
+use rust_decimal::Decimal;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example twap");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("twap", Requires::Signer);
+    let sdk = config.connect().await?;
 
     println!("TWAP Order Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
+    let user = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
+    println!("Address: {}", user);
 
-    if let Some(addr) = sdk.address() {
-        println!("Address: {}", addr);
-    }
+    // Check the symbol exists before sending anything
+    let registry = AssetRegistry::load(&sdk).await?;
+    let market = registry.resolve("BTC")?;
+    let info = TypedInfo::from_sdk(&sdk);
 
     // Get current price
-    let mid = sdk.get_mid("BTC").await?;
-    println!("\nBTC mid price: ${:.2}", mid);
+    let mid = info.mid("BTC").await?;
+    println!("\nBTC mid price: ${}", mid);
 
     // TWAP Buy Order
     println!("\n1. TWAP Buy Order:");
@@ -50,33 +47,29 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   Duration: 60 minutes");
     println!("   Randomize: true");
 
-    match sdk.twap_order("BTC", 0.1, true, 60, true, false).await {
-        Ok(result) => {
-            println!("   Result: {:?}", result);
-            if let Some(twap_id) = result.get("twapId").and_then(|v| v.as_i64()) {
-                println!("   TWAP ID: {}", twap_id);
-            }
-        }
+    let mut twap = NativeTwap::new(market, true, Decimal::new(1, 1), 60, true)?;
+    match twap.place(&config).await {
+        Ok(twap_id) => println!("   TWAP ID: {}", twap_id),
         Err(e) => println!("   Error: {}", e),
     }
 
-    // TWAP Sell Order
-    println!("\n2. TWAP Sell Order:");
-    println!("   Size: 0.1 BTC");
-    println!("   Duration: 120 minutes");
-
-    // match sdk.twap_order("BTC", false, 0.1, 120, true, false).await {
-    //     Ok(result) => println!("   Result: {:?}", result),
-    //     Err(e) => println!("   Error: {}", e),
-    // }
+    // Progress, as userTwapHistory and userTwapSliceFills report it
+    println!("\n2. Progress:");
+    match twap.progress(&info, &user).await {
+        Ok(progress) => println!("   {}", progress),
+        Err(e) => println!("   Error: {}", e),
+    }
 
     // Cancel TWAP
     println!("\n3. Cancel TWAP:");
-    println!("   sdk.twap_cancel(asset, twap_id)");
-    // match sdk.twap_cancel("BTC", 123456).await {
-    //     Ok(result) => println!("   Result: {:?}", result),
-    //     Err(e) => println!("   Error: {}", e),
-    // }
+    match twap.cancel(&config).await {
+        Ok(()) => println!("   Cancelled"),
+        Err(e) => println!("   Error: {}", e),
+    }
+    match twap.progress(&info, &user).await {
+        Ok(progress) => println!("   {}", progress),
+        Err(e) => println!("   Error: {}", e),
+    }
 
     println!("\n{}", "-".repeat(50));
     println!("TWAP Benefits:");
@@ -6,54 +6,40 @@
 //! ```bash
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
+//! cargo run --example vaults -- --dry-run   # sign and print, nothing sent
 //! cargo run --example vaults
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::dry_run::{self, actions};
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{Config, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example vaults");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("vaults", Requires::Signer);
+    let sdk = config.connect().await?;
 
     println!("Vaults Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
     // List vaults
     println!("\n1. Available Vaults:");
-    let info = sdk.info();
+    let info = TypedInfo::from_sdk(&sdk);
     match info.vault_summaries().await {
         Ok(vaults) => {
-            if let Some(arr) = vaults.as_array() {
-                println!("   Total: {} vaults", arr.len());
-                for (i, vault) in arr.iter().take(5).enumerate() {
-                    let name = vault.get("name").and_then(|v| v.as_str()).unwrap_or("?");
-                    let tvl = vault.get("tvl").and_then(|v| v.as_str()).unwrap_or("?");
-                    let addr = vault.get("vaultAddress").and_then(|v| v.as_str()).unwrap_or("?");
-                    println!("   [{}] {} (TVL: ${})", i + 1, name, tvl);
-                    println!("       {}", addr);
-                }
+            println!("   Total: {} vaults", vaults.len());
+            for (i, vault) in vaults.iter().take(5).enumerate() {
+                println!("   [{}] {} (TVL: ${:.2})", i + 1, vault.name, vault.tvl);
+                println!("       {}", vault.vault_address);
             }
         }
         Err(e) => println!("   Error: {}", e),
@@ -62,32 +48,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Vault deposit
     println!("\n2. Vault Deposit:");
     println!("   sdk.vault_deposit(vault_address, amount)");
-    // let vault_addr = "0xVaultAddress";
-    // match sdk.vault_deposit(vault_addr, 100.0).await {
-    //     Ok(result) => println!("   Result: {:?}", result),
-    //     Err(e) => println!("   Error: {}", e),
-    // }
+    let vault_addr = "0xVaultAddress";
+    if config.dry_run {
+        dry_run::show(&config, &actions::vault_transfer(vault_addr, true, 100.0)).await;
+    } else {
+        match sdk.vault_deposit(vault_addr, 100.0).await {
+            Ok(result) => println!("   Result: {:?}", result),
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
 
     // Vault withdraw
     println!("\n3. Vault Withdraw:");
     println!("   sdk.vault_withdraw(vault_address, shares)");
-    // match sdk.vault_withdraw(vault_addr, 10.0).await {
-    //     Ok(result) => println!("   Result: {:?}", result),
-    //     Err(e) => println!("   Error: {}", e),
-    // }
+    if config.dry_run {
+        dry_run::show(&config, &actions::vault_transfer(vault_addr, false, 10.0)).await;
+    } else {
+        match sdk.vault_withdraw(vault_addr, 10.0).await {
+            Ok(result) => println!("   Result: {:?}", result),
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
 
     // User vault positions
     println!("\n4. Your Vault Positions:");
     let address_str = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
     match info.user_vault_equities(&address_str).await {
         Ok(positions) => {
-            if let Some(arr) = positions.as_array() {
-                if arr.is_empty() {
-                    println!("   No vault positions");
-                } else {
-                    for pos in arr {
-                        println!("   {:?}", pos);
-                    }
+            if positions.is_empty() {
+                println!("   No vault positions");
+            } else {
+                for pos in &positions {
+                    println!("   {}: ${:.2}", pos.vault_address, pos.equity);
                 }
             }
         }
@@ -12,39 +12,58 @@
 //! cargo run --example websocket_streaming
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::models::MarketEvent;
+use hyperliquid_examples::{stream_duration, Config, Requires};
+use serde_json::Value;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+
+/// Print the trades and book changes of a message. Trades and book updates
+/// arrive in different shapes over WebSocket and gRPC; [`MarketEvent`] gives
+/// both the same fields, so this is the same function as in `grpc_streaming`.
+fn print_events(data: &Value) {
+    let events = match MarketEvent::parse(data) {
+        Ok(events) => events,
+        Err(e) => return println!("[ERROR] bad payload: {}", e),
+    };
+    for event in events {
+        match event {
+            MarketEvent::Trade(trade) => {
+                let side_name = if trade.side.is_buy() { "BUY" } else { "SELL" };
+                println!("[TRADE] {}: {} {} @ ${:.2}", trade.coin, side_name, trade.sz, trade.px);
+            }
+            MarketEvent::BookDelta(update) => {
+                if let (Some(bid), Some(ask)) = (update.bids.first(), update.asks.first()) {
+                    println!("[BOOK] {}: Bid ${:.2} | Ask ${:.2} | Spread ${:.2}", update.coin, bid.px, ask.px, ask.px - bid.px);
+                }
+            }
+            MarketEvent::BookSnapshot(book) => {
+                println!("[L2] {}: {} bid levels, {} ask levels", book.coin, book.bids().len(), book.asks().len());
+            }
+            MarketEvent::Block(block) => println!("[BLOCK] #{}", block.number),
+            _ => {}
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt::init();
 
-    let endpoint = std::env::var("ENDPOINT").ok();
+    let config = Config::load()?.require("websocket_streaming", Requires::Endpoint);
 
     println!("Hyperliquid WebSocket Streaming Example");
     println!("{}", "=".repeat(50));
-
-    if endpoint.is_none() {
-        println!();
-        println!("Usage:");
-        println!("  export ENDPOINT='https://YOUR-ENDPOINT/TOKEN'");
-        println!("  cargo run --example websocket_streaming");
-        std::process::exit(1);
-    }
-
-    if let Some(ref ep) = endpoint {
-        let display_len = ep.len().min(60);
-        println!("Endpoint: {}{}", &ep[..display_len], if ep.len() > 60 { "..." } else { "" });
-    }
+    println!("Endpoint: {}", config.redacted_endpoint());
     println!();
 
     // Create SDK
-    let sdk = HyperliquidSDK::new()
-        .endpoint(endpoint.as_ref().unwrap())
-        .build()
-        .await?;
+    let sdk = config.connect().await?;
 
     // Create stream via SDK
     let trade_count = Arc::new(AtomicUsize::new(0));
@@ -76,26 +95,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Subscribing to BTC and ETH trades...");
     let _trade_sub = stream.trades(&["BTC", "ETH"], move |data| {
         trade_count_cb.fetch_add(1, Ordering::SeqCst);
-
-        if let Some(block) = data.get("block") {
-            if let Some(events) = block.get("events").and_then(|e| e.as_array()) {
-                for event in events {
-                    if let Some(event_arr) = event.as_array() {
-                        if event_arr.len() >= 2 {
-                            if let Some(trade) = event_arr.get(1) {
-                                let coin = trade.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-                                let px = trade.get("px").and_then(|p| p.as_str()).unwrap_or("?");
-                                let sz = trade.get("sz").and_then(|s| s.as_str()).unwrap_or("?");
-                                let side = trade.get("side").and_then(|s| s.as_str()).unwrap_or("?");
-                                let side_name = if side == "B" { "BUY" } else { "SELL" };
-                                let price: f64 = px.parse().unwrap_or(0.0);
-                                println!("[TRADE] {}: {} {} @ ${:.2}", coin, side_name, sz, price);
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        print_events(&data);
     });
 
     // ─────────────────────────────────────────────────────────────────────────
@@ -105,46 +105,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("Subscribing to BTC book updates...");
     let _book_sub = stream.book_updates(&["BTC"], move |data| {
         book_count_cb.fetch_add(1, Ordering::SeqCst);
-
-        if let Some(block) = data.get("block") {
-            if let Some(events) = block.get("events").and_then(|e| e.as_array()) {
-                for event in events {
-                    if let Some(event_arr) = event.as_array() {
-                        if event_arr.len() >= 2 {
-                            if let Some(book_data) = event_arr.get(1) {
-                                let coin = book_data.get("coin").and_then(|c| c.as_str()).unwrap_or("?");
-                                if let Some(levels) = book_data.get("levels").and_then(|l| l.as_array()) {
-                                    let bids = levels.get(0).and_then(|b| b.as_array());
-                                    let asks = levels.get(1).and_then(|a| a.as_array());
-
-                                    if let (Some(bids), Some(asks)) = (bids, asks) {
-                                        if let (Some(best_bid), Some(best_ask)) = (bids.first(), asks.first()) {
-                                            let bid_px = best_bid
-                                                .get("px")
-                                                .and_then(|p| p.as_str())
-                                                .unwrap_or("0")
-                                                .parse::<f64>()
-                                                .unwrap_or(0.0);
-                                            let ask_px = best_ask
-                                                .get("px")
-                                                .and_then(|p| p.as_str())
-                                                .unwrap_or("0")
-                                                .parse::<f64>()
-                                                .unwrap_or(0.0);
-                                            let spread = ask_px - bid_px;
-                                            println!(
-                                                "[BOOK] {}: Bid ${:.2} | Ask ${:.2} | Spread ${:.2}",
-                                                coin, bid_px, ask_px, spread
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        print_events(&data);
     });
 
     // ─────────────────────────────────────────────────────────────────────────
@@ -152,13 +113,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // ─────────────────────────────────────────────────────────────────────────
 
     println!();
-    println!("Streaming... (will run for 30 seconds)");
+    let duration = stream_duration(30);
+    println!("Streaming... (will run for {} seconds)", duration.as_secs());
     println!("{}", "-".repeat(50));
 
     stream.start()?;
 
-    // Run for 30 seconds
-    tokio::time::sleep(Duration::from_secs(30)).await;
+    // Run for 30 seconds (HL_STREAM_SECONDS overrides)
+    tokio::time::sleep(duration).await;
 
     // Stop the stream
     stream.stop();
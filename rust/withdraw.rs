@@ -6,50 +6,38 @@
 //! ```bash
 //! export ENDPOINT="https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN"
 //! export PRIVATE_KEY="0x..."
+//! cargo run --example withdraw -- --dry-run   # sign and print, nothing sent
 //! cargo run --example withdraw
 //! ```
 
-use hyperliquid_sdk::HyperliquidSDK;
+use hyperliquid_examples::dry_run::{self, actions};
+use hyperliquid_examples::models::TypedInfo;
+use hyperliquid_examples::{Config, Requires};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let endpoint = std::env::var("ENDPOINT").ok();
-    let private_key = std::env::var("PRIVATE_KEY").ok();
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    hyperliquid_examples::prepare();
+    run()
+}
 
-    if endpoint.is_none() || private_key.is_none() {
-        eprintln!("Usage:");
-        eprintln!("  export ENDPOINT='https://your-endpoint.hype-mainnet.quiknode.pro/TOKEN'");
-        eprintln!("  export PRIVATE_KEY='0x...'");
-        eprintln!("  cargo run --example withdraw");
-        std::process::exit(1);
-    }
+#[tokio::main]
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?.require("withdraw", Requires::Signer);
+    let sdk = config.connect().await?;
 
     println!("Withdraw Example");
     println!("{}", "=".repeat(50));
 
-    let mut builder = HyperliquidSDK::new();
-    if let Some(ep) = &endpoint {
-        builder = builder.endpoint(ep);
-    }
-    if let Some(pk) = &private_key {
-        builder = builder.private_key(pk);
-    }
-    let sdk = builder.build().await?;
-
     if let Some(addr) = sdk.address() {
         println!("Address: {}", addr);
     }
 
     // Check withdrawable balance
     println!("\n1. Withdrawable Balance:");
-    let info = sdk.info();
+    let info = TypedInfo::from_sdk(&sdk);
     let address_str = sdk.address().map(|a| format!("{:?}", a)).unwrap_or_default();
 
     match info.clearinghouse_state(&address_str, None).await {
-        Ok(state) => {
-            let withdrawable = state.get("withdrawable").and_then(|v| v.as_str()).unwrap_or("0");
-            println!("   Withdrawable: ${}", withdrawable);
-        }
+        Ok(state) => println!("   Withdrawable: ${}", state.withdrawable),
         Err(e) => println!("   Error: {}", e),
     }
 
@@ -58,19 +46,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("   Amount: $100");
     println!("   Destination: Your wallet address");
 
-    // Uncomment to execute withdrawal
-    // match sdk.withdraw(100.0, None).await {
-    //     Ok(result) => println!("   Result: {:?}", result),
-    //     Err(e) => println!("   Error: {}", e),
-    // }
+    if config.dry_run {
+        dry_run::show(&config, &actions::withdraw(sdk.chain(), 100.0, &address_str)).await;
+    } else {
+        match sdk.withdraw(100.0, None).await {
+            Ok(result) => println!("   Result: {:?}", result),
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
 
     // Withdraw to specific address
     println!("\n3. Withdraw to Specific Address:");
     println!("   sdk.withdraw(amount, Some(\"0xRecipientAddress\"))");
-    // match sdk.withdraw(100.0, Some("0xRecipientAddress")).await {
-    //     Ok(result) => println!("   Result: {:?}", result),
-    //     Err(e) => println!("   Error: {}", e),
-    // }
+    if config.dry_run {
+        dry_run::show(&config, &actions::withdraw(sdk.chain(), 100.0, "0xRecipientAddress")).await;
+    } else {
+        match sdk.withdraw(100.0, Some("0xRecipientAddress")).await {
+            Ok(result) => println!("   Result: {:?}", result),
+            Err(e) => println!("   Error: {}", e),
+        }
+    }
 
     println!("\n{}", "-".repeat(50));
     println!("Withdrawal Notes:");